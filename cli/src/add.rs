@@ -2,9 +2,19 @@
 
 use anyhow::Result;
 use codey::{Agent, AgentRuntimeConfig, AgentStep, RequestMode, SimpleTool, ToolRegistry};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::io::{self, BufRead, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
+use worldview_validator::{Change, Claim, Document, Facet, ValidationError, ValidationOptions, estimate_tokens};
+
+use crate::draft::{self, Draft};
+use crate::encryption;
+use crate::journal::{self, Entry};
+use crate::provider::{Price, ProviderKind};
+use crate::resume::Checkpoint;
+use crate::transcript::{self, Transcript};
 
 /// The Worldview format specification (loaded from SPEC.md at compile time)
 const SPEC: &str = include_str!("../../SPEC.md");
@@ -15,7 +25,7 @@ const TASK_INSTRUCTIONS: &str = r#"
 
 When given a plain-text fact or statement:
 1. First, read the current Worldview file to understand its structure and existing concepts
-2. Determine if this fact belongs to an existing concept/facet or requires a new one
+2. Determine if this fact belongs to an existing concept/facet or requires a new one (in a large file, use search_worldview to find related claims). A near-synonym of an existing concept belongs under it: add the synonym as an alias (`Power (aka Authority)`) rather than creating a second concept
 3. Format the fact as proper Worldview notation following the specification above
 4. Use the edit_worldview tool to add or modify the appropriate line(s)
 5. After editing, briefly confirm what you added
@@ -44,16 +54,345 @@ If given an ephemeral event, respond politely explaining that the Worldview form
 Remember the design principles: state over narrative, predictability allows omission, conflict tolerance, freeform vocabulary, and LLM-native density.
 "#;
 
+/// Task instructions for removing claims
+const REMOVE_INSTRUCTIONS: &str = r#"
+# Your Task
+
+When given a description of claims to remove:
+1. First, read the current Worldview file
+2. Find the claim(s) matching the description; match on meaning, not exact wording
+3. Use the edit_worldview tool to delete exactly those claim lines (use an empty new_string)
+4. After editing, briefly confirm which claims you removed
+
+## Critical: Remove Only What Is Described
+
+- **Never remove claims the user did not describe**, even related ones
+- **If no claim matches**, say so and do NOT modify the file
+- **If the description is ambiguous** between several claims, remove none and list the candidates
+
+Facets and concepts left without claims are pruned automatically; you only need to delete the claim lines.
+"#;
+
+/// Task instructions for revising beliefs
+const REVISE_INSTRUCTIONS: &str = r#"
+# Your Task
+
+When told that a belief has changed:
+1. First, read the current Worldview file
+2. Find the existing claim the user no longer holds; match on meaning, not exact wording
+3. Rewrite that claim in place to state the new belief, and append an evolution marker recording the old claim text: `- fast [<= slow :DATE]`, using the date given in the request
+4. Keep any earlier evolution markers on the claim after the new one (nearest first)
+5. After editing, briefly confirm the old and new belief
+
+## Critical: Preserve the Prior Belief
+
+- **Never delete the old claim outright**; a revision replaces it with a claim whose marker records it
+- **Only encode the change the user stated**; do not revise related claims
+- **If no existing claim matches**, say so and do NOT modify the file; suggest `worldview add` for new beliefs
+
+Edits that drop a claim without recording it in an `[<= ...]` marker are rejected.
+"#;
+
+/// Task instructions for splitting an oversized facet
+const SPLIT_INSTRUCTIONS: &str = r#"
+# Your Task
+
+When asked to split a facet:
+1. First, read the current Worldview file
+2. Group the facet's claims by theme into a few sub-facets, each with a short lowercase name
+3. Use the edit_worldview tool to rewrite the facet: each sub-facet is a `.name` line indented 4 spaces, with its claims beneath it indented 6 spaces
+4. After editing, list the sub-facets and how many claims each holds
+
+## Critical: Move Claims, Never Change Them
+
+- **Keep every claim's text exactly**, including its conditions, sources, references, and evolution markers
+- **Never add, drop, merge, or reword claims**, and never move claims out of the facet
+- **Leave other facets untouched**
+- Claims that fit no group may stay directly under the facet, before its sub-facets
+
+Edits that change any claim are rejected.
+"#;
+
+/// Task instructions for restructuring concepts and facets
+const REORGANIZE_INSTRUCTIONS: &str = r#"
+# Your Task
+
+When asked to reorganize a Worldview file (or one concept and the concepts that overlap it):
+1. First, list the concepts, then read the file (just the named concept and the concepts that overlap it, in a large file)
+2. Look for structure that no longer fits the claims:
+   - **Synonym concepts** that hold the same subject under two names: move the claims into one concept and declare the other name as its alias (`Power (aka Authority)`), so references to either name still resolve
+   - **Bloated facets** holding several themes: split them into sub-facets (`.name` lines indented 4 spaces, claims beneath at 6) or into separate facets
+   - **Misfiled claims** that are about a different facet or concept than the one they sit under: move them there
+3. Use the edit_worldview tool to make the changes, as few edits as needed
+4. After editing, list each change and why, one line each; if the structure already fits, say so and do NOT modify the file
+
+## Critical: Move Claims, Never Change Them
+
+- **Keep every claim's text exactly**, including its conditions, sources, modifiers, and evolution markers
+- **Never add, drop, merge, or reword claims**, even ones that repeat each other
+- **Update `&references`** to a facet you renamed or moved, so every reference still resolves
+- **Rename with care**: new concept and facet names follow the names already in the file
+
+The user reviews the changes as a diff before anything is saved. Edits that change or drop any claim are rejected.
+"#;
+
+/// Task instructions for answering a question in light of the worldview
+const APPLY_INSTRUCTIONS: &str = r#"
+# Your Task
+
+The user's worldview follows these instructions: the beliefs, values, and perspectives they have recorded, in Worldview notation. When the user asks a question:
+1. Find the claims that bear on it, including their conditions and modifiers
+2. Answer the question the way someone holding those beliefs would reason about it, in plain language
+3. End with a line `Informed by:` followed by one line per claim you relied on: `- [Concept.facet] claim`
+
+## Critical: Cite What Informed the Answer
+
+- **Cite the claims behind each point** as `[Concept.facet]` after the sentence they support
+- **Let the worldview decide** where it bears on the question; use general knowledge only to fill in what it does not cover, and say when you do
+- **Keep conditions and uncertainty**: a claim under `| unchecked` or marked `?` does not support an unconditional or certain answer
+- **Cite only facets shown below**, exactly as written; if no claim bears on the question, say so and write `Informed by: nothing recorded`
+"#;
+
+/// Task instructions for answering questions
+const ASK_INSTRUCTIONS: &str = r#"
+# Your Task
+
+When asked a question about the user's beliefs:
+1. First, read the current Worldview file
+2. Find the claims that bear on the question, including conditions, sources, and evolution markers
+3. Answer in plain language, strictly from those claims
+
+## Critical: Answer Only From Recorded Claims
+
+- **Never add knowledge or opinions** that are not recorded in the file
+- **Cite every claim you use** as `(Concept.facet, line N)` after the sentence it supports
+- **Mention recorded prior beliefs** (`[<= ...]`) when the question is about how a view changed
+- **If nothing in the file addresses the question**, say so plainly rather than guessing
+
+You cannot modify the file.
+"#;
+
+/// Task instructions for auditing related claims for contradictions
+const AUDIT_INSTRUCTIONS: &str = r#"
+# Your Task
+
+You are given groups of related claims from a Worldview file, each claim with its line number: the claims under one concept, or a claim together with the facet it references. For each group:
+1. Compare the claims pairwise for meaning, taking their conditions, modifiers, and evolution markers into account
+2. Decide whether any two of them cannot both hold as written
+
+Then report each likely contradiction on its own line, most certain first:
+
+- lines A and B (Concept.facet, Concept.facet): why they cannot both hold
+
+## Critical: Report, Never Edit
+
+- **Only report contradictions in meaning**; operator conflicts the validator already flags (`a => b` against `a vs b`) are listed separately, so do not repeat them
+- **Claims under different conditions do not contradict** (`grows | unchecked` and `shrinks | with oversight`), and a prior belief in an `[<= ...]` marker is not a current claim
+- **Say so when a pair reads as a deliberate tension** or trade-off rather than an inconsistency; the format tolerates held conflicts
+- **Cite line numbers exactly as given**; if nothing contradicts, reply "No likely contradictions found."
+
+You cannot modify the file.
+"#;
+
+/// Task instructions for summarizing a concept or the whole file
+const SUMMARIZE_INSTRUCTIONS: &str = r#"
+# Your Task
+
+When asked to summarize a concept or the whole worldview:
+1. First, read the current Worldview file (just the concept, if one is named, by passing `concept` to read_worldview)
+2. Gather every claim in scope, with its conditions, sources, references, and evolution markers
+3. Write the stance those claims add up to as prose, in Markdown paragraphs, roughly one per facet or theme
+
+## Critical: Summarize Only What Is Recorded
+
+- **Every sentence must rest on recorded claims**; never add knowledge, examples, or opinions that are not in the file
+- **Keep conditions and uncertainty**: a claim under `| unchecked` or marked `?` must not become unconditional or certain
+- **Mention how views changed** when claims record prior beliefs (`[<= ...]`)
+- **Write in the first person**, as the user stating their own views, without line citations or Worldview notation
+- **Do not start with a heading**; a title is added for you
+
+You cannot modify the file.
+"#;
+
+/// Task instructions for an interactive session
+const CHAT_INSTRUCTIONS: &str = r#"
+# Your Task
+
+You are in a conversation with the user about their Worldview file. Each message is either:
+- **A fact or statement to record**: read the file if you have not yet, then use the edit_worldview tool to add it in proper Worldview notation, and briefly confirm what you added
+- **A question about their beliefs**: answer in plain language strictly from the recorded claims, citing each claim you use as `(Concept.facet, line N)`; if nothing addresses the question, say so
+
+The user reviews every edit before it is saved. If they reject one, the file returns to how it was before that message; do not retry the edit unless asked.
+
+## Critical: Encode Only What Is Stated
+
+- **Only encode information explicitly stated** by the user; never add supplementary knowledge, assumptions, or inferences
+- **Reject ephemeral personal events** ("I had coffee this morning"); the file stores durable beliefs, values, perspectives, and knowledge
+- **Never answer from your own knowledge**; answers come only from the file
+
+Remember the design principles: state over narrative, predictability allows omission, conflict tolerance, freeform vocabulary, and LLM-native density.
+"#;
+
+/// What the agent is asked to do with the user's text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Task {
+    /// Incorporate a fact or statement
+    Add,
+    /// Delete the claims matching a description
+    Remove,
+    /// Replace a claim with a new belief, recording the old one as a prior belief
+    Revise,
+    /// Answer a question from the recorded claims, without editing. With
+    /// `matching`, the agent reads only the claims the question matches by
+    /// full-text search.
+    Ask { matching: bool },
+    /// Record facts and answer questions, turn by turn
+    Chat,
+    /// Group a facet's claims into sub-facets; the text is the `Concept.facet`
+    Split,
+    /// Report likely contradictions among groups of related claims, without
+    /// editing; the text lists the groups
+    Audit,
+    /// Describe the stance a concept's claims (or the whole file's) add up to,
+    /// without editing; the text is the concept, or empty for the whole file
+    Summarize,
+    /// Merge synonym concepts, split bloated facets, and move misfiled claims;
+    /// the text is the concept, or empty for the whole file
+    Reorganize,
+    /// Answer a question in light of a worldview given in the system prompt,
+    /// without tools, citing the facets that informed the answer
+    Apply,
+}
+
+impl Task {
+    /// Whether the agent is given the edit tool
+    fn edits(self) -> bool {
+        !matches!(self, Task::Ask { .. } | Task::Audit | Task::Summarize | Task::Apply)
+    }
+
+    /// Whether the agent's replies are the command's output
+    fn replies(self) -> bool {
+        matches!(self, Task::Ask { .. } | Task::Chat | Task::Audit | Task::Summarize | Task::Apply)
+    }
+
+    /// The command that runs the task, for the journal
+    fn command(self) -> &'static str {
+        match self {
+            Task::Add => "add",
+            Task::Remove => "remove",
+            Task::Revise => "revise",
+            Task::Ask { .. } => "ask",
+            Task::Chat => "chat",
+            Task::Split => "suggest-split",
+            Task::Audit => "audit",
+            Task::Summarize => "summarize",
+            Task::Reorganize => "reorganize",
+            Task::Apply => "apply",
+        }
+    }
+
+    fn role(self) -> &'static str {
+        match self {
+            Task::Add => "Your task is to take plain-text facts or statements and incorporate them into a Worldview file using the proper notation.",
+            Task::Remove => "Your task is to find and delete claims in a Worldview file that match a plain-text description.",
+            Task::Revise => "Your task is to update claims in a Worldview file when the user's beliefs change, recording each prior belief with an evolution marker.",
+            Task::Ask { .. } => "Your task is to answer questions about the user's beliefs using only the claims recorded in their Worldview file.",
+            Task::Chat => "Your task is to keep the user's Worldview file up to date as they state beliefs, and to answer their questions from it.",
+            Task::Split => "Your task is to reorganize an oversized facet of a Worldview file into sub-facets that group related claims.",
+            Task::Audit => "Your task is to find claims in a Worldview file that likely contradict each other in meaning.",
+            Task::Summarize => "Your task is to summarize the beliefs recorded in a Worldview file as prose, using only its claims.",
+            Task::Apply => "Your task is to answer the user's questions by applying the worldview recorded in their Worldview file, citing the claims that informed each answer.",
+            Task::Reorganize => "Your task is to restructure the concepts and facets of a Worldview file so its claims sit where they belong, without changing any claim.",
+        }
+    }
+
+    fn instructions(self) -> &'static str {
+        match self {
+            Task::Add => TASK_INSTRUCTIONS,
+            Task::Remove => REMOVE_INSTRUCTIONS,
+            Task::Revise => REVISE_INSTRUCTIONS,
+            Task::Ask { .. } => ASK_INSTRUCTIONS,
+            Task::Chat => CHAT_INSTRUCTIONS,
+            Task::Split => SPLIT_INSTRUCTIONS,
+            Task::Audit => AUDIT_INSTRUCTIONS,
+            Task::Summarize => SUMMARIZE_INSTRUCTIONS,
+            Task::Reorganize => REORGANIZE_INSTRUCTIONS,
+            Task::Apply => APPLY_INSTRUCTIONS,
+        }
+    }
+
+    fn user_message(self, file_path: &PathBuf, text: &str) -> String {
+        match self {
+            Task::Add => format!(
+                "Please add this fact to the Worldview file at {:?}:\n\n{}",
+                file_path, text
+            ),
+            Task::Remove => format!(
+                "Please remove the claim(s) described below from the Worldview file at {:?}:\n\n{}",
+                file_path, text
+            ),
+            Task::Revise => format!(
+                "Please revise the Worldview file at {:?} to reflect this change of belief (today is {}):\n\n{}",
+                file_path,
+                today(),
+                text
+            ),
+            Task::Ask { .. } => format!(
+                "Please answer this question from the Worldview file at {:?}:\n\n{}",
+                file_path, text
+            ),
+            Task::Chat => format!("(Worldview file at {:?})\n\n{}", file_path, text),
+            Task::Split => format!(
+                "Please split the facet {} in the Worldview file at {:?} into sub-facets grouping its claims.",
+                text, file_path
+            ),
+            Task::Audit => format!(
+                "Please review these groups of related claims from the Worldview file at {:?} and report likely contradictions:\n\n{}",
+                file_path, text
+            ),
+            Task::Summarize if text.is_empty() => format!(
+                "Please summarize the whole worldview recorded in the Worldview file at {:?}.",
+                file_path
+            ),
+            Task::Summarize => format!(
+                "Please summarize the concept {} in the Worldview file at {:?}.",
+                text, file_path
+            ),
+            Task::Apply => text.to_string(),
+            Task::Reorganize if text.is_empty() => format!(
+                "Please reorganize the concepts and facets of the Worldview file at {:?}.",
+                file_path
+            ),
+            Task::Reorganize => format!(
+                "Please reorganize the concept {}, and the concepts that overlap it, in the Worldview file at {:?}.",
+                text, file_path
+            ),
+        }
+    }
+}
+
 /// Build the complete system prompt from spec + task instructions
-fn build_system_prompt() -> String {
+///
+/// `apply` gets the worldview itself, as `context`, in place of the spec: its
+/// prompt rendering carries a legend for the notation it uses.
+fn build_system_prompt(task: Task, context: &str) -> String {
+    if task == Task::Apply {
+        return format!(
+            "You are a Worldview format agent. {}\n\n{}\n---\n\n{}",
+            task.role(),
+            task.instructions(),
+            context
+        );
+    }
     format!(
-        "You are a Worldview format agent. Your task is to take plain-text facts or statements and incorporate them into a Worldview file using the proper notation.\n\n\
+        "You are a Worldview format agent. {}\n\n\
         Below is the complete Worldview format specification. Study it carefully before making any edits.\n\n\
         ---\n\n\
         {}\n\n\
         ---\n\n\
         {}",
-        SPEC, TASK_INSTRUCTIONS
+        task.role(), SPEC, task.instructions()
     )
 }
 
@@ -61,7 +400,32 @@ fn build_system_prompt() -> String {
 fn create_read_tool() -> SimpleTool {
     SimpleTool::new(
         "read_worldview",
-        "Read the current contents of the Worldview file. Returns the file contents with line numbers prefixed (e.g., '   1│content'). Use read_worldview first before editing to see current state.",
+        "Read the current contents of the Worldview file. Returns the file contents with line numbers prefixed (e.g., '   1│content'). Use read_worldview first before editing to see current state. For large files, pass `concept` or `line_range` to read only part of the file (use list_concepts to find where concepts are).",
+        json!({
+            "type": "object",
+            "properties": {
+                "concept": {
+                    "type": "string",
+                    "description": "Read only this concept and its facets and claims"
+                },
+                "line_range": {
+                    "type": "array",
+                    "items": {"type": "integer"},
+                    "minItems": 2,
+                    "maxItems": 2,
+                    "description": "Read only lines [start, end] (1-based, inclusive)"
+                }
+            },
+            "required": []
+        }),
+    )
+}
+
+/// Create the list_concepts tool definition
+fn create_list_tool() -> SimpleTool {
+    SimpleTool::new(
+        "list_concepts",
+        "List the concepts in the Worldview file, one per line with its line number, facet names, and claim count (e.g., '  12│Trust (9 claims): formation, erosion'). Use it to find the part of a large file to read.",
         json!({
             "type": "object",
             "properties": {},
@@ -70,6 +434,33 @@ fn create_read_tool() -> SimpleTool {
     )
 }
 
+/// Create the search_worldview tool definition
+fn create_search_tool() -> SimpleTool {
+    SimpleTool::new(
+        "search_worldview",
+        "Search the claims in the Worldview file, best match first. Returns one claim per line with its line number and Concept.facet (e.g., '  12│Trust.formation: slow'). Use it to find existing claims related to a fact before deciding where it belongs. Mode 'text' (default) ranks claims by the query's words; 'semantic' ranks them by meaning, if the file has an embeddings index.",
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words or a phrase to look for"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["text", "semantic"],
+                    "description": "Ranking by words ('text') or by meaning ('semantic')"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of claims to return (default 10)"
+                }
+            },
+            "required": ["query"]
+        }),
+    )
+}
+
 /// Create the edit_worldview tool definition
 fn create_edit_tool() -> SimpleTool {
     SimpleTool::new(
@@ -83,7 +474,7 @@ Rules:
 - To delete content, use an empty new_string
 - Multiple edits are applied sequentially
 
-The tool validates the result against Worldview syntax rules before writing."#,
+The tool validates the result against Worldview syntax rules before writing. If validation fails, nothing is written and the result is JSON listing each diagnostic's code, line, message, and the offending line's text; fix them all and retry. After 3 failed edits in one request, further edits are refused. Edits that repeat an existing claim in other words or contradict one are not applied until you confirm them with acknowledge_conflicts."#,
         json!({
             "type": "object",
             "properties": {
@@ -104,6 +495,10 @@ The tool validates the result against Worldview syntax rules before writing."#,
                         },
                         "required": ["old_string", "new_string"]
                     }
+                },
+                "acknowledge_conflicts": {
+                    "type": "boolean",
+                    "description": "Apply the edits even though they repeat or contradict existing claims"
                 }
             },
             "required": ["edits"]
@@ -112,27 +507,166 @@ The tool validates the result against Worldview syntax rules before writing."#,
 }
 
 /// Handle the read_worldview tool call
-fn handle_read_worldview(file_path: &PathBuf) -> String {
-    if !file_path.exists() {
+///
+/// With a `query`, only the claims it matches are returned, each labeled with
+/// its `Concept.facet`.
+fn handle_read_worldview(draft: &Draft, query: Option<&str>, params: &serde_json::Value) -> String {
+    if !draft.exists {
         return "File does not exist yet. Use edit_worldview with edits to create it.".to_string();
     }
 
-    match std::fs::read_to_string(file_path) {
-        Ok(content) => {
-            // Return with line numbers in codey format
-            content
-                .lines()
-                .enumerate()
-                .map(|(i, line)| format!("{:4}│{}", i + 1, line))
-                .collect::<Vec<_>>()
-                .join("\n")
+    if let Some(query) = query {
+        let mut hits = Document::parse(&draft.content).search(query);
+        if hits.is_empty() {
+            return "No claims match the question.".to_string();
+        }
+        hits.sort_by_key(|hit| hit.line);
+        return hits
+            .iter()
+            .map(|hit| format!("{:4}│{}: {}", hit.line, hit.target, hit.claim))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let lines: Vec<&str> = draft.content.lines().collect();
+    let (start, end) = if let Some(name) = params.get("concept").and_then(|v| v.as_str()) {
+        let doc = Document::parse(&draft.content);
+        match concept_lines(&doc, name, &lines) {
+            Some(range) => range,
+            None => return format!("No concept named '{}'. Use list_concepts to see them.", name),
         }
-        Err(e) => format!("Error reading file: {}", e),
+    } else if let Some(range) = params.get("line_range").and_then(|v| v.as_array()) {
+        let bound = |i: usize| range.get(i).and_then(|v| v.as_u64()).map(|n| n as usize);
+        match (bound(0), bound(1)) {
+            (Some(start), Some(end)) if start >= 1 && start <= end => (start, end.min(lines.len())),
+            _ => return "Error: 'line_range' must be [start, end] with 1 <= start <= end".to_string(),
+        }
+    } else {
+        (1, lines.len())
+    };
+
+    // Return with line numbers in codey format
+    lines
+        .iter()
+        .enumerate()
+        .skip(start - 1)
+        .take((end + 1).saturating_sub(start))
+        .map(|(i, line)| format!("{:4}│{}", i + 1, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// First and last line of a concept: up to the next concept or the `sources:`
+/// section, less trailing blank lines
+fn concept_lines(doc: &Document, name: &str, lines: &[&str]) -> Option<(usize, usize)> {
+    let start = doc.concept(name)?.line;
+    let mut end = doc
+        .concepts
+        .iter()
+        .map(|c| c.line)
+        .chain(doc.sources.as_ref().map(|s| s.line))
+        .filter(|&line| line > start)
+        .min()
+        .map_or(lines.len(), |line| line - 1);
+    while end > start && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    Some((start, end))
+}
+
+/// Handle the search_worldview tool call
+fn handle_search_worldview(draft: &Draft, params: &serde_json::Value) -> String {
+    let Some(query) = params.get("query").and_then(|v| v.as_str()) else {
+        return "Error: 'query' is required".to_string();
+    };
+    let limit = params
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(10, |n| n as usize);
+    let doc = Document::parse(&draft.content);
+
+    let hits: Vec<(usize, String, String)> = match params.get("mode").and_then(|v| v.as_str()) {
+        None | Some("text") => doc
+            .search(query)
+            .into_iter()
+            .map(|hit| (hit.line, hit.target, hit.claim))
+            .collect(),
+        Some("semantic") => match semantic_hits(&doc, &draft.path, query) {
+            Ok(hits) => hits,
+            Err(e) => return format!("Semantic search unavailable: {}. Use mode 'text' instead.", e),
+        },
+        Some(mode) => return format!("Error: unknown mode '{}' (use 'text' or 'semantic')", mode),
+    };
+    if hits.is_empty() {
+        return format!("No claims match '{}'.", query);
     }
+    hits.iter()
+        .take(limit)
+        .map(|(line, target, claim)| format!("{:4}│{}: {}", line, target, claim))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
+/// Claims nearest the query by embedding similarity, as (line, target, claim)
+#[cfg(feature = "embeddings")]
+fn semantic_hits(doc: &Document, file: &std::path::Path, query: &str) -> Result<Vec<(usize, String, String)>> {
+    let (hits, _) = crate::embeddings::nearest(doc, file, query)?;
+    Ok(hits
+        .into_iter()
+        .map(|(_, target, claim)| (claim.line, target, claim.data.to_wvf_string()))
+        .collect())
+}
+
+#[cfg(not(feature = "embeddings"))]
+fn semantic_hits(_doc: &Document, _file: &std::path::Path, _query: &str) -> Result<Vec<(usize, String, String)>> {
+    anyhow::bail!("worldview was built without the `embeddings` feature")
+}
+
+/// Handle the list_concepts tool call
+fn handle_list_concepts(draft: &Draft) -> String {
+    let doc = Document::parse(&draft.content);
+    if doc.concepts.is_empty() {
+        return "The file has no concepts yet.".to_string();
+    }
+    doc.concepts
+        .iter()
+        .map(|concept| {
+            let claims: usize = concept.facets.iter().map(count_claims).sum();
+            let facets: Vec<&str> = concept.facets.iter().map(|f| f.name.as_str()).collect();
+            format!(
+                "{:4}│{} ({} claim{}): {}",
+                concept.line,
+                concept.heading(),
+                claims,
+                if claims == 1 { "" } else { "s" },
+                facets.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn count_claims(facet: &Facet) -> usize {
+    facet.claims.len() + facet.sub_facets.iter().map(count_claims).sum::<usize>()
+}
+
+/// Validation failures the agent may repair in one request before edits are refused
+const MAX_REPAIR_ROUNDS: usize = 3;
+
 /// Handle the edit_worldview tool call
-fn handle_edit_worldview(file_path: &PathBuf, params: &serde_json::Value) -> String {
+///
+/// `repair_rounds` counts this request's edits that failed validation.
+fn handle_edit_worldview(draft: &mut Draft, task: Task, repair_rounds: &mut usize, params: &serde_json::Value) -> String {
+    if *repair_rounds >= MAX_REPAIR_ROUNDS {
+        return json!({
+            "status": "refused",
+            "file_modified": false,
+            "reason": format!("{} edits in this request failed validation", MAX_REPAIR_ROUNDS),
+            "next_step": "Do not edit again. Tell the user what you were trying to record and which errors you could not fix.",
+        })
+        .to_string();
+    }
+
     // Parse edits array
     let edits = match params.get("edits").and_then(|v| v.as_array()) {
         Some(arr) => arr,
@@ -143,15 +677,8 @@ fn handle_edit_worldview(file_path: &PathBuf, params: &serde_json::Value) -> Str
         return "Error: 'edits' array cannot be empty".to_string();
     }
 
-    // Read current file content (or start empty for new files)
-    let mut content = if file_path.exists() {
-        match std::fs::read_to_string(file_path) {
-            Ok(c) => c,
-            Err(e) => return format!("Error reading file: {}", e),
-        }
-    } else {
-        String::new()
-    };
+    // Start from the current draft (empty for new files)
+    let mut content = draft.content.clone();
 
     // Validate and apply each edit
     for (i, edit) in edits.iter().enumerate() {
@@ -206,19 +733,51 @@ fn handle_edit_worldview(file_path: &PathBuf, params: &serde_json::Value) -> Str
         content.push('\n');
     }
 
-    // Validate the new content before writing
-    let validation = worldview_validator::validate(&content);
+    if task == Task::Remove {
+        content = prune_empty(&content);
+    }
+    if task == Task::Revise
+        && let Some(error) = unrecorded_revision(&draft.content, &content)
+    {
+        return format!("Revision rejected - file not modified:\n{}", error);
+    }
+    if task == Task::Split
+        && let Some(error) = changed_claims(&draft.content, &content)
+    {
+        return format!("Split rejected - file not modified:\n{}", error);
+    }
+    if task == Task::Reorganize
+        && let Some(error) = rewritten_claims(&draft.content, &content)
+    {
+        return format!("Reorganization rejected - file not modified:\n{}", error);
+    }
+
+    // Validate the new content before writing (includes resolve relative to the file)
+    let options = ValidationOptions {
+        nested_facets: Document::parse(&content).has_nested_facets(),
+        ..ValidationOptions::default()
+    };
+    let validation = worldview_validator::validate_in_context(&content, &draft.path, &options);
 
     if !validation.is_valid() {
-        let errors: Vec<String> = validation.errors.iter().map(|e| e.to_string()).collect();
-        return format!(
-            "Validation failed - file not modified:\n{}",
-            errors.join("\n")
-        );
+        *repair_rounds += 1;
+        return validation_feedback(&validation.errors, &content, MAX_REPAIR_ROUNDS - *repair_rounds);
     }
 
-    // Write the file
-    if let Err(e) = std::fs::write(file_path, &content) {
+    let warnings: Vec<String> = validation.warnings.iter().map(|w| w.to_string()).collect();
+
+    // Repeats and contradictions are only warnings, so make the agent confirm them
+    let acknowledged = params
+        .get("acknowledge_conflicts")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let conflicts = draft::new_conflicts(&draft.content, &content);
+    if !conflicts.is_empty() && !acknowledged {
+        return conflict_feedback(&conflicts, &content);
+    }
+
+    // Update the draft (writing the file unless changes await confirmation)
+    if let Err(e) = draft.update(content) {
         return format!("Error writing file: {}", e);
     }
 
@@ -230,158 +789,1024 @@ fn handle_edit_worldview(file_path: &PathBuf, params: &serde_json::Value) -> Str
         if edit_count == 1 { "" } else { "s" }
     );
 
-    if validation.has_warnings() {
-        let warnings: Vec<String> = validation.warnings.iter().map(|w| w.to_string()).collect();
+    if !warnings.is_empty() {
         format!("{} Warnings:\n{}", base_msg, warnings.join("\n"))
     } else {
         format!("{} File validated.", base_msg)
     }
 }
 
+/// Structured tool output for an edit that failed validation, so the agent
+/// can see each error's kind and the exact line it is on
+fn validation_feedback(errors: &[ValidationError], content: &str, attempts_left: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let diagnostics: Vec<serde_json::Value> = errors
+        .iter()
+        .filter(|e| !e.is_warning())
+        .map(|e| {
+            let message = e.to_string();
+            json!({
+                "code": e.code(),
+                "line": e.line(),
+                "message": message.split_once(": ").map_or(message.as_str(), |(_, m)| m),
+                "text": lines.get(e.line().wrapping_sub(1)).copied().unwrap_or(""),
+            })
+        })
+        .collect();
+    let next_step = match attempts_left {
+        0 => "No repair attempts left. Do not edit again; explain the errors to the user.",
+        _ => "Fix every diagnostic, then call edit_worldview again with corrected edits against the unmodified file.",
+    };
+    json!({
+        "status": "validation_failed",
+        "file_modified": false,
+        "diagnostics": diagnostics,
+        "repair_attempts_left": attempts_left,
+        "next_step": next_step,
+    })
+    .to_string()
+}
+
+/// Structured tool output for an edit that would repeat or contradict claims
+fn conflict_feedback(conflicts: &[ValidationError], content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let text = |line: usize| lines.get(line.wrapping_sub(1)).map_or("", |l| l.trim());
+    let conflicts: Vec<serde_json::Value> = conflicts
+        .iter()
+        .map(|e| {
+            let other_line = match e {
+                ValidationError::DuplicateClaim { other_line, .. }
+                | ValidationError::ContradictoryRelation { other_line, .. } => *other_line,
+                _ => 0,
+            };
+            json!({
+                "code": e.code(),
+                "message": e.to_string(),
+                "text": text(e.line()),
+                "existing": text(other_line),
+            })
+        })
+        .collect();
+    json!({
+        "status": "conflicts",
+        "file_modified": false,
+        "conflicts": conflicts,
+        "next_step": "If the new claim repeats an existing one, do not add it (or fold its wording into the existing claim). If the tension is deliberate, call edit_worldview again with the same edits and \"acknowledge_conflicts\": true.",
+    })
+    .to_string()
+}
+
+/// Drop facets and concepts left without claims, keeping the rest of the text as written
+fn prune_empty(content: &str) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    loop {
+        let text = lines.join("\n");
+        let result = draft::validate(&text);
+        let mut empty: Vec<(usize, bool)> = result
+            .errors
+            .iter()
+            .filter_map(|e| match e {
+                ValidationError::FacetWithoutClaims { line, .. } => Some((line - 1, false)),
+                ValidationError::ConceptWithoutFacets { line, .. } => Some((line - 1, true)),
+                _ => None,
+            })
+            .collect();
+        if empty.is_empty() {
+            break;
+        }
+        empty.sort();
+        for &(i, concept) in empty.iter().rev() {
+            lines.remove(i);
+            // A removed concept leaves its blank separator line behind
+            let separator = lines.get(i).is_some_and(|l| l.trim().is_empty());
+            if concept && separator && (i == 0 || lines[i - 1].trim().is_empty()) {
+                lines.remove(i);
+            }
+        }
+    }
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let mut pruned = lines.join("\n");
+    if !pruned.is_empty() {
+        pruned.push('\n');
+    }
+    pruned
+}
+
+/// A claim the edit drops or rewrites without recording its old text as a prior belief
+fn unrecorded_revision(old: &str, new: &str) -> Option<String> {
+    let changes = Document::parse(old).diff(&Document::parse(new));
+    changes.iter().find_map(|change| match change {
+        Change::ClaimRemoved { target, claim } => Some(format!(
+            "{}: '{}' was removed; rewrite it with `[<= {}]` instead",
+            target,
+            claim,
+            Claim::parse(claim).data.text
+        )),
+        Change::ClaimChanged { target, from, to, prior_recorded: false } => {
+            let from = Claim::parse(from);
+            (from.data.text != Claim::parse(to).data.text).then(|| {
+                format!(
+                    "{}: '{}' was rewritten without `[<= {}]`",
+                    target, to, from.data.text
+                )
+            })
+        }
+        _ => None,
+    })
+}
+
+/// A claim the edit adds, drops, rewrites, or moves to another facet
+///
+/// Claims may move into sub-facets of their facet, so each is keyed by its
+/// concept and top-level facet.
+fn changed_claims(old: &str, new: &str) -> Option<String> {
+    fn claims(content: &str) -> Vec<(String, String)> {
+        fn collect(facet: &Facet, path: &str, out: &mut Vec<(String, String)>) {
+            for claim in &facet.claims {
+                out.push((path.to_string(), claim.data.to_wvf_string()));
+            }
+            for sub_facet in &facet.sub_facets {
+                collect(sub_facet, path, out);
+            }
+        }
+        let doc = Document::parse(content);
+        let mut claims = Vec::new();
+        for concept in &doc.concepts {
+            for facet in &concept.facets {
+                collect(facet, &format!("{}.{}", concept.name, facet.name), &mut claims);
+            }
+        }
+        claims.sort();
+        claims
+    }
+
+    let mut added = claims(new);
+    for (target, claim) in claims(old) {
+        match added.iter().position(|other| other.0 == target && other.1 == claim) {
+            Some(i) => {
+                added.remove(i);
+            }
+            None => {
+                return Some(format!("{}: '{}' was dropped, changed, or moved out of the facet", target, claim));
+            }
+        }
+    }
+    added.first().map(|(target, claim)| format!("{}: '{}' was added", target, claim))
+}
+
+/// A claim the edit adds, drops, or rewrites, wherever it moved
+///
+/// References are left out of the comparison, since moving a facet means
+/// updating the references to it.
+fn rewritten_claims(old: &str, new: &str) -> Option<String> {
+    fn claims(content: &str) -> Vec<String> {
+        fn collect(facet: &Facet, out: &mut Vec<String>) {
+            for claim in &facet.claims {
+                let mut data = claim.data.clone();
+                data.references.clear();
+                out.push(data.to_wvf_string());
+            }
+            for sub_facet in &facet.sub_facets {
+                collect(sub_facet, out);
+            }
+        }
+        let doc = Document::parse(content);
+        let mut claims = Vec::new();
+        for concept in &doc.concepts {
+            for facet in &concept.facets {
+                collect(facet, &mut claims);
+            }
+        }
+        claims
+    }
+
+    let mut added = claims(new);
+    for claim in claims(old) {
+        match added.iter().position(|other| *other == claim) {
+            Some(i) => {
+                added.remove(i);
+            }
+            None => return Some(format!("'{}' was dropped or changed", claim)),
+        }
+    }
+    added.first().map(|claim| format!("'{}' was added", claim))
+}
+
+/// Today's date as `YYYY-MM-DD`, for dating evolution markers
+pub fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    crate::log::civil_date(seconds)
+}
+
+/// How an `edit_worldview` call ended, for the transcript: `applied`, or the
+/// status the agent was sent back (`validation_failed`, `conflicts`, ...)
+fn edit_outcome(before: &str, after: &str, result: &str) -> String {
+    if before != after {
+        return "applied".to_string();
+    }
+    serde_json::from_str::<serde_json::Value>(result)
+        .ok()
+        .and_then(|value| value["status"].as_str().map(str::to_string))
+        .unwrap_or_else(|| "rejected".to_string())
+}
+
 /// Handle a tool call from the agent
-fn handle_tool_call(file_path: &PathBuf, tool_name: &str, params: &serde_json::Value) -> String {
+fn handle_tool_call(draft: &mut Draft, task: Task, query: Option<&str>, repair_rounds: &mut usize, tool_name: &str, params: &serde_json::Value) -> String {
     match tool_name {
-        "read_worldview" => handle_read_worldview(file_path),
-        "edit_worldview" => handle_edit_worldview(file_path, params),
+        "read_worldview" => handle_read_worldview(draft, query, params),
+        "list_concepts" => handle_list_concepts(draft),
+        "search_worldview" => handle_search_worldview(draft, params),
+        "edit_worldview" if task.edits() => handle_edit_worldview(draft, task, repair_rounds, params),
         _ => format!("Unknown tool: {}", tool_name),
     }
 }
 
-pub async fn run(fact: String, file: PathBuf, model: String, verbose: bool) -> Result<()> {
-    let start_time = std::time::Instant::now();
+/// When agent edits are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Apply {
+    /// Write each edit as the agent makes it
+    Immediately,
+    /// Show the changes and write them if the user approves
+    Confirm,
+    /// Show the changes without writing anything
+    DryRun,
+}
+
+/// Which model runs the agent, how much it may spend, and how much it reports
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Options {
+    pub provider: ProviderKind,
+    /// Model name or alias (the provider's default when `None`)
+    pub model: Option<String>,
+    pub verbose: bool,
+    pub limits: Limits,
+    /// Where to append a transcript of the session (`--log-json`)
+    pub log_json: Option<PathBuf>,
+    /// Record written edits in the file's journal even if it does not exist yet
+    #[serde(default)]
+    pub journal: bool,
+}
+
+/// Budgets for a command's whole agent session
+///
+/// Exceeding one stops the agent and leaves the file untouched.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Limits {
+    /// Estimated dollars, checked before each round of tool calls and as each
+    /// reply finishes
+    pub max_cost: Option<f64>,
+    /// Model responses, counting each round of tool calls
+    pub max_turns: Option<usize>,
+    pub max_tool_calls: Option<usize>,
+}
+
+impl Limits {
+    fn is_set(&self) -> bool {
+        self.max_cost.is_some() || self.max_turns.is_some() || self.max_tool_calls.is_some()
+    }
+}
+
+/// Why an agent reply ended before it finished
+#[derive(Debug)]
+enum Interrupted {
+    /// The agent runtime gave up
+    Error(String),
+    /// A budget in `Limits` ran out
+    Budget(String),
+}
 
-    // Check for API key
-    if std::env::var("ANTHROPIC_API_KEY").is_err() {
-        eprintln!("Error: ANTHROPIC_API_KEY environment variable not set");
-        std::process::exit(1);
+impl std::fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interrupted::Error(e) => write!(f, "{}", e),
+            Interrupted::Budget(limit) => write!(f, "budget exceeded: {}", limit),
+        }
     }
+}
 
-    // Resolve the file path
-    let file_path = if file.is_absolute() {
-        file.clone()
-    } else {
-        std::env::current_dir()?.join(&file)
-    };
+/// An agent session with the Worldview tools, editing a draft of one file
+struct Session {
+    agent: Agent,
+    draft: Draft,
+    task: Task,
+    verbose: bool,
+    limits: Limits,
+    /// List price of the model, for cost estimates
+    price: Option<Price>,
+    /// Requests sent so far; later ones can rely on the agent's earlier read
+    requests: usize,
+    /// Model responses so far
+    turns: usize,
+    tool_call_count: usize,
+    /// Estimated dollars spent so far
+    cost: f64,
+    /// Tokens in the conversation as of the latest finished reply
+    context_tokens: u64,
+    /// Edits that failed validation during the latest request
+    repair_rounds: usize,
+    start_time: std::time::Instant,
+    transcript: Option<Transcript>,
+    /// The provider and model, as the journal records them
+    provider: String,
+    model_id: String,
+    /// The file's journal, if edits are journaled
+    journal: Option<PathBuf>,
+    /// Journal entries for requests whose edits are not written yet
+    unjournaled: Vec<Entry>,
+    /// Saved progress, so an interrupted run can be resumed
+    checkpoint: Option<Checkpoint>,
+    /// Tool calls made before a resumed run was interrupted, for the first request
+    resume_note: Option<String>,
+    /// Text of the agent's latest reply: the output of tasks whose replies
+    /// are, and for a batch, why a fact changed nothing
+    reply: String,
+}
 
-    if verbose {
-        eprintln!("[config] Worldview file: {:?}", file_path);
-        eprintln!("[config] Model: {}", model);
-        eprintln!("[config] Fact: {}", fact);
-        eprintln!("[start] Beginning agent execution...");
-    }
-
-    // Create tool registry with our custom tools
-    let mut registry = ToolRegistry::empty();
-    registry.register(Arc::new(create_read_tool()));
-    registry.register(Arc::new(create_edit_tool()));
-
-    // Configure the agent
-    let config = AgentRuntimeConfig {
-        model: model.clone(),
-        max_tokens: 4096,
-        thinking_budget: 1024,  // Minimum required
-        max_retries: 3,
-        compaction_thinking_budget: 2000,
-    };
+impl Session {
+    fn start(task: Task, file: PathBuf, options: &Options, apply: Apply) -> Result<Session> {
+        Session::start_in_context(task, file, options, apply, "")
+    }
 
-    // Create the agent with the dynamically built system prompt
-    let system_prompt = build_system_prompt();
-    let mut agent = Agent::new(
-        config,
-        &system_prompt,
-        None, // Use ANTHROPIC_API_KEY env var
-        registry,
-    );
+    /// Start a session whose system prompt also carries `context`
+    fn start_in_context(task: Task, file: PathBuf, options: &Options, apply: Apply, context: &str) -> Result<Session> {
+        let start_time = std::time::Instant::now();
+        let verbose = options.verbose;
+        let provider = options.provider.provider();
+        let model = options
+            .model
+            .clone()
+            .unwrap_or_else(|| provider.default_model().to_string());
 
-    // Format the user message
-    let user_message = format!(
-        "Please add this fact to the Worldview file at {:?}:\n\n{}",
-        file_path, fact
-    );
+        // Resolve the file path
+        let file_path = if file.is_absolute() {
+            file.clone()
+        } else {
+            std::env::current_dir()?.join(&file)
+        };
 
-    // Send the request
-    agent.send_request(&user_message, RequestMode::Normal);
+        // With a budget, edits wait in the draft so an abort can drop them
+        let write_through = apply == Apply::Immediately && !options.limits.is_set();
+        let draft = Draft::open(file_path.clone(), write_through)?;
+        if !task.edits() && !draft.exists {
+            anyhow::bail!("{} does not exist", file_path.display());
+        }
+        // Journals and transcripts record claims in plaintext
+        let encrypted = encryption::is_encrypted(&file_path);
+        if encrypted && (options.journal || options.log_json.is_some()) {
+            anyhow::bail!("{} is encrypted; --journal and --log-json would write its claims in plaintext", file_path.display());
+        }
 
-    // Process the agent loop
-    let mut tool_call_count = 0;
-    let mut thinking_started = false;
+        if verbose {
+            eprintln!("[config] Worldview file: {:?}", file_path);
+            eprintln!("[config] Provider: {:?}", options.provider);
+            eprintln!("[config] Model: {}", provider.model_id(&model));
+        }
 
-    while let Some(step) = agent.next().await {
-        match step {
-            AgentStep::TextDelta(text) => {
-                if verbose {
-                    print!("{}", text);
+        let price = provider.price(&model);
+        if options.limits.max_cost.is_some() && price.is_none() {
+            anyhow::bail!("No price known for model {}; --max-cost cannot be enforced", model);
+        }
+
+        // Create tool registry with our custom tools
+        let mut registry = ToolRegistry::empty();
+        // `apply` has the worldview in its prompt already
+        if task != Task::Apply {
+            registry.register(Arc::new(create_read_tool()));
+            registry.register(Arc::new(create_list_tool()));
+            registry.register(Arc::new(create_search_tool()));
+        }
+        if task.edits() {
+            registry.register(Arc::new(create_edit_tool()));
+        }
+
+        // Configure the agent
+        let config = AgentRuntimeConfig {
+            model,
+            max_tokens: 4096,
+            thinking_budget: 1024,  // Minimum required
+            max_retries: 3,
+            compaction_thinking_budget: 2000,
+        };
+
+        // Create the agent with the dynamically built system prompt
+        let system_prompt = build_system_prompt(task, context);
+        let model_id = provider.model_id(&config.model);
+        let agent = provider.agent(config, &system_prompt, registry)?;
+
+        let provider_name = format!("{:?}", options.provider).to_lowercase();
+        let journal = Some(journal::path_for(&file_path))
+            .filter(|path| task.edits() && !encrypted && (options.journal || path.exists()));
+
+        let mut transcript = options.log_json.as_deref().map(Transcript::open).transpose()?;
+        if let Some(transcript) = &mut transcript {
+            transcript.record("start", json!({
+                "task": task.role(),
+                "file": file_path,
+                "provider": provider_name,
+                "model": model_id,
+                "apply": format!("{:?}", apply).to_lowercase(),
+            }));
+        }
+
+        Ok(Session {
+            agent,
+            draft,
+            task,
+            verbose,
+            limits: options.limits,
+            price,
+            requests: 0,
+            turns: 0,
+            tool_call_count: 0,
+            cost: 0.0,
+            context_tokens: estimate_tokens(&system_prompt) as u64,
+            repair_rounds: 0,
+            start_time,
+            transcript,
+            provider: provider_name,
+            model_id,
+            journal,
+            unjournaled: Vec::new(),
+            checkpoint: None,
+            resume_note: None,
+            reply: String::new(),
+        })
+    }
+
+    /// Record an event in the `--log-json` transcript, if there is one
+    fn log(&mut self, event: &str, fields: serde_json::Value) {
+        if let Some(transcript) = &mut self.transcript {
+            transcript.record(event, fields);
+        }
+    }
+
+    /// Send the user's text and run the agent until it finishes its reply
+    async fn send(&mut self, text: &str) -> std::result::Result<(), Interrupted> {
+        let verbose = self.verbose;
+        if verbose {
+            eprintln!("[config] Fact: {}", text);
+            eprintln!("[start] Beginning agent execution...");
+        }
+
+        // `ask --matching` reads only the claims the question matches
+        let query = matches!(self.task, Task::Ask { matching: true }).then_some(text);
+
+        // Format the user message
+        let mut user_message = self.task.user_message(&self.draft.path, text);
+        if self.requests > 0 && query.is_none() {
+            user_message.push_str(
+                "\n\nThe file has not changed since you last read it, except by your own edits.",
+            );
+        }
+        if let Some(note) = self.resume_note.take() {
+            user_message.push_str("\n\n");
+            user_message.push_str(&note);
+        }
+        self.requests += 1;
+        self.reply.clear();
+        self.log("request", json!({ "text": text }));
+        tracing::debug!(task = self.task.command(), request = self.requests, "sending request");
+
+        // Usage is reported only when the reply finishes, so until then each
+        // round of tool calls is priced from the text passing through the loop
+        let mut context = self.context_tokens + estimate_tokens(&user_message) as u64;
+        let mut estimated_cost = 0.0;
+        let mut response_text = String::new();
+
+        // Send the request
+        self.agent.send_request(&user_message, RequestMode::Normal);
+        let mut response_start = std::time::Instant::now();
+
+        // Process the agent loop
+        let mut thinking_started = false;
+        self.repair_rounds = 0;
+        let first_turn = self.turns;
+
+        while let Some(step) = self.agent.next().await {
+            match step {
+                AgentStep::TextDelta(text) => {
+                    // Answers are the output of `ask` and `chat`; other tasks only narrate
+                    if verbose || self.task.replies() {
+                        print!("{}", text);
+                    }
+                    self.reply.push_str(&text);
+                    response_text.push_str(&text);
                 }
-            }
-            AgentStep::ThinkingDelta(thinking) => {
-                if verbose {
-                    if !thinking_started {
-                        thinking_started = true;
-                        eprint!("\n[thinking] ");
+                AgentStep::ThinkingDelta(thinking) => {
+                    response_text.push_str(&thinking);
+                    if verbose {
+                        if !thinking_started {
+                            thinking_started = true;
+                            eprint!("\n[thinking] ");
+                        }
+                        eprint!("{}", thinking);
                     }
-                    eprint!("{}", thinking);
                 }
-            }
-            AgentStep::CompactionDelta(_) => {
-                // Not used in our simple case
-            }
-            AgentStep::ToolRequest(tool_calls) => {
-                if verbose && thinking_started {
-                    eprintln!();  // End thinking block
-                    thinking_started = false;
+                AgentStep::CompactionDelta(_) => {
+                    // Not used in our simple case
                 }
+                AgentStep::ToolRequest(tool_calls) => {
+                    tracing::debug!(
+                        turn = self.turns + 1,
+                        tool_calls = tool_calls.len(),
+                        elapsed_ms = response_start.elapsed().as_millis() as u64,
+                        "model response"
+                    );
+                    if verbose && thinking_started {
+                        eprintln!();  // End thinking block
+                        thinking_started = false;
+                    }
 
-                for call in tool_calls {
-                    tool_call_count += 1;
-                    let tool_start = std::time::Instant::now();
+                    // Tool results always go back for another response
+                    self.turns += 1;
+                    if let Some(max) = self.limits.max_turns
+                        && self.turns >= max
+                    {
+                        return Err(self.interrupt(Interrupted::Budget(format!("--max-turns {}", max))));
+                    }
 
-                    if verbose {
-                        // Format params nicely for readability
-                        let params_str = if call.params.is_object() {
-                            serde_json::to_string_pretty(&call.params).unwrap_or_else(|_| format!("{:?}", call.params))
-                        } else {
-                            format!("{:?}", call.params)
-                        };
-                        eprintln!("\n[tool:{}] {}", tool_call_count, call.name);
-                        eprintln!("[params] {}", params_str);
+                    // The response re-read the whole context and wrote its text and calls
+                    let output = estimate_tokens(&response_text) as u64
+                        + tool_calls.iter().map(|call| estimate_tokens(&call.params.to_string()) as u64).sum::<u64>();
+                    response_text.clear();
+                    if let Some(price) = self.price {
+                        estimated_cost += price.cost(context, output);
+                    }
+                    context += output;
+                    if let Some(max) = self.limits.max_cost
+                        && self.cost + estimated_cost > max
+                    {
+                        let limit = format!("--max-cost {} (spent ~${:.4})", max, self.cost + estimated_cost);
+                        return Err(self.interrupt(Interrupted::Budget(limit)));
                     }
 
-                    let result = handle_tool_call(&file_path, &call.name, &call.params);
+                    for call in tool_calls {
+                        if let Some(max) = self.limits.max_tool_calls
+                            && self.tool_call_count >= max
+                        {
+                            return Err(self.interrupt(Interrupted::Budget(format!("--max-tool-calls {}", max))));
+                        }
+                        self.tool_call_count += 1;
+                        let tool_start = std::time::Instant::now();
 
-                    if verbose {
+                        if verbose {
+                            // Format params nicely for readability
+                            let params_str = if call.params.is_object() {
+                                serde_json::to_string_pretty(&call.params).unwrap_or_else(|_| format!("{:?}", call.params))
+                            } else {
+                                format!("{:?}", call.params)
+                            };
+                            eprintln!("\n[tool:{}] {}", self.tool_call_count, call.name);
+                            eprintln!("[params] {}", params_str);
+                        }
+
+                        let before = (self.transcript.is_some() && call.name == "edit_worldview")
+                            .then(|| self.draft.content.clone());
+                        let result = tracing::debug_span!("tool_call", name = %call.name, n = self.tool_call_count)
+                            .in_scope(|| handle_tool_call(&mut self.draft, self.task, query, &mut self.repair_rounds, &call.name, &call.params));
                         let tool_elapsed = tool_start.elapsed();
-                        eprintln!("[result:{}ms] {}", tool_elapsed.as_millis(), result);
+
+                        if verbose {
+                            eprintln!("[result:{}ms] {}", tool_elapsed.as_millis(), result);
+                        }
+                        context += estimate_tokens(&result) as u64;
+                        if let Some(checkpoint) = &mut self.checkpoint {
+                            checkpoint.record(&call.name, &call.params, &result, &self.draft.content);
+                        }
+                        if self.transcript.is_some() {
+                            let outcome = before.map(|before| edit_outcome(&before, &self.draft.content, &result));
+                            self.log("tool_call", json!({
+                                "name": call.name,
+                                "params": call.params,
+                                "result": result,
+                                "outcome": outcome,
+                                "duration_ms": tool_elapsed.as_millis() as u64,
+                            }));
+                        }
+
+                        self.agent.submit_tool_result(&call.call_id, result);
+                    }
+                    response_start = std::time::Instant::now();
+                }
+                AgentStep::Retrying { attempt, error } => {
+                    tracing::debug!(attempt, %error, "retrying");
+                    if verbose {
+                        eprintln!("[retry] Attempt {} after error: {}", attempt, error);
                     }
+                }
+                AgentStep::Finished { usage } => {
+                    self.turns += 1;
+                    tracing::debug!(
+                        turn = self.turns,
+                        output_tokens = usage.output_tokens,
+                        context_tokens = usage.context_tokens,
+                        elapsed_ms = response_start.elapsed().as_millis() as u64,
+                        "model response"
+                    );
+                    // Every response re-reads the context, which only grows, so
+                    // billing the final context once per response bounds the input
+                    let input_tokens = u64::from(usage.context_tokens) * (self.turns - first_turn) as u64;
+                    let cost = self.price.map(|price| price.cost(input_tokens, usage.output_tokens.into()));
+                    self.cost += cost.unwrap_or(0.0);
+                    self.context_tokens = usage.context_tokens.into();
 
-                    agent.submit_tool_result(&call.call_id, result);
+                    let total_elapsed = self.start_time.elapsed();
+                    if verbose {
+                        let cost = cost.map_or_else(|| "unknown".to_string(), |c| format!("~${:.4}", c));
+                        eprintln!("\n[done] Output: {}, Context: {}, Cost: {}",
+                            usage.output_tokens, usage.context_tokens, cost);
+                        eprintln!("[timing] Total: {}ms, Tool calls: {}",
+                            total_elapsed.as_millis(), self.tool_call_count);
+                    }
+                    if self.repair_rounds > 0 {
+                        eprintln!("[repair] {}", self.repair_summary());
+                    }
+                    self.log("finished", json!({
+                        "output_tokens": usage.output_tokens,
+                        "context_tokens": usage.context_tokens,
+                        "turns": self.turns - first_turn,
+                        "repair_rounds": self.repair_rounds,
+                        "cost": cost,
+                    }));
+                    if let Some(max) = self.limits.max_cost
+                        && self.cost > max
+                    {
+                        let limit = format!("--max-cost {} (spent ~${:.4})", max, self.cost);
+                        return Err(self.interrupt(Interrupted::Budget(limit)));
+                    }
+                    break;
                 }
-            }
-            AgentStep::Retrying { attempt, error } => {
-                if verbose {
-                    eprintln!("[retry] Attempt {} after error: {}", attempt, error);
+                AgentStep::Error(e) => {
+                    let total_elapsed = self.start_time.elapsed();
+                    if verbose {
+                        eprintln!("[error:{}ms] {}", total_elapsed.as_millis(), e);
+                    }
+                    return Err(self.interrupt(Interrupted::Error(e)));
                 }
             }
-            AgentStep::Finished { usage } => {
-                let total_elapsed = start_time.elapsed();
-                if verbose {
-                    eprintln!("\n[done] Output: {}, Context: {}",
-                        usage.output_tokens, usage.context_tokens);
-                    eprintln!("[timing] Total: {}ms, Tool calls: {}",
-                        total_elapsed.as_millis(), tool_call_count);
-                }
-                break;
+        }
+        Ok(())
+    }
+
+    /// Note the claims `request` changed since `before`, for the journal once they are written
+    fn journal_request(&mut self, request: &str, before: &str) {
+        if self.journal.is_none() {
+            return;
+        }
+        let entry = Entry::new(self.task.command(), &self.provider, &self.model_id, request, before, &self.draft.content);
+        self.unjournaled.extend(entry);
+    }
+
+    /// Append the noted requests to the journal, now that their edits are written
+    fn write_journal(&mut self) {
+        let entries = std::mem::take(&mut self.unjournaled);
+        if let Some(path) = &self.journal
+            && let Err(e) = journal::append(path, &entries)
+        {
+            // Like the transcript, a journal that cannot be written should not fail the edit
+            eprintln!("warning: could not write journal: {:#}", e);
+        }
+    }
+
+    /// Record why the agent stopped, passing the reason on
+    fn interrupt(&mut self, reason: Interrupted) -> Interrupted {
+        let event = match reason {
+            Interrupted::Error(_) => "error",
+            Interrupted::Budget(_) => "budget_exceeded",
+        };
+        self.log(event, json!({ "message": reason.to_string() }));
+        reason
+    }
+
+    /// How many rounds of validation errors the latest request went through
+    fn repair_summary(&self) -> String {
+        let rounds = self.repair_rounds;
+        let plural = if rounds == 1 { "" } else { "s" };
+        if rounds >= MAX_REPAIR_ROUNDS {
+            format!("gave up after {} failed edit{}", rounds, plural)
+        } else {
+            format!("{} repair round{} after validation errors", rounds, plural)
+        }
+    }
+
+    /// Review changes still awaiting approval
+    fn finish(mut self, apply: Apply) -> Result<()> {
+        let written = if !self.task.edits() {
+            println!();
+            false
+        } else if apply != Apply::Immediately {
+            review(&self.draft, apply)?
+        } else if !self.draft.write_through && self.draft.is_changed() {
+            self.draft.save()?;
+            true
+        } else {
+            self.draft.is_changed()
+        };
+        if written {
+            self.write_journal();
+        }
+        self.log_end(written);
+        Ok(())
+    }
+
+    /// Record the session's net changes and whether they reached the file
+    fn log_end(&mut self, written: bool) {
+        if self.transcript.is_some() {
+            let changes = transcript::changes(&self.draft.original, &self.draft.content);
+            self.log("end", json!({
+                "changes": changes,
+                "written": written,
+                "turns": self.turns,
+                "tool_calls": self.tool_call_count,
+                "cost": self.price.map(|_| self.cost),
+                "duration_ms": self.start_time.elapsed().as_millis() as u64,
+            }));
+        }
+    }
+}
+
+pub async fn run(task: Task, fact: String, file: PathBuf, options: &Options, apply: Apply) -> Result<()> {
+    let mut session = Session::start(task, file, options, apply)?;
+    // A checkpoint would hold an encrypted file's contents in plaintext
+    if task.edits() && !encryption::is_encrypted(&session.draft.path) {
+        session.checkpoint = Some(Checkpoint::new(task, &fact, &session.draft, options, apply));
+    }
+    complete(session, &fact, apply).await
+}
+
+/// Run a task whose output is the agent's reply, returning the reply
+pub async fn run_reply(task: Task, request: String, file: PathBuf, options: &Options) -> Result<String> {
+    run_reply_in_context(task, request, file, options, "").await
+}
+
+/// Like `run_reply`, with `context` added to the agent's system prompt
+pub async fn run_reply_in_context(task: Task, request: String, file: PathBuf, options: &Options, context: &str) -> Result<String> {
+    let mut session = Session::start_in_context(task, file, options, Apply::Immediately, context)?;
+    if let Err(e) = session.send(&request).await {
+        session.log_end(false);
+        anyhow::bail!("{}", e);
+    }
+    let reply = std::mem::take(&mut session.reply);
+    session.finish(Apply::Immediately)?;
+    Ok(reply)
+}
+
+/// Continue an interrupted run from its checkpoint
+pub async fn resume(checkpoint: Checkpoint) -> Result<()> {
+    let apply = checkpoint.apply;
+    let mut session = Session::start(checkpoint.task, checkpoint.file.clone(), &checkpoint.options, apply)?;
+    session.draft.original = checkpoint.original.clone();
+    if session.draft.content != checkpoint.content {
+        session.draft.update(checkpoint.content.clone())?;
+    }
+    session.resume_note = Some(checkpoint.note());
+    let request = checkpoint.request.clone();
+    session.checkpoint = Some(checkpoint);
+    complete(session, &request, apply).await
+}
+
+/// Run a single request to the end, leaving a checkpoint behind if it is interrupted
+async fn complete(mut session: Session, request: &str, apply: Apply) -> Result<()> {
+    if let Some(checkpoint) = &session.checkpoint {
+        checkpoint.exit_on_interrupt();
+    }
+    match session.send(request).await {
+        Ok(()) => {}
+        Err(e @ Interrupted::Budget(_)) => {
+            // Nothing was written, so there is nothing to resume
+            if let Some(checkpoint) = &session.checkpoint {
+                checkpoint.remove();
             }
-            AgentStep::Error(e) => {
-                let total_elapsed = start_time.elapsed();
-                if verbose {
-                    eprintln!("[error:{}ms] {}", total_elapsed.as_millis(), e);
-                }
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+            session.log_end(false);
+            anyhow::bail!("{} - {} not modified", e, session.draft.path.display())
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            if let Some(checkpoint) = session.checkpoint.as_ref().filter(|c| c.is_saved()) {
+                eprintln!("Continue with `worldview resume {}`", checkpoint.id);
             }
+            std::process::exit(1);
         }
     }
+    if let Some(checkpoint) = session.checkpoint.take() {
+        checkpoint.remove();
+    }
+    // A resumed run's original is from before it was interrupted, so this covers both parts
+    let original = session.draft.original.clone();
+    session.journal_request(request, &original);
+    session.finish(apply)?;
 
     // Exit 0 for success (including correct rejections)
     Ok(())
 }
+
+/// Add many facts in one agent session, reporting what each one changed
+///
+/// Edits are validated and applied fact by fact as usual; a fact the agent
+/// fails on is reported and the rest still run. A fact the agent turns down,
+/// as ephemeral or as repeating a claim, is reported as skipped with its reason.
+pub async fn run_batch(task: Task, facts: Vec<String>, file: PathBuf, options: &Options, apply: Apply) -> Result<()> {
+    let mut session = Session::start(task, file, options, apply)?;
+    let total = facts.len();
+    let (mut skipped, mut failed) = (0, 0);
+    for (i, fact) in facts.iter().enumerate() {
+        let content = session.draft.content.clone();
+        let before = Document::parse(&content);
+        let status = match session.send(fact).await {
+            Ok(()) if session.draft.content == content => {
+                skipped += 1;
+                format!("skipped: {}", skip_reason(&session.reply))
+            }
+            Ok(()) => {
+                session.journal_request(fact, &content);
+                let summary = crate::log::summarize(&before.diff(&Document::parse(&session.draft.content)));
+                match session.repair_rounds {
+                    0 => summary,
+                    _ => format!("{} ({})", summary, session.repair_summary()),
+                }
+            }
+            Err(e @ Interrupted::Budget(_)) => {
+                session.log_end(false);
+                anyhow::bail!("{} at fact {} of {} - {} not modified", e, i + 1, total, session.draft.path.display())
+            }
+            Err(e) => {
+                failed += 1;
+                format!("error: {}", e)
+            }
+        };
+        // A fact may span lines (an imported highlight and its note); label it by the first
+        let label = fact.lines().next().unwrap_or_default();
+        println!("[{}/{}] {}: {}", i + 1, total, label, status);
+    }
+    session.finish(apply)?;
+    println!("{} added, {} skipped, {} failed", total - skipped - failed, skipped, failed);
+    if failed > 0 {
+        anyhow::bail!("{} of {} facts failed", failed, total);
+    }
+    Ok(())
+}
+
+/// The first sentence of the agent's reply to a fact it did not add
+fn skip_reason(reply: &str) -> String {
+    let reply = reply.split_whitespace().collect::<Vec<_>>().join(" ");
+    let end = reply.find(". ").map_or(reply.len(), |i| i + 1);
+    match &reply[..end] {
+        "" => "no change".to_string(),
+        reason => reason.to_string(),
+    }
+}
+
+/// Talk with one agent session: each line is a fact to record or a question to
+/// ask, and the edits from each turn are shown and confirmed before saving
+pub async fn run_chat(file: PathBuf, options: &Options) -> Result<()> {
+    let mut session = Session::start(Task::Chat, file, options, Apply::Confirm)?;
+    println!(
+        "{}: state a belief to record or ask a question (`exit` or Ctrl-D to quit)",
+        session.draft.path.display()
+    );
+
+    let mut rejected = false;
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "exit" | "quit" => break,
+            _ => {}
+        }
+
+        let mut message = line.to_string();
+        if rejected {
+            message.push_str("\n\n(I rejected your previous edits; the file is back to how it was before them.)");
+        }
+        match session.send(&message).await {
+            Ok(()) => {}
+            Err(e @ Interrupted::Budget(_)) => {
+                session.draft.discard();
+                session.log_end(false);
+                anyhow::bail!("{} - ending the session", e);
+            }
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        println!();
+
+        rejected = false;
+        if session.draft.is_changed() {
+            let original = session.draft.original.clone();
+            session.journal_request(line, &original);
+            let draft = &mut session.draft;
+            draft.print_diff();
+            draft.print_conflicts();
+            let changes = transcript::changes(&draft.original, &draft.content);
+            let accepted = draft::confirm("Apply these changes?")?;
+            if accepted {
+                draft.accept()?;
+                println!("{}: updated", draft.path.display());
+                session.write_journal();
+            } else {
+                draft.discard();
+                session.unjournaled.clear();
+                rejected = true;
+                println!("{}: changes discarded", draft.path.display());
+            }
+            session.log("review", json!({ "changes": changes, "accepted": accepted }));
+        }
+    }
+    // Accepted edits are saved as they go, so the draft holds nothing unsaved
+    session.log_end(false);
+    Ok(())
+}
+
+/// Facts for `--batch`: a JSON array of strings, or one fact per non-empty line
+pub fn parse_batch(content: &str) -> Result<Vec<String>> {
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(content)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON array of facts: {}", e));
+    }
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Check that `target` names a facet of `file` before asking the agent to split it
+pub fn check_split_target(file: &std::path::Path, target: &str) -> Result<()> {
+    let content = encryption::read(file)?;
+    let doc = Document::parse(&content);
+    let Some((concept, facet)) = target.split_once('.') else {
+        anyhow::bail!("{} is not a facet (expected Concept.facet)", target);
+    };
+    let Some(facet) = doc.concept(concept).and_then(|c| c.facet(facet)) else {
+        anyhow::bail!("{}: no facet {}", file.display(), target);
+    };
+    eprintln!("{}: {} claims", target, count_claims(facet));
+    Ok(())
+}
+
+/// Append a claim through the document model, without an agent
+pub fn run_direct(file: PathBuf, concept: &str, facet: &str, claim: &str, apply: Apply) -> Result<()> {
+    let mut draft = Draft::open(file, false)?;
+    let mut doc = Document::parse(&draft.content);
+    doc.add_claim(concept, facet, Claim::parse(claim))?;
+    let content = doc.to_string();
+
+    let options = ValidationOptions {
+        nested_facets: doc.has_nested_facets(),
+        ..ValidationOptions::default()
+    };
+    let validation = worldview_validator::validate_in_context(&content, &draft.path, &options);
+    if !validation.is_valid() {
+        anyhow::bail!(
+            "Validation failed - {} not modified:\n{}",
+            draft.path.display(),
+            validation
+        );
+    }
+
+    draft.content = content;
+    match apply {
+        Apply::Immediately => {
+            draft.save()?;
+            println!("{}: added claim to {}.{}", draft.path.display(), concept, facet);
+        }
+        _ => {
+            review(&draft, apply)?;
+        }
+    }
+    Ok(())
+}
+
+/// Show the agent's pending changes, then write them if approved
+///
+/// Returns whether the file was written.
+fn review(draft: &Draft, apply: Apply) -> Result<bool> {
+    if !draft.is_changed() {
+        println!("{}: no changes proposed", draft.path.display());
+        return Ok(false);
+    }
+    draft.print_diff();
+    draft.print_conflicts();
+    match apply {
+        Apply::DryRun => println!("{}: dry run, not modified", draft.path.display()),
+        _ if draft::confirm("Apply these changes?")? => {
+            draft.save()?;
+            println!("{}: updated", draft.path.display());
+            return Ok(true);
+        }
+        _ => println!("{}: changes discarded", draft.path.display()),
+    }
+    Ok(false)
+}
@@ -0,0 +1,78 @@
+//! Apply subcommand - answer a question in light of the worldview
+//!
+//! The worldview goes into the model's system prompt as the `prompt` export
+//! renders it (compressed to `--budget`, limited to `--concepts`, without
+//! `--redact` levels), and the answer cites the `[Concept.facet]` claims that
+//! informed it. Citations of facets the prompt did not include are reported.
+
+use anyhow::{Result, bail};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+use crate::add::{self, Options, Task};
+use crate::encryption;
+use crate::export::prompt;
+
+pub async fn run(
+    question: String,
+    file: PathBuf,
+    budget: Option<usize>,
+    concepts: Vec<String>,
+    redact: Vec<String>,
+    options: &Options,
+) -> Result<()> {
+    let content = encryption::read(&file)?;
+    let mut doc = Document::parse(&content);
+    if !redact.is_empty() {
+        let levels: Vec<&str> = redact.iter().map(String::as_str).collect();
+        doc = doc.redacted(&levels);
+    }
+    if let Some(name) = concepts.iter().find(|name| doc.concept(name).is_none()) {
+        bail!("{}: no concept {}", file.display(), name);
+    }
+    if doc.concepts.is_empty() {
+        bail!("{}: no claims to apply", file.display());
+    }
+
+    let prompt = prompt::render(&doc, &concepts, budget);
+    if prompt.omitted > 0 {
+        eprintln!(
+            "{}: omitted {} claims to fit {} tokens",
+            file.display(),
+            prompt.omitted,
+            budget.unwrap_or_default()
+        );
+    }
+    let answer =
+        add::run_reply_in_context(Task::Apply, question, file, options, &prompt.text).await?;
+
+    let unknown: Vec<&str> = citations(&answer)
+        .into_iter()
+        .filter(|target| prompt.doc.resolve_target(target).is_none())
+        .collect();
+    if !unknown.is_empty() {
+        eprintln!(
+            "Note: the answer cites facets that are not in the worldview: {}",
+            unknown.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// `[Concept.facet]` citations in `text`, each once, in order
+fn citations(text: &str) -> Vec<&str> {
+    let mut found = Vec::new();
+    for (start, _) in text.match_indices('[') {
+        let Some(len) = text[start + 1..].find(']') else {
+            continue;
+        };
+        let target = &text[start + 1..start + 1 + len];
+        let cited = target.contains('.')
+            && !target.starts_with("<=")
+            && !target.contains(char::is_whitespace);
+        if cited && !found.contains(&target) {
+            found.push(target);
+        }
+    }
+    found
+}
@@ -0,0 +1,117 @@
+//! Audit subcommand - reports likely contradictions between related claims
+//!
+//! The validator only flags operator conflicts (`a => b` against `a vs b`).
+//! `--contradictions` also has the agent compare related claims for meaning:
+//! the claims under each concept, and each claim with the facet it references.
+//! The agent is not given the edit tool, so the file is never modified.
+
+use anyhow::Result;
+use std::path::PathBuf;
+use worldview_validator::{Claim, Document, Facet, ValidationError};
+
+use crate::add::{self, Apply, Options, Task};
+use crate::encryption;
+use crate::show::find_facet;
+
+/// Claims the agent compares with each other
+struct Group<'d> {
+    label: String,
+    /// Claims with their `Concept.facet` target, in document order
+    claims: Vec<(String, &'d Claim)>,
+}
+
+pub async fn run(file: PathBuf, options: &Options) -> Result<()> {
+    let content = encryption::read(&file)?;
+    let doc = Document::parse(&content);
+
+    let conflicts: Vec<ValidationError> = worldview_validator::validate(&content)
+        .warnings
+        .into_iter()
+        .filter(|w| matches!(w, ValidationError::ContradictoryRelation { .. }))
+        .collect();
+    if !conflicts.is_empty() {
+        println!("Operator conflicts (from validation):");
+        for conflict in &conflicts {
+            println!("  {}", conflict);
+        }
+        println!();
+    }
+
+    let groups = related_groups(&doc);
+    if groups.is_empty() {
+        println!("{}: no related claims to compare", file.display());
+        return Ok(());
+    }
+    add::run(Task::Audit, request(&groups), file, options, Apply::Immediately).await
+}
+
+/// Claims under each concept, then each claim with the facet it references in
+/// another concept
+fn related_groups(doc: &Document) -> Vec<Group<'_>> {
+    let mut groups = Vec::new();
+    for concept in &doc.concepts {
+        let mut claims = Vec::new();
+        collect(&concept.facets, &concept.name, &mut claims);
+        if claims.len() > 1 {
+            groups.push(Group {
+                label: format!("Concept {}", concept.name),
+                claims,
+            });
+        }
+    }
+
+    let mut all = Vec::new();
+    for concept in &doc.concepts {
+        collect(&concept.facets, &concept.name, &mut all);
+    }
+    for (target, claim) in &all {
+        for reference in claim.data.parsed_references() {
+            let referenced = format!("{}.{}", reference.concept, reference.facet);
+            // Namespaced references point into other files, and claims in
+            // the same concept are already compared
+            if reference.namespace.is_some() || target.split('.').next() == Some(&reference.concept) {
+                continue;
+            }
+            let Some(facet) = find_facet(doc, &referenced) else {
+                continue;
+            };
+            let mut claims = vec![(target.clone(), *claim)];
+            collect(std::slice::from_ref(facet), &reference.concept, &mut claims);
+            if claims.len() > 1 {
+                groups.push(Group {
+                    label: format!("line {} ({}) references {}", claim.line, target, referenced),
+                    claims,
+                });
+            }
+        }
+    }
+    groups
+}
+
+fn collect<'d>(facets: &'d [Facet], parent: &str, out: &mut Vec<(String, &'d Claim)>) {
+    for facet in facets {
+        let target = format!("{}.{}", parent, facet.name);
+        out.extend(facet.claims.iter().map(|claim| (target.clone(), claim)));
+        collect(&facet.sub_facets, &target, out);
+    }
+}
+
+/// The groups as the agent reads them, one claim per line with its line number
+fn request(groups: &[Group<'_>]) -> String {
+    let mut text = String::new();
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        text.push_str(&format!("{}:\n", group.label));
+        for (target, claim) in &group.claims {
+            text.push_str(&format!(
+                "  line {} ({}): {}\n",
+                claim.line,
+                target,
+                claim.data.to_wvf_string()
+            ));
+        }
+    }
+    text
+}
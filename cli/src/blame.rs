@@ -0,0 +1,60 @@
+//! Blame subcommand - shows which agent request introduced each claim under a concept or facet
+//!
+//! Claims are matched against the journal (see `journal`) by facet and text,
+//! so a claim edited by hand since shows as not journaled.
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+use crate::journal;
+
+pub fn run(target: String, file: PathBuf) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let journal_path = journal::path_for(&file);
+    if !journal_path.exists() {
+        anyhow::bail!(
+            "{}: no journal; agent edits are journaled with --journal",
+            file.display()
+        );
+    }
+    let entries = journal::read(&journal_path)?;
+
+    let claims: Vec<_> = journal::claims(&content)
+        .into_iter()
+        .filter(|claim| {
+            claim
+                .target
+                .strip_prefix(target.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+        .collect();
+    if claims.is_empty() {
+        println!("{}: no claims under {}", file.display(), target);
+        return Ok(());
+    }
+
+    for group in claims.chunk_by(|a, b| a.target == b.target) {
+        println!("{}", group[0].target);
+        for claim in group {
+            println!("  - {}", claim.claim);
+            // The latest request that added this claim is the one that wrote it as it stands
+            match entries
+                .iter()
+                .rev()
+                .find(|entry| entry.added.contains(claim))
+            {
+                Some(entry) => println!(
+                    "      {} {} ({} {}): {:?}",
+                    crate::log::civil_date((entry.time_ms / 1000) as i64),
+                    entry.command,
+                    entry.provider,
+                    entry.model,
+                    entry.request
+                ),
+                None => println!("      not journaled"),
+            }
+        }
+    }
+    Ok(())
+}
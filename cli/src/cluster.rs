@@ -0,0 +1,100 @@
+//! Cluster subcommand - groups of similar claims spread across facets
+//!
+//! Similar claims in different facets are candidates to merge into one claim
+//! or to link with a `&Concept.facet` reference. Wording is compared by
+//! default; `--semantic` compares meaning with the embeddings index.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use worldview_validator::{Cluster, Document};
+
+#[cfg(feature = "embeddings")]
+use std::collections::HashMap;
+#[cfg(feature = "embeddings")]
+use worldview_validator::Claim;
+
+/// Default `--threshold` for word overlap (TF-IDF cosine)
+const LEXICAL_THRESHOLD: f64 = 0.4;
+
+/// Default `--threshold` for embedding cosine similarity
+const SEMANTIC_THRESHOLD: f64 = 0.85;
+
+pub fn run(file: PathBuf, threshold: Option<f64>, semantic: bool) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+
+    let clusters = if semantic {
+        semantic_clusters(&doc, &file, threshold.unwrap_or(SEMANTIC_THRESHOLD))?
+    } else {
+        doc.clusters(threshold.unwrap_or(LEXICAL_THRESHOLD))
+    };
+    if clusters.is_empty() {
+        println!("{}: no similar claims across facets", file.display());
+        return Ok(());
+    }
+
+    for (i, cluster) in clusters.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!(
+            "{} ({:.2})",
+            cluster.targets().join(", "),
+            cluster.similarity
+        );
+        let width = cluster
+            .members
+            .iter()
+            .map(|m| m.claim.chars().count())
+            .max()
+            .unwrap_or(0);
+        for member in &cluster.members {
+            println!(
+                "  - {:<width$}  {} (line {})",
+                member.claim,
+                member.target,
+                member.line,
+                width = width
+            );
+        }
+        println!("  {}", suggestion(cluster));
+    }
+    Ok(())
+}
+
+/// What to do about a cluster
+fn suggestion(cluster: &Cluster) -> String {
+    let references: Vec<String> = cluster
+        .targets()
+        .iter()
+        .map(|target| format!("&{}", target))
+        .collect();
+    format!(
+        "consolidate into one claim, or cross-reference with {}",
+        references.join(" ")
+    )
+}
+
+#[cfg(feature = "embeddings")]
+fn semantic_clusters(doc: &Document, file: &Path, threshold: f64) -> Result<Vec<Cluster>> {
+    use crate::embeddings;
+
+    let vectors = embeddings::vectors(file)?;
+    // Every pair is compared, so look up each claim's vector once
+    let mut by_line: HashMap<usize, Option<&Vec<f32>>> = HashMap::new();
+    Ok(doc.clusters_by(threshold, |a, b| {
+        let mut vector = |claim: &Claim| {
+            *by_line
+                .entry(claim.line)
+                .or_insert_with(|| vectors.get(&claim.data.stable_id()))
+        };
+        let (a, b) = (vector(a)?, vector(b)?);
+        Some(f64::from(embeddings::cosine(a, b)))
+    }))
+}
+
+#[cfg(not(feature = "embeddings"))]
+fn semantic_clusters(_doc: &Document, _file: &Path, _threshold: f64) -> Result<Vec<Cluster>> {
+    anyhow::bail!("--semantic requires worldview to be built with the `embeddings` feature")
+}
@@ -0,0 +1,286 @@
+//! Defaults for command-line options, from config files
+//!
+//! `~/.config/worldview/config.toml` (under `$XDG_CONFIG_HOME` when set) holds
+//! personal defaults, and the nearest `.worldview.toml` in the current
+//! directory or an ancestor holds a project's, taking precedence key by key.
+//! Flags given on the command line override both:
+//!
+//! ```toml
+//! file = "~/notes/worldview.wvf"
+//! provider = "anthropic"
+//! model = "sonnet"
+//!
+//! [lint]
+//! profile = "strict"
+//! nested-facets = true
+//! require-source = ["Science"]
+//! spec-version = "1.0"
+//!
+//! [fmt]
+//! tabs-as = 4
+//!
+//! [notion]
+//! database = "1f3e7a0c9b2d4e5f8a6b7c8d9e0f1a2b"
+//! properties = { concept = "Topic", claim = "Belief" }
+//! ```
+//!
+//! A relative `file` is relative to the directory of the config that sets it.
+//!
+//! Named profiles, selected with `worldview --profile NAME`, override any of
+//! these keys, so one config can serve several worldviews:
+//!
+//! ```toml
+//! [profiles.work]
+//! file = "~/work/worldview.wvf"
+//! model = "opus"
+//! ```
+
+use anyhow::{Context, Result};
+use clap::Command;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const PROJECT_FILE: &str = ".worldview.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Worldview file for commands given no `--file`
+    pub file: Option<PathBuf>,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    pub lint: Lint,
+    pub fmt: Fmt,
+    pub notion: Notion,
+    /// Named sets of overrides for `--profile`
+    pub profiles: BTreeMap<String, Config>,
+}
+
+/// Defaults for `validate`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Lint {
+    pub profile: Option<String>,
+    pub nested_facets: Option<bool>,
+    pub strict_whitespace: Option<bool>,
+    pub require_source: Option<Vec<String>>,
+    pub spec_version: Option<String>,
+}
+
+/// Defaults for `fmt`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Fmt {
+    pub tabs_as: Option<usize>,
+}
+
+/// Defaults for `import --from notion`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Notion {
+    pub database: Option<String>,
+    /// Property to read each field from, by field
+    pub properties: Option<BTreeMap<String, String>>,
+}
+
+impl Config {
+    /// The user's config overlaid with the project's
+    pub fn load() -> Result<Config> {
+        let mut config = Config::default();
+        if let Some(path) = user_path()
+            && path.exists()
+        {
+            config = Config::read(&path)?;
+        }
+        if let Some(path) = project_path(&std::env::current_dir()?) {
+            config.overlay(Config::read(&path)?);
+        }
+        Ok(config)
+    }
+
+    /// Parse a config file, resolving its `file` against the file's directory
+    pub fn read(path: &Path) -> Result<Config> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading {}", path.display()))?;
+        let mut config: Config = toml::from_str(&text)
+            .with_context(|| format!("Invalid config file {}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        config.resolve_file(dir);
+        for (name, profile) in &mut config.profiles {
+            if !profile.profiles.is_empty() {
+                anyhow::bail!(
+                    "Invalid config file {}: profile {} defines profiles",
+                    path.display(),
+                    name
+                );
+            }
+            profile.resolve_file(dir);
+        }
+        Ok(config)
+    }
+
+    fn resolve_file(&mut self, dir: &Path) {
+        if let Some(file) = self.file.take() {
+            self.file = Some(resolve(&file, dir));
+        }
+    }
+
+    /// These settings overridden by the named profile's
+    pub fn select(&self, profile: Option<&str>) -> Result<Config> {
+        let mut config = self.clone();
+        if let Some(name) = profile {
+            let Some(profile) = self.profiles.get(name) else {
+                anyhow::bail!("No profile named {} (see `worldview profiles list`)", name);
+            };
+            config.overlay(profile.clone());
+        }
+        Ok(config)
+    }
+
+    /// Take every key `other` sets
+    fn overlay(&mut self, other: Config) {
+        fn set<T>(value: &mut Option<T>, other: Option<T>) {
+            if other.is_some() {
+                *value = other;
+            }
+        }
+        set(&mut self.file, other.file);
+        set(&mut self.provider, other.provider);
+        set(&mut self.model, other.model);
+        set(&mut self.lint.profile, other.lint.profile);
+        set(&mut self.lint.nested_facets, other.lint.nested_facets);
+        set(
+            &mut self.lint.strict_whitespace,
+            other.lint.strict_whitespace,
+        );
+        set(&mut self.lint.require_source, other.lint.require_source);
+        set(&mut self.lint.spec_version, other.lint.spec_version);
+        set(&mut self.fmt.tabs_as, other.fmt.tabs_as);
+        set(&mut self.notion.database, other.notion.database);
+        set(&mut self.notion.properties, other.notion.properties);
+        for (name, profile) in other.profiles {
+            match self.profiles.get_mut(&name) {
+                Some(existing) => existing.overlay(profile),
+                None => {
+                    self.profiles.insert(name, profile);
+                }
+            }
+        }
+    }
+
+    /// Make the configured values the defaults of every command with the
+    /// matching option, so flags still override them and `--help` shows them
+    ///
+    /// Options are matched by argument id. Those that share a flag but not its
+    /// meaning (`encrypt`/`decrypt --file`, `index --provider`/`--model`) have
+    /// ids of their own, so no config key reaches them.
+    pub fn apply(&self, command: Command) -> Command {
+        let flag = |value: Option<bool>| value.map(|on| on.to_string());
+        let defaults: Vec<(&str, Vec<String>)> = [
+            (
+                "file",
+                self.file.as_ref().map(|f| vec![f.display().to_string()]),
+            ),
+            ("provider", self.provider.clone().map(|p| vec![p])),
+            ("model", self.model.clone().map(|m| vec![m])),
+            ("profile", self.lint.profile.clone().map(|p| vec![p])),
+            (
+                "nested_facets",
+                flag(self.lint.nested_facets).map(|f| vec![f]),
+            ),
+            (
+                "strict_whitespace",
+                flag(self.lint.strict_whitespace).map(|f| vec![f]),
+            ),
+            ("require_source", self.lint.require_source.clone()),
+            ("spec_version", self.lint.spec_version.clone().map(|v| vec![v])),
+            ("tabs_as", self.fmt.tabs_as.map(|n| vec![n.to_string()])),
+            ("database", self.notion.database.clone().map(|d| vec![d])),
+            (
+                "properties",
+                self.notion.properties.as_ref().map(|properties| {
+                    properties
+                        .iter()
+                        .map(|(field, name)| format!("{}={}", field, name))
+                        .collect()
+                }),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(id, values)| Some((id, values?)))
+        .collect();
+        with_defaults(command, &defaults)
+    }
+}
+
+/// Print each profile with the file and model it selects
+pub fn list_profiles(config: &Config, active: Option<&str>) -> Result<()> {
+    if config.profiles.is_empty() {
+        println!("No profiles defined");
+        return Ok(());
+    }
+    for name in config.profiles.keys() {
+        let settings = config.select(Some(name))?;
+        let marker = if active == Some(name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        let file = settings
+            .file
+            .map_or_else(|| "worldview.wvf".to_string(), |f| f.display().to_string());
+        let mut line = format!("{} {}  {}", marker, name, file);
+        if let Some(provider) = &settings.provider {
+            line.push_str(&format!("  {}", provider));
+        }
+        if let Some(model) = &settings.model {
+            line.push_str(&format!("  {}", model));
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn with_defaults(mut command: Command, defaults: &[(&str, Vec<String>)]) -> Command {
+    for (id, values) in defaults {
+        if command.get_arguments().any(|arg| arg.get_id() == id) {
+            let values = values.clone();
+            command = command.mut_arg(*id, |arg| arg.default_values(values));
+        }
+    }
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in names {
+        command = command.mut_subcommand(name, |sub| with_defaults(sub, defaults));
+    }
+    command
+}
+
+/// `~/.config/worldview/config.toml`, or under `$XDG_CONFIG_HOME`
+pub fn user_path() -> Option<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join("worldview").join("config.toml"))
+}
+
+/// The nearest `.worldview.toml` in `dir` or its ancestors
+pub fn project_path(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(PROJECT_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Expand a leading `~/`, and join relative paths onto `dir`
+fn resolve(path: &Path, dir: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+    dir.join(path)
+}
@@ -0,0 +1,287 @@
+//! Pending agent edits to a Worldview file, and a colored preview of them
+//!
+//! Agent tools read and edit the draft rather than the file. In write-through
+//! mode each validated edit is saved immediately; otherwise the draft is saved
+//! only once the user has seen the changes and approved them. Either way the
+//! file is snapshotted before it is overwritten, for `worldview undo`. An
+//! encrypted file is decrypted into the draft and encrypted again when saved.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use worldview_validator::{Change, Document, Facet, ValidationError, ValidationOptions, ValidationResult, normalize_claim};
+
+use crate::{encryption, undo};
+
+pub struct Draft {
+    pub path: PathBuf,
+    /// Contents when the draft was opened (empty for a new file)
+    pub original: String,
+    pub content: String,
+    pub exists: bool,
+    /// Save every edit as it is made
+    pub write_through: bool,
+}
+
+impl Draft {
+    pub fn open(path: PathBuf, write_through: bool) -> Result<Draft> {
+        let exists = path.exists();
+        let original = if exists {
+            encryption::read(&path)?
+        } else {
+            String::new()
+        };
+        Ok(Draft {
+            path,
+            content: original.clone(),
+            original,
+            exists,
+            write_through,
+        })
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.content != self.original
+    }
+
+    /// Replace the draft contents, saving them in write-through mode
+    pub fn update(&mut self, content: String) -> Result<()> {
+        if self.write_through {
+            undo::snapshot(&self.path)?;
+            encryption::write(&self.path, &content)?;
+        }
+        self.content = content;
+        self.exists = true;
+        Ok(())
+    }
+
+    /// Snapshot the file, then write the draft over it
+    pub fn save(&self) -> Result<()> {
+        undo::snapshot(&self.path)
+            .with_context(|| format!("Error saving history for {}", self.path.display()))?;
+        encryption::write(&self.path, &self.content)
+    }
+
+    /// Save the draft, then take it as the original for further edits
+    pub fn accept(&mut self) -> Result<()> {
+        self.save()?;
+        self.original = self.content.clone();
+        Ok(())
+    }
+
+    /// Drop the edits made since the draft was opened or last accepted
+    pub fn discard(&mut self) {
+        self.content = self.original.clone();
+        self.exists = self.path.exists();
+    }
+
+    /// Print the structural changes from the original to the draft
+    pub fn print_diff(&self) {
+        let old = Document::parse(&self.original);
+        let new = Document::parse(&self.content);
+        let changes = old.diff(&new);
+        if changes.is_empty() && self.is_changed() {
+            println!("{}: formatting changes only", self.path.display());
+        }
+        for change in &changes {
+            print_change(change, &new);
+        }
+    }
+
+    /// Print repeated or contradictory claims the draft adds
+    pub fn print_conflicts(&self) {
+        for conflict in new_conflicts(&self.original, &self.content) {
+            println!("{}", paint(&format!("warning: {}", conflict), YELLOW));
+        }
+    }
+}
+
+/// Validate an edited file, allowing sub-facets when it has them
+pub fn validate(text: &str) -> ValidationResult<'_> {
+    let options = ValidationOptions {
+        nested_facets: Document::parse(text).has_nested_facets(),
+        ..ValidationOptions::default()
+    };
+    worldview_validator::validate_with_options(text, &options)
+}
+
+/// Repeated-claim and contradictory-relation warnings in `new` that `old` lacks,
+/// plus claims `new` restates from another facet
+///
+/// Line numbers shift under edits, so warnings are matched by kind and the
+/// text of the line they are on, and counted: a third copy of a claim `old`
+/// already repeats is new.
+pub fn new_conflicts(old: &str, new: &str) -> Vec<ValidationError> {
+    let conflicts = |text: &str| -> Vec<(ValidationError, String)> {
+        let lines: Vec<&str> = text.lines().collect();
+        validate(text)
+            .warnings
+            .into_iter()
+            .filter(|w| {
+                matches!(
+                    w,
+                    ValidationError::DuplicateClaim { .. } | ValidationError::ContradictoryRelation { .. }
+                )
+            })
+            .map(|w| {
+                let line = lines.get(w.line().wrapping_sub(1)).map_or("", |l| l.trim());
+                let line = line.to_string();
+                (w, line)
+            })
+            .collect()
+    };
+    let before = conflicts(old);
+    let mut unmatched: HashMap<(&str, String), usize> = HashMap::new();
+    for (w, line) in before {
+        *unmatched.entry((w.code(), line)).or_default() += 1;
+    }
+    let mut found = Vec::new();
+    for (w, line) in conflicts(new) {
+        match unmatched.get_mut(&(w.code(), line)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => found.push(w),
+        }
+    }
+    for repeat in restated_claims(old, new) {
+        if !found.iter().any(|w| w.code() == repeat.code() && w.line() == repeat.line()) {
+            found.push(repeat);
+        }
+    }
+    found.sort_by_key(ValidationError::line);
+    found
+}
+
+/// Claims in `new` that say what another claim anywhere in it says, beyond
+/// the repeats `old` already had
+///
+/// Claims are compared by `normalize_claim`, so `trust forms slowly !` restates
+/// `Trust forms slowly`. Each extra copy is reported against the first one.
+fn restated_claims(old: &str, new: &str) -> Vec<ValidationError> {
+    fn collect(facet: &Facet, out: &mut Vec<(String, usize)>) {
+        for claim in &facet.claims {
+            out.push((normalize_claim(&claim.data.text), claim.line));
+        }
+        for sub_facet in &facet.sub_facets {
+            collect(sub_facet, out);
+        }
+    }
+    let claims = |text: &str| {
+        let mut out = Vec::new();
+        for concept in &Document::parse(text).concepts {
+            for facet in &concept.facets {
+                collect(facet, &mut out);
+            }
+        }
+        out.retain(|(key, _)| !key.is_empty());
+        out
+    };
+
+    let mut before: HashMap<String, usize> = HashMap::new();
+    for (key, _) in claims(old) {
+        *before.entry(key).or_default() += 1;
+    }
+    let mut copies: HashMap<String, Vec<usize>> = HashMap::new();
+    for (key, line) in claims(new) {
+        copies.entry(key).or_default().push(line);
+    }
+
+    let mut repeats = Vec::new();
+    for (key, lines) in copies {
+        let allowed = before.get(&key).copied().unwrap_or(0).max(1);
+        if lines.len() > allowed {
+            let other_line = lines[0];
+            repeats.extend(
+                lines[allowed..]
+                    .iter()
+                    .map(|&line| ValidationError::DuplicateClaim { line, other_line }),
+            );
+        }
+    }
+    repeats
+}
+
+/// Ask a yes/no question on stderr, defaulting to no
+pub fn confirm(question: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+const GREEN: &str = "32";
+const RED: &str = "31";
+const YELLOW: &str = "33";
+
+fn paint(text: &str, color: &str) -> String {
+    if io::stdout().is_terminal() {
+        format!("\x1b[{}m{}\x1b[0m", color, text)
+    } else {
+        text.to_string()
+    }
+}
+
+fn print_change(change: &Change, new: &Document) {
+    match change {
+        Change::ConceptAdded { concept } => {
+            println!("{}", paint(&format!("+ {}", concept), GREEN));
+            if let Some(concept) = new.concept(concept) {
+                print_claims(&concept.facets, &concept.name);
+            }
+        }
+        Change::FacetAdded { target } => {
+            println!("{}", paint(&format!("+ {}", target), GREEN));
+            if let Some(facet) = facet(new, target) {
+                print_claims(std::slice::from_ref(facet), parent(target));
+            }
+        }
+        Change::ConceptRemoved { concept } => {
+            println!("{}", paint(&format!("- {}", concept), RED))
+        }
+        Change::FacetRemoved { target } => println!("{}", paint(&format!("- {}", target), RED)),
+        Change::ClaimAdded { target, claim } => {
+            println!("{}", paint(&format!("+ {}: {}", target, claim), GREEN))
+        }
+        Change::ClaimRemoved { target, claim } => {
+            println!("{}", paint(&format!("- {}: {}", target, claim), RED))
+        }
+        Change::ClaimChanged {
+            target, from, to, ..
+        } => {
+            println!("{}", paint(&format!("~ {}", change), YELLOW));
+            println!("{}", paint(&format!("- {}: {}", target, from), RED));
+            println!("{}", paint(&format!("+ {}: {}", target, to), GREEN));
+        }
+    }
+}
+
+/// Claims under newly added facets, each prefixed with its target
+fn print_claims(facets: &[Facet], parent: &str) {
+    for facet in facets {
+        let target = format!("{}.{}", parent, facet.name);
+        for claim in &facet.claims {
+            let line = format!("+ {}: {}", target, claim.data.to_wvf_string());
+            println!("{}", paint(&line, GREEN));
+        }
+        print_claims(&facet.sub_facets, &target);
+    }
+}
+
+/// `Power.nature.sub` → `Power.nature`
+fn parent(target: &str) -> &str {
+    target.rsplit_once('.').map_or(target, |(parent, _)| parent)
+}
+
+fn facet<'d>(doc: &'d Document, target: &str) -> Option<&'d Facet> {
+    let mut parts = target.split('.');
+    let concept = doc.concept(parts.next()?)?;
+    let mut facets = &concept.facets;
+    let mut found = None;
+    for name in parts {
+        let facet = facets.iter().find(|f| f.name == name)?;
+        facets = &facet.sub_facets;
+        found = Some(facet);
+    }
+    found
+}
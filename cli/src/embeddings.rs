@@ -0,0 +1,298 @@
+//! Semantic search - embeds claims into a sidecar index and finds nearest claims
+//!
+//! `worldview index` writes `<file>.embeddings.json` next to the Worldview file.
+//! Vectors are keyed by each claim's stable ID, so re-indexing only embeds new or
+//! edited claims, and search ignores entries for claims that no longer exist.
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use worldview_validator::{Claim, Document, Facet};
+
+/// Claims sent per embedding request
+const BATCH_SIZE: usize = 128;
+
+/// Computes embedding vectors for text
+pub trait Embedder {
+    /// Model name, recorded in the index so search embeds queries the same way
+    fn model(&self) -> &str;
+
+    /// One vector per input text, in order
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// Embedding providers
+///
+/// Anthropic has no embeddings endpoint and recommends Voyage AI, so `voyage`
+/// pairs with the Anthropic models used by `add`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// Voyage AI (VOYAGE_API_KEY)
+    Voyage,
+    /// OpenAI (OPENAI_API_KEY)
+    Openai,
+    /// A local Ollama server (OLLAMA_HOST, default http://localhost:11434)
+    Ollama,
+}
+
+impl Provider {
+    fn default_model(self) -> &'static str {
+        match self {
+            Provider::Voyage => "voyage-3.5-lite",
+            Provider::Openai => "text-embedding-3-small",
+            Provider::Ollama => "nomic-embed-text",
+        }
+    }
+
+    /// Build an embedder, reading the API key or host from the environment
+    pub fn embedder(self, model: Option<String>) -> Result<Box<dyn Embedder>> {
+        let model = model.unwrap_or_else(|| self.default_model().to_string());
+        let (url, key_var) = match self {
+            Provider::Voyage => (
+                "https://api.voyageai.com/v1/embeddings".to_string(),
+                Some("VOYAGE_API_KEY"),
+            ),
+            Provider::Openai => (
+                "https://api.openai.com/v1/embeddings".to_string(),
+                Some("OPENAI_API_KEY"),
+            ),
+            Provider::Ollama => {
+                let host = std::env::var("OLLAMA_HOST")
+                    .unwrap_or_else(|_| "http://localhost:11434".into());
+                (
+                    format!("{}/v1/embeddings", host.trim_end_matches('/')),
+                    None,
+                )
+            }
+        };
+        let api_key = match key_var {
+            Some(var) => Some(
+                std::env::var(var)
+                    .with_context(|| format!("{} environment variable not set", var))?,
+            ),
+            None => None,
+        };
+        Ok(Box::new(HttpEmbedder {
+            url,
+            model,
+            api_key,
+        }))
+    }
+}
+
+/// Any provider speaking the OpenAI-style `/v1/embeddings` protocol
+struct HttpEmbedder {
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl Embedder for HttpEmbedder {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut request = ureq::post(&self.url);
+        if let Some(key) = &self.api_key {
+            request = request.header("Authorization", &format!("Bearer {}", key));
+        }
+        let mut response: EmbeddingResponse = request
+            .send_json(json!({ "model": self.model, "input": texts }))
+            .with_context(|| format!("Embedding request to {} failed", self.url))?
+            .body_mut()
+            .read_json()?;
+        if response.data.len() != texts.len() {
+            bail!(
+                "Expected {} embeddings, got {}",
+                texts.len(),
+                response.data.len()
+            );
+        }
+        response.data.sort_by_key(|d| d.index);
+        Ok(response.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+/// Sidecar index contents
+#[derive(Serialize, Deserialize)]
+struct Index {
+    provider: Provider,
+    model: String,
+    /// Vectors by claim stable ID
+    claims: HashMap<String, Vec<f32>>,
+}
+
+/// `worldview.wvf` → `worldview.wvf.embeddings.json`
+fn index_path(file: &Path) -> PathBuf {
+    let mut path = OsString::from(file.as_os_str());
+    path.push(".embeddings.json");
+    PathBuf::from(path)
+}
+
+/// Every claim with its `Concept.facet` target, in document order
+fn claims(doc: &Document) -> Vec<(String, &Claim)> {
+    fn collect<'d>(facets: &'d [Facet], parent: &str, out: &mut Vec<(String, &'d Claim)>) {
+        for facet in facets {
+            let target = format!("{}.{}", parent, facet.name);
+            out.extend(facet.claims.iter().map(|claim| (target.clone(), claim)));
+            collect(&facet.sub_facets, &target, out);
+        }
+    }
+    let mut out = Vec::new();
+    for concept in &doc.concepts {
+        collect(&concept.facets, &concept.name, &mut out);
+    }
+    out
+}
+
+fn read_index(path: &Path) -> Result<Option<Index>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Error reading {}", path.display()))?;
+    let index = serde_json::from_str(&content)
+        .with_context(|| format!("Error parsing {}", path.display()))?;
+    Ok(Some(index))
+}
+
+/// Embed every claim not already in the index, dropping entries for removed claims
+pub fn index(file: PathBuf, provider: Provider, model: Option<String>) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+    let embedder = provider.embedder(model)?;
+    let path = index_path(&file);
+
+    // Vectors from another model are not comparable, so switching models re-embeds everything
+    let mut previous = read_index(&path)?
+        .filter(|index| index.provider == provider && index.model == embedder.model())
+        .map(|index| index.claims)
+        .unwrap_or_default();
+
+    let mut vectors = HashMap::new();
+    let mut pending = Vec::new();
+    for (target, claim) in claims(&doc) {
+        let id = claim.data.stable_id();
+        match previous.remove(&id) {
+            Some(vector) => {
+                vectors.insert(id, vector);
+            }
+            // Embed with the target so the hierarchy informs the vector
+            None => pending.push((id, format!("{}: {}", target, claim.data.to_wvf_string()))),
+        }
+    }
+
+    let reused = vectors.len();
+    for batch in pending.chunks(BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+        let embedded = embedder.embed(&texts)?;
+        vectors.extend(batch.iter().map(|(id, _)| id.clone()).zip(embedded));
+    }
+
+    let index = Index {
+        provider,
+        model: embedder.model().to_string(),
+        claims: vectors,
+    };
+    std::fs::write(&path, serde_json::to_string(&index)?)
+        .with_context(|| format!("Error writing {}", path.display()))?;
+    println!(
+        "{}: embedded {} claim{}, reused {}",
+        path.display(),
+        pending.len(),
+        if pending.len() == 1 { "" } else { "s" },
+        reused
+    );
+    Ok(())
+}
+
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// A claim near the query: similarity, `Concept.facet` target, and the claim
+pub type Hit<'d> = (f32, String, &'d Claim);
+
+fn require_index(file: &Path) -> Result<Index> {
+    let path = index_path(file);
+    match read_index(&path)? {
+        Some(index) => Ok(index),
+        None => bail!(
+            "No embeddings index at {} (run `worldview index` first)",
+            path.display()
+        ),
+    }
+}
+
+/// Indexed vectors by claim stable ID
+pub fn vectors(file: &Path) -> Result<HashMap<String, Vec<f32>>> {
+    Ok(require_index(file)?.claims)
+}
+
+/// Indexed claims of `doc` by descending similarity to the query, and how many
+/// claims are missing from the index
+pub fn nearest<'d>(doc: &'d Document, file: &Path, query: &str) -> Result<(Vec<Hit<'d>>, usize)> {
+    let index = require_index(file)?;
+
+    let embedder = index.provider.embedder(Some(index.model.clone()))?;
+    let Some(query_vector) = embedder.embed(&[query.to_string()])?.pop() else {
+        bail!("Provider returned no embedding for the query");
+    };
+
+    let mut unindexed = 0;
+    let mut hits: Vec<Hit<'d>> = Vec::new();
+    for (target, claim) in claims(doc) {
+        match index.claims.get(&claim.data.stable_id()) {
+            Some(vector) => hits.push((cosine(&query_vector, vector), target, claim)),
+            None => unindexed += 1,
+        }
+    }
+    hits.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok((hits, unindexed))
+}
+
+/// Print the claims nearest to the query by cosine similarity
+pub fn search(query: String, file: PathBuf, limit: usize) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+    let (hits, unindexed) = nearest(&doc, &file, &query)?;
+
+    for (similarity, target, claim) in hits.iter().take(limit) {
+        println!("{:>6.3}  {} (line {})", similarity, target, claim.line);
+        println!("        - {}", claim.data.to_wvf_string());
+    }
+    if unindexed > 0 {
+        eprintln!(
+            "note: {} claim{} changed since indexing (run `worldview index` to include them)",
+            unindexed,
+            if unindexed == 1 { " has" } else { "s have" }
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,211 @@
+//! Encryption at rest - `.wvf.age` files, decrypted only in memory
+//!
+//! `encrypt` writes an age file, to a passphrase or to `--recipient` public
+//! keys, and `decrypt` writes the plaintext back out. `validate` and the agent
+//! commands read a `.wvf.age` file in memory and encrypt edits before saving
+//! them, so its plaintext never reaches the disk.
+//!
+//! Files encrypted to keys are decrypted with the identity file named by
+//! `WORLDVIEW_AGE_IDENTITY`, and re-encrypted to its public keys. Passphrase
+//! files use `WORLDVIEW_PASSPHRASE`, or ask for the passphrase once per run.
+
+use age::secrecy::SecretString;
+use anyhow::{Context, Result, bail};
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::undo;
+
+/// Environment variable naming an age identity file
+pub const IDENTITY_VAR: &str = "WORLDVIEW_AGE_IDENTITY";
+
+/// Environment variable holding the passphrase
+pub const PASSPHRASE_VAR: &str = "WORLDVIEW_PASSPHRASE";
+
+/// The passphrase entered this run, so it is asked for only once
+static PASSPHRASE: Mutex<Option<SecretString>> = Mutex::new(None);
+
+/// Whether `path` is an encrypted Worldview file (`.wvf.age`)
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "age")
+}
+
+/// Contents of `path`, decrypted in memory if it is encrypted
+pub fn read(path: &Path) -> Result<String> {
+    if is_encrypted(path) {
+        decrypt(path)
+    } else {
+        std::fs::read_to_string(path).with_context(|| format!("Error reading {}", path.display()))
+    }
+}
+
+/// Write `content` to `path`, encrypting it if the path is encrypted
+///
+/// An existing file is re-encrypted the way it was encrypted; a new one is
+/// encrypted to `WORLDVIEW_AGE_IDENTITY` if it is set, or else to a passphrase.
+pub fn write(path: &Path, content: &str) -> Result<()> {
+    if !is_encrypted(path) {
+        return std::fs::write(path, content)
+            .with_context(|| format!("Error writing {}", path.display()));
+    }
+    let uses_passphrase = if path.exists() {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Error reading {}", path.display()))?;
+        age::Decryptor::new(std::io::BufReader::new(file))
+            .with_context(|| format!("{} is not an age file", path.display()))?
+            .is_scrypt()
+    } else {
+        std::env::var_os(IDENTITY_VAR).is_none()
+    };
+    let ciphertext = if uses_passphrase {
+        encrypt_bytes(
+            age::Encryptor::with_user_passphrase(passphrase(false)?),
+            content,
+        )?
+    } else {
+        let identities = identity_file()?;
+        let recipients = identities
+            .to_recipients()
+            .context("Error reading public keys from the identity file")?;
+        encrypt_bytes(
+            age::Encryptor::with_recipients(recipients.iter().map(|r| r.as_ref() as _))?,
+            content,
+        )?
+    };
+    std::fs::write(path, ciphertext).with_context(|| format!("Error writing {}", path.display()))
+}
+
+/// Plaintext of an age file, whatever its name (snapshots have no `.age`)
+pub fn decrypt(path: &Path) -> Result<String> {
+    let file =
+        std::fs::File::open(path).with_context(|| format!("Error reading {}", path.display()))?;
+    let decryptor = age::Decryptor::new(std::io::BufReader::new(file))
+        .with_context(|| format!("{} is not an age file", path.display()))?;
+    let mut reader = if decryptor.is_scrypt() {
+        let identity = age::scrypt::Identity::new(passphrase(false)?);
+        decryptor.decrypt(std::iter::once(&identity as _))
+    } else {
+        let identities = identity_file()?
+            .into_identities()
+            .context("Error reading the identity file")?;
+        decryptor.decrypt(identities.iter().map(|i| i.as_ref() as _))
+    }
+    .with_context(|| format!("Error decrypting {}", path.display()))?;
+    let mut content = String::new();
+    reader
+        .read_to_string(&mut content)
+        .with_context(|| format!("Error decrypting {}", path.display()))?;
+    Ok(content)
+}
+
+/// Encrypt `file` to `file.age`, removing the plaintext unless `keep` is set
+pub fn run_encrypt(file: PathBuf, recipients: Vec<String>, keep: bool) -> Result<()> {
+    if is_encrypted(&file) {
+        bail!("{} is already encrypted", file.display());
+    }
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let mut name = file.clone().into_os_string();
+    name.push(".age");
+    let output = PathBuf::from(name);
+    if output.exists() {
+        bail!("{} already exists", output.display());
+    }
+
+    let encryptor = if recipients.is_empty() {
+        age::Encryptor::with_user_passphrase(passphrase(true)?)
+    } else {
+        let keys = recipients
+            .iter()
+            .map(|key| {
+                key.parse::<age::x25519::Recipient>()
+                    .map_err(|e| anyhow::anyhow!("Invalid recipient {}: {}", key, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        age::Encryptor::with_recipients(keys.iter().map(|key| key as _))?
+    };
+    std::fs::write(&output, encrypt_bytes(encryptor, &content)?)
+        .with_context(|| format!("Error writing {}", output.display()))?;
+    println!("{}: encrypted to {}", file.display(), output.display());
+
+    if !keep {
+        std::fs::remove_file(&file)
+            .with_context(|| format!("Error removing {}", file.display()))?;
+        println!("{}: removed", file.display());
+    }
+    if undo::has_snapshots(&file)? {
+        eprintln!(
+            "Note: earlier versions of {} in .wvf.history/ are not encrypted",
+            file.display()
+        );
+    }
+    Ok(())
+}
+
+/// Decrypt `file` to `output` (by default, the file name without `.age`)
+pub fn run_decrypt(file: PathBuf, output: Option<PathBuf>) -> Result<()> {
+    let content = decrypt(&file)?;
+    if output.as_deref() == Some(Path::new("-")) {
+        print!("{}", content);
+        return Ok(());
+    }
+    let output = match output {
+        Some(output) => output,
+        None if is_encrypted(&file) => file.with_extension(""),
+        None => bail!("{} has no .age extension; pass --output", file.display()),
+    };
+    if output.exists() {
+        bail!("{} already exists", output.display());
+    }
+    std::fs::write(&output, content)
+        .with_context(|| format!("Error writing {}", output.display()))?;
+    println!("{}: decrypted to {}", file.display(), output.display());
+    Ok(())
+}
+
+fn encrypt_bytes(encryptor: age::Encryptor, content: &str) -> Result<Vec<u8>> {
+    let mut ciphertext = Vec::new();
+    let mut writer = encryptor.wrap_output(&mut ciphertext)?;
+    writer.write_all(content.as_bytes())?;
+    writer.finish()?;
+    Ok(ciphertext)
+}
+
+fn identity_file() -> Result<age::IdentityFile<age::NoCallbacks>> {
+    let Some(path) = std::env::var_os(IDENTITY_VAR) else {
+        bail!(
+            "File is encrypted to a key; set {} to an age identity file",
+            IDENTITY_VAR
+        );
+    };
+    let path = PathBuf::from(path);
+    age::IdentityFile::from_file(path.to_string_lossy().into_owned())
+        .with_context(|| format!("Error reading identity file {}", path.display()))
+}
+
+/// The passphrase from the environment, or else from the terminal
+///
+/// `confirm` asks twice, for choosing a new passphrase.
+fn passphrase(confirm: bool) -> Result<SecretString> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_VAR) {
+        return Ok(passphrase.into());
+    }
+    let mut entered = PASSPHRASE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(passphrase) = entered.as_ref() {
+        return Ok(passphrase.clone());
+    }
+    if !std::io::stdin().is_terminal() {
+        bail!("No passphrase: set {} or run in a terminal", PASSPHRASE_VAR);
+    }
+    let passphrase = rpassword::prompt_password("Passphrase: ")?;
+    if confirm && rpassword::prompt_password("Confirm passphrase: ")? != passphrase {
+        bail!("Passphrases do not match");
+    }
+    if passphrase.is_empty() {
+        bail!("Empty passphrase");
+    }
+    let passphrase = SecretString::from(passphrase);
+    *entered = Some(passphrase.clone());
+    Ok(passphrase)
+}
@@ -0,0 +1,131 @@
+//! Export subcommand - writes a document in a structured format for other tools,
+//! as a readable Markdown or HTML page, as an Org outline, as RDF for
+//! knowledge-graph tools, as an Obsidian vault, or as a token-budgeted snippet
+//! for an assistant's system prompt
+
+mod html;
+mod markdown;
+mod obsidian;
+pub mod org;
+pub mod prompt;
+mod rdf;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use worldview_validator::Document;
+
+use crate::structured::StructuredDocument;
+
+/// Formats for `export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    Yaml,
+    /// A readable Markdown page
+    Md,
+    /// An Org-mode outline, with modifiers as tags
+    Org,
+    /// A standalone HTML page, with brief forms spelled out
+    Html,
+    /// RDF in Turtle
+    Turtle,
+    /// RDF in JSON-LD
+    Jsonld,
+    /// One linked Markdown note per concept, written to the --output directory
+    Obsidian,
+    /// Compact Worldview notation for another assistant's system prompt
+    Prompt,
+}
+
+/// What to include in a `prompt` export
+#[derive(Debug, Default)]
+pub struct Selection {
+    /// Token budget; the most important claims are kept first
+    pub budget: Option<usize>,
+    /// Concepts to include (every concept if empty)
+    pub concepts: Vec<String>,
+}
+
+pub fn run(
+    file: PathBuf,
+    format: ExportFormat,
+    base: Option<String>,
+    redact: Vec<String>,
+    selection: Selection,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let mut doc = Document::parse(&content);
+    if !redact.is_empty() {
+        // Before anything else, so no format or selection sees flagged claims
+        let levels: Vec<&str> = redact.iter().map(String::as_str).collect();
+        doc = doc.redacted(&levels);
+    }
+    if format != ExportFormat::Prompt && (selection.budget.is_some() || !selection.concepts.is_empty()) {
+        bail!("--budget and --concepts apply only to --format prompt");
+    }
+    if let Some(name) = selection.concepts.iter().find(|name| doc.concept(name).is_none()) {
+        bail!("{}: no concept {}", file.display(), name);
+    }
+    let rendered = match format {
+        ExportFormat::Obsidian => {
+            let Some(dir) = output else {
+                bail!("Obsidian export writes a directory of notes; pass --output DIR");
+            };
+            return write_vault(&doc, &dir);
+        }
+        ExportFormat::Json => serde_json::to_string_pretty(&StructuredDocument::from(&doc))? + "\n",
+        ExportFormat::Yaml => serde_yaml::to_string(&StructuredDocument::from(&doc))?,
+        ExportFormat::Md => markdown::render(&doc, &title(&file)),
+        ExportFormat::Org => org::render(&doc, &title(&file)),
+        ExportFormat::Html => html::render(&doc, &title(&file)),
+        ExportFormat::Turtle | ExportFormat::Jsonld => {
+            let base = base.unwrap_or_else(|| format!("urn:worldview:{}/", title(&file)));
+            match format {
+                ExportFormat::Turtle => rdf::turtle(&doc, &base),
+                _ => rdf::json_ld(&doc, &base),
+            }
+        }
+        ExportFormat::Prompt => {
+            let prompt = prompt::render(&doc, &selection.concepts, selection.budget);
+            if prompt.omitted > 0 {
+                eprintln!(
+                    "{}: omitted {} claims to fit {} tokens",
+                    file.display(),
+                    prompt.omitted,
+                    selection.budget.unwrap_or_default()
+                );
+            }
+            prompt.text
+        }
+    };
+    match output {
+        Some(path) => std::fs::write(&path, rendered)
+            .with_context(|| format!("Error writing {}", path.display()))?,
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+/// Write one note per concept into `dir`, creating it if needed
+fn write_vault(doc: &Document, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Error creating {}", dir.display()))?;
+    let notes = obsidian::render(doc);
+    for note in &notes {
+        let path = dir.join(format!("{}.md", note.name));
+        std::fs::write(&path, &note.content)
+            .with_context(|| format!("Error writing {}", path.display()))?;
+    }
+    println!("{}: wrote {} notes", dir.display(), notes.len());
+    Ok(())
+}
+
+/// Page title: the file name without its extension
+fn title(file: &Path) -> String {
+    file.file_stem().map_or_else(
+        || "Worldview".into(),
+        |stem| stem.to_string_lossy().into_owned(),
+    )
+}
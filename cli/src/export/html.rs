@@ -0,0 +1,117 @@
+//! HTML rendering: a standalone page with linked references
+
+use worldview_validator::{Claim, Document, Facet};
+
+const STYLE: &str = "body { font-family: system-ui, sans-serif; max-width: 48rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }
+.conditions { font-style: italic; }
+.sources, .evolution { color: #666; font-size: 0.9em; }";
+
+pub fn render(doc: &Document, title: &str) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n",
+        title = escape(title)
+    );
+    if !doc.includes.is_empty() {
+        let paths: Vec<String> = doc.includes.iter().map(|i| escape(&i.path)).collect();
+        out.push_str(&format!("<p>Includes: {}</p>\n", paths.join(", ")));
+    }
+    for concept in &doc.concepts {
+        out.push_str(&format!(
+            "<section id=\"{}\">\n<h2>{}</h2>\n",
+            escape(&concept.name),
+            escape(&concept.name)
+        ));
+        render_facets(&mut out, &concept.facets, &concept.name, 3);
+        out.push_str("</section>\n");
+    }
+    if let Some(sources) = &doc.sources {
+        out.push_str("<section id=\"sources\">\n<h2>Sources</h2>\n<dl>\n");
+        for entry in &sources.entries {
+            out.push_str(&format!(
+                "<dt id=\"source-{}\">{}</dt><dd>{}</dd>\n",
+                escape(&entry.handle),
+                escape(&entry.handle),
+                escape(&entry.description)
+            ));
+        }
+        out.push_str("</dl>\n</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn render_facets(out: &mut String, facets: &[Facet], parent: &str, level: usize) {
+    for facet in facets {
+        let path = format!("{}.{}", parent, facet.name);
+        out.push_str(&format!(
+            "<h{level} id=\"{}\">{}</h{level}>\n",
+            escape(&path),
+            escape(&facet.name)
+        ));
+        if !facet.claims.is_empty() {
+            out.push_str("<ul>\n");
+            for claim in &facet.claims {
+                out.push_str(&format!("<li>{}</li>\n", item(claim)));
+            }
+            out.push_str("</ul>\n");
+        }
+        render_facets(out, &facet.sub_facets, &path, (level + 1).min(6));
+    }
+}
+
+fn item(claim: &Claim) -> String {
+    let data = &claim.data;
+    let mut html = escape(&data.describe());
+    if !data.conditions.is_empty() {
+        let conditions: Vec<String> = data.conditions.iter().map(|c| escape(c)).collect();
+        html.push_str(&format!(
+            " — <span class=\"conditions\">{}</span>",
+            conditions.join(", ")
+        ));
+    }
+    if !data.references.is_empty() {
+        let links: Vec<String> = data
+            .references
+            .iter()
+            .map(|r| format!("<a href=\"#{}\">{}</a>", escape(r), escape(r)))
+            .collect();
+        html.push_str(&format!(" (see {})", links.join(", ")));
+    }
+    if !data.sources.is_empty() {
+        let sources: Vec<String> = data
+            .sources
+            .iter()
+            .map(|s| format!("<cite>{}</cite>", escape(s)))
+            .collect();
+        html.push_str(&format!(
+            " <span class=\"sources\">[{}]</span>",
+            sources.join(", ")
+        ));
+    }
+    if !data.evolution.is_empty() {
+        let prior: Vec<String> = data
+            .evolution
+            .iter()
+            .map(|marker| match marker.changed_at {
+                Some(date) => format!(
+                    "<del>{}</del> (until {})",
+                    escape(&marker.prior_belief),
+                    date
+                ),
+                None => format!("<del>{}</del>", escape(&marker.prior_belief)),
+            })
+            .collect();
+        html.push_str(&format!(
+            " <span class=\"evolution\">formerly {}</span>",
+            prior.join("; ")
+        ));
+    }
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
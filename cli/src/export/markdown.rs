@@ -0,0 +1,64 @@
+//! Markdown rendering: concepts and facets as headings, claims as bullets
+
+use worldview_validator::{Claim, Document, Facet};
+
+pub fn render(doc: &Document, title: &str) -> String {
+    let mut out = format!("# {}\n", title);
+    if !doc.includes.is_empty() {
+        let paths: Vec<&str> = doc.includes.iter().map(|i| i.path.as_str()).collect();
+        out.push_str(&format!("\n> Includes: {}\n", paths.join(", ")));
+    }
+    for concept in &doc.concepts {
+        out.push_str(&format!("\n## {}\n", concept.name));
+        render_facets(&mut out, &concept.facets, 3);
+    }
+    if let Some(sources) = &doc.sources {
+        out.push_str("\n## Sources\n\n");
+        for entry in &sources.entries {
+            out.push_str(&format!("- **{}**: {}\n", entry.handle, entry.description));
+        }
+    }
+    out
+}
+
+fn render_facets(out: &mut String, facets: &[Facet], level: usize) {
+    for facet in facets {
+        out.push_str(&format!("\n{} {}\n", "#".repeat(level), facet.name));
+        if !facet.claims.is_empty() {
+            out.push('\n');
+        }
+        for claim in &facet.claims {
+            out.push_str(&format!("- {}\n", bullet(claim)));
+        }
+        render_facets(out, &facet.sub_facets, level + 1);
+    }
+}
+
+fn bullet(claim: &Claim) -> String {
+    let data = &claim.data;
+    let mut text = data.describe();
+    if !data.conditions.is_empty() {
+        let conditions: Vec<&str> = data.conditions.iter().map(|c| c.as_ref()).collect();
+        text.push_str(&format!(" — *{}*", conditions.join(", ")));
+    }
+    if !data.references.is_empty() {
+        let references: Vec<&str> = data.references.iter().map(|r| r.as_ref()).collect();
+        text.push_str(&format!(" (see {})", references.join(", ")));
+    }
+    if !data.sources.is_empty() {
+        let sources: Vec<&str> = data.sources.iter().map(|s| s.as_ref()).collect();
+        text.push_str(&format!(" [source: {}]", sources.join(", ")));
+    }
+    if !data.evolution.is_empty() {
+        let prior: Vec<String> = data
+            .evolution
+            .iter()
+            .map(|marker| match marker.changed_at {
+                Some(date) => format!("{} (until {})", marker.prior_belief, date),
+                None => marker.prior_belief.to_string(),
+            })
+            .collect();
+        text.push_str(&format!(" (formerly: {})", prior.join("; ")));
+    }
+    text
+}
@@ -0,0 +1,137 @@
+//! Obsidian vault rendering: one note per concept, linked for the graph view
+//!
+//! Facets become headings, so `&Trust.formation` becomes `[[Trust#formation]]`.
+//! Modifiers become inline tags (`#increasing`) and sources link to headings
+//! in a `Sources` note.
+
+use worldview_validator::{Claim, Document, Facet, Reference, modifier_meaning};
+
+/// Note holding the source registry
+const SOURCES_NOTE: &str = "Sources";
+
+/// A note's file name (without `.md`) and contents
+pub struct Note {
+    pub name: String,
+    pub content: String,
+}
+
+pub fn render(doc: &Document) -> Vec<Note> {
+    let mut notes: Vec<Note> = doc
+        .concepts
+        .iter()
+        .map(|concept| {
+            let mut content = String::new();
+            render_facets(&mut content, &concept.facets, 2);
+            Note {
+                name: note_name(&concept.name),
+                content: content.trim_start().to_string(),
+            }
+        })
+        .collect();
+    if let Some(sources) = &doc.sources {
+        let mut content = String::new();
+        for entry in &sources.entries {
+            content.push_str(&format!("\n## {}\n\n{}\n", entry.handle, entry.description));
+        }
+        notes.push(Note {
+            name: SOURCES_NOTE.to_string(),
+            content: content.trim_start().to_string(),
+        });
+    }
+    notes
+}
+
+/// Characters Obsidian does not allow in note names or links
+fn note_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '[' | ']' | '#' | '^' | '|' | '\\' | '/' | ':' => '-',
+            _ => c,
+        })
+        .collect()
+}
+
+fn render_facets(out: &mut String, facets: &[Facet], level: usize) {
+    for facet in facets {
+        out.push_str(&format!("\n{} {}\n", "#".repeat(level), facet.name));
+        if !facet.claims.is_empty() {
+            out.push('\n');
+        }
+        for claim in &facet.claims {
+            out.push_str(&format!("- {}\n", bullet(claim)));
+        }
+        render_facets(out, &facet.sub_facets, level + 1);
+    }
+}
+
+/// `&Concept.facet.sub` → `[[Concept#facet#sub]]`
+fn wikilink(reference: &str) -> String {
+    match Reference::parse(reference) {
+        Some(parsed) => {
+            let link = format!(
+                "{}#{}",
+                note_name(&parsed.concept),
+                parsed.facet.replace('.', "#")
+            );
+            match parsed.namespace {
+                // Other files' concepts keep their qualified name as the label
+                Some(_) => format!("[[{}|{}]]", link, reference),
+                None => format!("[[{}]]", link),
+            }
+        }
+        None => format!("[[{}]]", note_name(reference)),
+    }
+}
+
+/// `increasing, trending up` → `#increasing`
+fn tag(meaning: &str) -> String {
+    let tag: String = meaning
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => c,
+            _ => '-',
+        })
+        .collect();
+    format!("#{}", tag)
+}
+
+fn bullet(claim: &Claim) -> String {
+    let data = &claim.data;
+    let mut text = data.describe();
+    if !data.conditions.is_empty() {
+        let conditions: Vec<&str> = data.conditions.iter().map(|c| c.as_ref()).collect();
+        text.push_str(&format!(" — *{}*", conditions.join(", ")));
+    }
+    let mut tags: Vec<String> = Vec::new();
+    for modifier in &data.modifiers {
+        if let Some(meaning) = modifier_meaning(modifier.symbol) {
+            let tag = tag(meaning);
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    for tag in tags {
+        text.push(' ');
+        text.push_str(&tag);
+    }
+    for reference in &data.references {
+        text.push(' ');
+        text.push_str(&wikilink(reference));
+    }
+    for source in &data.sources {
+        text.push_str(&format!(" [[{}#{}|@{}]]", SOURCES_NOTE, source, source));
+    }
+    if !data.evolution.is_empty() {
+        let prior: Vec<String> = data
+            .evolution
+            .iter()
+            .map(|marker| match marker.changed_at {
+                Some(date) => format!("{} (until {})", marker.prior_belief, date),
+                None => marker.prior_belief.to_string(),
+            })
+            .collect();
+        text.push_str(&format!(" (formerly: {})", prior.join("; ")));
+    }
+    text
+}
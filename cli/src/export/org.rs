@@ -0,0 +1,64 @@
+//! Org-mode rendering: concepts as level-1 headings, facets as level-2 (and
+//! sub-facets below), and claims as list items in Worldview notation
+//!
+//! Each claim's modifiers are also listed as Org tags (`:uncertain:`), so
+//! agenda searches and sparse trees can find them. The source registry is a
+//! `Sources` heading tagged `:sources:`, with a description list of handles.
+
+use worldview_validator::{Claim, Document, Facet, MODIFIERS, modifier_meaning};
+
+/// Tag marking the heading that holds the source registry
+pub const SOURCES_TAG: &str = "sources";
+
+pub fn render(doc: &Document, title: &str) -> String {
+    let mut out = format!("#+TITLE: {}\n", title);
+    if !doc.includes.is_empty() {
+        let paths: Vec<&str> = doc.includes.iter().map(|i| i.path.as_str()).collect();
+        out.push_str(&format!("# Includes: {}\n", paths.join(", ")));
+    }
+    for concept in &doc.concepts {
+        out.push_str(&format!("\n* {}\n", concept.heading()));
+        render_facets(&mut out, &concept.facets, 2);
+    }
+    if let Some(sources) = &doc.sources {
+        out.push_str(&format!("\n* Sources :{}:\n", SOURCES_TAG));
+        for entry in &sources.entries {
+            out.push_str(&format!("- {} :: {}\n", entry.handle, entry.description));
+        }
+    }
+    out
+}
+
+fn render_facets(out: &mut String, facets: &[Facet], level: usize) {
+    for facet in facets {
+        out.push_str(&format!("{} {}\n", "*".repeat(level), facet.name));
+        for claim in &facet.claims {
+            out.push_str(&format!("- {}\n", item(claim)));
+        }
+        render_facets(out, &facet.sub_facets, level + 1);
+    }
+}
+
+fn item(claim: &Claim) -> String {
+    let mut text = claim.data.to_wvf_string();
+    let mut tags: Vec<&str> = Vec::new();
+    for modifier in &claim.data.modifiers {
+        if let Some(tag) = modifier_meaning(modifier.symbol)
+            && !tags.contains(&tag)
+        {
+            tags.push(tag);
+        }
+    }
+    if !tags.is_empty() {
+        text.push_str(&format!(" :{}:", tags.join(":")));
+    }
+    text
+}
+
+/// The modifier an Org tag stands for (`uncertain` is `?`)
+pub fn tag_modifier(tag: &str) -> Option<char> {
+    MODIFIERS
+        .iter()
+        .filter_map(|(symbol, _)| symbol.chars().next())
+        .find(|&symbol| modifier_meaning(symbol) == Some(tag))
+}
@@ -0,0 +1,92 @@
+//! Prompt rendering: a compact worldview for another assistant's system prompt
+//!
+//! Claims stay in Worldview notation, which is denser than prose, with a legend
+//! for the operators and modifiers they use. Under a token budget,
+//! `Document::compress` chooses the claims: the strongest (`!`, then `*`) first,
+//! then the most referenced and most recent, and tentative ones (`?`) last.
+
+use worldview_validator::{
+    Claim, Document, PriorityPolicy, estimate_tokens, modifier_meaning, operator_meaning,
+};
+
+const PREAMBLE: &str = "My worldview, in Worldview notation: each concept lists its .facets, and each facet the - claims I hold about it.";
+
+/// A rendered prompt and how many claims it leaves out
+pub struct Prompt {
+    pub text: String,
+    pub omitted: usize,
+    /// The concepts and claims the text holds
+    pub doc: Document,
+}
+
+/// Render `concepts` (or every concept, if empty) within `budget` tokens
+pub fn render(doc: &Document, concepts: &[String], budget: Option<usize>) -> Prompt {
+    // Only the concepts reach the prompt
+    let mut doc = Document {
+        concepts: doc.concepts.clone(),
+        ..Document::default()
+    };
+    if !concepts.is_empty() {
+        doc.concepts
+            .retain(|concept| concepts.contains(&concept.name));
+    }
+    let total = doc.claims().count();
+    if let Some(budget) = budget {
+        // Leave room for a legend covering every claim, so any selection fits
+        let overhead = estimate_tokens(&header(&doc));
+        doc = doc.compressed(budget.saturating_sub(overhead), PriorityPolicy::default());
+    }
+    Prompt {
+        omitted: total - doc.claims().count(),
+        text: format!("{}\n{}", header(&doc), doc.to_wvf_string()),
+        doc,
+    }
+}
+
+/// Parse a `--budget` such as `2000`, `2000-tokens`, or `2000 tokens`
+pub fn parse_budget(value: &str) -> Result<usize, String> {
+    let count = value.trim();
+    let count = count
+        .strip_suffix("tokens")
+        .unwrap_or(count)
+        .trim_end_matches(['-', ' ']);
+    count.parse().map_err(|_| {
+        format!(
+            "expected a token count such as 2000 or 2000-tokens, found {:?}",
+            value
+        )
+    })
+}
+
+/// The preamble and the legend for the claims in `doc`
+fn header(doc: &Document) -> String {
+    let mut out = format!("{}\n", PREAMBLE);
+    let legend = legend(doc.claims());
+    if !legend.is_empty() {
+        out.push_str(&format!("Notation: {}.\n", legend.join(", ")));
+    }
+    out
+}
+
+/// Meanings of the operators and modifiers the claims use, in order of use
+fn legend<'d>(claims: impl Iterator<Item = &'d Claim>) -> Vec<String> {
+    let mut legend = Vec::new();
+    for claim in claims {
+        let data = &claim.data;
+        let operators = data
+            .brief_forms
+            .iter()
+            .filter_map(|b| Some((b.operator.to_string(), operator_meaning(b.operator)?)));
+        let modifiers = data
+            .modifiers
+            .iter()
+            .filter_map(|m| Some((m.symbol.to_string(), modifier_meaning(m.symbol)?)));
+        for (symbol, meaning) in operators.chain(modifiers) {
+            let item = format!("{} {}", symbol, meaning);
+            if !legend.contains(&item) {
+                legend.push(item);
+            }
+        }
+    }
+    legend
+}
@@ -0,0 +1,329 @@
+//! RDF rendering (Turtle or JSON-LD) for knowledge-graph tools
+//!
+//! Concepts, facets, and claims become resources; each claim links to its
+//! facet, conditions, references, and sources (as `prov:wasDerivedFrom`).
+//! Every brief form becomes a triple between operand terms scoped to the
+//! facet (`<Power.nature/concentration> wv:causes <Power.nature/abuse>`),
+//! reified as an `rdf:Statement` attached to its claim so provenance is kept.
+
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
+use worldview_validator::{Claim, Document, Facet, operator_meaning};
+
+const WV: &str = "urn:worldview:vocab#";
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const PREFIXES: &[(&str, &str)] = &[
+    ("wv", WV),
+    ("rdf", RDF),
+    ("rdfs", "http://www.w3.org/2000/01/rdf-schema#"),
+    ("prov", "http://www.w3.org/ns/prov#"),
+];
+
+#[derive(Clone)]
+enum Node {
+    /// Absolute IRI
+    Iri(String),
+    Blank(usize),
+    Literal(String),
+}
+
+struct Triple {
+    subject: Node,
+    /// Prefixed name (`wv:claim`)
+    predicate: String,
+    object: Node,
+}
+
+struct Graph {
+    base: String,
+    triples: Vec<Triple>,
+    blanks: usize,
+}
+
+impl Graph {
+    fn iri(&self, path: &str) -> Node {
+        Node::Iri(format!("{}{}", self.base, encode(path)))
+    }
+
+    fn add(&mut self, subject: &Node, predicate: &str, object: Node) {
+        self.triples.push(Triple {
+            subject: subject.clone(),
+            predicate: predicate.to_string(),
+            object,
+        });
+    }
+
+    fn blank(&mut self) -> Node {
+        self.blanks += 1;
+        Node::Blank(self.blanks)
+    }
+}
+
+/// Percent-encode everything but unreserved characters and path separators
+fn encode(path: &str) -> String {
+    let mut out = String::new();
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+/// `contrasts with` → `wv:contrastsWith`
+fn predicate(operator: &str) -> String {
+    let meaning = operator_meaning(operator).unwrap_or(operator);
+    let mut name = String::from("wv:");
+    for (i, word) in meaning.split_whitespace().enumerate() {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            match i {
+                0 => name.extend(first.to_lowercase()),
+                _ => name.extend(first.to_uppercase()),
+            }
+            name.push_str(chars.as_str());
+        }
+    }
+    name
+}
+
+fn graph(doc: &Document, base: &str) -> Graph {
+    let mut graph = Graph {
+        base: base.to_string(),
+        triples: Vec::new(),
+        blanks: 0,
+    };
+    for concept in &doc.concepts {
+        let subject = graph.iri(&concept.name);
+        graph.add(&subject, "rdf:type", Node::Iri(format!("{}Concept", WV)));
+        graph.add(&subject, "rdfs:label", Node::Literal(concept.name.clone()));
+        add_facets(&mut graph, &subject, &concept.facets, &concept.name);
+    }
+    for entry in doc.sources.iter().flat_map(|s| &s.entries) {
+        let subject = graph.iri(&format!("source/{}", entry.handle));
+        graph.add(&subject, "rdf:type", Node::Iri(format!("{}Source", WV)));
+        graph.add(&subject, "rdfs:label", Node::Literal(entry.handle.clone()));
+        if !entry.description.is_empty() {
+            graph.add(
+                &subject,
+                "rdfs:comment",
+                Node::Literal(entry.description.clone()),
+            );
+        }
+    }
+    graph
+}
+
+fn add_facets(graph: &mut Graph, parent: &Node, facets: &[Facet], parent_path: &str) {
+    for facet in facets {
+        let path = format!("{}.{}", parent_path, facet.name);
+        let subject = graph.iri(&path);
+        graph.add(parent, "wv:facet", subject.clone());
+        graph.add(&subject, "rdf:type", Node::Iri(format!("{}Facet", WV)));
+        graph.add(&subject, "rdfs:label", Node::Literal(facet.name.clone()));
+        for claim in &facet.claims {
+            add_claim(graph, &subject, &path, claim);
+        }
+        add_facets(graph, &subject, &facet.sub_facets, &path);
+    }
+}
+
+fn add_claim(graph: &mut Graph, facet: &Node, facet_path: &str, claim: &Claim) {
+    let data = &claim.data;
+    let subject = graph.iri(&format!("claim/{}", data.stable_id()));
+    graph.add(facet, "wv:claim", subject.clone());
+    graph.add(&subject, "rdf:type", Node::Iri(format!("{}Claim", WV)));
+    graph.add(&subject, "wv:text", Node::Literal(data.to_wvf_string()));
+    graph.add(&subject, "rdfs:label", Node::Literal(data.describe()));
+    for condition in &data.conditions {
+        graph.add(
+            &subject,
+            "wv:condition",
+            Node::Literal(condition.to_string()),
+        );
+    }
+    for reference in &data.references {
+        let target = graph.iri(reference);
+        graph.add(&subject, "wv:references", target);
+    }
+    for source in &data.sources {
+        let source = graph.iri(&format!("source/{}", source));
+        graph.add(&subject, "prov:wasDerivedFrom", source);
+    }
+    for marker in &data.evolution {
+        graph.add(
+            &subject,
+            "wv:supersedes",
+            Node::Literal(marker.prior_belief.to_string()),
+        );
+    }
+
+    for usage in &data.brief_forms {
+        if usage.left_operand.is_empty() || usage.right_operand.is_empty() {
+            continue;
+        }
+        let left = graph.iri(&format!("{}/{}", facet_path, usage.left_operand));
+        let right = graph.iri(&format!("{}/{}", facet_path, usage.right_operand));
+        let predicate = predicate(usage.operator);
+        for (term, label) in [(&left, &usage.left_operand), (&right, &usage.right_operand)] {
+            graph.add(term, "rdfs:label", Node::Literal(label.to_string()));
+        }
+        graph.add(&left, &predicate, right.clone());
+
+        let statement = graph.blank();
+        graph.add(&subject, "wv:asserts", statement.clone());
+        graph.add(
+            &statement,
+            "rdf:type",
+            Node::Iri(format!("{}Statement", RDF)),
+        );
+        graph.add(&statement, "rdf:subject", left);
+        graph.add(&statement, "rdf:predicate", Node::Iri(expand(&predicate)));
+        graph.add(&statement, "rdf:object", right);
+    }
+}
+
+/// `wv:claim` → full IRI
+fn expand(name: &str) -> String {
+    let (prefix, local) = name.split_once(':').unwrap_or(("", name));
+    match PREFIXES.iter().find(|(p, _)| *p == prefix) {
+        Some((_, namespace)) => format!("{}{}", namespace, local),
+        None => name.to_string(),
+    }
+}
+
+/// Triples grouped by subject in first-seen order, with duplicate triples dropped
+fn grouped(graph: &Graph) -> Vec<(&Node, Vec<(&str, &Node)>)> {
+    let mut groups: Vec<(&Node, Vec<(&str, &Node)>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut seen = HashSet::new();
+    for triple in &graph.triples {
+        let subject = key(&triple.subject);
+        if !seen.insert((subject.clone(), &triple.predicate, key(&triple.object))) {
+            continue;
+        }
+        let entry = (triple.predicate.as_str(), &triple.object);
+        match index.get(&subject) {
+            Some(&i) => groups[i].1.push(entry),
+            None => {
+                index.insert(subject, groups.len());
+                groups.push((&triple.subject, vec![entry]));
+            }
+        }
+    }
+    groups
+}
+
+fn key(node: &Node) -> String {
+    match node {
+        Node::Iri(iri) => format!("<{}>", iri),
+        Node::Blank(id) => format!("_:b{}", id),
+        Node::Literal(text) => format!("\"{}\"", text),
+    }
+}
+
+fn turtle_literal(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn turtle_node(node: &Node) -> String {
+    match node {
+        Node::Literal(text) => turtle_literal(text),
+        Node::Iri(iri) => compact(iri).unwrap_or_else(|| key(node)),
+        Node::Blank(_) => key(node),
+    }
+}
+
+/// Prefixed name for a vocabulary IRI (`wv:Claim`)
+fn compact(iri: &str) -> Option<String> {
+    PREFIXES.iter().find_map(|(prefix, namespace)| {
+        let local = iri.strip_prefix(namespace)?;
+        let simple = !local.is_empty() && local.chars().all(|c| c.is_ascii_alphanumeric());
+        simple.then(|| format!("{}:{}", prefix, local))
+    })
+}
+
+pub fn turtle(doc: &Document, base: &str) -> String {
+    let graph = graph(doc, base);
+    let mut out = String::new();
+    for (prefix, namespace) in PREFIXES {
+        out.push_str(&format!("@prefix {}: <{}> .\n", prefix, namespace));
+    }
+    for (subject, properties) in grouped(&graph) {
+        out.push('\n');
+        out.push_str(&turtle_node(subject));
+        for (i, (predicate, object)) in properties.iter().enumerate() {
+            let predicate = match *predicate {
+                "rdf:type" => "a",
+                other => other,
+            };
+            let separator = if i == 0 { "\n    " } else { " ;\n    " };
+            out.push_str(separator);
+            out.push_str(&format!("{} {}", predicate, turtle_node(object)));
+        }
+        out.push_str(" .\n");
+    }
+    out
+}
+
+pub fn json_ld(doc: &Document, base: &str) -> String {
+    let graph = graph(doc, base);
+    let context: Map<String, Value> = PREFIXES
+        .iter()
+        .map(|(prefix, namespace)| (prefix.to_string(), json!(namespace)))
+        .collect();
+
+    let nodes: Vec<Value> = grouped(&graph)
+        .into_iter()
+        .map(|(subject, properties)| {
+            let mut node = Map::new();
+            node.insert("@id".into(), json!(id(subject)));
+            for (predicate, object) in properties {
+                let (name, value) = match predicate {
+                    "rdf:type" => ("@type", json!(id(object))),
+                    _ => (
+                        predicate,
+                        match object {
+                            Node::Literal(text) => json!(text),
+                            _ => json!({ "@id": id(object) }),
+                        },
+                    ),
+                };
+                match node.get_mut(name) {
+                    Some(Value::Array(values)) => values.push(value),
+                    Some(existing) => *existing = json!([existing.take(), value]),
+                    None => {
+                        node.insert(name.into(), value);
+                    }
+                }
+            }
+            Value::Object(node)
+        })
+        .collect();
+
+    let document = json!({ "@context": context, "@graph": nodes });
+    serde_json::to_string_pretty(&document).unwrap_or_default() + "\n"
+}
+
+fn id(node: &Node) -> String {
+    match node {
+        Node::Iri(iri) => iri.clone(),
+        Node::Blank(id) => format!("_:b{}", id),
+        Node::Literal(text) => text.clone(),
+    }
+}
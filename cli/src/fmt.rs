@@ -0,0 +1,46 @@
+//! Fmt subcommand - rewrites a Worldview file in canonical form
+
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+use worldview_validator::{Document, ValidationError, expand_tabs, validate};
+
+use crate::validate;
+
+pub fn run(file: PathBuf, tabs_as: Option<usize>, check: bool) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+
+    let expanded = match tabs_as {
+        Some(width) => expand_tabs(&content, width),
+        None => {
+            let tabbed = validate(&content)
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::TabIndentation { .. }));
+            if tabbed {
+                bail!(
+                    "{} is indented with tabs; pass --tabs-as N to convert each tab to N spaces",
+                    file.display()
+                );
+            }
+            content.as_str().into()
+        }
+    };
+
+    let doc = Document::parse(&expanded);
+    let formatted = doc.to_wvf_string();
+
+    if check {
+        if formatted != content {
+            println!("{}: not formatted", file.display());
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if formatted != content {
+        validate::write_if_valid(&file, &formatted, doc.has_nested_facets())?;
+        println!("{}: formatted", file.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+//! History subcommand - shows how beliefs under a concept or facet changed, oldest first
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+pub fn run(target: String, file: PathBuf) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+
+    let changes: Vec<_> = doc
+        .evolution_timeline()
+        .into_iter()
+        .filter(|change| {
+            change
+                .target
+                .strip_prefix(target.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+        .collect();
+    if changes.is_empty() {
+        println!("{}: no belief changes under {}", file.display(), target);
+        return Ok(());
+    }
+
+    // One line per run of changes to a claim, with when each belief was adopted
+    for group in changes.chunk_by(|a, b| a.line == b.line) {
+        let mut drift = vec![group[0].from.clone()];
+        drift.extend(group.iter().map(|change| match change.changed_at {
+            Some(date) => format!("{} ({})", change.to, date),
+            None => change.to.clone(),
+        }));
+        println!("{} (line {}): {}", group[0].target, group[0].line, drift.join(" → "));
+    }
+    Ok(())
+}
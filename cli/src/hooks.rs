@@ -0,0 +1,96 @@
+//! Install-hooks subcommand - writes a git pre-commit hook that validates staged .wvf files
+
+use anyhow::{Context, Result, bail};
+use git2::Repository;
+use std::path::Path;
+
+/// First line after the shebang, identifying hooks this command may overwrite
+const MARKER: &str = "# Installed by `worldview install-hooks`";
+
+fn script(worldview: &str, fmt: bool, nested_facets: bool) -> String {
+    let validate = match nested_facets {
+        true => "validate --stdin --nested-facets",
+        false => "validate --stdin",
+    };
+    let mut script = format!(
+        r#"#!/bin/sh
+{MARKER}: blocks commits that stage invalid .wvf files
+worldview='{worldview}'
+status=0
+files=$(git diff --cached --name-only --diff-filter=ACMR -- '*.wvf')
+[ -z "$files" ] && exit 0
+
+echo "$files" | {{
+while IFS= read -r file; do
+    # Validate the staged contents, not the working tree
+    if ! output=$(git show ":$file" | "$worldview" {validate}); then
+        echo "$file:"
+        echo "$output"
+        status=1
+    fi
+"#
+    );
+    if fmt {
+        script.push_str(
+            r#"    if ! "$worldview" fmt --check --file "$file" >/dev/null; then
+        echo "$file: not formatted (run \`worldview fmt --file $file\`)"
+        status=1
+    fi
+"#,
+        );
+    }
+    script.push_str(
+        r#"done
+exit $status
+}
+"#,
+    );
+    script
+}
+
+pub fn run(fmt: bool, nested_facets: bool, force: bool) -> Result<()> {
+    let repo = Repository::discover(".").context("Not in a git repository")?;
+    let hooks_dir = match repo.config()?.get_path("core.hooksPath") {
+        Ok(path) if path.is_absolute() => path,
+        Ok(path) => repo.workdir().unwrap_or(repo.path()).join(path),
+        Err(_) => repo.path().join("hooks"),
+    };
+    let hook = hooks_dir.join("pre-commit");
+
+    if let Ok(existing) = std::fs::read_to_string(&hook)
+        && !existing.contains(MARKER)
+        && !force
+    {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            hook.display()
+        );
+    }
+
+    // Run this binary from the hook, so it works without worldview on PATH
+    let worldview = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.to_str().map(str::to_string))
+        .filter(|exe| !exe.contains('\''))
+        .unwrap_or_else(|| "worldview".to_string());
+
+    std::fs::create_dir_all(&hooks_dir)
+        .with_context(|| format!("Error creating {}", hooks_dir.display()))?;
+    std::fs::write(&hook, script(&worldview, fmt, nested_facets))
+        .with_context(|| format!("Error writing {}", hook.display()))?;
+    make_executable(&hook)?;
+    println!("{}: installed pre-commit hook", hook.display());
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Error making {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,278 @@
+//! Import subcommand - converts a structured (JSON/YAML) document, a Markdown
+//! or Org outline, a CSV/TSV table of claims, or a Notion database to Worldview, and
+//! has the agent triage Readwise highlights into one
+
+mod csv;
+#[cfg(feature = "notion")]
+mod notion;
+mod org;
+mod outline;
+#[cfg(feature = "readwise")]
+mod readwise;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use worldview_validator::{BuildError, Document, DocumentBuilder, Facet};
+
+use crate::add::{self, Apply, Options};
+use crate::structured::StructuredDocument;
+use crate::validate;
+
+/// Formats for `import`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    /// A document exported as JSON
+    Json,
+    /// A document exported as YAML
+    Yaml,
+    /// A Markdown bullet outline
+    Md,
+    /// An Org-mode outline, with modifiers as tags
+    Org,
+    /// Rows of `concept,facet,claim,condition,source`
+    Csv,
+    /// Tab-separated `concept,facet,claim,condition,source` rows
+    Tsv,
+    /// Rows of a Notion database, by --database ID
+    Notion,
+    /// Readwise highlights, triaged by the agent into the --into file
+    Readwise,
+}
+
+impl ImportFormat {
+    /// Infer the format from a file extension
+    pub fn from_path(path: &Path) -> Option<ImportFormat> {
+        match path.extension()?.to_str()? {
+            "json" => Some(ImportFormat::Json),
+            "yaml" | "yml" => Some(ImportFormat::Yaml),
+            "md" => Some(ImportFormat::Md),
+            "org" => Some(ImportFormat::Org),
+            "csv" => Some(ImportFormat::Csv),
+            "tsv" => Some(ImportFormat::Tsv),
+            _ => None,
+        }
+    }
+}
+
+/// An input line that could not be mapped
+pub struct Skipped {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// Why a claim is invalid on its own, if it is
+///
+/// References are not resolved: their targets may be elsewhere in the import,
+/// and the imported document is validated as a whole before it is written.
+fn claim_error(text: &str) -> Option<String> {
+    let built = DocumentBuilder::new()
+        .concept("Import")
+        .facet("claims")
+        .claim(text)
+        .build();
+    match built {
+        Err(BuildError::Invalid(errors)) => errors
+            .iter()
+            .find(|e| e.code() != "undefined-reference")
+            .map(|e| {
+                // Drop the "line N: " prefix, which refers to the probe document
+                let message = e.to_string();
+                message
+                    .split_once(": ")
+                    .map_or(message.clone(), |(_, rest)| rest.to_string())
+            }),
+        Err(e) => Some(e.to_string()),
+        Ok(_) => None,
+    }
+}
+
+pub fn run(
+    input: Option<PathBuf>,
+    format: Option<ImportFormat>,
+    output: Option<PathBuf>,
+    into: Option<PathBuf>,
+    database: Option<String>,
+    properties: Vec<String>,
+) -> Result<()> {
+    let (doc, skipped, origin) = match (format, input) {
+        (Some(ImportFormat::Notion), Some(input)) => {
+            bail!(
+                "Notion import reads a database, not {}; pass --database ID",
+                input.display()
+            )
+        }
+        (Some(ImportFormat::Notion), None) => {
+            let Some(database) = database else {
+                bail!("Notion import needs --database ID");
+            };
+            let (doc, skipped) = import_notion(&database, &properties)?;
+            (doc, skipped, format!("notion:{}", database))
+        }
+        (Some(ImportFormat::Readwise), _) => {
+            bail!("Readwise import adds highlights to a worldview; pass --into FILE")
+        }
+        (_, None) => bail!("No file to import"),
+        (format, Some(input)) => {
+            let (doc, skipped) = import_file(&input, format)?;
+            (doc, skipped, input.display().to_string())
+        }
+    };
+    for line in &skipped {
+        eprintln!("{}:{}: skipped: {}", origin, line.line, line.reason);
+    }
+
+    let (doc, output) = match into {
+        Some(target) => {
+            let existing = std::fs::read_to_string(&target)
+                .with_context(|| format!("Error reading {}", target.display()))?;
+            let mut merged = Document::parse(&existing);
+            let added = merge(&mut merged, doc);
+            eprintln!("{}: {} new claim(s)", target.display(), added);
+            (merged, Some(output.unwrap_or(target)))
+        }
+        None => (doc, output),
+    };
+    write(doc, &origin, output)
+}
+
+fn import_file(input: &Path, format: Option<ImportFormat>) -> Result<(Document, Vec<Skipped>)> {
+    let Some(format) = format.or_else(|| ImportFormat::from_path(input)) else {
+        bail!(
+            "Cannot tell the format of {} from its extension; pass --from",
+            input.display()
+        );
+    };
+    let content = std::fs::read_to_string(input)
+        .with_context(|| format!("Error reading {}", input.display()))?;
+    let (doc, skipped) = match format {
+        ImportFormat::Json => {
+            let structured: StructuredDocument = serde_json::from_str(&content)
+                .with_context(|| format!("Error parsing {}", input.display()))?;
+            (structured.into_document(), Vec::new())
+        }
+        ImportFormat::Yaml => {
+            let structured: StructuredDocument = serde_yaml::from_str(&content)
+                .with_context(|| format!("Error parsing {}", input.display()))?;
+            (structured.into_document(), Vec::new())
+        }
+        ImportFormat::Md => outline::parse(&content),
+        ImportFormat::Org => org::parse(&content),
+        ImportFormat::Csv => csv::parse(&content, b',')?,
+        ImportFormat::Tsv => csv::parse(&content, b'\t')?,
+        ImportFormat::Notion | ImportFormat::Readwise => {
+            bail!("{:?} import does not read a file", format)
+        }
+    };
+    Ok((doc, skipped))
+}
+
+/// Fetch Readwise highlights and have the agent add the durable beliefs among
+/// them to `into`, reporting which it added and which it skipped
+pub async fn run_readwise(into: PathBuf, since: Option<String>, options: &Options) -> Result<()> {
+    let facts = fetch_readwise(since.as_deref())?;
+    if facts.is_empty() {
+        println!("No highlights to import");
+        return Ok(());
+    }
+    eprintln!("{}: triaging {} highlight(s)", into.display(), facts.len());
+    add::run_batch(add::Task::Add, facts, into, options, Apply::Immediately).await
+}
+
+#[cfg(feature = "readwise")]
+fn fetch_readwise(since: Option<&str>) -> Result<Vec<String>> {
+    readwise::fetch(since)
+}
+
+#[cfg(not(feature = "readwise"))]
+fn fetch_readwise(_since: Option<&str>) -> Result<Vec<String>> {
+    bail!("--from readwise requires worldview to be built with the `readwise` feature")
+}
+
+#[cfg(feature = "notion")]
+fn import_notion(database: &str, properties: &[String]) -> Result<(Document, Vec<Skipped>)> {
+    let rows = notion::fetch(database)?;
+    notion::parse(&rows, properties)
+}
+
+#[cfg(not(feature = "notion"))]
+fn import_notion(_database: &str, _properties: &[String]) -> Result<(Document, Vec<Skipped>)> {
+    bail!("--from notion requires worldview to be built with the `notion` feature")
+}
+
+/// Add the claims of `from` missing from `into`, creating concepts and facets as needed
+fn merge(into: &mut Document, from: Document) -> usize {
+    let mut added = 0;
+    for concept in from.concepts {
+        match into.concept_mut(&concept.name) {
+            Some(existing) => {
+                added += merge_facets(&mut existing.facets, concept.facets);
+            }
+            None => {
+                added += count(&concept.facets);
+                into.concepts.push(concept);
+            }
+        }
+    }
+    if let Some(sources) = from.sources {
+        let registry = into.sources.get_or_insert_with(Default::default);
+        for entry in sources.entries {
+            if !registry.entries.iter().any(|e| e.handle == entry.handle) {
+                registry.entries.push(entry);
+            }
+        }
+    }
+    added
+}
+
+fn merge_facets(into: &mut Vec<Facet>, from: Vec<Facet>) -> usize {
+    let mut added = 0;
+    for facet in from {
+        match into.iter_mut().find(|f| f.name == facet.name) {
+            Some(existing) => {
+                for claim in facet.claims {
+                    let text = claim.data.to_wvf_string();
+                    if !existing
+                        .claims
+                        .iter()
+                        .any(|c| c.data.to_wvf_string() == text)
+                    {
+                        existing.claims.push(claim);
+                        added += 1;
+                    }
+                }
+                added += merge_facets(&mut existing.sub_facets, facet.sub_facets);
+            }
+            None => {
+                added += count(std::slice::from_ref(&facet));
+                into.push(facet);
+            }
+        }
+    }
+    added
+}
+
+fn count(facets: &[Facet]) -> usize {
+    facets
+        .iter()
+        .map(|f| f.claims.len() + count(&f.sub_facets))
+        .sum()
+}
+
+fn write(doc: Document, origin: &str, output: Option<PathBuf>) -> Result<()> {
+    let wvf = doc.to_wvf_string();
+    match output {
+        Some(path) => {
+            validate::write_if_valid(&path, &wvf, doc.has_nested_facets())?;
+            println!("{}: imported from {}", path.display(), origin);
+        }
+        None => {
+            let validation = doc.validate();
+            if !validation.is_valid() {
+                bail!("Imported document is invalid:\n{}", validation);
+            }
+            print!("{}", wvf);
+        }
+    }
+    Ok(())
+}
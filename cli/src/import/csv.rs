@@ -0,0 +1,113 @@
+//! CSV/TSV import: one claim per row as `concept,facet,claim,condition,source`
+//!
+//! A header row naming these columns may list them in any order. Several
+//! conditions are separated with `|` and several sources with spaces or `;`.
+
+use anyhow::{Context, Result};
+use worldview_validator::{Claim, Concept, Document, Facet};
+
+use super::{Skipped, claim_error};
+
+pub const COLUMNS: [&str; 5] = ["concept", "facet", "claim", "condition", "source"];
+
+pub fn parse(content: &str, delimiter: u8) -> Result<(Document, Vec<Skipped>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let mut doc = Document::default();
+    let mut skipped = Vec::new();
+    // Column index of each field in COLUMNS order
+    let mut order: [Option<usize>; 5] = [Some(0), Some(1), Some(2), Some(3), Some(4)];
+
+    for (i, record) in reader.records().enumerate() {
+        let record = record.context("Error reading CSV")?;
+        let line = record.position().map_or(i + 1, |p| p.line() as usize);
+        if i == 0 && is_header(&record) {
+            order = COLUMNS.map(|column| {
+                record.iter().position(|field| {
+                    field.eq_ignore_ascii_case(column)
+                        || field.eq_ignore_ascii_case(&format!("{}s", column))
+                })
+            });
+            continue;
+        }
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let field = |column: usize| order[column].and_then(|i| record.get(i)).unwrap_or("");
+        let [concept, facet, claim, condition, source] = [0, 1, 2, 3, 4].map(field);
+
+        let missing: Vec<&str> = [("concept", concept), ("facet", facet), ("claim", claim)]
+            .into_iter()
+            .filter(|(_, value)| value.is_empty())
+            .map(|(name, _)| name)
+            .collect();
+        if !missing.is_empty() {
+            skipped.push(Skipped {
+                line,
+                reason: format!("missing {}", missing.join(", ")),
+            });
+            continue;
+        }
+
+        let text = claim_text(claim, condition, source);
+        if let Some(reason) = claim_error(&text) {
+            skipped.push(Skipped { line, reason });
+            continue;
+        }
+        add_claim(&mut doc, concept, facet, Claim::parse(&text));
+    }
+    Ok((doc, skipped))
+}
+
+fn is_header(record: &csv::StringRecord) -> bool {
+    let fields: Vec<String> = record.iter().map(|f| f.to_lowercase()).collect();
+    ["concept", "facet", "claim"]
+        .iter()
+        .all(|column| fields.iter().any(|f| f == column))
+}
+
+/// Positional claim syntax from the claim, condition, and source cells
+pub fn claim_text(claim: &str, condition: &str, source: &str) -> String {
+    let mut text = claim.to_string();
+    for condition in condition
+        .split('|')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+    {
+        text.push_str(" | ");
+        text.push_str(condition);
+    }
+    for source in source
+        .split(|c: char| c == ';' || c.is_whitespace())
+        .map(|s| s.trim_start_matches('@'))
+        .filter(|s| !s.is_empty())
+    {
+        text.push_str(" @");
+        text.push_str(source);
+    }
+    text
+}
+
+/// Append a claim, creating its concept and facet on first use
+pub fn add_claim(doc: &mut Document, concept: &str, facet: &str, claim: Claim) {
+    let concept = match doc.concepts.iter().position(|c| c.name == concept) {
+        Some(i) => &mut doc.concepts[i],
+        None => {
+            doc.concepts.push(Concept::new(concept));
+            doc.concepts.last_mut().unwrap()
+        }
+    };
+    let facet = match concept.facets.iter().position(|f| f.name == facet) {
+        Some(i) => &mut concept.facets[i],
+        None => {
+            concept.facets.push(Facet::new(facet));
+            concept.facets.last_mut().unwrap()
+        }
+    };
+    facet.claims.push(claim);
+}
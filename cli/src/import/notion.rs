@@ -0,0 +1,246 @@
+//! Notion import: one claim per row of a Notion database
+//!
+//! Rows are read through the Notion API with the integration token in
+//! `NOTION_TOKEN`; the database must be shared with that integration. Each
+//! row's properties map to the CSV columns, `concept`, `facet`, `claim`,
+//! `condition`, and `source`. By default they are read from properties with
+//! those names (the claim from the row's title), and `--property FIELD=NAME`,
+//! or the `[notion]` config section, picks others:
+//!
+//! ```toml
+//! [notion]
+//! database = "1f3e7a0c9b2d4e5f8a6b7c8d9e0f1a2b"
+//! properties = { concept = "Topic", facet = "Aspect", source = "Reference" }
+//! ```
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value, json};
+use worldview_validator::{Claim, Document};
+
+use super::csv::{COLUMNS, add_claim, claim_text};
+use super::{Skipped, claim_error};
+
+/// Environment variable holding the Notion integration token
+pub const TOKEN_VAR: &str = "NOTION_TOKEN";
+
+/// Notion API version requested
+const API_VERSION: &str = "2022-06-28";
+
+/// Rows fetched per request (the API's maximum)
+const PAGE_SIZE: usize = 100;
+
+/// Every row of `database`, in the database's default order
+pub fn fetch(database: &str) -> Result<Vec<Value>> {
+    let token = std::env::var(TOKEN_VAR)
+        .with_context(|| format!("{} environment variable not set", TOKEN_VAR))?;
+    let url = format!(
+        "https://api.notion.com/v1/databases/{}/query",
+        database.replace('-', "")
+    );
+    // Read Notion's error messages rather than failing on the status alone
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .into();
+
+    let mut rows = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut body = json!({ "page_size": PAGE_SIZE });
+        if let Some(cursor) = &cursor {
+            body["start_cursor"] = json!(cursor);
+        }
+        let mut response = agent
+            .post(&url)
+            .header("Authorization", &format!("Bearer {}", token))
+            .header("Notion-Version", API_VERSION)
+            .send_json(body)
+            .with_context(|| format!("Notion request to {} failed", url))?;
+        let status = response.status();
+        let page: Value = response
+            .body_mut()
+            .read_json()
+            .context("Error reading the Notion response")?;
+        if !status.is_success() {
+            let message = page["message"].as_str().unwrap_or("no message");
+            bail!("Notion database {}: {} ({})", database, message, status);
+        }
+        if let Some(results) = page["results"].as_array() {
+            rows.extend(results.iter().cloned());
+        }
+        match page["next_cursor"].as_str() {
+            Some(next) if page["has_more"].as_bool() == Some(true) => {
+                cursor = Some(next.to_string());
+            }
+            _ => break,
+        }
+    }
+    Ok(rows)
+}
+
+/// Map rows to claims, with `mapping` as `FIELD=PROPERTY` pairs
+///
+/// Rows are numbered from 1 in skipped-row reports.
+pub fn parse(rows: &[Value], mapping: &[String]) -> Result<(Document, Vec<Skipped>)> {
+    let mut doc = Document::default();
+    let mut skipped = Vec::new();
+    let Some(first) = rows.first() else {
+        return Ok((doc, skipped));
+    };
+    let names = property_names(&properties(first), mapping)?;
+
+    for (i, row) in rows.iter().enumerate() {
+        let line = i + 1;
+        let properties = properties(row);
+        let field = |column: usize| {
+            names[column]
+                .as_ref()
+                .and_then(|name| properties.get(name))
+                .map(|value| text(value, column))
+                .unwrap_or_default()
+        };
+        let [concept, facet, claim, condition, source] = [0, 1, 2, 3, 4].map(field);
+
+        let missing: Vec<&str> = [("concept", &concept), ("facet", &facet), ("claim", &claim)]
+            .into_iter()
+            .filter(|(_, value)| value.is_empty())
+            .map(|(name, _)| name)
+            .collect();
+        if !missing.is_empty() {
+            skipped.push(Skipped {
+                line,
+                reason: format!("missing {}", missing.join(", ")),
+            });
+            continue;
+        }
+
+        let text = claim_text(&claim, &condition, &source);
+        if let Some(reason) = claim_error(&text) {
+            skipped.push(Skipped { line, reason });
+            continue;
+        }
+        add_claim(&mut doc, &concept, &facet, Claim::parse(&text));
+    }
+    Ok((doc, skipped))
+}
+
+fn properties(row: &Value) -> Map<String, Value> {
+    row["properties"].as_object().cloned().unwrap_or_default()
+}
+
+/// The property read for each field in COLUMNS order
+///
+/// A mapped property must exist. Unmapped fields use the property named like
+/// the field, in any case and optionally plural, and the claim falls back to
+/// the title property; the condition and source may have no property at all.
+fn property_names(
+    properties: &Map<String, Value>,
+    mapping: &[String],
+) -> Result<[Option<String>; 5]> {
+    let mut names: [Option<String>; 5] = Default::default();
+    for pair in mapping {
+        let Some((field, name)) = pair.split_once('=') else {
+            bail!("Invalid property mapping {}; expected FIELD=PROPERTY", pair);
+        };
+        let Some(column) = COLUMNS.iter().position(|c| *c == field.trim()) else {
+            bail!(
+                "Unknown field {} in property mapping; expected one of {}",
+                field,
+                COLUMNS.join(", ")
+            );
+        };
+        let name = name.trim();
+        if !properties.contains_key(name) {
+            let mut known: Vec<&str> = properties.keys().map(String::as_str).collect();
+            known.sort_unstable();
+            bail!(
+                "Notion database has no property {} (it has {})",
+                name,
+                known.join(", ")
+            );
+        }
+        names[column] = Some(name.to_string());
+    }
+
+    for (column, field) in COLUMNS.iter().enumerate() {
+        if names[column].is_some() {
+            continue;
+        }
+        names[column] = properties
+            .keys()
+            .find(|name| {
+                name.eq_ignore_ascii_case(field)
+                    || name.eq_ignore_ascii_case(&format!("{}s", field))
+            })
+            .cloned();
+    }
+    if names[2].is_none() {
+        names[2] = properties
+            .iter()
+            .find(|(_, value)| value["type"] == "title")
+            .map(|(name, _)| name.clone());
+    }
+    let missing: Vec<&str> = names[..3]
+        .iter()
+        .zip(COLUMNS)
+        .filter(|(name, _)| name.is_none())
+        .map(|(_, field)| field)
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Notion database has no property for {}; map one with --property {}=NAME",
+            missing.join(", "),
+            missing[0]
+        );
+    }
+    Ok(names)
+}
+
+/// A property value as plain text
+///
+/// Multi-selects list their values the way the CSV cells for `column` do:
+/// conditions separated with `|`, anything else with `;`.
+fn text(value: &Value, column: usize) -> String {
+    let separator = if COLUMNS[column] == "condition" {
+        " | "
+    } else {
+        "; "
+    };
+    let kind = value["type"].as_str().unwrap_or_default();
+    let inner = &value[kind];
+    let text = match kind {
+        "title" | "rich_text" => inner
+            .as_array()
+            .map(|spans| {
+                spans
+                    .iter()
+                    .filter_map(|span| span["plain_text"].as_str())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "select" | "status" => inner["name"].as_str().unwrap_or_default().to_string(),
+        "multi_select" => inner
+            .as_array()
+            .map(|options| {
+                options
+                    .iter()
+                    .filter_map(|option| option["name"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(separator)
+            })
+            .unwrap_or_default(),
+        "url" | "email" | "phone_number" => inner.as_str().unwrap_or_default().to_string(),
+        "number" => inner.as_number().map(|n| n.to_string()).unwrap_or_default(),
+        "formula" => match inner["type"].as_str() {
+            Some("string") => inner["string"].as_str().unwrap_or_default().to_string(),
+            Some("number") => inner["number"]
+                .as_number()
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        },
+        _ => String::new(),
+    };
+    // Notion text may span lines; a claim is one line
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,206 @@
+//! Org-mode import: level-1 headings are concepts, level-2 headings facets,
+//! level-3 headings sub-facets, and list items under a facet claims
+//!
+//! A claim's tags that name modifiers (`:uncertain:`) add the modifier when
+//! its text lacks it; other tags, TODO keywords, drawers, and `#` lines are
+//! dropped. A level-1 heading tagged `:sources:` holds the source registry as
+//! `- handle :: description` items.
+
+use worldview_validator::{Claim, Concept, Document, Facet, SourceEntry};
+
+use super::{Skipped, claim_error};
+use crate::export::org::{SOURCES_TAG, tag_modifier};
+
+/// A headline's level and title, without TODO keyword or tags
+fn headline(line: &str) -> Option<(usize, &str, Vec<&str>)> {
+    let level = line.len() - line.trim_start_matches('*').len();
+    if level == 0 || !line[level..].starts_with(' ') {
+        return None;
+    }
+    let (title, tags) = split_tags(line[level..].trim());
+    let title = ["TODO ", "DONE "]
+        .iter()
+        .find_map(|keyword| title.strip_prefix(keyword))
+        .unwrap_or(title);
+    Some((level, title.trim(), tags))
+}
+
+/// A list item's text, without its bullet or checkbox
+fn item(line: &str) -> Option<&str> {
+    let text = line.trim_start();
+    let rest = text
+        .strip_prefix("- ")
+        .or_else(|| text.strip_prefix("+ "))?;
+    let rest = ["[ ] ", "[X] ", "[-] "]
+        .iter()
+        .find_map(|checkbox| rest.strip_prefix(checkbox))
+        .unwrap_or(rest);
+    Some(rest.trim())
+}
+
+/// Text and trailing `:tag:tag:` of a headline or item
+fn split_tags(text: &str) -> (&str, Vec<&str>) {
+    if let Some((rest, last)) = text.rsplit_once([' ', '\t'])
+        && last.len() > 2
+        && last.starts_with(':')
+        && last.ends_with(':')
+    {
+        let tags: Vec<&str> = last[1..last.len() - 1].split(':').collect();
+        if tags.iter().all(|tag| !tag.is_empty()) {
+            return (rest.trim_end(), tags);
+        }
+    }
+    (text, Vec::new())
+}
+
+/// Claim text with the modifiers its tags name and its text lacks
+fn claim_text(text: &str, tags: &[&str]) -> String {
+    let mut data = Claim::parse(text).data;
+    let mut added = false;
+    for symbol in tags.iter().filter_map(|tag| tag_modifier(tag)) {
+        if !data.modifiers.iter().any(|m| m.symbol == symbol) {
+            data.text = format!("{} {}", data.text, symbol).into();
+            added = true;
+        }
+    }
+    if added {
+        data.to_wvf_string()
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn parse(content: &str) -> (Document, Vec<Skipped>) {
+    let mut doc = Document::default();
+    let mut skipped = Vec::new();
+    let mut in_sources = false;
+    // Whether the last heading was skipped, and its items with it
+    let mut under_skipped = false;
+    // Facet indices from the open concept down to the open facet or sub-facet
+    let mut path: Vec<usize> = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+        let trimmed = line.trim();
+        // Keywords, comments, and drawer lines
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(':') {
+            continue;
+        }
+        let skip = |reason: &str| Skipped {
+            line: number,
+            reason: reason.into(),
+        };
+
+        if let Some((level, title, tags)) = headline(line) {
+            under_skipped = true;
+            if level == 1 {
+                in_sources = tags.contains(&SOURCES_TAG);
+                path.clear();
+                if !in_sources {
+                    doc.concepts.push(Concept {
+                        line: number,
+                        ..Concept::from_heading(title)
+                    });
+                }
+                under_skipped = false;
+                continue;
+            }
+            let Some(concept) = doc.concepts.last_mut().filter(|_| !in_sources) else {
+                skipped.push(skip("heading outside a concept"));
+                continue;
+            };
+            if level > 3 {
+                skipped.push(skip("nested deeper than a sub-facet"));
+                continue;
+            }
+            if level - 2 > path.len() {
+                skipped.push(skip("heading skips a level"));
+                continue;
+            }
+            path.truncate(level - 2);
+            let siblings = if path.is_empty() {
+                &mut concept.facets
+            } else {
+                &mut facet_at(&mut concept.facets, &path).sub_facets
+            };
+            siblings.push(Facet {
+                line: number,
+                ..Facet::new(title)
+            });
+            path.push(siblings.len() - 1);
+            under_skipped = false;
+            continue;
+        }
+
+        let Some(text) = item(line) else {
+            skipped.push(skip("not a heading or list item"));
+            continue;
+        };
+        if under_skipped {
+            skipped.push(skip("under a skipped heading"));
+            continue;
+        }
+        if in_sources {
+            match text.split_once(" :: ") {
+                Some((handle, description)) => {
+                    let sources = doc.sources.get_or_insert_with(Default::default);
+                    sources.entries.push(SourceEntry {
+                        line: number,
+                        ..SourceEntry::new(handle.trim(), description.trim())
+                    });
+                }
+                None => skipped.push(skip("source is not `handle :: description`")),
+            }
+            continue;
+        }
+        let Some(concept) = doc.concepts.last_mut().filter(|_| !path.is_empty()) else {
+            skipped.push(skip("list item outside a facet"));
+            continue;
+        };
+        let (text, tags) = split_tags(text);
+        let text = claim_text(text, &tags);
+        match claim_error(&text) {
+            Some(reason) => skipped.push(Skipped {
+                line: number,
+                reason,
+            }),
+            None => facet_at(&mut concept.facets, &path)
+                .claims
+                .push(Claim::parse(&text)),
+        }
+    }
+
+    doc.concepts.retain_mut(|concept| {
+        prune(&mut concept.facets, &mut skipped);
+        if concept.facets.is_empty() {
+            skipped.push(Skipped {
+                line: concept.line,
+                reason: "concept has no facets".into(),
+            });
+        }
+        !concept.facets.is_empty()
+    });
+    skipped.sort_by_key(|s| s.line);
+    (doc, skipped)
+}
+
+fn facet_at<'a>(facets: &'a mut [Facet], path: &[usize]) -> &'a mut Facet {
+    let (first, rest) = path.split_first().expect("path names a facet");
+    rest.iter()
+        .fold(&mut facets[*first], |facet, &i| &mut facet.sub_facets[i])
+}
+
+/// Drop facets left without claims or sub-facets, innermost first
+fn prune(facets: &mut Vec<Facet>, skipped: &mut Vec<Skipped>) {
+    facets.retain_mut(|facet| {
+        prune(&mut facet.sub_facets, skipped);
+        let empty = facet.claims.is_empty() && facet.sub_facets.is_empty();
+        if empty {
+            skipped.push(Skipped {
+                line: facet.line,
+                reason: "facet has no claims".into(),
+            });
+        }
+        !empty
+    });
+}
@@ -0,0 +1,143 @@
+//! Markdown outline import: top-level bullets are concepts, the next level
+//! facets, and the level below that claims
+
+use worldview_validator::{Claim, Concept, Document, Facet};
+
+use super::{Skipped, claim_error};
+
+/// The bullet text of a list item, without its marker or task checkbox
+fn bullet(line: &str) -> Option<&str> {
+    let text = line.trim_start();
+    let rest = match text.strip_prefix(['-', '*', '+']) {
+        Some(rest) => rest,
+        None => {
+            let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                return None;
+            }
+            text[digits..].strip_prefix(['.', ')'])?
+        }
+    };
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let rest = rest.trim();
+    let rest = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find_map(|checkbox| rest.strip_prefix(checkbox))
+        .unwrap_or(rest);
+    Some(rest.trim())
+}
+
+/// Concept and facet names without list decoration (`**Power**:` is `Power`)
+fn name(text: &str) -> String {
+    text.trim_end_matches(':')
+        .trim_matches(|c| c == '*' || c == '_' || c == '`')
+        .trim()
+        .to_string()
+}
+
+pub fn parse(content: &str) -> (Document, Vec<Skipped>) {
+    let mut doc = Document::default();
+    let mut skipped = Vec::new();
+    // Indentation of each open level: concept, facet, claim
+    let mut indents: Vec<usize> = Vec::new();
+    // Outline line of the open concept and facet, for reporting empty ones
+    let mut concept_line = 0;
+    let mut facet_line = 0;
+
+    for (i, line) in content.lines().enumerate() {
+        let number = i + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(text) = bullet(line) else {
+            skipped.push(Skipped {
+                line: number,
+                reason: "not a list item".into(),
+            });
+            continue;
+        };
+        let indent = line.len() - line.trim_start().len();
+        while indents.last().is_some_and(|&open| open >= indent) {
+            indents.pop();
+        }
+        let level = indents.len();
+        if level > 2 {
+            skipped.push(Skipped {
+                line: number,
+                reason: "nested deeper than a claim".into(),
+            });
+            continue;
+        }
+        indents.push(indent);
+        if text.is_empty() {
+            skipped.push(Skipped {
+                line: number,
+                reason: "empty item".into(),
+            });
+            continue;
+        }
+
+        match level {
+            0 => {
+                close_concept(&mut doc, concept_line, facet_line, &mut skipped);
+                doc.concepts.push(Concept::new(name(text)));
+                concept_line = number;
+                facet_line = 0;
+            }
+            1 => {
+                close_facet(&mut doc, facet_line, &mut skipped);
+                if let Some(concept) = doc.concepts.last_mut() {
+                    concept.facets.push(Facet::new(name(text)));
+                    facet_line = number;
+                }
+            }
+            _ => match claim_error(text) {
+                Some(reason) => skipped.push(Skipped {
+                    line: number,
+                    reason,
+                }),
+                None => {
+                    if let Some(facet) = doc.concepts.last_mut().and_then(|c| c.facets.last_mut()) {
+                        facet.claims.push(Claim::parse(text));
+                    }
+                }
+            },
+        }
+    }
+    close_concept(&mut doc, concept_line, facet_line, &mut skipped);
+    skipped.sort_by_key(|s| s.line);
+    (doc, skipped)
+}
+
+/// Drop the last facet if no claims made it in
+fn close_facet(doc: &mut Document, facet_line: usize, skipped: &mut Vec<Skipped>) {
+    let Some(concept) = doc.concepts.last_mut() else {
+        return;
+    };
+    if concept.facets.last().is_some_and(|f| f.claims.is_empty()) {
+        concept.facets.pop();
+        skipped.push(Skipped {
+            line: facet_line,
+            reason: "facet has no claims".into(),
+        });
+    }
+}
+
+/// Drop the last concept if no facets made it in
+fn close_concept(
+    doc: &mut Document,
+    concept_line: usize,
+    facet_line: usize,
+    skipped: &mut Vec<Skipped>,
+) {
+    close_facet(doc, facet_line, skipped);
+    if doc.concepts.last().is_some_and(|c| c.facets.is_empty()) {
+        doc.concepts.pop();
+        skipped.push(Skipped {
+            line: concept_line,
+            reason: "concept has no facets".into(),
+        });
+    }
+}
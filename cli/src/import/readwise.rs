@@ -0,0 +1,124 @@
+//! Readwise import: highlights triaged into a worldview by the agent
+//!
+//! Highlights are read through the Readwise export API with the access token
+//! in `READWISE_TOKEN`. A highlight is a passage someone else wrote, so each
+//! one, with its book and any note on it, goes to the agent as a fact to add:
+//! it records the beliefs the highlight states and turns down plot points,
+//! passing details, and claims the file already holds.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+/// Environment variable holding the Readwise access token
+pub const TOKEN_VAR: &str = "READWISE_TOKEN";
+
+const EXPORT_URL: &str = "https://readwise.io/api/v2/export/";
+
+/// One page of the export: books with their highlights
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Export {
+    results: Vec<Book>,
+    next_page_cursor: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Book {
+    #[serde(alias = "readable_title")]
+    title: String,
+    author: Option<String>,
+    highlights: Vec<Highlight>,
+}
+
+#[derive(Deserialize)]
+struct Highlight {
+    text: String,
+    note: Option<String>,
+    #[serde(default)]
+    is_discard: bool,
+}
+
+/// Highlights updated after `since` (all of them by default), as facts for
+/// the agent, in the order Readwise lists them
+pub fn fetch(since: Option<&str>) -> Result<Vec<String>> {
+    let token = std::env::var(TOKEN_VAR)
+        .with_context(|| format!("{} environment variable not set", TOKEN_VAR))?;
+    // Read Readwise's error messages rather than failing on the status alone
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .into();
+
+    let mut facts = Vec::new();
+    let mut cursor: Option<String> = None;
+    loop {
+        let mut request = agent
+            .get(EXPORT_URL)
+            .header("Authorization", &format!("Token {}", token));
+        if let Some(since) = since {
+            request = request.query("updatedAfter", since);
+        }
+        if let Some(cursor) = &cursor {
+            request = request.query("pageCursor", cursor);
+        }
+        let mut response = request
+            .call()
+            .with_context(|| format!("Readwise request to {} failed", EXPORT_URL))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            bail!("Readwise export: {} ({})", body.trim(), status);
+        }
+        let page: Export = response
+            .body_mut()
+            .read_json()
+            .context("Error reading the Readwise export")?;
+        for book in &page.results {
+            facts.extend(
+                book.highlights
+                    .iter()
+                    .filter(|highlight| !highlight.is_discard)
+                    .map(|highlight| fact(book, highlight)),
+            );
+        }
+        // Cursors are numbers in some responses and strings in others
+        cursor = match page.next_page_cursor {
+            Some(serde_json::Value::String(next)) => Some(next),
+            Some(serde_json::Value::Number(next)) => Some(next.to_string()),
+            _ => None,
+        };
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(facts)
+}
+
+/// The request for one highlight: the passage on the first line, so batch
+/// progress can show it, then where it is from and what to do with it
+fn fact(book: &Book, highlight: &Highlight) -> String {
+    let text = highlight
+        .text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut fact = format!("\"{}\"\n\nHighlighted in \"{}\"", text, book.title);
+    if let Some(author) = book.author.as_deref().filter(|a| !a.is_empty()) {
+        fact.push_str(&format!(" by {}", author));
+    }
+    fact.push('.');
+    if let Some(note) = highlight
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+    {
+        fact.push_str(&format!(" My note on it: {}", note));
+    }
+    fact.push_str(
+        "\n\n(Imported from my reading highlights: record only a durable belief the passage states, \
+         citing the book as its source, and add nothing if it is a plot point, a passing detail, \
+         or already in the file.)",
+    );
+    fact
+}
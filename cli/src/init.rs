@@ -0,0 +1,115 @@
+//! Init subcommand - starts a Worldview file from a template, with a project config
+//!
+//! The templates live in `cli/templates/`. Each is validated with the settings
+//! its config sets before anything is written, and an existing
+//! `.worldview.toml` is kept.
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use worldview_validator::{Profile, ValidationOptions};
+
+use crate::config::PROJECT_FILE;
+
+/// Starter layouts for `init`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Template {
+    /// Values, relationships, and work
+    #[default]
+    Personal,
+    /// A team's mission, norms, process, and decisions
+    Team,
+    /// Questions, findings, and methods, with sources required and a source registry
+    Research,
+}
+
+impl Template {
+    fn content(self) -> &'static str {
+        match self {
+            Template::Personal => include_str!("../templates/personal.wvf"),
+            Template::Team => include_str!("../templates/team.wvf"),
+            Template::Research => include_str!("../templates/research.wvf"),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Template::Personal => "personal",
+            Template::Team => "team",
+            Template::Research => "research",
+        }
+    }
+
+    /// Lint profile and concepts whose claims must cite a source
+    fn lint(self) -> (Profile, &'static [&'static str]) {
+        match self {
+            Template::Personal | Template::Team => (Profile::Standard, &[]),
+            Template::Research => (Profile::Strict, &["Findings", "Methods"]),
+        }
+    }
+
+    fn options(self) -> ValidationOptions {
+        let (profile, require_sources) = self.lint();
+        ValidationOptions {
+            require_sources: require_sources.iter().map(|c| c.to_string()).collect(),
+            ..profile.options()
+        }
+    }
+
+    /// `.worldview.toml` pointing commands at `file`
+    fn config(self, file: &Path) -> String {
+        let (profile, require_sources) = self.lint();
+        let file_name = file
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let mut config = format!(
+            "# Created by `worldview init --template {}`\nfile = {:?}\n\n[lint]\nprofile = \"{}\"\n",
+            self.name(),
+            file_name,
+            profile
+        );
+        if !require_sources.is_empty() {
+            let concepts: Vec<String> =
+                require_sources.iter().map(|c| format!("{:?}", c)).collect();
+            config.push_str(&format!("require-source = [{}]\n", concepts.join(", ")));
+        }
+        config
+    }
+}
+
+pub fn run(template: Template, file: PathBuf, force: bool) -> Result<()> {
+    if file.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to replace it",
+            file.display()
+        );
+    }
+
+    let content = template.content();
+    let result = worldview_validator::validate_with_options(content, &template.options());
+    if !result.is_valid() || result.has_warnings() {
+        bail!(
+            "The {} template does not validate:\n{}",
+            template.name(),
+            result
+        );
+    }
+
+    std::fs::write(&file, content).with_context(|| format!("Error writing {}", file.display()))?;
+    println!(
+        "{}: created from the {} template",
+        file.display(),
+        template.name()
+    );
+
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let config = dir.join(PROJECT_FILE);
+    if config.exists() {
+        println!("{}: kept existing config", config.display());
+    } else {
+        std::fs::write(&config, template.config(&file))
+            .with_context(|| format!("Error writing {}", config.display()))?;
+        println!("{}: created", config.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Provenance journal of agent edits (`worldview.wvf.journal`)
+//!
+//! With `--journal`, or once the journal exists beside the file, every agent
+//! edit that reaches the file appends one JSON line: which command, provider,
+//! and model made it, the user's original text, and the claims it added and
+//! removed, keyed by `Concept.facet`. `worldview blame` reads it back.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use worldview_validator::{Document, Facet};
+
+/// One agent request whose edits were written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub time_ms: u64,
+    /// `add`, `revise`, `remove`, `chat`, or `suggest-split`
+    pub command: String,
+    pub provider: String,
+    pub model: String,
+    /// The fact, change, or description as the user gave it
+    pub request: String,
+    #[serde(default)]
+    pub added: Vec<JournalClaim>,
+    #[serde(default)]
+    pub removed: Vec<JournalClaim>,
+}
+
+/// A claim and the facet or sub-facet it is under
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct JournalClaim {
+    pub target: String,
+    pub claim: String,
+}
+
+impl Entry {
+    /// An entry for the claims that differ between `old` and `new`; `None` if none do
+    pub fn new(
+        command: &str,
+        provider: &str,
+        model: &str,
+        request: &str,
+        old: &str,
+        new: &str,
+    ) -> Option<Entry> {
+        let mut added = claims(new);
+        let mut removed = Vec::new();
+        for claim in claims(old) {
+            match added.iter().position(|other| *other == claim) {
+                Some(i) => {
+                    added.remove(i);
+                }
+                None => removed.push(claim),
+            }
+        }
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+        let time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        Some(Entry {
+            time_ms,
+            command: command.to_string(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            request: request.to_string(),
+            added,
+            removed,
+        })
+    }
+}
+
+/// `worldview.wvf` → `worldview.wvf.journal`
+pub fn path_for(file: &Path) -> PathBuf {
+    let mut path = file.as_os_str().to_owned();
+    path.push(".journal");
+    PathBuf::from(path)
+}
+
+/// Every claim of `content`, in document order
+pub fn claims(content: &str) -> Vec<JournalClaim> {
+    fn collect(facet: &Facet, target: String, out: &mut Vec<JournalClaim>) {
+        for claim in &facet.claims {
+            out.push(JournalClaim {
+                target: target.clone(),
+                claim: claim.data.to_wvf_string(),
+            });
+        }
+        for sub_facet in &facet.sub_facets {
+            collect(sub_facet, format!("{}.{}", target, sub_facet.name), out);
+        }
+    }
+    let doc = Document::parse(content);
+    let mut claims = Vec::new();
+    for concept in &doc.concepts {
+        for facet in &concept.facets {
+            collect(
+                facet,
+                format!("{}.{}", concept.name, facet.name),
+                &mut claims,
+            );
+        }
+    }
+    claims
+}
+
+/// Append entries to the journal at `path`, creating it if needed
+pub fn append(path: &Path, entries: &[Entry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Error opening {}", path.display()))?;
+    for entry in entries {
+        let line = serde_json::to_string(entry)?;
+        writeln!(file, "{}", line).with_context(|| format!("Error writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Entries of the journal at `path`, oldest first; none if it does not exist
+pub fn read(path: &Path) -> Result<Vec<Entry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Error reading {}", path.display()))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("{}:{}: invalid journal entry", path.display(), i + 1))
+        })
+        .collect()
+}
@@ -0,0 +1,114 @@
+//! JUnit XML output for `validate --format junit`
+//!
+//! One test case per file: a file with errors fails, listing them, and one
+//! that could not be read is an error. Warnings go to the test case's
+//! `system-out`, so CI dashboards show them without failing the build.
+
+use std::fmt::Write;
+use worldview_validator::ValidationError;
+
+use crate::validate::Checked;
+
+/// The JUnit report for one `validate` run
+pub fn report(checked: &Checked) -> String {
+    let failures = checked
+        .files
+        .iter()
+        .filter(|(_, result)| !result.is_valid())
+        .count();
+    let tests = checked.files.len() + checked.unreadable.len();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"worldview validate\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+        tests,
+        failures,
+        checked.unreadable.len()
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"worldview validate\" tests=\"{}\" failures=\"{}\" errors=\"{}\">",
+        tests,
+        failures,
+        checked.unreadable.len()
+    );
+
+    for (name, result) in &checked.files {
+        let _ = writeln!(
+            xml,
+            "    <testcase classname=\"worldview.validate\" name=\"{}\">",
+            escape(name)
+        );
+        if !result.is_valid() {
+            let count = result.errors.len();
+            let plural = if count == 1 { "" } else { "s" };
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"{} error{}\" type=\"{}\">{}</failure>",
+                count,
+                plural,
+                result.errors[0].code(),
+                escape(&lines(name, &result.errors))
+            );
+        }
+        if !result.warnings.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                escape(&lines(name, &result.warnings))
+            );
+        }
+        xml.push_str("    </testcase>\n");
+    }
+
+    for (name, error) in &checked.unreadable {
+        let _ = writeln!(
+            xml,
+            "    <testcase classname=\"worldview.validate\" name=\"{}\">",
+            escape(name)
+        );
+        let _ = writeln!(
+            xml,
+            "      <error message=\"{}\" type=\"io\"/>",
+            escape(&format!("Error reading {}: {}", name, error))
+        );
+        xml.push_str("    </testcase>\n");
+    }
+
+    xml.push_str("  </testsuite>\n</testsuites>");
+    xml
+}
+
+/// `worldview.wvf:5: [undefined-reference] ...`, one per line
+fn lines(name: &str, diagnostics: &[ValidationError]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| {
+            let message = d.to_string();
+            let message = message
+                .strip_prefix(&format!("line {}: ", d.line()))
+                .unwrap_or(&message)
+                .to_string();
+            format!("{}:{}: [{}] {}", name, d.line(), d.code(), message)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newline are not allowed in XML 1.0
+            c if c.is_control() && c != '\t' && c != '\n' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
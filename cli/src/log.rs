@@ -0,0 +1,104 @@
+//! Log subcommand - a belief-change timeline from the git history of a file
+//!
+//! Follows first parents from HEAD, oldest first, and diffs each revision of the
+//! file that differs from the one before it.
+
+use anyhow::{Context, Result, bail};
+use git2::{Oid, Repository, Sort};
+use std::path::{Path, PathBuf};
+use worldview_validator::Document;
+
+pub fn run(file: PathBuf) -> Result<()> {
+    let dir = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let repo = Repository::discover(dir)
+        .with_context(|| format!("{} is not in a git repository", file.display()))?;
+    let Some(workdir) = repo.workdir() else {
+        bail!("Cannot read history from a bare repository");
+    };
+    let absolute = std::fs::canonicalize(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let path = absolute
+        .strip_prefix(workdir.canonicalize()?)
+        .with_context(|| format!("{} is outside the repository", file.display()))?;
+
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.simplify_first_parent()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+    let mut previous: Option<Oid> = None;
+    let mut old = Document::default();
+    let mut revisions = 0;
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let blob = commit.tree()?.get_path(path).ok().map(|entry| entry.id());
+        if blob == previous {
+            continue;
+        }
+        previous = blob;
+
+        let new = match blob {
+            Some(id) => Document::parse(&String::from_utf8_lossy(repo.find_blob(id)?.content())),
+            None => Document::default(),
+        };
+        let summary = match blob {
+            None => "deleted".to_string(),
+            Some(_) => summarize(&old.diff(&new)),
+        };
+        let id = commit.id().to_string();
+        println!("{} {}: {}", date(&commit.time()), &id[..7], summary);
+        old = new;
+        revisions += 1;
+    }
+    if revisions == 0 {
+        println!("{}: no committed history", file.display());
+    }
+    Ok(())
+}
+
+/// Changes joined in order, with repeats counted
+pub fn summarize(changes: &[worldview_validator::Change]) -> String {
+    if changes.is_empty() {
+        return "no structural changes".to_string();
+    }
+    let mut counted: Vec<(String, usize)> = Vec::new();
+    for change in changes {
+        let text = change.to_string();
+        match counted.iter_mut().find(|(t, _)| *t == text) {
+            Some((_, count)) => *count += 1,
+            None => counted.push((text, 1)),
+        }
+    }
+    counted
+        .into_iter()
+        .map(|(text, count)| match count {
+            1 => text,
+            _ => format!("{} (×{})", text, count),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Commit date in the committer's time zone, as `YYYY-MM-DD`
+fn date(time: &git2::Time) -> String {
+    civil_date(time.seconds() + i64::from(time.offset_minutes()) * 60)
+}
+
+/// `YYYY-MM-DD` for seconds since the Unix epoch
+pub fn civil_date(seconds: i64) -> String {
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let days = seconds.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,421 @@
+//! LSP subcommand - language server for .wvf files over stdio
+//!
+//! Provides live diagnostics from the validator, go-to-definition for
+//! `&Concept.facet` references, completion for reference targets and claim
+//! operators, hover text for modifiers and brief forms, and semantic tokens
+//! for highlighting.
+
+use anyhow::Result;
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, GotoDefinition, HoverRequest, Request as RequestTrait, SemanticTokensFullRequest,
+};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, Position,
+    PublishDiagnosticsParams, Range, SemanticToken, SemanticTokenType, SemanticTokens,
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions, SemanticTokensParams,
+    SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use worldview_validator::{
+    BRIEF_FORMS, Document, MODIFIERS, Reference, TokenKind, ValidationOptions, tokenize,
+};
+
+/// Open documents, keyed by URI
+type Documents = HashMap<Url, String>;
+
+/// Semantic token types, indexed by `token_type_index`
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::MACRO,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::STRING,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::DECORATOR,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::COMMENT,
+];
+
+fn token_type_index(kind: TokenKind) -> u32 {
+    match kind {
+        TokenKind::Version | TokenKind::Include | TokenKind::SourcesSection => 0,
+        TokenKind::ConceptName => 1,
+        TokenKind::FacetName => 2,
+        TokenKind::ClaimText => 3,
+        TokenKind::Operator => 4,
+        TokenKind::Modifier => 5,
+        TokenKind::Condition => 6,
+        TokenKind::Source | TokenKind::SourceDefinition => 7,
+        TokenKind::Reference => 8,
+        TokenKind::EvolutionMarker | TokenKind::ClaimId => 9,
+    }
+}
+
+pub fn run(options: ValidationOptions) -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["&".to_string(), ".".to_string()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: SemanticTokensLegend {
+                    token_types: TOKEN_TYPES.to_vec(),
+                    token_modifiers: Vec::new(),
+                },
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut documents = Documents::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = handle_request(request, &documents)?;
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(uri) = handle_notification(notification, &mut documents)? {
+                    let diagnostics = publish_diagnostics(&uri, &documents, &options)?;
+                    connection.sender.send(Message::Notification(diagnostics))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    // The writer thread exits once the connection's sender is dropped
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+fn handle_request(request: Request, documents: &Documents) -> Result<Response> {
+    let id = request.id.clone();
+    let result = match request.method.as_str() {
+        GotoDefinition::METHOD => {
+            let (_, params) = request.extract::<GotoDefinitionParams>(GotoDefinition::METHOD)?;
+            serde_json::to_value(definition(params, documents))?
+        }
+        Completion::METHOD => {
+            let (_, params) = request.extract::<CompletionParams>(Completion::METHOD)?;
+            serde_json::to_value(completion(params, documents))?
+        }
+        HoverRequest::METHOD => {
+            let (_, params) = request.extract::<HoverParams>(HoverRequest::METHOD)?;
+            serde_json::to_value(hover(params, documents))?
+        }
+        SemanticTokensFullRequest::METHOD => {
+            let (_, params) =
+                request.extract::<SemanticTokensParams>(SemanticTokensFullRequest::METHOD)?;
+            serde_json::to_value(semantic_tokens(params, documents))?
+        }
+        _ => serde_json::Value::Null,
+    };
+    Ok(Response::new_ok(id, result))
+}
+
+/// Track document contents; returns the URI whose diagnostics need refreshing
+fn handle_notification(
+    notification: Notification,
+    documents: &mut Documents,
+) -> Result<Option<Url>> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.clone(), params.text_document.text);
+            Ok(Some(uri))
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+            let uri = params.text_document.uri;
+            // Full sync: the last change holds the whole document
+            if let Some(change) = params.content_changes.into_iter().last() {
+                documents.insert(uri.clone(), change.text);
+            }
+            Ok(Some(uri))
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            documents.remove(&params.text_document.uri);
+            Ok(None)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn publish_diagnostics(
+    uri: &Url,
+    documents: &Documents,
+    options: &ValidationOptions,
+) -> Result<Notification> {
+    let text = documents.get(uri).map(String::as_str).unwrap_or("");
+    let result = match uri.to_file_path() {
+        Ok(path) => worldview_validator::validate_in_context(text, &path, options),
+        Err(_) => worldview_validator::validate_with_options(text, options),
+    };
+
+    let lines: Vec<&str> = text.lines().collect();
+    let diagnostics = result
+        .errors
+        .iter()
+        .chain(&result.warnings)
+        .map(|error| {
+            let line = error.line().saturating_sub(1);
+            let width = lines.get(line).map_or(0, |l| utf16_len(l));
+            Diagnostic {
+                range: Range::new(
+                    Position::new(line as u32, 0),
+                    Position::new(line as u32, width),
+                ),
+                severity: Some(if error.is_warning() {
+                    DiagnosticSeverity::WARNING
+                } else {
+                    DiagnosticSeverity::ERROR
+                }),
+                source: Some("worldview".to_string()),
+                message: error.to_string(),
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    let params = PublishDiagnosticsParams {
+        uri: uri.clone(),
+        diagnostics,
+        version: None,
+    };
+    Ok(Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        params,
+    ))
+}
+
+fn definition(
+    params: GotoDefinitionParams,
+    documents: &Documents,
+) -> Option<GotoDefinitionResponse> {
+    let position = params.text_document_position_params;
+    let uri = position.text_document.uri;
+    let text = documents.get(&uri)?;
+    let word = word_at(text, position.position)?;
+    let reference = Reference::parse(word.strip_prefix('&')?)?;
+    let target = reference.target();
+
+    for (file_uri, doc) in scope(&uri, text) {
+        let namespace_matches = reference.namespace.is_none()
+            || file_uri
+                .to_file_path()
+                .ok()
+                .and_then(|p| worldview_validator::namespace_for(&p))
+                == reference.namespace;
+        if !namespace_matches {
+            continue;
+        }
+        if let Some(line) = doc.definition_line(&target) {
+            let start = Position::new(line.saturating_sub(1) as u32, 0);
+            return Some(GotoDefinitionResponse::Scalar(Location {
+                uri: file_uri,
+                range: Range::new(start, start),
+            }));
+        }
+    }
+    None
+}
+
+fn completion(params: CompletionParams, documents: &Documents) -> Option<CompletionResponse> {
+    let position = params.text_document_position;
+    let uri = position.text_document.uri;
+    let text = documents.get(&uri)?;
+    let line = text
+        .lines()
+        .nth(position.position.line as usize)
+        .unwrap_or("");
+    let before = &line[..byte_offset(line, position.position.character)];
+    let current = before.rsplit(char::is_whitespace).next().unwrap_or("");
+
+    let items = if current.starts_with('&') {
+        let mut targets: Vec<String> = scope(&uri, text)
+            .into_iter()
+            .flat_map(|(_, doc)| doc.reference_targets())
+            .map(|(target, _)| target)
+            .collect();
+        targets.sort();
+        targets.dedup();
+        targets
+            .into_iter()
+            .map(|target| CompletionItem {
+                label: target,
+                kind: Some(CompletionItemKind::REFERENCE),
+                ..Default::default()
+            })
+            .collect()
+    } else if line.trim_start().starts_with('-') {
+        BRIEF_FORMS
+            .iter()
+            .map(|(symbol, meaning)| (symbol, meaning, CompletionItemKind::OPERATOR))
+            .chain(
+                MODIFIERS
+                    .iter()
+                    .map(|(symbol, meaning)| (symbol, meaning, CompletionItemKind::KEYWORD)),
+            )
+            .map(|(symbol, meaning, kind)| CompletionItem {
+                label: symbol.to_string(),
+                kind: Some(kind),
+                detail: Some(meaning.to_string()),
+                ..Default::default()
+            })
+            .collect()
+    } else {
+        return None;
+    };
+    Some(CompletionResponse::Array(items))
+}
+
+fn hover(params: HoverParams, documents: &Documents) -> Option<Hover> {
+    let position = params.text_document_position_params;
+    let text = documents.get(&position.text_document.uri)?;
+    let word = word_at(text, position.position)?;
+
+    let (kind, symbol, meaning) =
+        if let Some((symbol, meaning)) = BRIEF_FORMS.iter().find(|(s, _)| *s == word) {
+            ("brief form", symbol, meaning)
+        } else {
+            // Modifiers stand alone or trail a word (`collapse?`); `v` only stands alone
+            let (symbol, meaning) = MODIFIERS.iter().find(|(symbol, _)| {
+                word == *symbol
+                    || (!symbol.chars().all(char::is_alphanumeric) && word.ends_with(symbol))
+            })?;
+            ("modifier", symbol, meaning)
+        };
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: format!("`{}` ({}): {}", symbol, kind, meaning),
+        }),
+        range: None,
+    })
+}
+
+/// Delta-encoded highlighting tokens for the whole document
+fn semantic_tokens(
+    params: SemanticTokensParams,
+    documents: &Documents,
+) -> Option<SemanticTokensResult> {
+    let text = documents.get(&params.text_document.uri)?;
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+
+    let mut data = Vec::new();
+    let (mut previous_line, mut previous_start) = (0, 0);
+    for token in tokenize(text) {
+        let line = (token.line - 1) as u32;
+        let line_start = line_starts[token.line - 1];
+        let start = utf16_len(&text[line_start..token.span.start]);
+        let delta_start = if line == previous_line {
+            start - previous_start
+        } else {
+            start
+        };
+        data.push(SemanticToken {
+            delta_line: line - previous_line,
+            delta_start,
+            length: utf16_len(token.text(text)),
+            token_type: token_type_index(token.kind),
+            token_modifiers_bitset: 0,
+        });
+        (previous_line, previous_start) = (line, start);
+    }
+
+    Some(SemanticTokensResult::Tokens(SemanticTokens {
+        result_id: None,
+        data,
+    }))
+}
+
+/// The open document plus the files it includes and its sibling `.wvf` files
+fn scope(uri: &Url, text: &str) -> Vec<(Url, Document)> {
+    let doc = Document::parse(text);
+    let mut files = Vec::new();
+
+    if let Ok(path) = uri.to_file_path() {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut others: Vec<PathBuf> = doc.includes.iter().map(|i| dir.join(&i.path)).collect();
+        let mut siblings: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p != &path && p.extension().is_some_and(|ext| ext == "wvf"))
+            .collect();
+        siblings.sort();
+        others.extend(siblings);
+
+        for other in others {
+            let (Ok(content), Ok(other_uri)) =
+                (std::fs::read_to_string(&other), Url::from_file_path(&other))
+            else {
+                continue;
+            };
+            files.push((other_uri, Document::parse(&content)));
+        }
+    }
+
+    files.insert(0, (uri.clone(), doc));
+    files
+}
+
+/// The whitespace-delimited word at a position
+fn word_at(text: &str, position: Position) -> Option<&str> {
+    let line = text.lines().nth(position.line as usize)?;
+    let offset = byte_offset(line, position.character);
+    let start = line[..offset]
+        .rfind(char::is_whitespace)
+        .map_or(0, |i| i + 1);
+    let end = line[offset..]
+        .find(char::is_whitespace)
+        .map_or(line.len(), |i| offset + i);
+    let word = &line[start..end];
+    (!word.is_empty()).then_some(word)
+}
+
+/// Byte offset of a UTF-16 column within a line, clamped to the line length
+fn byte_offset(line: &str, character: u32) -> usize {
+    let mut units = 0;
+    for (index, c) in line.char_indices() {
+        if units >= character as usize {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+fn utf16_len(line: &str) -> u32 {
+    line.chars().map(char::len_utf16).sum::<usize>() as u32
+}
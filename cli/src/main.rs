@@ -1,14 +1,99 @@
 //! Worldview CLI - Tools for working with Worldview format files
 //!
 //! Commands:
+//!   init      - Start a Worldview file and project config from a template
 //!   validate  - Validate .wvf files for syntax errors
 //!   add       - Add facts to a Worldview file using an AI agent
+//!   revise    - Update a belief using an AI agent, recording the prior belief
+//!   remove    - Remove claims matching a description using an AI agent
+//!   ask       - Answer a question from recorded claims using an AI agent
+//!   apply     - Answer a question in light of the worldview, citing the claims behind it
+//!   chat      - Record beliefs and ask questions in one interactive agent session
+//!   audit     - Report likely contradictions between related claims using an AI agent
+//!   summarize - Summarize a concept or the whole file as prose using an AI agent
+//!   undo      - Restore a file to before the last agent edit
+//!   encrypt   - Encrypt a file to FILE.age with a passphrase or age keys
+//!   decrypt   - Write out the plaintext of an encrypted file
+//!   profiles  - List the named profiles in the config files
+//!   resume    - Continue an interrupted add, revise, or remove run
+//!   rename    - Rename a concept or facet and update references to it
+//!   move      - Move a claim to another facet
+//!   sort      - Order concepts, facets, and claims canonically
+//!   fmt       - Rewrite a file in canonical form
+//!   sources   - List cited sources with citation counts
+//!   references - List facets by how often claims reference them
+//!   stats     - Count concepts, facets, claims, and (with --tokens) tokens
+//!   suggest-split - Group an oversized facet's claims into sub-facets using an AI agent
+//!   reorganize - Merge synonym concepts, split bloated facets, and move misfiled claims using an AI agent
+//!   show      - Read a concept or facet as prose, with references resolved inline
+//!   history   - Show how beliefs under a concept or facet changed
+//!   blame     - Show which agent request introduced each claim (`--journal`)
+//!   log       - Timeline of belief changes from the file's git history
+//!   search    - Find claims by ranked full-text search (or by meaning, with `embeddings`)
+//!   index     - Embed claims for semantic search (`embeddings` feature)
+//!   cluster   - Group similar claims across facets, to consolidate or cross-reference
+//!   export    - Write a document as JSON, YAML, Markdown, Org, HTML, RDF, an Obsidian vault, or a prompt snippet
+//!   schema    - Print the JSON Schema of the JSON export (`schema` feature)
+//!   import    - Convert JSON, YAML, Markdown, Org, CSV/TSV, or Notion to Worldview, or triage Readwise highlights into it
+//!   lsp       - Language server for editors
+//!   install-hooks - Install a git pre-commit hook that validates staged .wvf files
+//!   self-test - Check this build against the spec conformance corpus
+//!
+//! Option defaults can be set in `~/.config/worldview/config.toml` and a
+//! project's `.worldview.toml` (see `config`), and `--profile NAME` picks one of
+//! the named sets of overrides they define.
+//!
+//! `--trace` (or a `RUST_LOG` filter) logs validation passes, agent responses,
+//! and tool calls to stderr with their timings.
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use worldview_validator::{
+    ClaimOrder, Profile, SCHEMA_FILE, SENSITIVITY_LEVELS, Schema, SortKey, SortOrder, SpecConfig, SpecVersion,
+    ValidationOptions,
+};
 
 mod add;
+mod apply;
+mod audit;
+mod blame;
+mod cluster;
+mod config;
+mod draft;
+#[cfg(feature = "embeddings")]
+mod embeddings;
+mod encryption;
+mod fmt;
+mod export;
+mod history;
+mod hooks;
+mod import;
+mod init;
+mod journal;
+mod junit;
+mod log;
+mod lsp;
+mod move_claim;
+mod provider;
+mod references;
+mod rename;
+mod reorganize;
+mod report;
+mod resume;
+mod sarif;
+#[cfg(feature = "schema")]
+mod schema;
+mod search;
+mod self_test;
+mod show;
+mod sort;
+mod sources;
+mod stats;
+mod structured;
+mod summarize;
+mod transcript;
+mod undo;
 mod validate;
 
 /// CLI for working with Worldview format files
@@ -18,49 +103,984 @@ mod validate;
 #[command(version)]
 #[command(after_long_help = include_str!("../../system.md"))]
 struct Cli {
+    /// Use a named profile from the config files (`[profiles.NAME]`)
+    #[arg(long = "profile", id = "config_profile", value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Log validation passes, agent responses, and tool calls to stderr with
+    /// their timings (`RUST_LOG` overrides which targets and levels)
+    #[arg(long, global = true)]
+    trace: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Start a Worldview file from a template of common concepts and facets,
+    /// with a `.worldview.toml` beside it
+    Init {
+        /// Which starter layout to use
+        #[arg(long, value_enum, default_value = "personal")]
+        template: init::Template,
+
+        /// Path of the Worldview file to create
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Replace an existing file
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Validate Worldview files for syntax errors (also runs automatically on `add`)
+    ///
+    /// Exits 0 when valid, 1 on errors, 3 when the only failures are warnings
+    /// (`--warnings-as-errors`, `--max-warnings`, `--profile strict`), and 4 when
+    /// a file cannot be read.
     Validate {
-        /// Files to validate
+        /// Files, directories (searched recursively), or glob patterns to validate
         #[arg(required_unless_present = "stdin")]
         files: Vec<PathBuf>,
 
         /// Read from stdin instead of files
         #[arg(long)]
         stdin: bool,
+
+        /// Allow sub-facets (4-space indent) with claims at 6 spaces
+        #[arg(long)]
+        nested_facets: bool,
+
+        /// Validate all files together so references resolve across them
+        #[arg(long, conflicts_with = "stdin")]
+        workspace: bool,
+
+        /// Report a byte order mark and trailing whitespace as errors
+        #[arg(long)]
+        strict_whitespace: bool,
+
+        /// Checks to run: standard, strict (warnings fail), lenient (recover
+        /// misprefixed lines), or agent
+        #[arg(long, default_value_t = Profile::Standard)]
+        profile: Profile,
+
+        /// Warn about claims without an @source under a concept (`Science`),
+        /// facet (`Science.method`), or facet in any concept (`*.empirical`)
+        #[arg(long, value_name = "SCOPE")]
+        require_source: Vec<String>,
+
+        /// Spec version (1.0, 1.1) to check files without a `wvf X.Y` line
+        /// against; defaults to the latest
+        #[arg(long, value_name = "VERSION")]
+        spec_version: Option<SpecVersion>,
+
+        /// Warn about concepts and facets that no &reference targets
+        #[arg(long)]
+        warn_unreferenced: bool,
+
+        /// Also warn about claims that repeat another's words in a different
+        /// order or form, such as `consistency is required` after `requires consistency`
+        #[arg(long)]
+        fuzzy_duplicates: bool,
+
+        /// Warn about claims with more characters of text than this (0 for no limit),
+        /// as well as more than 2 brief forms or 3 conditions
+        #[arg(long, value_name = "N", default_value_t = 120)]
+        max_claim_length: usize,
+
+        /// Warn about facets and sub-facets with more claims of their own than
+        /// this (0 for no limit); see `suggest-split`
+        #[arg(long, value_name = "N", default_value_t = 15)]
+        max_facet_claims: usize,
+
+        /// Also accept the operators and modifiers listed in a YAML file
+        /// (`brief_forms` and `modifiers`, as in spec/tokens.yaml)
+        #[arg(long, value_name = "FILE")]
+        spec: Option<PathBuf>,
+
+        /// Check allowed concepts, required facets, naming, and claim counts
+        /// from a schema file; defaults to worldview.schema.yaml in the current
+        /// directory, when there is one
+        #[arg(long, value_name = "FILE")]
+        schema: Option<PathBuf>,
+
+        /// Color diagnostics by severity
+        #[arg(long, value_enum, default_value_t = report::ColorChoice::Auto)]
+        color: report::ColorChoice,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = validate::Format::Text)]
+        format: validate::Format,
+
+        /// Print only errors and the summary
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Fail (exit 3) if there are any warnings
+        #[arg(long)]
+        warnings_as_errors: bool,
+
+        /// Fail (exit 3) if there are more than N warnings across all files
+        #[arg(long, value_name = "N")]
+        max_warnings: Option<usize>,
     },
 
     /// Add a fact to a Worldview file using an AI agent
     Add {
         /// The fact or statement to add
+        #[arg(
+            required_unless_present_any = ["batch", "no_agent"],
+            conflicts_with_all = ["batch", "no_agent"]
+        )]
+        fact: Option<String>,
+
+        /// Add many facts in one agent session, from a file (`-` for stdin)
+        /// with one fact per line or a JSON array of strings
+        #[arg(long, value_name = "FILE", conflicts_with = "no_agent")]
+        batch: Option<PathBuf>,
+
+        /// Append `--claim` under `--concept` and `--facet` directly, without an agent
+        #[arg(long, requires_all = ["concept", "facet", "claim"])]
+        no_agent: bool,
+
+        /// Concept for `--no-agent` (created if missing)
+        #[arg(long, requires = "no_agent")]
+        concept: Option<String>,
+
+        /// Facet for `--no-agent` (created if missing)
+        #[arg(long, requires = "no_agent")]
+        facet: Option<String>,
+
+        /// Claim text for `--no-agent`, in Worldview notation without the `- `
+        #[arg(long, requires = "no_agent")]
+        claim: Option<String>,
+
+        #[command(flatten)]
+        agent: AgentArgs,
+    },
+
+    /// Revise a belief using an AI agent, recording the prior belief with `[<= ...]`
+    Revise {
+        /// The change of belief, in plain language
+        #[arg(required = true)]
+        change: String,
+
+        #[command(flatten)]
+        agent: AgentArgs,
+    },
+
+    /// Remove claims matching a description using an AI agent, pruning emptied facets
+    Remove {
+        /// The claim(s) to remove, described in plain language
+        #[arg(required = true)]
+        description: String,
+
+        #[command(flatten)]
+        agent: AgentArgs,
+    },
+
+    /// Answer a question strictly from recorded claims using an AI agent, citing them
+    Ask {
+        /// The question, in plain language
+        #[arg(required = true)]
+        question: String,
+
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Give the agent only the claims the question matches by full-text search
+        #[arg(long)]
+        matching: bool,
+
+        #[command(flatten)]
+        model: ModelArgs,
+    },
+
+    /// Answer a question in light of the worldview, citing the Concept.facet
+    /// claims that informed the answer
+    ///
+    /// Unlike `ask`, the answer may go beyond what is recorded: the worldview
+    /// is the lens, given to the model in its system prompt.
+    Apply {
+        /// The question, in plain language
         #[arg(required = true)]
-        fact: String,
+        question: String,
+
+        /// Path to the Worldview file to apply
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Token budget for the worldview, such as 2000-tokens; the most important claims are kept first
+        #[arg(long, value_name = "TOKENS", value_parser = export::prompt::parse_budget)]
+        budget: Option<usize>,
+
+        /// Concepts to include, comma-separated (default: all)
+        #[arg(long, value_delimiter = ',', value_name = "CONCEPTS")]
+        concepts: Vec<String>,
+
+        /// Leave out claims under sensitivity markers of these levels (.private, .confidential), comma-separated
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "LEVELS",
+            value_parser = clap::builder::PossibleValuesParser::new(SENSITIVITY_LEVELS)
+        )]
+        redact: Vec<String>,
+
+        #[command(flatten)]
+        model: ModelArgs,
+    },
+
+    /// Record beliefs and ask questions in one agent session, approving each edit
+    Chat {
+        /// Path to the Worldview file to work on
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        #[command(flatten)]
+        model: ModelArgs,
+    },
+
+    /// Report likely contradictions between related claims using an AI agent,
+    /// citing their lines; the file is never modified
+    Audit {
+        /// Have the agent compare claims under each concept, and claims with
+        /// the facets they reference, for contradictions in meaning
+        #[arg(long, required = true)]
+        contradictions: bool,
+
+        /// Path to the Worldview file to audit
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        #[command(flatten)]
+        model: ModelArgs,
+    },
+
+    /// Summarize a concept, or the whole file, as prose grounded in its claims
+    /// using an AI agent
+    Summarize {
+        /// The concept to summarize (default: the whole file)
+        concept: Option<String>,
+
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Also write the summary to this Markdown file
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        model: ModelArgs,
+    },
+
+    /// Restore a file to its version before the last agent edit
+    Undo {
+        /// Path to the Worldview file to restore
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// List saved versions, newest first, without restoring
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Encrypt a file to FILE.age with a passphrase (or age public keys); validate
+    /// and agent commands then read it in memory
+    Encrypt {
+        /// Path to the Worldview file to encrypt
+        #[arg(short, long, id = "plaintext_file", value_name = "FILE", default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Encrypt to this age public key (age1...) instead of a passphrase; repeatable
+        #[arg(long, value_name = "KEY")]
+        recipient: Vec<String>,
+
+        /// Keep the plaintext file instead of removing it
+        #[arg(long)]
+        keep: bool,
+    },
+
+    /// Write out the plaintext of an encrypted file
+    Decrypt {
+        /// Path to the encrypted Worldview file
+        #[arg(short, long, id = "encrypted_file", value_name = "FILE", default_value = "worldview.wvf.age")]
+        file: PathBuf,
+
+        /// Where to write the plaintext (default: the file name without .age; - for stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Inspect the named profiles in the config files
+    Profiles {
+        #[command(subcommand)]
+        command: ProfilesCommand,
+    },
+
+    /// Continue an `add`, `remove`, or `revise` run that was interrupted
+    Resume {
+        /// Session to continue (the most recent when omitted)
+        session_id: Option<String>,
+
+        /// List interrupted sessions, newest first, without resuming
+        #[arg(long)]
+        list: bool,
+    },
+
+    /// Rename a concept or facet, rewriting references to it
+    Rename {
+        /// Concept (`Power`) or facet (`Power.core`) to rename
+        target: String,
+
+        /// New concept or facet name
+        to: String,
+
+        /// Path to the Worldview file defining the target
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Other files whose references to the target should be updated
+        #[arg(long, value_name = "FILE")]
+        update: Vec<PathBuf>,
+    },
+
+    /// Move a claim to another facet (created if missing)
+    Move {
+        /// Claim text, without conditions, sources, or references
+        claim: String,
+
+        /// Facet holding the claim (`Concept.facet`)
+        #[arg(long)]
+        from: String,
+
+        /// Destination facet (`Concept.facet`)
+        #[arg(long)]
+        to: String,
 
         /// Path to the Worldview file to modify
         #[arg(short, long, default_value = "worldview.wvf")]
         file: PathBuf,
+    },
+
+    /// Sort concepts, facets, and claims into a canonical order
+    Sort {
+        /// Path to the Worldview file to sort
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Concept order: alpha, size, or preserve
+        #[arg(long, default_value_t = SortKey::Alphabetical)]
+        concepts: SortKey,
+
+        /// Facet order: alpha, size, or preserve
+        #[arg(long, default_value_t = SortKey::Alphabetical)]
+        facets: SortKey,
+
+        /// Claim order: preserve, alpha, modifier, or recency
+        #[arg(long, default_value_t = ClaimOrder::Preserve)]
+        claims: ClaimOrder,
+
+        /// Exit with an error if the file is not sorted, without modifying it
+        #[arg(long)]
+        check: bool,
+    },
 
-        /// Model to use
-        #[arg(short, long, default_value = "claude-sonnet-4-20250514")]
-        model: String,
+    /// Rewrite a Worldview file in canonical form
+    Fmt {
+        /// Path to the Worldview file to format
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Convert each tab in indentation to N spaces
+        #[arg(long, value_name = "N")]
+        tabs_as: Option<usize>,
+
+        /// Exit with an error if the file is not formatted, without modifying it
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Group an oversized facet's claims into sub-facets using an AI agent
+    ///
+    /// Claims are moved, never changed; use `--confirm` to review the proposed
+    /// sub-facets before they are written.
+    SuggestSplit {
+        /// The facet to split (Concept.facet)
+        #[arg(required = true)]
+        target: String,
 
-        /// Enable verbose output
+        #[command(flatten)]
+        agent: AgentArgs,
+    },
+
+    /// Restructure concepts and facets using an AI agent: merge synonym
+    /// concepts, split bloated facets, and move misfiled claims
+    ///
+    /// Claims are moved, never changed, and the proposed changes are shown as
+    /// a diff to approve before anything is written.
+    Reorganize {
+        /// Reorganize this concept and the concepts that overlap it (default: the whole file)
+        #[arg(long)]
+        concept: Option<String>,
+
+        /// Path to the Worldview file to reorganize
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// Show the proposed changes without asking to apply them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// List cited sources with how often each is cited
+    Sources {
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+    },
+
+    /// List facets by how often claims reference them, most referenced first
+    References {
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Only list facets nothing references
+        #[arg(long)]
+        unreferenced: bool,
+    },
+
+    /// Count concepts, facets, claims, references, and sources
+    Stats {
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Also count the tokens a model reads for the file, in total and per concept
+        #[arg(long)]
+        tokens: bool,
+
+        /// Model whose tokenizer to count with (default: the anthropic provider's);
+        /// models without a public tokenizer are approximated
+        #[arg(long, requires = "tokens")]
+        model: Option<String>,
+    },
+
+    /// Read a concept or facet as prose, with operators and modifiers spelled out
+    /// and referenced claims shown inline
+    Show {
+        /// Concept (`Power`), facet (`Power.core`), or sub-facet to show; all if omitted
+        target: Option<String>,
+
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+    },
+
+    /// Show belief drift recorded by evolution markers, oldest belief first
+    History {
+        /// Concept (`Power`) or facet (`Power.core`) to show
+        target: String,
+
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+    },
+
+    /// Show which command, model, and request introduced each claim, from the journal
+    Blame {
+        /// Concept (`Power`) or facet (`Power.core`) to show
+        target: String,
+
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+    },
+
+    /// Show a belief-change timeline from the git history of a file
+    Log {
+        /// Path to the Worldview file to read
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+    },
+
+    /// Search claims, conditions, and sources; best matches first
+    Search {
+        /// Words to search for
+        query: String,
+
+        /// Path to the Worldview file to search
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Show at most N matches
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 20)]
+        limit: usize,
+
+        /// Rank by meaning using the embeddings index (requires the `embeddings` feature)
+        #[arg(long)]
+        semantic: bool,
+    },
+
+    /// Embed claims into a sidecar index for `search --semantic`
+    #[cfg(feature = "embeddings")]
+    Index {
+        /// Path to the Worldview file to index
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Embedding provider
+        #[arg(long, id = "embedding_provider", value_name = "PROVIDER", value_enum, default_value = "voyage")]
+        provider: embeddings::Provider,
+
+        /// Embedding model (defaults to the provider's small general-purpose model)
+        #[arg(long, id = "embedding_model", value_name = "MODEL")]
+        model: Option<String>,
+    },
+
+    /// Group similar claims from different facets, to consolidate them or add
+    /// cross-references
+    Cluster {
+        /// Path to the Worldview file to check
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Least similarity, from 0 to 1, that links two claims (default 0.4
+        /// for wording, 0.85 with --semantic)
+        #[arg(long, value_name = "SIMILARITY")]
+        threshold: Option<f64>,
+
+        /// Compare meaning using the embeddings index (requires the `embeddings` feature)
+        #[arg(long)]
+        semantic: bool,
+    },
+
+    /// Export a document as JSON, YAML, Markdown, Org, HTML, RDF, an Obsidian vault, or a
+    /// snippet for another assistant's system prompt
+    Export {
+        /// Path to the Worldview file to export
+        #[arg(short, long, default_value = "worldview.wvf")]
+        file: PathBuf,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "json")]
+        format: export::ExportFormat,
+
+        /// Base IRI for RDF resources (default: urn:worldview:<file name>/)
+        #[arg(long, value_name = "IRI")]
+        base: Option<String>,
+
+        /// Token budget for prompt, such as 2000-tokens; the most important claims are kept first
+        #[arg(long, value_name = "TOKENS", value_parser = export::prompt::parse_budget)]
+        budget: Option<usize>,
+
+        /// Concepts to include in prompt, comma-separated (default: all)
+        #[arg(long, value_delimiter = ',', value_name = "CONCEPTS")]
+        concepts: Vec<String>,
+
+        /// Leave out claims under sensitivity markers of these levels (.private, .confidential), comma-separated
+        #[arg(
+            long,
+            value_delimiter = ',',
+            value_name = "LEVELS",
+            value_parser = clap::builder::PossibleValuesParser::new(SENSITIVITY_LEVELS)
+        )]
+        redact: Vec<String>,
+
+        /// Write to this file instead of stdout (the vault directory for obsidian)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Convert an exported JSON or YAML document, a Markdown or Org outline, a CSV/TSV table, or a
+    /// Notion database to Worldview format, or have the agent add Readwise highlights to a file
+    Import {
+        /// Path to the file to import (none for --from notion or readwise)
+        input: Option<PathBuf>,
+
+        /// Input format: json, yaml, md, org, csv, tsv, notion, or readwise (inferred from the extension by default)
+        #[arg(long = "from", visible_alias = "format", value_enum, value_name = "FORMAT")]
+        format: Option<import::ImportFormat>,
+
+        /// Notion database to import, by ID (reads NOTION_TOKEN; requires the `notion` feature)
+        #[arg(long, value_name = "ID")]
+        database: Option<String>,
+
+        /// Read a field from a Notion property: concept, facet, claim, condition, or source
+        #[arg(long = "property", value_name = "FIELD=NAME")]
+        properties: Vec<String>,
+
+        /// Readwise highlights updated after this date or time, e.g. 2024-05-01 (reads READWISE_TOKEN;
+        /// requires the `readwise` feature)
+        #[arg(long, value_name = "DATE")]
+        since: Option<String>,
+
+        #[command(flatten)]
+        model: ModelArgs,
+
+        /// Write to this Worldview file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Merge into this existing Worldview file (written in place unless --output is given)
+        #[arg(long, value_name = "FILE")]
+        into: Option<PathBuf>,
+    },
+
+    /// Print the JSON Schema of `export --format json`, to validate exports or generate bindings
+    #[cfg(feature = "schema")]
+    Schema {
+        /// Write the schema to this file instead of stdout
         #[arg(short, long)]
-        verbose: bool,
+        output: Option<PathBuf>,
     },
+
+    /// Install a git pre-commit hook that blocks commits staging invalid .wvf files
+    InstallHooks {
+        /// Also block commits of .wvf files that `fmt --check` would change
+        #[arg(long)]
+        fmt: bool,
+
+        /// Validate with sub-facets allowed
+        #[arg(long)]
+        nested_facets: bool,
+
+        /// Replace an existing pre-commit hook not installed by worldview
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Check this build's validator against the spec conformance corpus
+    SelfTest,
+
+    /// Run a language server (LSP over stdio) for editor integration
+    Lsp {
+        /// Allow sub-facets (4-space indent) with claims at 6 spaces
+        #[arg(long)]
+        nested_facets: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfilesCommand {
+    /// List profiles with the file and model each selects (`*` marks `--profile`)
+    List,
+}
+
+/// Log spans to stderr as they close, if `--trace` is passed or `RUST_LOG` is set
+fn init_tracing(trace: bool) {
+    use std::io::IsTerminal;
+    use tracing_subscriber::{EnvFilter, fmt::format::FmtSpan};
+
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) if trace => EnvFilter::new("worldview=debug,worldview_validator=debug"),
+        Err(_) => return,
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .init();
+}
+
+/// `--profile NAME` given before the subcommand, read ahead of parsing since the
+/// profile supplies the defaults the parser uses
+fn profile_arg() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+        if !arg.starts_with('-') {
+            break;
+        }
+    }
+    None
+}
+
+/// Model selection shared by the agent commands
+#[derive(Args, Debug)]
+struct ModelArgs {
+    /// LLM provider
+    #[arg(long, value_enum, default_value = "anthropic")]
+    provider: provider::ProviderKind,
+
+    /// Model to use, or `sonnet`, `opus`, or `haiku` (defaults to the provider's)
+    #[arg(short, long)]
+    model: Option<String>,
+
+    /// Enable verbose output
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Stop, leaving the file untouched, once the estimated cost passes this many dollars
+    #[arg(long, value_name = "DOLLARS")]
+    max_cost: Option<f64>,
+
+    /// Stop, leaving the file untouched, if the agent needs more model responses than this
+    #[arg(long, value_name = "N")]
+    max_turns: Option<usize>,
+
+    /// Stop, leaving the file untouched, if the agent makes more tool calls than this
+    #[arg(long, value_name = "N")]
+    max_tool_calls: Option<usize>,
+
+    /// Append a JSON Lines transcript of tool calls, usage, and the final diff to PATH
+    #[arg(long, value_name = "PATH")]
+    log_json: Option<PathBuf>,
+
+    /// Record who wrote each claim in FILE.journal, for `blame` (always, once it exists)
+    #[arg(long)]
+    journal: bool,
+}
+
+impl ModelArgs {
+    fn options(self) -> add::Options {
+        add::Options {
+            provider: self.provider,
+            model: self.model,
+            verbose: self.verbose,
+            limits: add::Limits {
+                max_cost: self.max_cost,
+                max_turns: self.max_turns,
+                max_tool_calls: self.max_tool_calls,
+            },
+            log_json: self.log_json,
+            journal: self.journal,
+        }
+    }
+}
+
+/// Options shared by the agent commands that edit
+#[derive(Args, Debug)]
+struct AgentArgs {
+    /// Path to the Worldview file to modify
+    #[arg(short, long, default_value = "worldview.wvf")]
+    file: PathBuf,
+
+    #[command(flatten)]
+    model: ModelArgs,
+
+    /// Show the changes the agent proposes without writing them
+    #[arg(long, conflicts_with = "confirm")]
+    dry_run: bool,
+
+    /// Show the changes the agent proposes and ask before writing them
+    #[arg(long)]
+    confirm: bool,
+}
+
+impl AgentArgs {
+    fn apply(&self) -> add::Apply {
+        match (self.dry_run, self.confirm) {
+            (true, _) => add::Apply::DryRun,
+            (_, true) => add::Apply::Confirm,
+            _ => add::Apply::Immediately,
+        }
+    }
+
+    async fn run(self, task: add::Task, text: String) -> Result<()> {
+        let apply = self.apply();
+        add::run(task, text, self.file, &self.model.options(), apply).await
+    }
+
+    async fn run_batch(self, task: add::Task, path: PathBuf) -> Result<()> {
+        let content = if path.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin()).context("Error reading stdin")?
+        } else {
+            std::fs::read_to_string(&path)
+                .with_context(|| format!("Error reading {}", path.display()))?
+        };
+        let facts = add::parse_batch(&content)?;
+        if facts.is_empty() {
+            anyhow::bail!("{}: no facts to add", path.display());
+        }
+        let apply = self.apply();
+        add::run_batch(task, facts, self.file, &self.model.options(), apply).await
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    // Config files supply defaults; flags on the command line override them
+    let config = config::Config::load()?;
+    let profile = profile_arg();
+    let matches = config.select(profile.as_deref())?.apply(Cli::command()).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    init_tracing(cli.trace);
 
     match cli.command {
-        Commands::Validate { files, stdin } => validate::run(files, stdin),
-        Commands::Add { fact, file, model, verbose } => add::run(fact, file, model, verbose).await,
+        Commands::Init { template, file, force } => init::run(template, file, force),
+        Commands::Validate {
+            files,
+            stdin,
+            nested_facets,
+            workspace,
+            strict_whitespace,
+            profile,
+            require_source,
+            spec_version,
+            warn_unreferenced,
+            fuzzy_duplicates,
+            max_claim_length,
+            max_facet_claims,
+            spec,
+            schema,
+            color,
+            format,
+            quiet,
+            warnings_as_errors,
+            max_warnings,
+        } => {
+            let mut options = profile.options();
+            options.nested_facets |= nested_facets;
+            options.strict_whitespace |= strict_whitespace;
+            options.require_sources = require_source;
+            options.spec_version = spec_version;
+            options.report_unreferenced = warn_unreferenced;
+            options.fuzzy_duplicates = fuzzy_duplicates;
+            options.claim_limits.max_length = Some(max_claim_length).filter(|&n| n > 0);
+            options.max_facet_claims = Some(max_facet_claims).filter(|&n| n > 0);
+            if let Some(path) = spec {
+                options.spec = SpecConfig::from_yaml(&path)?;
+            }
+            let default_schema = Path::new(SCHEMA_FILE);
+            let schema = schema.or_else(|| default_schema.exists().then(|| default_schema.into()));
+            if let Some(path) = schema {
+                options.schema = Some(Schema::from_yaml(&path)?);
+            }
+            let output = validate::Output { format, color, quiet };
+            // Warnings the profile fails on exit as warnings, not errors
+            let profile = std::mem::take(&mut options.deny_warnings).then_some(profile);
+            let policy = validate::Policy { warnings_as_errors, max_warnings, profile };
+            if workspace {
+                validate::run_workspace(files, &options, &output, policy)
+            } else {
+                validate::run(files, stdin, &options, &output, policy)
+            }
+        }
+        Commands::Add { fact, batch, concept, facet, claim, agent, .. } => {
+            // `--no-agent` requires all three
+            match (concept, facet, claim) {
+                (Some(concept), Some(facet), Some(claim)) => {
+                    let apply = agent.apply();
+                    add::run_direct(agent.file, &concept, &facet, &claim, apply)
+                }
+                _ => match batch {
+                    Some(path) => agent.run_batch(add::Task::Add, path).await,
+                    None => agent.run(add::Task::Add, fact.unwrap_or_default()).await,
+                },
+            }
+        }
+        Commands::Revise { change, agent } => agent.run(add::Task::Revise, change).await,
+        Commands::Remove { description, agent } => agent.run(add::Task::Remove, description).await,
+        Commands::SuggestSplit { target, agent } => {
+            add::check_split_target(&agent.file, &target)?;
+            agent.run(add::Task::Split, target).await
+        }
+        Commands::Reorganize { concept, file, model, dry_run } => {
+            reorganize::run(concept, file, &model.options(), dry_run).await
+        }
+        Commands::Ask { question, file, matching, model } => {
+            let task = add::Task::Ask { matching };
+            add::run(task, question, file, &model.options(), add::Apply::Immediately).await
+        }
+        Commands::Apply { question, file, budget, concepts, redact, model } => {
+            apply::run(question, file, budget, concepts, redact, &model.options()).await
+        }
+        Commands::Chat { file, model } => add::run_chat(file, &model.options()).await,
+        Commands::Audit { contradictions: _, file, model } => audit::run(file, &model.options()).await,
+        Commands::Summarize { concept, file, output, model } => {
+            summarize::run(concept, file, output, &model.options()).await
+        }
+        Commands::Undo { file, list } => undo::run(file, list),
+        Commands::Encrypt { file, recipient, keep } => encryption::run_encrypt(file, recipient, keep),
+        Commands::Decrypt { file, output } => encryption::run_decrypt(file, output),
+        Commands::Profiles { command: ProfilesCommand::List } => {
+            config::list_profiles(&config, cli.profile.as_deref())
+        }
+        Commands::Resume { session_id, list } => resume::run(session_id, list).await,
+        Commands::Rename { target, to, file, update } => rename::run(target, to, file, update),
+        Commands::Move { claim, from, to, file } => move_claim::run(claim, from, to, file),
+        Commands::Sort { file, concepts, facets, claims, check } => {
+            sort::run(file, SortOrder { concepts, facets, claims }, check)
+        }
+        Commands::Fmt { file, tabs_as, check } => fmt::run(file, tabs_as, check),
+        Commands::Sources { file } => sources::run(file),
+        Commands::References { file, unreferenced } => references::run(file, unreferenced),
+        Commands::Stats { file, tokens, model } => stats::run(file, tokens, model),
+        Commands::Show { target, file } => show::run(target, file),
+        Commands::History { target, file } => history::run(target, file),
+        Commands::Blame { target, file } => blame::run(target, file),
+        Commands::Log { file } => log::run(file),
+        Commands::Search { query, file, limit, semantic } => {
+            if semantic {
+                semantic_search(query, file, limit)
+            } else {
+                search::run(query, file, limit)
+            }
+        }
+        Commands::Cluster { file, threshold, semantic } => cluster::run(file, threshold, semantic),
+        Commands::Export {
+            file,
+            format,
+            base,
+            budget,
+            concepts,
+            redact,
+            output,
+        } => export::run(file, format, base, redact, export::Selection { budget, concepts }, output),
+        Commands::Import {
+            format: Some(import::ImportFormat::Readwise),
+            into: Some(into),
+            since,
+            model,
+            ..
+        } => import::run_readwise(into, since, &model.options()).await,
+        Commands::Import {
+            input,
+            format,
+            output,
+            into,
+            database,
+            properties,
+            ..
+        } => import::run(input, format, output, into, database, properties),
+        #[cfg(feature = "schema")]
+        Commands::Schema { output } => schema::run(output),
+        #[cfg(feature = "embeddings")]
+        Commands::Index { file, provider, model } => embeddings::index(file, provider, model),
+        Commands::InstallHooks {
+            fmt,
+            nested_facets,
+            force,
+        } => hooks::run(fmt, nested_facets, force),
+        Commands::SelfTest => self_test::run(),
+        Commands::Lsp { nested_facets } => lsp::run(ValidationOptions {
+            nested_facets,
+            ..ValidationOptions::default()
+        }),
     }
 }
+
+#[cfg(feature = "embeddings")]
+fn semantic_search(query: String, file: PathBuf, limit: usize) -> Result<()> {
+    embeddings::search(query, file, limit)
+}
+
+#[cfg(not(feature = "embeddings"))]
+fn semantic_search(_query: String, _file: PathBuf, _limit: usize) -> Result<()> {
+    anyhow::bail!("--semantic requires worldview to be built with the `embeddings` feature")
+}
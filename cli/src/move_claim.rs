@@ -0,0 +1,19 @@
+//! Move subcommand - moves a claim to another facet
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+use crate::validate;
+
+pub fn run(claim: String, from: String, to: String, file: PathBuf) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let mut doc = Document::parse(&content);
+
+    doc.move_claim(&from, &claim, &to)?;
+    validate::write_if_valid(&file, &doc.to_string(), doc.has_nested_facets())?;
+    println!("{}: moved '{}' from {} to {}", file.display(), claim, from, to);
+
+    Ok(())
+}
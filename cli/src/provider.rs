@@ -0,0 +1,225 @@
+//! LLM providers for the agent commands
+//!
+//! Each provider supplies its credentials and maps model names to the
+//! `namespace::model` form the agent runtime uses to pick an API adapter.
+//! Short aliases (`sonnet`, `opus`, `haiku`) expand to current Anthropic models.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use codey::{Agent, AgentRuntimeConfig, ToolRegistry};
+use serde::{Deserialize, Serialize};
+
+/// Dollars per million tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Price {
+    pub input: f64,
+    pub output: f64,
+}
+
+impl Price {
+    const fn new(input: f64, output: f64) -> Price {
+        Price { input, output }
+    }
+
+    /// Dollars for the given token counts
+    pub fn cost(self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input + output_tokens as f64 * self.output) / 1_000_000.0
+    }
+}
+
+/// A source of chat models for the agent
+pub trait Provider {
+    /// Environment variable holding the API key, if the provider needs one
+    fn key_var(&self) -> Option<&'static str>;
+
+    /// Model used when none is given
+    fn default_model(&self) -> &'static str;
+
+    /// Model name as the agent runtime expects it
+    fn model_id(&self, model: &str) -> String;
+
+    /// List price of a model (as given on the command line), if known
+    fn price(&self, model: &str) -> Option<Price>;
+
+    /// An agent running `config.model` with this provider's credentials
+    fn agent(
+        &self,
+        mut config: AgentRuntimeConfig,
+        system_prompt: &str,
+        tools: ToolRegistry,
+    ) -> Result<Agent> {
+        config.model = self.model_id(&config.model);
+        let api_key = match self.key_var() {
+            Some(var) => Some(
+                std::env::var(var)
+                    .with_context(|| format!("{} environment variable not set", var))?,
+            ),
+            None => None,
+        };
+        Ok(Agent::new(config, system_prompt, api_key, tools))
+    }
+}
+
+pub struct Anthropic;
+
+impl Provider for Anthropic {
+    fn key_var(&self) -> Option<&'static str> {
+        Some("ANTHROPIC_API_KEY")
+    }
+
+    fn default_model(&self) -> &'static str {
+        "claude-sonnet-4-20250514"
+    }
+
+    fn model_id(&self, model: &str) -> String {
+        match model {
+            "sonnet" => "claude-sonnet-4-5-20250929",
+            "opus" => "claude-opus-4-5-20251101",
+            "haiku" => "claude-haiku-4-5-20251001",
+            _ => model,
+        }
+        .to_string()
+    }
+
+    fn price(&self, model: &str) -> Option<Price> {
+        claude_price(&self.model_id(model))
+    }
+}
+
+pub struct OpenAi;
+
+impl Provider for OpenAi {
+    fn key_var(&self) -> Option<&'static str> {
+        Some("OPENAI_API_KEY")
+    }
+
+    fn default_model(&self) -> &'static str {
+        "gpt-4.1"
+    }
+
+    fn model_id(&self, model: &str) -> String {
+        namespaced("openai", model)
+    }
+
+    fn price(&self, model: &str) -> Option<Price> {
+        openai_price(model.rsplit("::").next().unwrap_or(model))
+    }
+}
+
+/// A local Ollama server, so nothing leaves the machine
+pub struct Ollama;
+
+impl Provider for Ollama {
+    fn key_var(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn default_model(&self) -> &'static str {
+        "llama3.1"
+    }
+
+    fn model_id(&self, model: &str) -> String {
+        namespaced("ollama", model)
+    }
+
+    fn price(&self, _model: &str) -> Option<Price> {
+        Some(Price::new(0.0, 0.0))
+    }
+}
+
+/// OpenRouter, with models named `vendor/model` (`anthropic/claude-sonnet-4`)
+pub struct OpenRouter;
+
+impl Provider for OpenRouter {
+    fn key_var(&self) -> Option<&'static str> {
+        Some("OPENROUTER_API_KEY")
+    }
+
+    fn default_model(&self) -> &'static str {
+        "anthropic/claude-sonnet-4"
+    }
+
+    fn model_id(&self, model: &str) -> String {
+        namespaced("openrouter", model)
+    }
+
+    /// OpenRouter passes through the vendor's list price
+    fn price(&self, model: &str) -> Option<Price> {
+        let model = model.rsplit("::").next().unwrap_or(model);
+        match model.split_once('/')? {
+            ("anthropic", name) => claude_price(name),
+            ("openai", name) => openai_price(name),
+            _ => None,
+        }
+    }
+}
+
+/// Claude list prices, by model family
+fn claude_price(model: &str) -> Option<Price> {
+    let family = model.strip_prefix("claude-")?;
+    const TABLE: &[(&str, Price)] = &[
+        ("opus-4-5", Price::new(5.0, 25.0)),
+        ("opus-4", Price::new(15.0, 75.0)),
+        ("sonnet-4", Price::new(3.0, 15.0)),
+        ("3-7-sonnet", Price::new(3.0, 15.0)),
+        ("haiku-4-5", Price::new(1.0, 5.0)),
+        ("3-5-haiku", Price::new(0.8, 4.0)),
+    ];
+    // Dotted OpenRouter names (`claude-sonnet-4.5`) share the dashed prices
+    let family = family.replace('.', "-");
+    TABLE
+        .iter()
+        .find(|(prefix, _)| family.starts_with(prefix))
+        .map(|(_, price)| *price)
+}
+
+/// OpenAI list prices; longer names come first so `gpt-4.1-mini` is not `gpt-4.1`
+fn openai_price(model: &str) -> Option<Price> {
+    const TABLE: &[(&str, Price)] = &[
+        ("gpt-4.1-nano", Price::new(0.1, 0.4)),
+        ("gpt-4.1-mini", Price::new(0.4, 1.6)),
+        ("gpt-4.1", Price::new(2.0, 8.0)),
+        ("gpt-4o-mini", Price::new(0.15, 0.6)),
+        ("gpt-4o", Price::new(2.5, 10.0)),
+        ("o4-mini", Price::new(1.1, 4.4)),
+        ("o3", Price::new(2.0, 8.0)),
+    ];
+    TABLE
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, price)| *price)
+}
+
+/// `ollama::llama3.1`, leaving names that already carry a namespace alone
+fn namespaced(namespace: &str, model: &str) -> String {
+    if model.contains("::") {
+        model.to_string()
+    } else {
+        format!("{}::{}", namespace, model)
+    }
+}
+
+/// Providers selectable with `--provider`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// Anthropic (ANTHROPIC_API_KEY)
+    Anthropic,
+    /// OpenAI (OPENAI_API_KEY)
+    Openai,
+    /// A local Ollama server (no API key)
+    Ollama,
+    /// OpenRouter (OPENROUTER_API_KEY)
+    Openrouter,
+}
+
+impl ProviderKind {
+    pub fn provider(self) -> &'static dyn Provider {
+        match self {
+            ProviderKind::Anthropic => &Anthropic,
+            ProviderKind::Openai => &OpenAi,
+            ProviderKind::Ollama => &Ollama,
+            ProviderKind::Openrouter => &OpenRouter,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! References subcommand - lists facets by how often claims reference them
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+pub fn run(file: PathBuf, unreferenced: bool) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+    let counts: Vec<_> = doc
+        .reference_counts()
+        .into_iter()
+        .filter(|c| !unreferenced || c.count == 0)
+        .collect();
+    if counts.is_empty() {
+        let what = if unreferenced {
+            "unreferenced facets"
+        } else {
+            "facets"
+        };
+        println!("{}: no {}", file.display(), what);
+        return Ok(());
+    }
+
+    let width = counts.iter().map(|c| c.target.len()).max().unwrap_or(0);
+    for count in &counts {
+        println!(
+            "{:>4}  &{:<width$}  line {}",
+            count.count, count.target, count.line
+        );
+    }
+    Ok(())
+}
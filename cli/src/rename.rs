@@ -0,0 +1,59 @@
+//! Rename subcommand - renames a concept or facet and updates references to it
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::{Document, Rename, namespace_for};
+
+use crate::validate;
+
+pub fn run(target: String, to: String, file: PathBuf, update: Vec<PathBuf>) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let mut doc = Document::parse(&content);
+
+    let (rename, rewritten) = match target.split_once('.') {
+        Some((concept, facet)) => {
+            let count = doc.rename_facet(concept, facet, &to)?;
+            let rename = Rename::Facet {
+                concept: concept.to_string(),
+                from: facet.to_string(),
+                to: to.clone(),
+            };
+            (rename, count)
+        }
+        None => {
+            let count = doc.rename_concept(&target, &to)?;
+            let rename = Rename::Concept {
+                from: target.clone(),
+                to: to.clone(),
+            };
+            (rename, count)
+        }
+    };
+
+    let renamed = doc.to_string();
+    validate::write_if_valid(&file, &renamed, doc.has_nested_facets())?;
+    println!(
+        "{}: renamed {} -> {} ({} reference(s) updated)",
+        file.display(),
+        target,
+        to,
+        rewritten
+    );
+
+    // References from other files may be unqualified or use the defining file's namespace
+    let namespace = namespace_for(&file);
+    for path in &update {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Error reading {}", path.display()))?;
+        let mut other = Document::parse(&content);
+        let count = other.rewrite_references(&rename, namespace.as_deref());
+        if count > 0 {
+            std::fs::write(path, other.to_string())
+                .with_context(|| format!("Error writing {}", path.display()))?;
+        }
+        println!("{}: {} reference(s) updated", path.display(), count);
+    }
+
+    Ok(())
+}
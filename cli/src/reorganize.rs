@@ -0,0 +1,44 @@
+//! Reorganize subcommand - restructure concepts and facets using an AI agent
+//!
+//! The agent merges synonym concepts (keeping the other name as an alias),
+//! splits bloated facets, and moves misfiled claims, editing through the same
+//! validated pipeline as `add`. Claims are moved, never changed, and the
+//! result is always shown as a diff to approve before it is written.
+
+use anyhow::{Result, bail};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+use crate::add::{self, Apply, Options, Task};
+use crate::encryption;
+
+pub async fn run(
+    concept: Option<String>,
+    file: PathBuf,
+    options: &Options,
+    dry_run: bool,
+) -> Result<()> {
+    let content = encryption::read(&file)?;
+    let doc = Document::parse(&content);
+    match &concept {
+        Some(name) if doc.concept(name).is_none() => {
+            bail!("{}: no concept {}", file.display(), name)
+        }
+        None if doc.concepts.is_empty() => bail!("{}: no concepts to reorganize", file.display()),
+        _ => {}
+    }
+
+    let apply = if dry_run {
+        Apply::DryRun
+    } else {
+        Apply::Confirm
+    };
+    add::run(
+        Task::Reorganize,
+        concept.unwrap_or_default(),
+        file,
+        options,
+        apply,
+    )
+    .await
+}
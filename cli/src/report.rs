@@ -0,0 +1,172 @@
+//! Source-annotated validation diagnostics
+//!
+//! Each error and warning is printed with the line it is on and a caret under
+//! the offending text, rustc-style, followed by a per-file count:
+//!
+//! ```text
+//! error[undefined-reference]: undefined reference 'Trust.formaton'
+//!  --> worldview.wvf:5:12
+//!   |
+//! 5 |     - fast &Trust.formaton
+//!   |            ^^^^^^^^^^^^^^^
+//!
+//! worldview.wvf: 1 error
+//! ```
+
+use clap::ValueEnum;
+use std::io::{self, IsTerminal};
+use worldview_validator::{ValidationError, ValidationResult};
+
+/// When to color output (`--color`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// When writing to a terminal and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                io::stdout().is_terminal()
+                    && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+const BOLD_RED: &str = "1;31";
+const BOLD_YELLOW: &str = "1;33";
+const BOLD_BLUE: &str = "1;34";
+const BOLD_GREEN: &str = "1;32";
+const BOLD: &str = "1";
+
+pub struct Report {
+    color: bool,
+    /// Leave out warnings, and files without errors
+    quiet: bool,
+}
+
+impl Report {
+    pub fn new(color: ColorChoice, quiet: bool) -> Report {
+        Report {
+            color: color.enabled(),
+            quiet,
+        }
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        if self.color {
+            format!("\x1b[{}m{}\x1b[0m", style, text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Print the diagnostics for `name`, errors first, then its count
+    pub fn print(&self, name: &str, result: &ValidationResult) {
+        if self.quiet && result.is_valid() {
+            return;
+        }
+        let warnings = if self.quiet { &[][..] } else { &result.warnings[..] };
+        let diagnostics: Vec<&ValidationError> = result.errors.iter().chain(warnings).collect();
+        let gutter = diagnostics
+            .iter()
+            .map(|d| d.line().to_string().len())
+            .max()
+            .unwrap_or(1);
+        for diagnostic in diagnostics {
+            self.print_diagnostic(name, result, diagnostic, gutter);
+        }
+        println!("{}", self.footer(name, result));
+    }
+
+    fn print_diagnostic(
+        &self,
+        name: &str,
+        result: &ValidationResult,
+        diagnostic: &ValidationError,
+        gutter: usize,
+    ) {
+        let (label, style) = if diagnostic.is_warning() {
+            ("warning", BOLD_YELLOW)
+        } else {
+            ("error", BOLD_RED)
+        };
+        let line = diagnostic.line();
+        let message = diagnostic.to_string();
+        let prefix = format!("line {}: ", line);
+        let message = message.strip_prefix(&prefix).unwrap_or(&message);
+        println!(
+            "{}{}",
+            self.paint(&format!("{}[{}]", label, diagnostic.code()), style),
+            self.paint(&format!(": {}", message), BOLD)
+        );
+
+        let bar = self.paint("|", BOLD_BLUE);
+        let blank = " ".repeat(gutter);
+        let Some(text) = line.checked_sub(1).and_then(|i| result.lines.get(i)) else {
+            println!(
+                "{}{} {}:{}",
+                blank,
+                self.paint("-->", BOLD_BLUE),
+                name,
+                line
+            );
+            println!();
+            return;
+        };
+        let text = text.raw.trim_end_matches('\r');
+        let span = diagnostic.span(text);
+        let column = text[..span.start].chars().count() + 1;
+        println!(
+            "{}{} {}:{}:{}",
+            blank,
+            self.paint("-->", BOLD_BLUE),
+            name,
+            line,
+            column
+        );
+        println!("{} {}", blank, bar);
+        println!(
+            "{} {} {}",
+            self.paint(&format!("{:>gutter$}", line), BOLD_BLUE),
+            bar,
+            text
+        );
+        // Keep tabs so the caret lines up however the terminal renders them
+        let indent: String = text[..span.start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(text[span].chars().count().max(1));
+        println!("{} {} {}{}", blank, bar, indent, self.paint(&carets, style));
+        println!();
+    }
+
+    /// `worldview.wvf: 2 errors, 1 warning`, or `worldview.wvf: valid`
+    fn footer(&self, name: &str, result: &ValidationResult) -> String {
+        let count =
+            |n: usize, noun: &str| format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" });
+        let errors = result.errors.len();
+        let warnings = result.warnings.len();
+        let status = match (errors, warnings) {
+            (0, 0) => self.paint("valid", BOLD_GREEN),
+            (0, _) => self.paint(
+                &format!("valid, {}", count(warnings, "warning")),
+                BOLD_YELLOW,
+            ),
+            (_, 0) => self.paint(&count(errors, "error"), BOLD_RED),
+            _ => self.paint(
+                &format!("{}, {}", count(errors, "error"), count(warnings, "warning")),
+                BOLD_RED,
+            ),
+        };
+        format!("{}: {}", name, status)
+    }
+}
@@ -0,0 +1,235 @@
+//! Resume subcommand - continues an `add`, `remove`, or `revise` run that was
+//! interrupted
+//!
+//! While the agent works, its request and every completed tool call are saved
+//! with the draft under `~/.worldview/sessions/<id>.json`. A run that fails or
+//! is stopped with Ctrl-C leaves that checkpoint behind; `worldview resume`
+//! restores the draft and tells a new agent which calls were already made, so
+//! edits are not applied twice. Finished runs remove their checkpoint.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::add::{self, Apply, Options, Task};
+use crate::draft::Draft;
+
+/// Tools whose results are the file's contents, which a resumed agent reads afresh
+const READ_TOOLS: &[&str] = &["read_worldview", "list_concepts", "search_worldview"];
+
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    #[serde(skip)]
+    pub id: String,
+    pub task: Task,
+    /// The fact or description the run was given
+    pub request: String,
+    pub file: PathBuf,
+    pub options: Options,
+    pub apply: Apply,
+    /// Contents of the file when the run started
+    pub original: String,
+    /// The draft after the last completed tool call
+    pub content: String,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+/// A tool call the agent made before the interruption
+#[derive(Serialize, Deserialize)]
+pub struct ToolCall {
+    pub name: String,
+    pub params: Value,
+    pub result: String,
+}
+
+fn sessions_dir() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").context("HOME environment variable not set")?;
+    Ok(PathBuf::from(home).join(".worldview").join("sessions"))
+}
+
+impl Checkpoint {
+    /// A checkpoint for a run that has just started; nothing is saved until
+    /// the first tool call completes
+    pub fn new(
+        task: Task,
+        request: &str,
+        draft: &Draft,
+        options: &Options,
+        apply: Apply,
+    ) -> Checkpoint {
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        Checkpoint {
+            id: format!("{:013}", millis),
+            task,
+            request: request.to_string(),
+            file: draft.path.clone(),
+            options: options.clone(),
+            apply,
+            original: draft.original.clone(),
+            content: draft.content.clone(),
+            tool_calls: Vec::new(),
+        }
+    }
+
+    fn path(&self) -> Result<PathBuf> {
+        Ok(sessions_dir()?.join(format!("{}.json", self.id)))
+    }
+
+    fn load(id: &str) -> Result<Checkpoint> {
+        let path = sessions_dir()?.join(format!("{}.json", id));
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("No interrupted session {} ({})", id, path.display()))?;
+        let mut checkpoint: Checkpoint = serde_json::from_str(&json)
+            .with_context(|| format!("Error reading {}", path.display()))?;
+        checkpoint.id = id.to_string();
+        Ok(checkpoint)
+    }
+
+    /// Whether a tool call has been saved, so the run can be resumed
+    pub fn is_saved(&self) -> bool {
+        !self.tool_calls.is_empty()
+    }
+
+    /// Save a completed tool call and the draft it left
+    pub fn record(&mut self, name: &str, params: &Value, result: &str, content: &str) {
+        self.tool_calls.push(ToolCall {
+            name: name.to_string(),
+            params: params.clone(),
+            result: result.to_string(),
+        });
+        self.content = content.to_string();
+        // Losing the checkpoint only loses the ability to resume
+        if let Err(e) = self.save() {
+            eprintln!("warning: could not save session {}: {:#}", self.id, e);
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self.path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Error creating {}", dir.display()))?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Error writing {}", path.display()))
+    }
+
+    pub fn remove(&self) {
+        if let Ok(path) = self.path()
+            && path.exists()
+            && let Err(e) = std::fs::remove_file(&path)
+        {
+            eprintln!("warning: could not remove {}: {}", path.display(), e);
+        }
+    }
+
+    /// On Ctrl-C, point at the checkpoint (if one was saved) and exit
+    pub fn exit_on_interrupt(&self) {
+        let Ok(path) = self.path() else { return };
+        let id = self.id.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                if path.exists() {
+                    eprintln!("\nInterrupted - continue with `worldview resume {}`", id);
+                }
+                std::process::exit(130);
+            }
+        });
+    }
+
+    /// What the resumed agent is told about the calls made before the interruption
+    pub fn note(&self) -> String {
+        let mut note = String::from(
+            "This request was interrupted and is being resumed. Before the interruption \
+             you made these tool calls, and the edits they applied are already in the file:\n",
+        );
+        for (i, call) in self.tool_calls.iter().enumerate() {
+            let result = if READ_TOOLS.contains(&call.name.as_str()) {
+                "(output omitted)"
+            } else {
+                call.result.as_str()
+            };
+            note.push_str(&format!(
+                "\n{}. {} {}\n   -> {}\n",
+                i + 1,
+                call.name,
+                call.params,
+                result
+            ));
+        }
+        note.push_str(
+            "\nContinue from where you left off. Read the file again before editing, \
+             and do not repeat edits that were already applied.",
+        );
+        note
+    }
+}
+
+/// Saved checkpoints, oldest first
+fn checkpoints() -> Result<Vec<Checkpoint>> {
+    let dir = sessions_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut ids: Vec<String> = std::fs::read_dir(&dir)
+        .with_context(|| format!("Error reading {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            name.strip_suffix(".json").map(str::to_string)
+        })
+        .collect();
+    // Zero-padded timestamps sort chronologically
+    ids.sort();
+    ids.iter().map(|id| Checkpoint::load(id)).collect()
+}
+
+pub async fn run(id: Option<String>, list: bool) -> Result<()> {
+    if list {
+        for checkpoint in checkpoints()?.iter().rev() {
+            println!(
+                "{}  {:?} {:?} in {} ({} tool calls)",
+                checkpoint.id,
+                checkpoint.task,
+                checkpoint.request,
+                checkpoint.file.display(),
+                checkpoint.tool_calls.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let checkpoint = match id {
+        Some(id) => Checkpoint::load(&id)?,
+        None => checkpoints()?
+            .pop()
+            .context("No interrupted sessions to resume")?,
+    };
+
+    // The file holds either the original or, with edits written as they are
+    // made, the checkpointed draft; anything else was edited since
+    let current = if checkpoint.file.exists() {
+        std::fs::read_to_string(&checkpoint.file)
+            .with_context(|| format!("Error reading {}", checkpoint.file.display()))?
+    } else {
+        String::new()
+    };
+    if current != checkpoint.original && current != checkpoint.content {
+        anyhow::bail!(
+            "{} has changed since session {} was interrupted",
+            checkpoint.file.display(),
+            checkpoint.id
+        );
+    }
+
+    eprintln!(
+        "Resuming {:?} after {} tool calls",
+        checkpoint.request,
+        checkpoint.tool_calls.len()
+    );
+    add::resume(checkpoint).await
+}
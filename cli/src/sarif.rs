@@ -0,0 +1,138 @@
+//! SARIF 2.1.0 output for `validate --format sarif`
+//!
+//! One run of the `worldview` tool, with a result per error or warning and a
+//! rule for each diagnostic code that occurs, so the log can be uploaded to
+//! GitHub code scanning or read by other SARIF consumers. Files that could not
+//! be read are reported as tool execution notifications.
+
+use serde_json::{Value, json};
+use worldview_validator::{ValidationError, ValidationResult};
+
+use crate::validate::Checked;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The SARIF log for one `validate` run
+pub fn log(checked: &Checked) -> Value {
+    let mut rules: Vec<Value> = Vec::new();
+    let mut rule_ids: Vec<&str> = Vec::new();
+    let mut results = Vec::new();
+
+    for (name, result) in &checked.files {
+        for diagnostic in result.errors.iter().chain(&result.warnings) {
+            let code = diagnostic.code();
+            let index = match rule_ids.iter().position(|id| *id == code) {
+                Some(index) => index,
+                None => {
+                    rule_ids.push(code);
+                    rules.push(rule(diagnostic));
+                    rule_ids.len() - 1
+                }
+            };
+            results.push(sarif_result(name, result, diagnostic, index));
+        }
+    }
+
+    let notifications: Vec<Value> = checked
+        .unreadable
+        .iter()
+        .map(|(name, error)| {
+            json!({
+                "level": "error",
+                "message": { "text": format!("Error reading {}: {}", name, error) },
+                "locations": [{ "physicalLocation": { "artifactLocation": { "uri": uri(name) } } }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "worldview",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "invocations": [{
+                "executionSuccessful": notifications.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+            "results": results,
+        }]
+    })
+}
+
+fn level(diagnostic: &ValidationError) -> &'static str {
+    if diagnostic.is_warning() {
+        "warning"
+    } else {
+        "error"
+    }
+}
+
+/// Rule metadata from the diagnostic's code and the requirement it checks
+fn rule(diagnostic: &ValidationError) -> Value {
+    json!({
+        "id": diagnostic.code(),
+        "name": camel_case(diagnostic.code()),
+        "shortDescription": { "text": diagnostic.description() },
+        "defaultConfiguration": { "level": level(diagnostic) },
+    })
+}
+
+fn sarif_result(
+    name: &str,
+    result: &ValidationResult,
+    diagnostic: &ValidationError,
+    rule_index: usize,
+) -> Value {
+    let line = diagnostic.line();
+    let message = diagnostic.to_string();
+    let message = message
+        .strip_prefix(&format!("line {}: ", line))
+        .unwrap_or(&message);
+
+    let mut region = json!({ "startLine": line.max(1) });
+    if let Some(parsed) = line.checked_sub(1).and_then(|i| result.lines.get(i)) {
+        let text = parsed.raw.trim_end_matches('\r');
+        let span = diagnostic.span(text);
+        let start = text[..span.start].chars().count() + 1;
+        region["startColumn"] = json!(start);
+        region["endColumn"] = json!(start + text[span].chars().count());
+        region["snippet"] = json!({ "text": text });
+    }
+
+    json!({
+        "ruleId": diagnostic.code(),
+        "ruleIndex": rule_index,
+        "level": level(diagnostic),
+        "message": { "text": message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri(name) },
+                "region": region,
+            }
+        }],
+    })
+}
+
+/// Paths as URI references, with `/` separators
+fn uri(name: &str) -> String {
+    name.replace('\\', "/")
+}
+
+/// `undefined-reference` as `UndefinedReference`
+fn camel_case(code: &str) -> String {
+    code.split('-')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_uppercase().chain(chars).collect()
+            })
+        })
+        .collect()
+}
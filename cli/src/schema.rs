@@ -0,0 +1,30 @@
+//! Schema subcommand - prints the JSON Schema of `export --format json`
+//!
+//! The schema is generated from the export's types, so it cannot drift from
+//! what `export` writes; `spec/export.schema.json` holds a copy for tools that
+//! do not build the CLI.
+
+use anyhow::{Context, Result};
+use schemars::generate::SchemaSettings;
+use std::path::PathBuf;
+
+use crate::structured::StructuredDocument;
+
+/// The schema as pretty-printed JSON
+pub fn render() -> Result<String> {
+    let schema = SchemaSettings::draft2020_12()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<StructuredDocument>();
+    Ok(serde_json::to_string_pretty(&schema)? + "\n")
+}
+
+pub fn run(output: Option<PathBuf>) -> Result<()> {
+    let schema = render()?;
+    match output {
+        Some(path) => std::fs::write(&path, schema)
+            .with_context(|| format!("Error writing {}", path.display()))?,
+        None => print!("{}", schema),
+    }
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! Search subcommand - ranked full-text search that keeps concept/facet context
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+pub fn run(query: String, file: PathBuf, limit: usize) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+
+    let hits = doc.search(&query);
+    if hits.is_empty() {
+        println!("{}: no claims match \"{}\"", file.display(), query);
+        return Ok(());
+    }
+    for hit in hits.iter().take(limit) {
+        println!("{:>6.2}  {} (line {})", hit.score, hit.target, hit.line);
+        println!("        - {}", hit.claim);
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+//! Self-test subcommand - runs the spec conformance corpus (`spec-tests/`) against this build
+
+use anyhow::Result;
+
+pub fn run() -> Result<()> {
+    let cases =
+        conformance::cases().map_err(|(name, e)| anyhow::anyhow!("{}.expected: {}", name, e))?;
+    let failures = conformance::check_all(&cases);
+    for (case, mismatch) in &failures {
+        println!("{}: {}", case.name, mismatch);
+    }
+    if !failures.is_empty() {
+        anyhow::bail!(
+            "{} of {} conformance cases failed",
+            failures.len(),
+            cases.len()
+        );
+    }
+    println!("{} conformance cases passed", cases.len());
+    Ok(())
+}
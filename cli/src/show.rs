@@ -0,0 +1,163 @@
+//! Show subcommand - renders a concept or facet as readable prose
+//!
+//! Brief forms and modifiers are spelled out, conditions, sources, and prior
+//! beliefs are listed under their claim, and each `&reference` is followed by
+//! the claims it points at:
+//!
+//! ```text
+//! Power
+//!   core
+//!     • concentration (increasing) causes abuse, when unchecked
+//!       source: acton
+//!       see Trust.erosion: fast; asymmetric to formation
+//! ```
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::{
+    Claim, Concept, Condition, ConditionKind, Document, Facet, Reference, modifier_meaning,
+};
+
+pub fn run(target: Option<String>, file: PathBuf) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+
+    let mut out = Vec::new();
+    match target.as_deref() {
+        None => {
+            for concept in &doc.concepts {
+                render_concept(&doc, concept, &mut out);
+            }
+        }
+        Some(target) => match target.split_once('.') {
+            None => {
+                let Some(concept) = doc.concept(target) else {
+                    anyhow::bail!("{}: no concept {}", file.display(), target);
+                };
+                render_concept(&doc, concept, &mut out);
+            }
+            Some((concept, _)) => {
+                let Some(facet) = find_facet(&doc, target) else {
+                    anyhow::bail!("{}: no facet {}", file.display(), target);
+                };
+                out.push(concept.to_string());
+                render_facet(&doc, facet, 1, &mut out);
+            }
+        },
+    }
+    if out.is_empty() {
+        println!("{}: no concepts", file.display());
+    }
+    for line in out {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+fn render_concept(doc: &Document, concept: &Concept, out: &mut Vec<String>) {
+    if !out.is_empty() {
+        out.push(String::new());
+    }
+    out.push(concept.name.clone());
+    for facet in &concept.facets {
+        render_facet(doc, facet, 1, out);
+    }
+}
+
+fn render_facet(doc: &Document, facet: &Facet, depth: usize, out: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    out.push(format!("{}{}", indent, facet.name));
+    for claim in &facet.claims {
+        render_claim(doc, claim, &format!("{}  ", indent), out);
+    }
+    for sub_facet in &facet.sub_facets {
+        render_facet(doc, sub_facet, depth + 1, out);
+    }
+}
+
+fn render_claim(doc: &Document, claim: &Claim, indent: &str, out: &mut Vec<String>) {
+    let data = &claim.data;
+    let mut sentence = data.describe();
+    for condition in &data.conditions {
+        sentence.push_str(", ");
+        sentence.push_str(&describe_condition(condition));
+    }
+    out.push(format!("{}• {}", indent, sentence));
+
+    let detail = format!("{}  ", indent);
+    for source in &data.sources {
+        out.push(format!("{}source: {}", detail, source));
+    }
+    for marker in &data.evolution {
+        let until = marker
+            .changed_at
+            .map(|date| format!(" (until {})", date))
+            .unwrap_or_default();
+        out.push(format!(
+            "{}previously: {}{}",
+            detail, marker.prior_belief, until
+        ));
+    }
+    for reference in &data.references {
+        out.push(format!("{}see {}", detail, resolve(doc, reference)));
+    }
+}
+
+/// `unchecked !` reads `when unchecked (significant)`; other conditions read as written
+fn describe_condition(condition: &str) -> String {
+    let text = condition.trim_end_matches(|c: char| {
+        c.is_whitespace() || (!c.is_alphanumeric() && modifier_meaning(c).is_some())
+    });
+    let parsed = Condition::parse(text);
+    let mut described = if parsed.kind == ConditionKind::State && !parsed.negated {
+        format!("when {}", parsed.text)
+    } else {
+        text.to_string()
+    };
+    let meanings: Vec<&str> = condition[text.len()..]
+        .chars()
+        .filter_map(modifier_meaning)
+        .collect();
+    if !meanings.is_empty() {
+        described.push_str(&format!(" ({})", meanings.join(", ")));
+    }
+    described
+}
+
+/// `Trust.erosion: fast; asymmetric to formation`, or a note when the target is not here
+fn resolve(doc: &Document, reference: &str) -> String {
+    let Some(parsed) = Reference::parse(reference) else {
+        return reference.to_string();
+    };
+    if parsed.namespace.is_some() {
+        return format!("{} (in another file)", reference);
+    }
+    let Some(facet) = find_facet(doc, reference) else {
+        return format!("{} (not found)", reference);
+    };
+    let mut claims = Vec::new();
+    collect_claims(facet, &mut claims);
+    if claims.is_empty() {
+        return reference.to_string();
+    }
+    format!("{}: {}", reference, claims.join("; "))
+}
+
+fn collect_claims(facet: &Facet, out: &mut Vec<String>) {
+    out.extend(facet.claims.iter().map(|claim| claim.data.describe()));
+    for sub_facet in &facet.sub_facets {
+        collect_claims(sub_facet, out);
+    }
+}
+
+/// The facet or sub-facet at `Concept.facet[.sub]`
+pub fn find_facet<'d>(doc: &'d Document, target: &str) -> Option<&'d Facet> {
+    let (concept, path) = target.split_once('.')?;
+    let mut names = path.split('.');
+    let mut facet = doc.concept(concept)?.facet(names.next()?)?;
+    for name in names {
+        facet = facet.sub_facets.iter().find(|f| f.name == name)?;
+    }
+    Some(facet)
+}
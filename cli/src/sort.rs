@@ -0,0 +1,29 @@
+//! Sort subcommand - puts a Worldview file into a canonical order
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::{Document, SortOrder};
+
+use crate::validate;
+
+pub fn run(file: PathBuf, order: SortOrder, check: bool) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let mut doc = Document::parse(&content);
+    doc.sort(order);
+    let sorted = doc.to_string();
+
+    if check {
+        if sorted != content {
+            println!("{}: not sorted", file.display());
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if sorted != content {
+        validate::write_if_valid(&file, &sorted, doc.has_nested_facets())?;
+        println!("{}: sorted", file.display());
+    }
+    Ok(())
+}
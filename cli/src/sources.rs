@@ -0,0 +1,31 @@
+//! Sources subcommand - lists cited sources with citation counts
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+pub fn run(file: PathBuf) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+    let citations = doc.citations();
+    if citations.is_empty() {
+        println!("{}: no sources", file.display());
+        return Ok(());
+    }
+
+    let width = citations.iter().map(|c| c.handle.len()).max().unwrap_or(0);
+    for citation in &citations {
+        let description = match (&citation.description, &doc.sources) {
+            (Some(description), _) => description.as_str(),
+            (None, Some(_)) => "(not in sources registry)",
+            (None, None) => "",
+        };
+        let line = format!(
+            "{:>4}  @{:<width$}  {}",
+            citation.count, citation.handle, description
+        );
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Stats subcommand - counts of concepts, facets, claims, references, and sources
+//!
+//! `--tokens` adds how many tokens a model reads for the file, in total and per
+//! concept, to check how much context a worldview uses before sending it.
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use worldview_validator::{Document, Facet};
+
+use crate::provider::ProviderKind;
+
+pub fn run(file: PathBuf, tokens: bool, model: Option<String>) -> Result<()> {
+    let content = std::fs::read_to_string(&file)
+        .with_context(|| format!("Error reading {}", file.display()))?;
+    let doc = Document::parse(&content);
+
+    let (mut facets, mut claims, mut references) = (0, 0, 0);
+    for concept in &doc.concepts {
+        count(&concept.facets, &mut facets, &mut claims, &mut references);
+    }
+    let rows = [
+        ("concepts", doc.concepts.len()),
+        ("facets", facets),
+        ("claims", claims),
+        ("references", references),
+        ("sources", doc.citations().len()),
+    ];
+    println!("{}", file.display());
+    for (label, n) in rows {
+        println!("  {:<10}  {}", label, n);
+    }
+
+    if tokens {
+        let model = model.unwrap_or_else(|| {
+            ProviderKind::Anthropic
+                .provider()
+                .default_model()
+                .to_string()
+        });
+        // OpenRouter names models `vendor/model`
+        let name = model.rsplit('/').next().unwrap_or(&model);
+        let estimate = doc.token_estimate(name);
+        let counted = if estimate.exact {
+            format!("{} for {}", estimate.tokenizer, model)
+        } else {
+            format!("{}, approximating {}", estimate.tokenizer, model)
+        };
+        println!();
+        println!("Tokens ({}): {}", counted, estimate.total);
+        let width = estimate
+            .concepts
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (name, n) in &estimate.concepts {
+            println!("  {:<width$}  {}", name, n, width = width);
+        }
+    }
+    Ok(())
+}
+
+fn count(facets: &[Facet], total: &mut usize, claims: &mut usize, references: &mut usize) {
+    for facet in facets {
+        *total += 1;
+        *claims += facet.claims.len();
+        *references += facet
+            .claims
+            .iter()
+            .map(|claim| claim.data.references.len())
+            .sum::<usize>();
+        count(&facet.sub_facets, total, claims, references);
+    }
+}
@@ -0,0 +1,301 @@
+//! Structured (JSON/YAML) representation of a document for export and import
+//!
+//! Claims carry their parsed parts so other tools need not re-implement the
+//! positional grammar. Import reads only what cannot be derived from the text:
+//! modifiers, brief forms, and parsed condition fields are ignored.
+//!
+//! With the `schema` feature, the types also describe themselves as a JSON
+//! Schema of the export, which `worldview schema` prints.
+
+use serde::{Deserialize, Serialize};
+use worldview_validator::{
+    CLAIM_ID_PREFIX, Claim, Concept, ConditionKind, Document, Facet, Include, SourceEntry, Sources,
+};
+
+/// A Worldview document, as written by `worldview export --format json`
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(title = "Worldview document"))]
+pub struct StructuredDocument {
+    /// Paths of the other Worldview files this one includes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<String>,
+    pub concepts: Vec<StructuredConcept>,
+    /// The source registry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<StructuredSource>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Concept"))]
+pub struct StructuredConcept {
+    pub name: String,
+    /// Other names references may use for the concept
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    pub facets: Vec<StructuredFacet>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Facet"))]
+pub struct StructuredFacet {
+    pub name: String,
+    #[serde(default)]
+    pub claims: Vec<StructuredClaim>,
+    /// Sub-facets (nested facets only)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub facets: Vec<StructuredFacet>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Claim"))]
+pub struct StructuredClaim {
+    /// The claim without its conditions, sources, references, or evolution
+    pub text: String,
+    /// Explicit `^id:` identifier
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<StructuredCondition>,
+    /// Source handles, without `@`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<String>,
+    /// `Concept.facet` targets, without `&`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<StructuredModifier>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub brief_forms: Vec<StructuredBriefForm>,
+    /// Prior beliefs, nearest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evolution: Vec<StructuredEvolution>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Condition"))]
+pub struct StructuredCondition {
+    /// The condition as written
+    pub text: String,
+    /// `state`, `temporal`, or `when`
+    #[serde(default, skip_deserializing)]
+    pub kind: &'static str,
+    #[serde(default, skip_deserializing)]
+    pub negated: bool,
+    /// The condition without keyword or negation
+    #[serde(default, skip_deserializing)]
+    pub subject: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Modifier"))]
+pub struct StructuredModifier {
+    pub symbol: char,
+    pub attached_to: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "BriefForm"))]
+pub struct StructuredBriefForm {
+    pub operator: String,
+    pub left: String,
+    pub right: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Evolution"))]
+pub struct StructuredEvolution {
+    pub prior_belief: String,
+    /// `YYYY`, `YYYY-MM`, or `YYYY-MM-DD`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changed_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(rename = "Source"))]
+pub struct StructuredSource {
+    pub handle: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl From<&Document> for StructuredDocument {
+    fn from(doc: &Document) -> Self {
+        StructuredDocument {
+            includes: doc.includes.iter().map(|i| i.path.clone()).collect(),
+            concepts: doc
+                .concepts
+                .iter()
+                .map(|concept| StructuredConcept {
+                    name: concept.name.clone(),
+                    aliases: concept.aliases.clone(),
+                    facets: concept.facets.iter().map(StructuredFacet::from).collect(),
+                })
+                .collect(),
+            sources: doc
+                .sources
+                .iter()
+                .flat_map(|s| &s.entries)
+                .map(|entry| StructuredSource {
+                    handle: entry.handle.clone(),
+                    description: entry.description.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&Facet> for StructuredFacet {
+    fn from(facet: &Facet) -> Self {
+        StructuredFacet {
+            name: facet.name.clone(),
+            claims: facet.claims.iter().map(StructuredClaim::from).collect(),
+            facets: facet.sub_facets.iter().map(StructuredFacet::from).collect(),
+        }
+    }
+}
+
+impl From<&Claim> for StructuredClaim {
+    fn from(claim: &Claim) -> Self {
+        let data = &claim.data;
+        let strings =
+            |values: &[std::borrow::Cow<'_, str>]| values.iter().map(|v| v.to_string()).collect();
+        StructuredClaim {
+            text: data.text.to_string(),
+            id: data.id.as_ref().map(|id| id.to_string()),
+            conditions: data
+                .conditions
+                .iter()
+                .zip(data.parsed_conditions())
+                .map(|(text, parsed)| StructuredCondition {
+                    text: text.to_string(),
+                    kind: match parsed.kind {
+                        ConditionKind::State => "state",
+                        ConditionKind::Temporal => "temporal",
+                        ConditionKind::When => "when",
+                    },
+                    negated: parsed.negated,
+                    subject: parsed.text,
+                })
+                .collect(),
+            sources: strings(&data.sources),
+            references: strings(&data.references),
+            modifiers: data
+                .modifiers
+                .iter()
+                .map(|m| StructuredModifier {
+                    symbol: m.symbol,
+                    attached_to: m.attached_to.to_string(),
+                })
+                .collect(),
+            brief_forms: data
+                .brief_forms
+                .iter()
+                .map(|b| StructuredBriefForm {
+                    operator: b.operator.to_string(),
+                    left: b.left_operand.to_string(),
+                    right: b.right_operand.to_string(),
+                })
+                .collect(),
+            evolution: data
+                .evolution
+                .iter()
+                .map(|e| StructuredEvolution {
+                    prior_belief: e.prior_belief.to_string(),
+                    changed_at: e.changed_at.map(|date| date.to_string()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl StructuredDocument {
+    pub fn into_document(self) -> Document {
+        let sources = (!self.sources.is_empty()).then(|| Sources {
+            entries: self
+                .sources
+                .into_iter()
+                .map(|s| SourceEntry::new(s.handle, s.description))
+                .collect(),
+            ..Sources::default()
+        });
+        Document {
+            includes: self.includes.into_iter().map(Include::new).collect(),
+            concepts: self
+                .concepts
+                .into_iter()
+                .map(|structured| {
+                    let mut concept = Concept::new(structured.name);
+                    concept.aliases = structured.aliases;
+                    concept.facets = structured
+                        .facets
+                        .into_iter()
+                        .map(StructuredFacet::into_facet)
+                        .collect();
+                    concept
+                })
+                .collect(),
+            sources,
+            ..Document::default()
+        }
+    }
+}
+
+impl StructuredFacet {
+    fn into_facet(self) -> Facet {
+        let mut facet = Facet::new(self.name);
+        facet.claims = self
+            .claims
+            .iter()
+            .map(|claim| Claim::parse(&claim.to_wvf_string()))
+            .collect();
+        facet.sub_facets = self
+            .facets
+            .into_iter()
+            .map(StructuredFacet::into_facet)
+            .collect();
+        facet
+    }
+}
+
+impl StructuredClaim {
+    /// Positional claim syntax (without the `- ` prefix)
+    fn to_wvf_string(&self) -> String {
+        let mut out = self.text.clone();
+        for condition in &self.conditions {
+            out.push_str(" | ");
+            out.push_str(&condition.text);
+        }
+        for source in &self.sources {
+            out.push_str(" @");
+            out.push_str(source);
+        }
+        for reference in &self.references {
+            out.push_str(" &");
+            out.push_str(reference);
+        }
+        for marker in &self.evolution {
+            out.push_str(" [<= ");
+            out.push_str(&marker.prior_belief);
+            if let Some(date) = &marker.changed_at {
+                out.push_str(" :");
+                out.push_str(date);
+            }
+            out.push(']');
+        }
+        if let Some(id) = &self.id {
+            out.push(' ');
+            out.push_str(CLAIM_ID_PREFIX);
+            out.push_str(id);
+        }
+        out
+    }
+}
@@ -0,0 +1,49 @@
+//! Summarize subcommand - prose summary of a concept or the whole file, from its claims
+//!
+//! The agent writes the summary from the recorded claims alone. With
+//! `--output`, it is also saved as a Markdown file titled with the concept.
+
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+use worldview_validator::Document;
+
+use crate::add::{self, Options, Task};
+use crate::encryption;
+
+pub async fn run(
+    concept: Option<String>,
+    file: PathBuf,
+    output: Option<PathBuf>,
+    options: &Options,
+) -> Result<()> {
+    let content = encryption::read(&file)?;
+    let doc = Document::parse(&content);
+    match &concept {
+        Some(name) if doc.concept(name).is_none() => {
+            bail!("{}: no concept {}", file.display(), name)
+        }
+        None if doc.concepts.is_empty() => bail!("{}: no concepts to summarize", file.display()),
+        _ => {}
+    }
+
+    let request = concept.clone().unwrap_or_default();
+    let summary = add::run_reply(Task::Summarize, request, file.clone(), options).await?;
+
+    if let Some(output) = output {
+        let source = file.file_name().map_or_else(
+            || file.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let markdown = format!(
+            "# {}\n\n{}\n\n_Summarized from {} on {}._\n",
+            concept.as_deref().unwrap_or("Worldview"),
+            summary.trim(),
+            source,
+            add::today()
+        );
+        std::fs::write(&output, markdown)
+            .with_context(|| format!("Error writing {}", output.display()))?;
+        eprintln!("{}: written", output.display());
+    }
+    Ok(())
+}
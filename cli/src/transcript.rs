@@ -0,0 +1,62 @@
+//! JSON Lines audit log of agent runs (`--log-json`)
+//!
+//! Each line is one event object with an `event` name and a `time_ms`
+//! timestamp. A run starts with `start` and ends with `end`, which carries the
+//! structural diff and whether it was written; in between come the user's
+//! `request`s, every `tool_call` with its parameters, result, and outcome, and
+//! `finished` usage or the `error` that stopped the agent. Runs are appended,
+//! so one file can collect many.
+
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use worldview_validator::Document;
+
+pub struct Transcript {
+    out: BufWriter<File>,
+}
+
+impl Transcript {
+    pub fn open(path: &Path) -> Result<Transcript> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Error opening {}", path.display()))?;
+        Ok(Transcript {
+            out: BufWriter::new(file),
+        })
+    }
+
+    /// Append an event, flushing so the log survives an interrupted run
+    pub fn record(&mut self, event: &str, mut fields: Value) {
+        let time_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        if let Value::Object(map) = &mut fields {
+            map.insert("event".into(), event.into());
+            map.insert("time_ms".into(), time_ms.into());
+        }
+        // An audit log that cannot be written should not stop the edit itself
+        let written = serde_json::to_writer(&mut self.out, &fields)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writeln!(self.out))
+            .and_then(|()| self.out.flush());
+        if let Err(e) = written {
+            eprintln!("warning: could not write transcript: {}", e);
+        }
+    }
+}
+
+/// Structural changes from `old` to `new`, for `end` and review events
+pub fn changes(old: &str, new: &str) -> Value {
+    let changes: Vec<String> = Document::parse(old)
+        .diff(&Document::parse(new))
+        .iter()
+        .map(|change| change.to_string())
+        .collect();
+    json!(changes)
+}
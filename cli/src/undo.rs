@@ -0,0 +1,104 @@
+//! Undo subcommand - restores the version of a file from before the last agent edit
+//!
+//! Agent edits snapshot the file into `.wvf.history/` beside it first, named
+//! `<file name>.<unix milliseconds>`. Each undo restores and removes the newest
+//! snapshot, so repeated undos step further back.
+
+use anyhow::{Context, Result};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::draft::Draft;
+use crate::encryption;
+
+const HISTORY_DIR: &str = ".wvf.history";
+
+fn history_dir(file: &Path) -> PathBuf {
+    file.parent().unwrap_or(Path::new("")).join(HISTORY_DIR)
+}
+
+fn file_name(file: &Path) -> String {
+    file.file_name()
+        .map_or_else(String::new, |name| name.to_string_lossy().into_owned())
+}
+
+/// Copy the current contents of `file` into its history, if it exists
+pub fn snapshot(file: &Path) -> io::Result<()> {
+    if !file.exists() {
+        return Ok(());
+    }
+    let dir = history_dir(file);
+    std::fs::create_dir_all(&dir)?;
+    let mut millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    // Keep snapshots taken within the same millisecond apart
+    let mut path = dir.join(format!("{}.{:013}", file_name(file), millis));
+    while path.exists() {
+        millis += 1;
+        path = dir.join(format!("{}.{:013}", file_name(file), millis));
+    }
+    std::fs::copy(file, path)?;
+    Ok(())
+}
+
+/// Whether `file` has any snapshots
+pub fn has_snapshots(file: &Path) -> Result<bool> {
+    Ok(!snapshots(file)?.is_empty())
+}
+
+/// Snapshots of `file`, oldest first
+fn snapshots(file: &Path) -> Result<Vec<PathBuf>> {
+    let dir = history_dir(file);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let prefix = format!("{}.", file_name(file));
+    let mut snapshots: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .with_context(|| format!("Error reading {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str()?.strip_prefix(&prefix))
+                .is_some_and(|stamp| !stamp.is_empty() && stamp.bytes().all(|b| b.is_ascii_digit()))
+        })
+        .collect();
+    // Zero-padded timestamps sort chronologically
+    snapshots.sort();
+    Ok(snapshots)
+}
+
+pub fn run(file: PathBuf, list: bool) -> Result<()> {
+    let snapshots = snapshots(&file)?;
+    if list {
+        for snapshot in snapshots.iter().rev() {
+            println!("{}", snapshot.display());
+        }
+        return Ok(());
+    }
+    let Some(latest) = snapshots.last() else {
+        println!("{}: nothing to undo", file.display());
+        return Ok(());
+    };
+
+    let mut draft = Draft::open(file.clone(), false)?;
+    draft.content = if encryption::is_encrypted(&file) {
+        encryption::decrypt(latest)?
+    } else {
+        std::fs::read_to_string(latest)
+            .with_context(|| format!("Error reading {}", latest.display()))?
+    };
+    draft.print_diff();
+    // Snapshots of an encrypted file are encrypted too, so copy the bytes back
+    std::fs::copy(latest, &file).with_context(|| format!("Error writing {}", file.display()))?;
+    std::fs::remove_file(latest).with_context(|| format!("Error removing {}", latest.display()))?;
+    println!(
+        "{}: restored from {} ({} earlier version{} left)",
+        file.display(),
+        latest.display(),
+        snapshots.len() - 1,
+        if snapshots.len() == 2 { "" } else { "s" }
+    );
+    Ok(())
+}
@@ -1,50 +1,280 @@
 //! Validate subcommand - validates .wvf files for syntax errors
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use std::fmt;
 use std::io::{self, Read};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use worldview_validator::{Profile, ValidationOptions, ValidationResult};
 
-pub fn run(files: Vec<PathBuf>, stdin: bool) -> Result<()> {
-    let mut all_valid = true;
+use crate::encryption;
+use crate::junit;
+use crate::report::{ColorChoice, Report};
+use crate::sarif;
+
+/// How `validate` reports its results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Diagnostics on their source lines, for people
+    #[default]
+    Text,
+    /// SARIF 2.1.0, for code-scanning tools
+    Sarif,
+    /// JUnit XML with a test case per file, for CI test reports
+    Junit,
+}
+
+/// Results of one run, in the order the files were given
+#[derive(Default)]
+pub struct Checked {
+    /// Display name (path or `<stdin>`) and result of each file validated
+    pub files: Vec<(String, ValidationResult<'static>)>,
+    /// Files that could not be read, with the I/O error message
+    pub unreadable: Vec<(String, String)>,
+}
+
+/// Totals printed after validating several files
+#[derive(Debug, Default)]
+struct Summary {
+    files: usize,
+    invalid: usize,
+    warnings: usize,
+}
+
+impl Summary {
+    fn of(checked: &Checked) -> Summary {
+        let results = checked.files.iter().map(|(_, result)| result);
+        Summary {
+            files: checked.files.len() + checked.unreadable.len(),
+            invalid: results.clone().filter(|result| !result.is_valid()).count()
+                + checked.unreadable.len(),
+            warnings: results.map(|result| result.warnings.len()).sum(),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} files, {} invalid, {} warnings",
+            self.files, self.invalid, self.warnings
+        )
+    }
+}
+
+/// Exit status when a file has errors
+pub const EXIT_ERRORS: i32 = 1;
+/// Exit status when files are valid but their warnings fail the `Policy`
+pub const EXIT_WARNINGS: i32 = 3;
+/// Exit status when a file or pattern could not be read (not 2, which clap
+/// exits with on bad usage)
+pub const EXIT_IO: i32 = 4;
+
+/// How results are printed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Output {
+    pub format: Format,
+    pub color: ColorChoice,
+    /// Print only errors and the summary (text format)
+    pub quiet: bool,
+}
+
+/// When warnings fail a run that has no errors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Policy {
+    /// Any warning fails
+    pub warnings_as_errors: bool,
+    /// More warnings than this, across all files, fail
+    pub max_warnings: Option<usize>,
+    /// The profile, when it is one under which any warning fails (`strict`)
+    pub profile: Option<Profile>,
+}
+
+impl Policy {
+    /// Why `warnings` fail the run, if they do
+    fn violation(&self, warnings: usize) -> Option<String> {
+        let plural = if warnings == 1 { "" } else { "s" };
+        if self.warnings_as_errors && warnings > 0 {
+            Some(format!("{} warning{} (--warnings-as-errors)", warnings, plural))
+        } else if let Some(profile) = self.profile.filter(|_| warnings > 0) {
+            Some(format!("{} warning{} (--profile {})", warnings, plural, profile))
+        } else {
+            self.max_warnings
+                .filter(|max| warnings > *max)
+                .map(|max| format!("{} warning{} (--max-warnings {})", warnings, plural, max))
+        }
+    }
+}
+
+pub fn run(
+    files: Vec<PathBuf>,
+    stdin: bool,
+    options: &ValidationOptions,
+    output: &Output,
+    policy: Policy,
+) -> Result<()> {
+    let mut checked = Checked::default();
+    let files = expand_paths(&files).unwrap_or_else(|e| io_failure(e));
 
     if stdin {
         // Read from stdin
         let mut content = String::new();
-        io::stdin().read_to_string(&mut content)?;
-        let result = worldview_validator::validate(&content);
-        print!("{}", result);
-        if !result.is_valid() {
-            all_valid = false;
+        if let Err(e) = io::stdin().read_to_string(&mut content) {
+            io_failure(anyhow::Error::new(e).context("Error reading stdin"));
         }
+        let result = worldview_validator::validate_with_options(&content, options);
+        checked.files.push(("<stdin>".to_string(), result.into_owned()));
     } else {
         // Validate each file
         for path in &files {
-            if files.len() > 1 {
-                println!("{}:", path.display());
-            }
-
-            match worldview_validator::validate_file(path) {
-                Ok(result) => {
-                    println!("{}", result);
-                    if !result.is_valid() {
-                        all_valid = false;
+            let name = path.display().to_string();
+            if encryption::is_encrypted(path) {
+                // Decrypted in memory only
+                match encryption::read(path) {
+                    Ok(content) => {
+                        let result = worldview_validator::validate_in_context(&content, path, options);
+                        checked.files.push((name, result.into_owned()));
                     }
+                    Err(e) => checked.unreadable.push((name, format!("{:#}", e))),
                 }
-                Err(e) => {
-                    eprintln!("Error reading {}: {}", path.display(), e);
-                    all_valid = false;
-                }
+                continue;
+            }
+            match worldview_validator::validate_file_with_options(path, options) {
+                Ok(result) => checked.files.push((name, result)),
+                Err(e) => checked.unreadable.push((name, e.to_string())),
             }
+        }
+    }
+
+    report(&checked, output, policy, files.len() > 1)
+}
+
+/// Validate files as one workspace, resolving references across all of them
+pub fn run_workspace(
+    files: Vec<PathBuf>,
+    options: &ValidationOptions,
+    output: &Output,
+    policy: Policy,
+) -> Result<()> {
+    let files = expand_paths(&files).unwrap_or_else(|e| io_failure(e));
+    let result = worldview_validator::validate_workspace_with_options(&files, options);
+    let checked = Checked {
+        files: result
+            .files
+            .into_iter()
+            .map(|file| (file.path.display().to_string(), file.result))
+            .collect(),
+        unreadable: result
+            .unreadable
+            .into_iter()
+            .map(|(path, error)| (path.display().to_string(), error))
+            .collect(),
+    };
+    report(&checked, output, policy, true)
+}
+
+fn io_failure(error: anyhow::Error) -> ! {
+    eprintln!("Error: {:#}", error);
+    std::process::exit(EXIT_IO);
+}
 
-            if files.len() > 1 {
+/// Print the results, then exit with `EXIT_IO`, `EXIT_ERRORS`, or
+/// `EXIT_WARNINGS` (in that order of precedence) if the run failed
+fn report(checked: &Checked, output: &Output, policy: Policy, summary: bool) -> Result<()> {
+    match output.format {
+        Format::Text => {
+            let report = Report::new(output.color, output.quiet);
+            for (name, result) in &checked.files {
+                report.print(name, result);
+            }
+            for (name, error) in &checked.unreadable {
+                eprintln!("Error reading {}: {}", name, error);
+            }
+            if summary {
                 println!();
+                println!("{}", Summary::of(checked));
             }
         }
+        Format::Sarif => println!("{}", serde_json::to_string_pretty(&sarif::log(checked))?),
+        Format::Junit => println!("{}", junit::report(checked)),
     }
 
-    if all_valid {
-        Ok(())
-    } else {
-        std::process::exit(1);
+    let results = checked.files.iter().map(|(_, result)| result);
+    if !checked.unreadable.is_empty() {
+        std::process::exit(EXIT_IO);
+    }
+    if results.clone().any(|result| !result.is_valid()) {
+        std::process::exit(EXIT_ERRORS);
+    }
+    let warnings = results.map(|result| result.warnings.len()).sum();
+    if let Some(violation) = policy.violation(warnings) {
+        eprintln!("Validation failed: {}", violation);
+        std::process::exit(EXIT_WARNINGS);
+    }
+    Ok(())
+}
+
+/// Expand directories (recursively) and glob patterns to `.wvf` files
+///
+/// Paths that exist are used as given; anything else containing a glob
+/// metacharacter is treated as a pattern, so quoted `worldviews/**/*.wvf` works.
+fn expand_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut found = Vec::new();
+            collect_wvf_files(path, &mut found)?;
+            found.sort();
+            files.extend(found);
+        } else if !path.exists() && path.to_string_lossy().contains(['*', '?', '[']) {
+            let pattern = path.to_string_lossy();
+            let matches = glob::glob(&pattern)
+                .with_context(|| format!("Invalid glob pattern {}", pattern))?;
+            let before = files.len();
+            for entry in matches {
+                let entry = entry?;
+                if entry.is_file() {
+                    files.push(entry);
+                }
+            }
+            if files.len() == before {
+                bail!("No files match {}", pattern);
+            }
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn collect_wvf_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in
+        std::fs::read_dir(dir).with_context(|| format!("Error reading {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_wvf_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "wvf") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Write an edited document, refusing if it no longer validates
+pub fn write_if_valid(path: &Path, content: &str, nested_facets: bool) -> Result<()> {
+    let options = ValidationOptions {
+        nested_facets,
+        ..ValidationOptions::default()
+    };
+    let validation = worldview_validator::validate_in_context(content, path, &options);
+    if !validation.is_valid() {
+        bail!(
+            "Validation failed - {} not modified:\n{}",
+            path.display(),
+            validation
+        );
     }
+    std::fs::write(path, content).with_context(|| format!("Error writing {}", path.display()))
 }
@@ -0,0 +1,339 @@
+//! Snapshots of CLI output for the files in `tests/fixtures`
+//!
+//! Each snapshot holds the exit code, stdout, and stderr of one command run in
+//! the fixtures directory, with config files out of reach. After an intended
+//! change to output, review and accept the new snapshots with `cargo insta
+//! review` (or rerun with `INSTA_UPDATE=always`).
+
+use std::path::Path;
+use std::process::Command;
+
+fn run(args: &[&str]) -> String {
+    run_with_env(args, &[])
+}
+
+fn run_with_env(args: &[&str], vars: &[(&str, &str)]) -> String {
+    run_in(".", args, vars)
+}
+
+/// Run in a subdirectory of the fixtures, e.g. one with a `.worldview.toml`
+fn run_in(dir: &str, args: &[&str], vars: &[(&str, &str)]) -> String {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let output = Command::new(env!("CARGO_BIN_EXE_worldview"))
+        .args(args)
+        .current_dir(fixtures.join(dir))
+        // No user config, no trace logs, and no backtraces on errors
+        .env("XDG_CONFIG_HOME", fixtures.join("no-config"))
+        .env("HOME", fixtures.join("no-home"))
+        .env_remove("RUST_LOG")
+        .env("RUST_BACKTRACE", "0")
+        .env("RUST_LIB_BACKTRACE", "0")
+        .envs(vars.iter().copied())
+        .output()
+        .expect("failed to run worldview");
+    format!(
+        "exit: {}\n--- stdout\n{}--- stderr\n{}",
+        output
+            .status
+            .code()
+            .map_or("signal".to_string(), |c| c.to_string()),
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn validate_valid() {
+    insta::assert_snapshot!(run(&["validate", "worldview.wvf"]));
+}
+
+#[test]
+fn validate_invalid() {
+    insta::assert_snapshot!(run(&["validate", "--color", "never", "invalid.wvf"]));
+}
+
+#[test]
+fn validate_invalid_quiet() {
+    insta::assert_snapshot!(run(&["validate", "--quiet", "invalid.wvf"]));
+}
+
+#[test]
+fn validate_sarif() {
+    insta::assert_snapshot!(run(&["validate", "--format", "sarif", "invalid.wvf"]));
+}
+
+#[test]
+fn validate_junit() {
+    insta::assert_snapshot!(run(&[
+        "validate",
+        "--format",
+        "junit",
+        "worldview.wvf",
+        "invalid.wvf"
+    ]));
+}
+
+#[test]
+fn validate_warnings_as_errors() {
+    insta::assert_snapshot!(run(&[
+        "validate",
+        "--warnings-as-errors",
+        "--color",
+        "never",
+        "warnings.wvf"
+    ]));
+}
+
+#[test]
+fn validate_strict_profile_warnings() {
+    insta::assert_snapshot!(run(&[
+        "validate",
+        "--profile",
+        "strict",
+        "--color",
+        "never",
+        "warnings.wvf"
+    ]));
+}
+
+#[test]
+fn validate_missing_file() {
+    insta::assert_snapshot!(run(&["validate", "missing.wvf"]));
+}
+
+#[test]
+fn export_json() {
+    insta::assert_snapshot!(run(&["export", "--format", "json"]));
+}
+
+#[test]
+fn export_yaml() {
+    insta::assert_snapshot!(run(&["export", "--format", "yaml"]));
+}
+
+#[test]
+fn export_markdown() {
+    insta::assert_snapshot!(run(&["export", "--format", "md"]));
+}
+
+#[test]
+fn export_org() {
+    insta::assert_snapshot!(run(&["export", "--format", "org"]));
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn schema_matches_spec() {
+    // After changing the export, regenerate with `worldview schema -o spec/export.schema.json`
+    assert_eq!(
+        run(&["schema"]),
+        format!(
+            "exit: 0\n--- stdout\n{}--- stderr\n",
+            include_str!("../../spec/export.schema.json")
+        )
+    );
+}
+
+#[test]
+fn export_prompt() {
+    insta::assert_snapshot!(run(&[
+        "export",
+        "--format",
+        "prompt",
+        "--budget",
+        "120-tokens",
+        "--concepts",
+        "Power,Trust",
+    ]));
+}
+
+#[test]
+fn export_redacted() {
+    insta::assert_snapshot!(run(&[
+        "export",
+        "--format",
+        "md",
+        "--redact",
+        "private",
+        "--file",
+        "private.wvf",
+    ]));
+}
+
+#[test]
+fn validate_sensitivity_markers() {
+    insta::assert_snapshot!(run(&["validate", "--color", "never", "private.wvf"]));
+}
+
+#[test]
+fn fmt_check() {
+    insta::assert_snapshot!(run(&["fmt", "--check"]));
+}
+
+#[test]
+fn sources() {
+    insta::assert_snapshot!(run(&["sources"]));
+}
+
+#[test]
+fn references() {
+    insta::assert_snapshot!(run(&["references"]));
+}
+
+#[test]
+fn stats_tokens() {
+    insta::assert_snapshot!(run(&["stats", "--tokens", "--model", "gpt-4o"]));
+}
+
+#[test]
+fn show_facet() {
+    insta::assert_snapshot!(run(&["show", "Power.institutional"]));
+}
+
+#[test]
+fn history() {
+    insta::assert_snapshot!(run(&["history", "Human-nature"]));
+}
+
+#[test]
+fn search() {
+    insta::assert_snapshot!(run(&["search", "transparency"]));
+}
+
+#[test]
+fn cluster() {
+    insta::assert_snapshot!(run(&["cluster"]));
+}
+
+#[test]
+fn validate_encrypted() {
+    insta::assert_snapshot!(run_with_env(
+        &["validate", "secret.wvf.age"],
+        &[("WORLDVIEW_AGE_IDENTITY", "age-identity.txt")],
+    ));
+}
+
+#[test]
+fn missing_file() {
+    insta::assert_snapshot!(run(&["show", "--file", "missing.wvf"]));
+}
+
+#[test]
+fn import_outline() {
+    insta::assert_snapshot!(run(&["import", "import/outline.md"]));
+}
+
+#[test]
+fn import_csv() {
+    insta::assert_snapshot!(run(&["import", "import/claims.csv"]));
+}
+
+#[test]
+fn import_tsv() {
+    insta::assert_snapshot!(run(&["import", "import/claims.tsv"]));
+}
+
+#[test]
+fn import_json() {
+    insta::assert_snapshot!(run(&["import", "import/document.json"]));
+}
+
+#[test]
+fn import_yaml() {
+    insta::assert_snapshot!(run(&["import", "import/document.yaml"]));
+}
+
+#[test]
+fn import_into() {
+    // Written to stdout rather than back into the fixture
+    insta::assert_snapshot!(run(&[
+        "import",
+        "import/claims.tsv",
+        "--into",
+        "worldview.wvf",
+        "--output",
+        "/dev/stdout",
+    ]));
+}
+
+#[test]
+fn import_export_only_format() {
+    insta::assert_snapshot!(run(&["import", "--from", "html", "report.html"]));
+}
+
+#[test]
+fn export_import_only_format() {
+    insta::assert_snapshot!(run(&["export", "--format", "csv"]));
+}
+
+#[test]
+fn import_notion_without_database() {
+    insta::assert_snapshot!(run(&["import", "--from", "notion"]));
+}
+
+#[test]
+fn import_notion_from_file() {
+    insta::assert_snapshot!(run(&["import", "--from", "notion", "import/claims.csv"]));
+}
+
+#[test]
+fn import_readwise_without_into() {
+    insta::assert_snapshot!(run(&["import", "--from", "readwise"]));
+}
+
+#[test]
+fn import_org() {
+    insta::assert_snapshot!(run(&["import", "import/outline.org"]));
+}
+
+/// A user config in `fixtures/config` defaulting to warnings.wvf and the
+/// strict profile, with a `clean` profile for worldview.wvf
+fn run_with_config(args: &[&str]) -> String {
+    let config = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/config");
+    run_with_env(args, &[("XDG_CONFIG_HOME", config.to_str().unwrap())])
+}
+
+#[test]
+fn config_lint_profile() {
+    insta::assert_snapshot!(run_with_config(&["validate", "warnings.wvf"]));
+}
+
+#[test]
+fn config_file() {
+    insta::assert_snapshot!(run_with_config(&["show", "Power.core"]));
+}
+
+#[test]
+fn config_named_profile() {
+    insta::assert_snapshot!(run_with_config(&[
+        "--profile",
+        "clean",
+        "show",
+        "Power.institutional"
+    ]));
+}
+
+#[test]
+fn config_unknown_profile() {
+    insta::assert_snapshot!(run_with_config(&[
+        "--profile",
+        "missing",
+        "show",
+        "Power.core"
+    ]));
+}
+
+#[test]
+fn config_project_file() {
+    insta::assert_snapshot!(run_in("project", &["show", "Notes"], &[]));
+}
+
+#[test]
+fn config_project_decrypt() {
+    // The project's `file` is not the default of `decrypt --file`
+    insta::assert_snapshot!(run_in(
+        "project",
+        &["decrypt", "--output", "-"],
+        &[("WORLDVIEW_AGE_IDENTITY", "../age-identity.txt")],
+    ));
+}
@@ -0,0 +1,38 @@
+//! Build script for conformance
+//! Embeds the corpus, so `worldview self-test` runs without the source tree.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut cases = String::from("pub(crate) const CORPUS: &[(&str, &str, Option<&str>)] = &[\n");
+    for dir in ["valid", "invalid"] {
+        println!("cargo:rerun-if-changed={}", dir);
+        let mut names: Vec<String> = fs::read_dir(root.join(dir))
+            .expect("Failed to read corpus directory")
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|name| name.strip_suffix(".wvf").map(str::to_string))
+            .collect();
+        names.sort();
+        for name in names {
+            let input = root.join(dir).join(format!("{}.wvf", name));
+            let expected = root.join(dir).join(format!("{}.expected", name));
+            let expected = match expected.exists() {
+                true => format!("Some(include_str!({:?}))", expected),
+                false => "None".to_string(),
+            };
+            cases.push_str(&format!(
+                "    ({:?}, include_str!({:?}), {}),\n",
+                format!("{}/{}", dir, name),
+                input,
+                expected
+            ));
+        }
+    }
+    cases.push_str("];\n");
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("corpus.rs"), cases).expect("Failed to write corpus.rs");
+}
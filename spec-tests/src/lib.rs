@@ -0,0 +1,247 @@
+//! Spec conformance corpus for the Worldview format
+//!
+//! Documents in `valid/` must validate without errors, and documents in
+//! `invalid/` must fail. Beside each `NAME.wvf`, an optional `NAME.expected`
+//! lists the diagnostics an implementation must report, one `LINE CODE` per
+//! line (`3 orphan-claim`), and may turn on grammar extensions with an
+//! `options:` line (`options: nested-facets`). A case without one must report
+//! nothing at all. Lines starting with `#` are comments.
+//!
+//! The corpus is embedded at build time; `check_all` runs it against this
+//! repository's validator, and `compare` checks another implementation's
+//! output.
+
+use std::fmt;
+use thiserror::Error;
+use worldview_validator::{ValidationOptions, validate_with_options};
+
+include!(concat!(env!("OUT_DIR"), "/corpus.rs"));
+
+/// A `.expected` file that could not be read
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ExpectedError {
+    #[error("line {line}: expected `LINE CODE`, found '{text}'")]
+    InvalidDiagnostic { line: usize, text: String },
+    #[error("line {line}: unknown option '{option}' (expected nested-facets or strict-whitespace)")]
+    UnknownOption { line: usize, option: String },
+}
+
+/// What validating a case must report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub nested_facets: bool,
+    pub strict_whitespace: bool,
+    /// `(line, code)` of every error and warning, sorted
+    pub diagnostics: Vec<(usize, String)>,
+}
+
+impl Expected {
+    pub fn parse(text: &str) -> Result<Expected, ExpectedError> {
+        let mut expected = Expected::default();
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(options) = line.strip_prefix("options:") {
+                for option in options.split([',', ' ']).filter(|o| !o.is_empty()) {
+                    match option {
+                        "nested-facets" => expected.nested_facets = true,
+                        "strict-whitespace" => expected.strict_whitespace = true,
+                        _ => {
+                            return Err(ExpectedError::UnknownOption {
+                                line: line_number,
+                                option: option.to_string(),
+                            });
+                        }
+                    }
+                }
+                continue;
+            }
+            let diagnostic = line
+                .split_once(' ')
+                .and_then(|(number, code)| Some((number.parse().ok()?, code.trim().to_string())))
+                .filter(|(_, code): &(usize, String)| !code.is_empty() && !code.contains(' '));
+            let Some(diagnostic) = diagnostic else {
+                return Err(ExpectedError::InvalidDiagnostic {
+                    line: line_number,
+                    text: line.to_string(),
+                });
+            };
+            expected.diagnostics.push(diagnostic);
+        }
+        expected.diagnostics.sort();
+        Ok(expected)
+    }
+}
+
+/// One corpus document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    /// Directory and file stem (`invalid/orphan-claim`)
+    pub name: &'static str,
+    pub input: &'static str,
+    pub expected: Expected,
+}
+
+impl Case {
+    /// Whether the case is in `valid/`
+    pub fn is_valid(&self) -> bool {
+        self.name.starts_with("valid/")
+    }
+
+    pub fn options(&self) -> ValidationOptions {
+        ValidationOptions {
+            nested_facets: self.expected.nested_facets,
+            strict_whitespace: self.expected.strict_whitespace,
+            ..ValidationOptions::default()
+        }
+    }
+}
+
+/// Every case in the corpus, `valid/` first
+///
+/// Fails on the first malformed `.expected` file, naming its case.
+pub fn cases() -> Result<Vec<Case>, (String, ExpectedError)> {
+    CORPUS
+        .iter()
+        .map(|&(name, input, expected)| {
+            let expected = match expected {
+                Some(text) => Expected::parse(text).map_err(|e| (name.to_string(), e))?,
+                None => Expected::default(),
+            };
+            Ok(Case {
+                name,
+                input,
+                expected,
+            })
+        })
+        .collect()
+}
+
+/// How an implementation's output differs from what a case expects
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The document was accepted when it should fail, or rejected when it should pass
+    pub wrong_verdict: bool,
+    /// Expected but not reported
+    pub missing: Vec<(usize, String)>,
+    /// Reported but not expected
+    pub unexpected: Vec<(usize, String)>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.wrong_verdict {
+            parts.push("wrong verdict".to_string());
+        }
+        for (line, code) in &self.missing {
+            parts.push(format!("missing {} on line {}", code, line));
+        }
+        for (line, code) in &self.unexpected {
+            parts.push(format!("unexpected {} on line {}", code, line));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Compare an implementation's verdict and `(line, code)` diagnostics with a case
+pub fn compare(case: &Case, valid: bool, reported: &[(usize, String)]) -> Option<Mismatch> {
+    let mut missing = case.expected.diagnostics.clone();
+    let mut unexpected = Vec::new();
+    for diagnostic in reported {
+        match missing.iter().position(|d| d == diagnostic) {
+            Some(i) => {
+                missing.remove(i);
+            }
+            None => unexpected.push(diagnostic.clone()),
+        }
+    }
+    unexpected.sort();
+    let mismatch = Mismatch {
+        wrong_verdict: valid != case.is_valid(),
+        missing,
+        unexpected,
+    };
+    (mismatch != Mismatch::default()).then_some(mismatch)
+}
+
+/// Validate a case with this repository's validator
+pub fn check(case: &Case) -> Option<Mismatch> {
+    let result = validate_with_options(case.input, &case.options());
+    let reported: Vec<(usize, String)> = result
+        .errors
+        .iter()
+        .chain(&result.warnings)
+        .map(|d| (d.line(), d.code().to_string()))
+        .collect();
+    compare(case, result.is_valid(), &reported)
+}
+
+/// Cases this repository's validator does not conform on
+pub fn check_all(cases: &[Case]) -> Vec<(&Case, Mismatch)> {
+    cases
+        .iter()
+        .filter_map(|case| check(case).map(|mismatch| (case, mismatch)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expected() {
+        let expected = Expected::parse(
+            "# sub-facets\noptions: nested-facets\n\n4 orphan-claim\n2 facet-without-claims\n",
+        )
+        .unwrap();
+        assert!(expected.nested_facets && !expected.strict_whitespace);
+        assert_eq!(
+            expected.diagnostics,
+            vec![
+                (2, "facet-without-claims".to_string()),
+                (4, "orphan-claim".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_malformed_expected() {
+        assert_eq!(
+            Expected::parse("orphan-claim"),
+            Err(ExpectedError::InvalidDiagnostic {
+                line: 1,
+                text: "orphan-claim".to_string()
+            })
+        );
+        assert_eq!(
+            Expected::parse("options: tabs"),
+            Err(ExpectedError::UnknownOption {
+                line: 1,
+                option: "tabs".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_compare_reports_each_difference() {
+        let case = Case {
+            name: "invalid/example",
+            input: "",
+            expected: Expected::parse("3 orphan-claim\n").unwrap(),
+        };
+        assert_eq!(
+            compare(&case, false, &[(3, "orphan-claim".to_string())]),
+            None
+        );
+
+        let mismatch = compare(&case, true, &[(2, "duplicate-claim".to_string())]).unwrap();
+        assert_eq!(
+            mismatch.to_string(),
+            "wrong verdict; missing orphan-claim on line 3; unexpected duplicate-claim on line 2"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Runs the conformance corpus against the validator
+
+#[test]
+fn test_corpus_conforms() {
+    let cases = conformance::cases().unwrap_or_else(|(name, e)| panic!("{}.expected: {}", name, e));
+    assert!(cases.iter().any(|case| case.is_valid()));
+    assert!(cases.iter().any(|case| !case.is_valid()));
+
+    let failures: Vec<String> = conformance::check_all(&cases)
+        .iter()
+        .map(|(case, mismatch)| format!("{}: {}", case.name, mismatch))
+        .collect();
+    assert!(
+        failures.is_empty(),
+        "non-conforming cases:\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_invalid_cases_expect_an_error() {
+    // A case in invalid/ with nothing expected could pass by reporting anything
+    let cases = conformance::cases().unwrap();
+    for case in cases.iter().filter(|case| !case.is_valid()) {
+        assert!(
+            !case.expected.diagnostics.is_empty(),
+            "{} has no .expected diagnostics",
+            case.name
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Parser and validator benchmarks over synthetic documents
+//!
+//! Run with `cargo bench`; compare against a saved run with
+//! `cargo bench -- --save-baseline main` then `cargo bench -- --baseline main`.
+//! CI does this for each pull request against its base commit and fails when
+//! `regressions.py` finds a benchmark slower beyond the noise.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use worldview_validator::{Claim, ClaimExpr, Document, tokenize, validate};
+
+/// Claim bodies cycled through when generating documents
+const CLAIMS: &[&str] = &[
+    "slow",
+    "requires consistency | over time",
+    "contextual @personal-experience",
+    "fast !",
+    "single violation => collapse?",
+    "asymmetric vs formation &Concept0.facet0",
+    "concentration^ => abuse^ vs accountability",
+    "formal = official ~ sanctioned",
+    "institutional memory v | after turnover @history",
+    "reveals character * [<= corrupts absolutely]",
+];
+
+/// A valid document with `concepts` x `facets` x `claims` nodes
+fn document(concepts: usize, facets: usize, claims: usize) -> String {
+    let mut out = String::new();
+    for c in 0..concepts {
+        out.push_str(&format!("Concept{}\n", c));
+        for f in 0..facets {
+            out.push_str(&format!("  .facet{}\n", f));
+            for n in 0..claims {
+                out.push_str("    - ");
+                out.push_str(CLAIMS[(c + f + n) % CLAIMS.len()]);
+                out.push('\n');
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn sizes() -> [(&'static str, String); 3] {
+    [
+        ("small", document(3, 2, 4)),
+        ("medium", document(50, 4, 8)),
+        ("huge", document(1000, 5, 10)),
+    ]
+}
+
+fn bench_documents(c: &mut Criterion) {
+    for (name, input) in sizes() {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function("tokenize", |b| b.iter(|| tokenize(black_box(&input))));
+        group.bench_function("validate", |b| b.iter(|| validate(black_box(&input))));
+        group.bench_function("document", |b| {
+            b.iter(|| Document::parse(black_box(&input)))
+        });
+        group.finish();
+    }
+}
+
+fn bench_claims(c: &mut Criterion) {
+    let mut group = c.benchmark_group("claim");
+    for (name, text) in [
+        ("plain", "slow"),
+        (
+            "elements",
+            "requires consistency | over time @history &Trust.formation",
+        ),
+        (
+            "brief_forms",
+            "concentration^ => abuse^ vs accountability = power ~ control",
+        ),
+        ("evolution", "reveals character * [<= corrupts absolutely]"),
+    ] {
+        group.bench_with_input(BenchmarkId::new("parse", name), text, |b, text| {
+            b.iter(|| Claim::parse(black_box(text)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_brief_forms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("brief_forms");
+    for (name, text) in [
+        ("single", "power => corruption"),
+        ("chain", "concentration^ => abuse^ vs accountability"),
+        (
+            "long_chain",
+            "power => control ~ influence vs accountability = oversight => trust",
+        ),
+    ] {
+        let claim = Claim::parse(text);
+        let expression = claim.data.expression.as_ref().expect("claim has brief forms");
+        group.bench_with_input(
+            BenchmarkId::new("extract", name),
+            expression,
+            |b, expression| b.iter(|| ClaimExpr::brief_forms(black_box(expression))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_documents, bench_claims, bench_brief_forms);
+criterion_main!(benches);
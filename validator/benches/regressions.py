@@ -0,0 +1,49 @@
+"""Fail when a criterion comparison run found benchmarks slower than the threshold.
+
+Reads the `change/estimates.json` criterion writes for each benchmark compared
+against a baseline (`cargo bench -- --baseline NAME`). A benchmark regresses
+when even the lower bound of its mean's confidence interval is slower by more
+than the threshold, so noise on shared CI runners does not fail a build.
+
+Usage: python3 regressions.py [CRITERION_DIR] [--threshold 0.10]
+"""
+
+import argparse
+import json
+import sys
+from pathlib import Path
+
+
+def main() -> int:
+    parser = argparse.ArgumentParser()
+    parser.add_argument("criterion_dir", nargs="?", default="target/criterion")
+    parser.add_argument("--threshold", type=float, default=0.10,
+                        help="relative slowdown that fails, e.g. 0.10 for 10%%")
+    args = parser.parse_args()
+
+    changes = sorted(Path(args.criterion_dir).glob("**/change/estimates.json"))
+    if not changes:
+        print("No benchmark comparisons found; was cargo bench run with --baseline?")
+        return 0
+
+    regressions = []
+    for path in changes:
+        name = str(path.parent.parent.relative_to(args.criterion_dir))
+        mean = json.loads(path.read_text())["mean"]
+        change = mean["point_estimate"]
+        lower = mean["confidence_interval"]["lower_bound"]
+        print(f"{change:+7.1%}  {name}")
+        if lower > args.threshold:
+            regressions.append((name, change))
+
+    if regressions:
+        print(f"\n{len(regressions)} benchmark(s) slower by more than {args.threshold:.0%}:")
+        for name, change in regressions:
+            print(f"  {name}: {change:+.1%}")
+        return 1
+    print(f"\nNo benchmark slower by more than {args.threshold:.0%}")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
@@ -8,10 +8,24 @@ use std::path::Path;
 
 #[derive(Deserialize)]
 struct TokenSpec {
+    structure: Structure,
     brief_forms: Vec<BriefForm>,
     modifiers: Vec<Modifier>,
 }
 
+#[derive(Deserialize)]
+struct Structure {
+    concept: Element,
+    facet: Element,
+    claim: Element,
+}
+
+#[derive(Deserialize)]
+struct Element {
+    indent: usize,
+    prefix: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct BriefForm {
     symbol: String,
@@ -61,7 +75,25 @@ fn generate_tokens_rs(spec: &TokenSpec) -> String {
     for m in &spec.modifiers {
         output.push_str(&format!("    (\"{}\", \"{}\"),\n", m.symbol, m.meaning));
     }
-    output.push_str("];\n");
+    output.push_str("];\n\n");
+
+    // Generate structure constants
+    let s = &spec.structure;
+    output.push_str("/// Indentation levels (in spaces)\n");
+    output.push_str(&format!("pub const CONCEPT_INDENT: usize = {};\n", s.concept.indent));
+    output.push_str(&format!("pub const FACET_INDENT: usize = {};\n", s.facet.indent));
+    output.push_str(&format!("pub const CLAIM_INDENT: usize = {};\n\n", s.claim.indent));
+    output.push_str("/// Element prefixes\n");
+    output.push_str(&format!("pub const FACET_PREFIX: char = '{}';\n", prefix(&s.facet)));
+    output.push_str(&format!("pub const CLAIM_PREFIX: char = '{}';\n", prefix(&s.claim)));
 
     output
 }
+
+fn prefix(element: &Element) -> char {
+    element
+        .prefix
+        .as_deref()
+        .and_then(|p| p.chars().next())
+        .expect("structure element must declare a prefix")
+}
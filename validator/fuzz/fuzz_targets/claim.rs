@@ -0,0 +1,51 @@
+//! Structured claims around an evolution marker: extraction must keep every word
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use worldview_validator::Claim;
+
+#[derive(Debug, Arbitrary)]
+struct MarkedClaim {
+    before: String,
+    prior: String,
+    after: String,
+}
+
+/// Drop characters that start inline elements or markers, so only the marker under test remains
+fn plain(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '|' | '@' | '&' | '[' | ']' | ':' | '\n' | '\r'))
+        .collect()
+}
+
+fuzz_target!(|input: MarkedClaim| {
+    let before = plain(&input.before);
+    let prior = plain(&input.prior);
+    let after = plain(&input.after);
+    let claim = Claim::parse(&format!("{} [<= {}] {}", before, prior, after));
+
+    let [marker] = claim.data.evolution.as_slice() else {
+        panic!("expected one closed marker, got {:?}", claim.data.evolution);
+    };
+    assert_eq!(marker.prior_belief, prior.trim());
+
+    let expected = before.split_whitespace().chain(after.split_whitespace());
+    assert!(
+        claim.data.text.split_whitespace().eq(expected),
+        "text {:?} lost words from {:?} / {:?}",
+        claim.data.text,
+        before,
+        after
+    );
+
+    // Brief-form and modifier operands are slices of the claim text
+    for usage in &claim.data.brief_forms {
+        assert!(claim.data.text.contains(usage.left_operand.as_ref()));
+        assert!(claim.data.text.contains(usage.right_operand.as_ref()));
+    }
+    for usage in &claim.data.modifiers {
+        assert!(claim.data.text.contains(usage.attached_to.as_ref()));
+    }
+});
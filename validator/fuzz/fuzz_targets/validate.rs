@@ -0,0 +1,28 @@
+//! Arbitrary input must never panic, and spans and raw lines must point into the input
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use worldview_validator::{Document, ValidationOptions, tokenize, validate_with_options};
+
+fuzz_target!(|data: &[u8]| {
+    let input = String::from_utf8_lossy(data);
+
+    for options in [ValidationOptions::default(), ValidationOptions::nested()] {
+        let result = validate_with_options(&input, &options);
+        for line in &result.lines {
+            assert!(input.contains(line.raw.as_ref()));
+        }
+    }
+
+    let mut end = 0;
+    for token in tokenize(&input) {
+        assert!(token.span.start >= end, "overlapping tokens");
+        assert!(!token.text(&input).is_empty());
+        end = token.span.end;
+    }
+
+    let doc = Document::parse(&input);
+    let _ = doc.to_wvf_string();
+    let _ = doc.validate();
+});
@@ -0,0 +1,50 @@
+/*
+ * C API for the Worldview validator.
+ *
+ * Build the shared library with `cargo build --release --features ffi` in
+ * validator/ and link against libworldview_validator.
+ *
+ *     wvf_result *result = wvf_validate(text);
+ *     for (size_t i = 0; i < wvf_error_count(result); i++)
+ *         puts(wvf_error_message(result, i));
+ *     wvf_free(result);
+ *
+ * Message strings are owned by the result and valid until wvf_free.
+ */
+
+#ifndef WORLDVIEW_H
+#define WORLDVIEW_H
+
+#include <stdbool.h>
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct WvfResult wvf_result;
+
+/* Validate a NUL-terminated document. Returns NULL if input is NULL. */
+wvf_result *wvf_validate(const char *input);
+
+/* Release a result. NULL is ignored. */
+void wvf_free(wvf_result *result);
+
+/* True if the document has no errors (warnings are allowed). */
+bool wvf_is_valid(const wvf_result *result);
+
+size_t wvf_error_count(const wvf_result *result);
+/* "line N: ..." message, or NULL if index is out of range. */
+const char *wvf_error_message(const wvf_result *result, size_t index);
+/* 1-based line, or 0 if index is out of range. */
+size_t wvf_error_line(const wvf_result *result, size_t index);
+
+size_t wvf_warning_count(const wvf_result *result);
+const char *wvf_warning_message(const wvf_result *result, size_t index);
+size_t wvf_warning_line(const wvf_result *result, size_t index);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WORLDVIEW_H */
@@ -0,0 +1,238 @@
+//! Concept aliases: other names a concept answers to
+//!
+//! A concept line may list aliases after its name, `Power (aka Authority,
+//! Control)`. References may name a concept by any of them, so
+//! `&Authority.nature` resolves to `Power.nature`, and `Document::concept`
+//! finds the concept by alias too. An alias that is also another concept's
+//! name or alias is a warning, since references to it become ambiguous.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::document::Document;
+use crate::{ParsedLine, ValidationError};
+
+/// Opens an alias list after a concept name
+pub const ALIAS_OPEN: &str = "(aka";
+
+/// Closes an alias list
+pub const ALIAS_CLOSE: char = ')';
+
+/// Split a concept line's content into its name and aliases
+///
+/// Content without an alias list is all name. `None` when the list is
+/// malformed: unclosed, followed by more text, empty, or with an empty alias.
+pub(crate) fn split(content: &str) -> Option<(&str, Vec<&str>)> {
+    let Some(start) = alias_list_start(content) else {
+        return Some((content, Vec::new()));
+    };
+    let name = content[..start].trim_end();
+    let list = content[start + ALIAS_OPEN.len()..].strip_suffix(ALIAS_CLOSE)?;
+    let aliases: Vec<&str> = list.split(',').map(str::trim).collect();
+    if name.is_empty() || aliases.iter().any(|alias| alias.is_empty()) {
+        return None;
+    }
+    Some((name, aliases))
+}
+
+/// A concept line's name without its alias list, well-formed or not
+pub(crate) fn name(content: &str) -> &str {
+    match alias_list_start(content).map(|start| content[..start].trim_end()) {
+        Some(name) if !name.is_empty() => name,
+        _ => content,
+    }
+}
+
+/// Where `(aka` opens an alias list: followed by a space or `)`, so a name
+/// like `Backpack (akubra)` is left alone
+fn alias_list_start(content: &str) -> Option<usize> {
+    content
+        .match_indices(ALIAS_OPEN)
+        .map(|(i, _)| i)
+        .find(|&i| {
+            content[i + ALIAS_OPEN.len()..]
+                .chars()
+                .next()
+                .is_none_or(|c| c == ' ' || c == ALIAS_CLOSE)
+        })
+}
+
+/// Aliases declared on a concept line, as written (`raw`)
+pub(crate) fn aliases(raw: &str) -> Vec<&str> {
+    split(raw.trim())
+        .map(|(_, aliases)| aliases)
+        .unwrap_or_default()
+}
+
+/// A concept line's canonical text: `Name`, or `Name (aka A, B)`
+pub(crate) fn heading(name: &str, aliases: &[String]) -> String {
+    if aliases.is_empty() {
+        name.to_string()
+    } else {
+        format!(
+            "{} {} {}{}",
+            name,
+            ALIAS_OPEN,
+            aliases.join(", "),
+            ALIAS_CLOSE
+        )
+    }
+}
+
+impl Document {
+    /// The canonical `Concept.facet` (or `Concept.facet.sub`) target a
+    /// reference target names, by the concept's name or one of its aliases
+    pub fn resolve_target(&self, target: &str) -> Option<String> {
+        let (concept, facets) = target.split_once('.')?;
+        let resolved = format!("{}.{}", self.concept(concept)?.name, facets);
+        self.reference_targets()
+            .into_iter()
+            .any(|(defined, _)| defined == resolved)
+            .then_some(resolved)
+    }
+
+    /// Every target a reference may name, under concept names and aliases
+    pub(crate) fn resolvable_targets(&self) -> HashSet<String> {
+        let mut targets: HashSet<String> = self
+            .reference_targets()
+            .into_iter()
+            .map(|(target, _)| target)
+            .collect();
+        for concept in &self.concepts {
+            for alias in &concept.aliases {
+                for facet in &concept.facets {
+                    targets.insert(format!("{}.{}", alias, facet.name));
+                    for sub in &facet.sub_facets {
+                        targets.insert(format!("{}.{}.{}", alias, facet.name, sub.name));
+                    }
+                }
+            }
+        }
+        targets
+    }
+}
+
+/// Flag aliases that another concept already goes by, as its name or alias
+///
+/// Names compare case-insensitively, so `Authority` collides with
+/// `authority`. A concept defined in several places may repeat its aliases.
+pub(crate) fn alias_collisions(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    let doc = Document::from_lines(lines);
+    // Each name's first owner: (concept, line)
+    let mut owners: HashMap<String, (&str, usize)> = HashMap::new();
+    for concept in &doc.concepts {
+        owners
+            .entry(concept.name.to_lowercase())
+            .or_insert((&concept.name, concept.line));
+    }
+
+    let mut warnings = Vec::new();
+    for concept in &doc.concepts {
+        for alias in &concept.aliases {
+            let owner = owners
+                .entry(alias.to_lowercase())
+                .or_insert((&concept.name, concept.line));
+            if owner.0 != concept.name {
+                warnings.push(ValidationError::AliasCollision {
+                    line: concept.line,
+                    alias: alias.clone(),
+                    other: owner.0.to_string(),
+                    other_line: owner.1,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::document::Concept;
+    use crate::{LineType, validate};
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("Power"), Some(("Power", vec![])));
+        assert_eq!(
+            split("Power (aka Authority, Control)"),
+            Some(("Power", vec!["Authority", "Control"]))
+        );
+        assert_eq!(
+            split("Backpack (akubra)"),
+            Some(("Backpack (akubra)", vec![]))
+        );
+        assert_eq!(split("Power (aka Authority"), None);
+        assert_eq!(split("Power (aka Authority) now"), None);
+        assert_eq!(split("Power (aka)"), None);
+        assert_eq!(split("Power (aka Authority,)"), None);
+        assert_eq!(split("(aka Authority)"), None);
+    }
+
+    #[test]
+    fn test_aliases_parse_and_round_trip() {
+        let input = "Power (aka Authority, Control)\n  .nature\n    - corrupts\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        assert_eq!(result.lines[0].line_type, LineType::Concept("Power".into()));
+
+        let doc = Document::parse(input);
+        assert_eq!(doc.concepts[0].aliases, ["Authority", "Control"]);
+        assert_eq!(doc.to_wvf_string(), input);
+        assert_eq!(
+            doc.concept("Control").map(|c| c.name.as_str()),
+            Some("Power")
+        );
+
+        let concept = Concept::from_heading(&doc.concepts[0].heading());
+        assert_eq!(
+            (concept.name.as_str(), concept.aliases),
+            ("Power", vec!["Authority".to_string(), "Control".to_string()])
+        );
+        assert_eq!(
+            Concept::from_heading("Power (aka Authority").name,
+            "Power (aka Authority"
+        );
+    }
+
+    #[test]
+    fn test_references_resolve_through_aliases() {
+        let input = "Power (aka Authority)\n  .nature\n    - corrupts\n\nTrust\n  .erosion\n    - fast &Authority.nature\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+
+        let doc = Document::parse(input);
+        assert_eq!(
+            doc.resolve_target("Authority.nature").as_deref(),
+            Some("Power.nature")
+        );
+        assert_eq!(doc.resolve_target("Authority.limits"), None);
+        assert_eq!(doc.definition_line("Authority.nature"), Some(2));
+        let counts = doc.reference_counts();
+        assert_eq!(
+            (counts[0].target.as_str(), counts[0].count),
+            ("Power.nature", 1)
+        );
+    }
+
+    #[test]
+    fn test_malformed_aliases() {
+        let result = validate("Power (aka Authority\n  .nature\n    - corrupts\n");
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), "malformed-aliases");
+    }
+
+    #[test]
+    fn test_alias_collisions() {
+        let input = "Power (aka Authority, trust)\n  .nature\n    - corrupts\n\nTrust\n  .erosion\n    - fast\n\nControl (aka authority)\n  .limits\n    - checks\n\nPower (aka Authority)\n  .limits\n    - balanced\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        let found: Vec<String> = result.warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            found,
+            [
+                "line 1: alias 'trust' is already a name of concept 'Trust' (line 5)",
+                "line 9: alias 'authority' is already a name of concept 'Power' (line 1)",
+            ]
+        );
+    }
+}
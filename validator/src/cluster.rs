@@ -0,0 +1,247 @@
+//! Groups of similar claims across facets
+//!
+//! Claims are linked when their similarity reaches a threshold, and linked
+//! claims form one cluster. Only clusters spanning more than one facet are
+//! reported: repeats within a facet are the duplicate lint's concern, while
+//! similar claims in different facets may be worth consolidating or
+//! cross-referencing.
+
+use std::collections::HashMap;
+
+use crate::document::{Claim, Document, Facet};
+use crate::lint::content_words;
+use crate::{SpecConfig, normalize_claim};
+
+/// A claim in a cluster, with where it sits in the hierarchy
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClusterMember {
+    /// `Concept.facet` (or `Concept.facet.sub`) holding the claim
+    pub target: String,
+    /// Line of the claim, or 0 for claims created in code
+    pub line: usize,
+    /// The claim in canonical form
+    pub claim: String,
+}
+
+/// Similar claims from more than one facet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    /// Members in document order
+    pub members: Vec<ClusterMember>,
+    /// Similarity of the closest two members in different facets
+    pub similarity: f64,
+}
+
+impl Cluster {
+    /// The facets the cluster spans, in document order
+    pub fn targets(&self) -> Vec<&str> {
+        let mut targets: Vec<&str> = Vec::new();
+        for member in &self.members {
+            if !targets.contains(&member.target.as_str()) {
+                targets.push(&member.target);
+            }
+        }
+        targets
+    }
+}
+
+impl Document {
+    /// Clusters of claims with similar wording, most similar first
+    ///
+    /// Similarity is the cosine of the claims' words weighted by TF-IDF, after
+    /// `normalize_claim` and ignoring filler words, operators, and plural or
+    /// tense endings; it runs from 0 (no words shared) to 1.
+    pub fn clusters(&self, threshold: f64) -> Vec<Cluster> {
+        let claims = claims(self);
+        let spec = SpecConfig::builtin();
+        let words: Vec<Vec<String>> = claims
+            .iter()
+            .map(|(_, claim)| {
+                let mut words = content_words(&normalize_claim(&claim.data.text));
+                words.retain(|word| spec.operator(word).is_none());
+                words
+            })
+            .collect();
+
+        let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+        for claim_words in &words {
+            let mut distinct: Vec<&str> = claim_words.iter().map(String::as_str).collect();
+            distinct.sort();
+            distinct.dedup();
+            for word in distinct {
+                *document_frequency.entry(word).or_default() += 1;
+            }
+        }
+        let n = words.len() as f64;
+        let vectors: Vec<HashMap<&str, f64>> = words
+            .iter()
+            .map(|claim_words| {
+                let mut vector: HashMap<&str, f64> = HashMap::new();
+                for word in claim_words {
+                    *vector.entry(word).or_default() += 1.0;
+                }
+                for (word, weight) in vector.iter_mut() {
+                    let df = document_frequency[word] as f64;
+                    *weight *= ((1.0 + n) / (1.0 + df)).ln() + 1.0;
+                }
+                vector
+            })
+            .collect();
+
+        link(&claims, threshold, |i, j| {
+            Some(cosine(&vectors[i], &vectors[j]))
+        })
+    }
+
+    /// Clusters of claims by another measure of similarity, most similar first
+    ///
+    /// `similarity` returns `None` for pairs it cannot compare, such as claims
+    /// missing from an embeddings index; those pairs are never linked.
+    pub fn clusters_by(
+        &self,
+        threshold: f64,
+        mut similarity: impl FnMut(&Claim, &Claim) -> Option<f64>,
+    ) -> Vec<Cluster> {
+        let claims = claims(self);
+        link(&claims, threshold, |i, j| {
+            similarity(claims[i].1, claims[j].1)
+        })
+    }
+}
+
+/// Every claim with its `Concept.facet` target, in document order
+fn claims(doc: &Document) -> Vec<(String, &Claim)> {
+    fn collect<'d>(facets: &'d [Facet], parent: &str, out: &mut Vec<(String, &'d Claim)>) {
+        for facet in facets {
+            let target = format!("{}.{}", parent, facet.name);
+            out.extend(facet.claims.iter().map(|claim| (target.clone(), claim)));
+            collect(&facet.sub_facets, &target, out);
+        }
+    }
+    let mut out = Vec::new();
+    for concept in &doc.concepts {
+        collect(&concept.facets, &concept.name, &mut out);
+    }
+    out
+}
+
+fn cosine(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(word, x)| b.get(word).map(|y| x * y))
+        .sum();
+    let norm = |v: &HashMap<&str, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// Join claims whose similarity reaches `threshold`, keeping groups that span facets
+fn link(
+    claims: &[(String, &Claim)],
+    threshold: f64,
+    mut similarity: impl FnMut(usize, usize) -> Option<f64>,
+) -> Vec<Cluster> {
+    // Each claim points toward the first claim of its group
+    let mut parent: Vec<usize> = (0..claims.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut closest: HashMap<usize, f64> = HashMap::new();
+    let mut cross_facet = Vec::new();
+    for i in 0..claims.len() {
+        for j in i + 1..claims.len() {
+            let Some(score) = similarity(i, j).filter(|&score| score >= threshold) else {
+                continue;
+            };
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            parent[a.max(b)] = a.min(b);
+            if claims[i].0 != claims[j].0 {
+                cross_facet.push((i, score));
+            }
+        }
+    }
+    for (i, score) in cross_facet {
+        let group = root(&mut parent, i);
+        let best = closest.entry(group).or_insert(score);
+        *best = best.max(score);
+    }
+
+    let mut clusters: Vec<Cluster> = Vec::new();
+    let mut index: HashMap<usize, usize> = HashMap::new();
+    for (i, (target, claim)) in claims.iter().enumerate() {
+        let group = root(&mut parent, i);
+        let Some(&similarity) = closest.get(&group) else {
+            continue;
+        };
+        let cluster = *index.entry(group).or_insert_with(|| {
+            clusters.push(Cluster {
+                members: Vec::new(),
+                similarity,
+            });
+            clusters.len() - 1
+        });
+        clusters[cluster].members.push(ClusterMember {
+            target: target.clone(),
+            line: claim.line,
+            claim: claim.data.to_wvf_string(),
+        });
+    }
+    // Stable sort keeps document order among equal similarities
+    clusters.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "Trust\n  .formation\n    - trust forms slowly through repeated reliability\n    - shared risk builds trust\n  .erosion\n    - betrayal erodes trust quickly\nWork\n  .teams\n    - reliability repeated over time forms trust\n    - shared risk binds teams!\n  .pay\n    - wages lag inflation\n";
+
+    #[test]
+    fn test_lexical_clusters_span_facets() {
+        let doc = Document::parse(DOC);
+        let clusters = doc.clusters(0.4);
+        assert_eq!(clusters.len(), 2);
+
+        let lines: Vec<Vec<usize>> = clusters
+            .iter()
+            .map(|c| c.members.iter().map(|m| m.line).collect())
+            .collect();
+        assert_eq!(lines, [vec![3, 9], vec![4, 10]]);
+        assert_eq!(clusters[0].targets(), ["Trust.formation", "Work.teams"]);
+        assert!(clusters[0].similarity >= clusters[1].similarity);
+        assert_eq!(clusters[1].members[1].claim, "shared risk binds teams!");
+    }
+
+    #[test]
+    fn test_clusters_within_one_facet_are_skipped() {
+        let doc = Document::parse(
+            "Trust\n  .formation\n    - trust forms slowly\n    - trust forms slowly over years\n",
+        );
+        assert!(doc.clusters(0.4).is_empty());
+    }
+
+    #[test]
+    fn test_clusters_by_custom_similarity() {
+        let doc = Document::parse(DOC);
+        // Link only the claims about wages and betrayal
+        let clusters = doc.clusters_by(0.9, |a, b| {
+            let pair = [a.line, b.line];
+            Some(if pair == [6, 12] { 1.0 } else { 0.0 })
+        });
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].targets(), ["Trust.erosion", "Work.pay"]);
+        assert_eq!(clusters[0].similarity, 1.0);
+
+        assert!(doc.clusters_by(0.5, |_, _| None).is_empty());
+    }
+}
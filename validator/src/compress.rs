@@ -0,0 +1,316 @@
+//! Fitting a document into a token budget
+//!
+//! `Document::compress` keeps the claims that score highest under a
+//! `PriorityPolicy` and drops the rest, for integrations that must fit a
+//! worldview into a model's context window. What remains is still a valid
+//! document: facets and concepts left without claims are pruned, references to
+//! pruned facets are dropped, and the source registry keeps only cited sources.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::document::{Claim, Document, Facet};
+use crate::reference::Reference;
+use crate::token_estimate::estimate_tokens;
+
+/// How `Document::compress` ranks claims; higher scores are kept first
+///
+/// A claim's score is the weighted sum of three signals: its modifiers (`!`
+/// counts 2, `*` 1, `?` -1), its reference degree (references it makes plus
+/// references to its facet), and its recency (0 for the first claim in the
+/// file, up to 1 for the last, assuming claims are appended). Claims with
+/// equal scores are kept in document order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriorityPolicy {
+    pub modifiers: f64,
+    pub references: f64,
+    pub recency: f64,
+}
+
+impl Default for PriorityPolicy {
+    fn default() -> Self {
+        PriorityPolicy {
+            modifiers: 1.0,
+            references: 0.25,
+            recency: 0.5,
+        }
+    }
+}
+
+impl PriorityPolicy {
+    /// Rank by modifiers alone
+    pub fn modifiers_only() -> Self {
+        PriorityPolicy {
+            modifiers: 1.0,
+            references: 0.0,
+            recency: 0.0,
+        }
+    }
+}
+
+impl Document {
+    /// Canonical `.wvf` text of the highest-priority claims that fit in
+    /// `budget_tokens`, as estimated by `estimate_tokens`
+    ///
+    /// The result is valid whenever this document is.
+    pub fn compress(&self, budget_tokens: usize, policy: PriorityPolicy) -> String {
+        self.compressed(budget_tokens, policy).to_wvf_string()
+    }
+
+    /// The document `compress` writes
+    pub fn compressed(&self, budget_tokens: usize, policy: PriorityPolicy) -> Document {
+        let claims = claims(self);
+        let scores = scores(self, &claims, policy);
+        let mut order: Vec<usize> = (0..claims.len()).collect();
+        // Stable, so equal scores keep document order
+        order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+
+        // Choose claims by the length of the lines they add, then check the
+        // estimate against the rendered text
+        let budget = budget_tokens.saturating_mul(4);
+        let mut used = retain(self, &vec![false; claims.len()])
+            .to_wvf_string()
+            .chars()
+            .count();
+        let mut keep = vec![false; claims.len()];
+        let mut opened: HashSet<String> = HashSet::new();
+        for &i in &order {
+            let claim = &claims[i];
+            let mut added = claim.depth * 2 + 5 + claim.claim.data.to_wvf_string().chars().count();
+            let mut path = claim.concept.to_string();
+            if !opened.contains(&path) {
+                // The concept line and the blank line before it
+                added += path.chars().count() + 2;
+            }
+            let mut new_paths = vec![path.clone()];
+            for (depth, facet) in claim.facets.iter().enumerate() {
+                path = format!("{}.{}", path, facet);
+                if !opened.contains(&path) {
+                    added += depth * 2 + 4 + facet.chars().count();
+                }
+                new_paths.push(path.clone());
+            }
+            if used + added <= budget {
+                used += added;
+                keep[i] = true;
+                opened.extend(new_paths);
+            }
+        }
+
+        let mut doc = retain(self, &keep);
+        for &i in order.iter().rev() {
+            if estimate_tokens(&doc.to_wvf_string()) <= budget_tokens {
+                break;
+            }
+            if keep[i] {
+                keep[i] = false;
+                doc = retain(self, &keep);
+            }
+        }
+        doc
+    }
+}
+
+/// A claim with where it sits in the hierarchy
+pub(crate) struct Located<'d> {
+    pub concept: &'d str,
+    pub facets: Vec<&'d str>,
+    /// Facet nesting: 1 for a facet's claims, 2 for a sub-facet's
+    pub depth: usize,
+    pub claim: &'d Claim,
+}
+
+/// Every claim in document order, the order `retain` visits them in
+pub(crate) fn claims(doc: &Document) -> Vec<Located<'_>> {
+    fn collect<'d>(
+        facets: &'d [Facet],
+        concept: &'d str,
+        path: &mut Vec<&'d str>,
+        out: &mut Vec<Located<'d>>,
+    ) {
+        for facet in facets {
+            path.push(&facet.name);
+            out.extend(facet.claims.iter().map(|claim| Located {
+                concept,
+                facets: path.clone(),
+                depth: path.len(),
+                claim,
+            }));
+            collect(&facet.sub_facets, concept, path, out);
+            path.pop();
+        }
+    }
+    let mut out = Vec::new();
+    for concept in &doc.concepts {
+        collect(&concept.facets, &concept.name, &mut Vec::new(), &mut out);
+    }
+    out
+}
+
+fn scores(doc: &Document, claims: &[Located<'_>], policy: PriorityPolicy) -> Vec<f64> {
+    let incoming: HashMap<String, usize> = doc
+        .reference_counts()
+        .into_iter()
+        .map(|count| (count.target, count.count))
+        .collect();
+    // Claims created in code have line 0 and count as newest
+    let mut by_line: Vec<usize> = (0..claims.len()).collect();
+    by_line.sort_by_key(|&i| match claims[i].claim.line {
+        0 => usize::MAX,
+        line => line,
+    });
+    let mut recency = vec![0.0; claims.len()];
+    let newest = claims.len().saturating_sub(1).max(1) as f64;
+    for (rank, &i) in by_line.iter().enumerate() {
+        recency[i] = rank as f64 / newest;
+    }
+
+    claims
+        .iter()
+        .enumerate()
+        .map(|(i, located)| {
+            let data = &located.claim.data;
+            let has = |symbol| data.modifiers.iter().any(|m| m.symbol == symbol);
+            let modifiers = if has('!') {
+                2.0
+            } else if has('*') {
+                1.0
+            } else if has('?') {
+                -1.0
+            } else {
+                0.0
+            };
+            let target = format!("{}.{}", located.concept, located.facets.join("."));
+            let degree = data.references.len() + incoming.get(&target).copied().unwrap_or(0);
+            policy.modifiers * modifiers
+                + policy.references * degree as f64
+                + policy.recency * recency[i]
+        })
+        .collect()
+}
+
+/// A copy of `doc` with only the claims `keep` marks, pruned so it stays valid
+pub(crate) fn retain(doc: &Document, keep: &[bool]) -> Document {
+    fn retain_facets(facets: &mut Vec<Facet>, keep: &mut impl Iterator<Item = bool>) {
+        for facet in facets.iter_mut() {
+            facet.claims.retain(|_| keep.next().unwrap_or(false));
+            retain_facets(&mut facet.sub_facets, keep);
+        }
+        facets.retain(|facet| !facet.claims.is_empty() || !facet.sub_facets.is_empty());
+    }
+
+    let mut doc = doc.clone();
+    let mut keep = keep.iter().copied();
+    for concept in &mut doc.concepts {
+        retain_facets(&mut concept.facets, &mut keep);
+    }
+    doc.concepts.retain(|concept| !concept.facets.is_empty());
+
+    let targets = doc.resolvable_targets();
+    let mut cited: HashSet<String> = HashSet::new();
+    for concept in &mut doc.concepts {
+        for_each_claim(&mut concept.facets, &mut |claim| {
+            claim
+                .data
+                .references
+                .retain(|reference| match Reference::parse(reference) {
+                    Some(parsed) if parsed.namespace.is_none() => {
+                        targets.contains(&parsed.target())
+                    }
+                    _ => true,
+                });
+            cited.extend(claim.data.sources.iter().map(|s| s.to_string()));
+        });
+    }
+    if let Some(sources) = &mut doc.sources {
+        sources
+            .entries
+            .retain(|entry| cited.contains(&entry.handle));
+        if sources.entries.is_empty() {
+            doc.sources = None;
+        }
+    }
+    doc
+}
+
+fn for_each_claim(facets: &mut [Facet], f: &mut impl FnMut(&mut Claim)) {
+    for facet in facets {
+        facet.claims.iter_mut().for_each(&mut *f);
+        for_each_claim(&mut facet.sub_facets, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "Trust\n  .formation\n    - slow\n    - requires consistency | over time\n  .erosion\n    - fast !\n    - single violation => collapse?\n\nPower\n  .nature\n    - corrupts* | unchecked &Trust.formation\n    - reveals character @history\n\nsources:\n  @history: the historical record\n";
+
+    #[test]
+    fn test_compress_keeps_everything_within_budget() {
+        let doc = Document::parse(DOC);
+        let compressed = doc.compressed(1000, PriorityPolicy::default());
+        assert_eq!(compressed, doc);
+        assert_eq!(
+            doc.compress(1000, PriorityPolicy::default()),
+            doc.to_wvf_string()
+        );
+    }
+
+    #[test]
+    fn test_compress_keeps_strongest_claims() {
+        let doc = Document::parse(DOC);
+        let text = doc.compress(25, PriorityPolicy::modifiers_only());
+        assert!(estimate_tokens(&text) <= 25);
+        assert_eq!(
+            text,
+            "Trust\n  .erosion\n    - fast !\n\nPower\n  .nature\n    - corrupts* | unchecked\n"
+        );
+        assert!(Document::parse(&text).validate().is_valid());
+    }
+
+    #[test]
+    fn test_compress_prunes_sources() {
+        let doc = Document::parse(DOC);
+        // Nothing cites @history once its claim is dropped
+        let compressed = doc.compressed(25, PriorityPolicy::modifiers_only());
+        assert_eq!(compressed.sources, None);
+
+        let policy = PriorityPolicy {
+            modifiers: 0.0,
+            references: 0.0,
+            recency: 1.0,
+        };
+        let compressed = doc.compressed(25, policy);
+        let kept: Vec<String> = claims(&compressed)
+            .iter()
+            .map(|c| c.claim.data.to_wvf_string())
+            .collect();
+        assert!(
+            kept.contains(&"reveals character @history".to_string()),
+            "{:?}",
+            kept
+        );
+        assert_eq!(compressed.sources.map(|s| s.entries.len()), Some(1));
+    }
+
+    #[test]
+    fn test_compress_weighs_references() {
+        let doc = Document::parse(DOC);
+        // Trust.formation is referenced, so its claims outrank the others
+        let policy = PriorityPolicy {
+            modifiers: 0.0,
+            references: 1.0,
+            recency: 0.0,
+        };
+        assert_eq!(
+            doc.compress(20, policy),
+            "Trust\n  .formation\n    - slow\n    - requires consistency | over time\n"
+        );
+    }
+
+    #[test]
+    fn test_compress_to_nothing() {
+        let doc = Document::parse(DOC);
+        assert_eq!(doc.compress(0, PriorityPolicy::default()), "");
+    }
+}
@@ -0,0 +1,156 @@
+//! Structured `| condition` clauses
+//!
+//! Conditions are free text, but most follow a few patterns: a bare state
+//! (`| unchecked`), a temporal qualifier (`| over time`), or a keyword clause
+//! (`| when trust is low`), any of which may be negated (`| without oversight`,
+//! `| unless audited`). Parsing separates the pattern from its subject so
+//! claims can be queried by what they are conditioned on.
+
+use std::fmt;
+
+/// The pattern a condition follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConditionKind {
+    /// A state the claim holds under (`unchecked`, `high transparency`)
+    State,
+    /// A time frame (`over time`, `long-term`, `after a crisis`)
+    Temporal,
+    /// A clause introduced by `when`, `if`, `while`, or `unless`
+    When,
+}
+
+/// A parsed condition
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Condition {
+    pub kind: ConditionKind,
+    /// Introduced by `not`, `no`, `without`, `never`, or `unless`
+    pub negated: bool,
+    /// The subject, without keyword or negation (`low transparency`)
+    pub text: String,
+}
+
+const WHEN_KEYWORDS: &[&str] = &["when", "if", "while", "whenever"];
+const NEGATIONS: &[&str] = &["not", "no", "without", "never"];
+const TEMPORAL_PHRASES: &[&str] = &[
+    "over time",
+    "eventually",
+    "long-term",
+    "short-term",
+    "in the long run",
+    "in the short run",
+    "initially",
+];
+const TEMPORAL_PREFIXES: &[&str] = &["over", "after", "before", "during", "within", "until"];
+
+impl Condition {
+    /// Parse condition text (without the leading `|`)
+    pub fn parse(condition: &str) -> Condition {
+        let condition = condition.trim();
+        let (keyword, rest) = first_word(condition);
+        let keyword = keyword.to_lowercase();
+
+        if !rest.is_empty() {
+            if keyword == "unless" {
+                return Condition::when(true, rest);
+            }
+            if WHEN_KEYWORDS.contains(&keyword.as_str()) {
+                let (next, after) = first_word(rest);
+                let negated =
+                    !after.is_empty() && NEGATIONS.contains(&next.to_lowercase().as_str());
+                return Condition::when(negated, if negated { after } else { rest });
+            }
+            if NEGATIONS.contains(&keyword.as_str()) {
+                return Condition {
+                    kind: ConditionKind::State,
+                    negated: true,
+                    text: rest.to_string(),
+                };
+            }
+        }
+
+        let lower = condition.to_lowercase();
+        let temporal = TEMPORAL_PHRASES.contains(&lower.as_str())
+            || (!rest.is_empty() && TEMPORAL_PREFIXES.contains(&keyword.as_str()));
+        Condition {
+            kind: if temporal {
+                ConditionKind::Temporal
+            } else {
+                ConditionKind::State
+            },
+            negated: false,
+            text: condition.to_string(),
+        }
+    }
+
+    fn when(negated: bool, text: &str) -> Condition {
+        Condition {
+            kind: ConditionKind::When,
+            negated,
+            text: text.to_string(),
+        }
+    }
+
+    /// Whether this condition is about `subject` (case-insensitive, negated or not)
+    pub fn is_about(&self, subject: &str) -> bool {
+        self.text.to_lowercase() == subject.trim().to_lowercase()
+    }
+}
+
+fn first_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim_start()),
+        None => (text, ""),
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.kind, self.negated) {
+            (ConditionKind::When, true) => write!(f, "unless {}", self.text),
+            (ConditionKind::When, false) => write!(f, "when {}", self.text),
+            (_, true) => write!(f, "not {}", self.text),
+            (_, false) => f.write_str(&self.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(condition: &str) -> (ConditionKind, bool, String) {
+        let c = Condition::parse(condition);
+        (c.kind, c.negated, c.text)
+    }
+
+    #[test]
+    fn test_condition_patterns() {
+        use ConditionKind::*;
+        assert_eq!(parsed("unchecked"), (State, false, "unchecked".into()));
+        assert_eq!(parsed("over time"), (Temporal, false, "over time".into()));
+        assert_eq!(
+            parsed("after a crisis"),
+            (Temporal, false, "after a crisis".into())
+        );
+        assert_eq!(
+            parsed("When low transparency"),
+            (When, false, "low transparency".into())
+        );
+        assert_eq!(parsed("if not audited"), (When, true, "audited".into()));
+        assert_eq!(parsed("unless audited"), (When, true, "audited".into()));
+        assert_eq!(
+            parsed("without oversight"),
+            (State, true, "oversight".into())
+        );
+        // A lone keyword is a state, not an empty clause
+        assert_eq!(parsed("over"), (State, false, "over".into()));
+        assert_eq!(parsed("when"), (State, false, "when".into()));
+    }
+
+    #[test]
+    fn test_condition_subject() {
+        assert!(Condition::parse("when low transparency").is_about("Low Transparency"));
+        assert!(Condition::parse("low transparency").is_about("low transparency"));
+        assert!(!Condition::parse("over time").is_about("time"));
+    }
+}
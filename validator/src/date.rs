@@ -0,0 +1,147 @@
+//! Calendar dates for evolution markers (`[<= rational actor :2022-03]`)
+//!
+//! A date is a year with an optional month and day, so a change can be
+//! recorded as precisely as it is remembered. Partial dates sort before
+//! more precise dates within the same period.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Marks a date at the end of an evolution marker
+pub const DATE_PREFIX: char = ':';
+
+/// `YYYY`, `YYYY-MM`, or `YYYY-MM-DD`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: u16,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+impl FromStr for Date {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid date '{}' (expected YYYY, YYYY-MM, or YYYY-MM-DD)",
+                s
+            )
+        };
+        let mut parts = s.split('-');
+        let year = parts.next().filter(|y| y.len() == 4).ok_or_else(invalid)?;
+        let year: u16 = number(year).ok_or_else(invalid)?;
+        let month = match parts.next() {
+            Some(m) if m.len() == 2 => Some(
+                number(m)
+                    .filter(|m| (1..=12).contains(m))
+                    .ok_or_else(invalid)?,
+            ),
+            Some(_) => return Err(invalid()),
+            None => None,
+        };
+        let day = match (month, parts.next()) {
+            (Some(month), Some(d)) if d.len() == 2 => Some(
+                number(d)
+                    .filter(|d| (1..=days_in_month(year, month)).contains(d))
+                    .ok_or_else(invalid)?,
+            ),
+            (_, Some(_)) => return Err(invalid()),
+            (_, None) => None,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Date { year, month, day })
+    }
+}
+
+fn number<T: FromStr>(digits: &str) -> Option<T> {
+    if digits.bytes().all(|b| b.is_ascii_digit()) {
+        digits.parse().ok()
+    } else {
+        None
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a trailing `:date` word off marker content, if there is one
+///
+/// Only a `:` followed by a digit starts a date, so other colons stay part of
+/// the prior belief.
+pub(crate) fn split_date(content: &str) -> (&str, Option<&str>) {
+    let start = content.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let word = &content[start..];
+    match word.strip_prefix(DATE_PREFIX) {
+        Some(date) if date.starts_with(|c: char| c.is_ascii_digit()) => {
+            (content[..start].trim_end(), Some(date))
+        }
+        _ => (content, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dates() {
+        assert_eq!(
+            "2022".parse(),
+            Ok(Date {
+                year: 2022,
+                month: None,
+                day: None
+            })
+        );
+        assert_eq!(
+            "2024-02-29".parse::<Date>().unwrap().to_string(),
+            "2024-02-29"
+        );
+        for invalid in [
+            "22",
+            "2022-13",
+            "2023-02-29",
+            "2022-3",
+            "2022-03-01-01",
+            "2o22",
+        ] {
+            assert!(invalid.parse::<Date>().is_err(), "{invalid}");
+        }
+        assert!("2022".parse::<Date>().unwrap() < "2022-01".parse().unwrap());
+    }
+
+    #[test]
+    fn test_split_date() {
+        assert_eq!(
+            split_date("rational actor :2022"),
+            ("rational actor", Some("2022"))
+        );
+        assert_eq!(split_date(":2022"), ("", Some("2022")));
+        assert_eq!(split_date("ratio 3:2"), ("ratio 3:2", None));
+        assert_eq!(split_date("see :note"), ("see :note", None));
+    }
+}
@@ -0,0 +1,188 @@
+//! Structural differences between two versions of a document
+//!
+//! Concepts and facets are matched by name and claims by their full text within
+//! a facet. A removed claim and an added claim in the same facet are paired as
+//! one edit when they share a `^id:`, keep the same text with new conditions or
+//! sources, or the new claim records the old text as a prior belief.
+
+use std::fmt;
+
+use crate::document::{Claim, Document, Facet};
+
+/// One structural difference, from the old document to the new
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    ConceptAdded {
+        concept: String,
+    },
+    ConceptRemoved {
+        concept: String,
+    },
+    /// `target` is `Concept.facet` (or `Concept.facet.sub`)
+    FacetAdded {
+        target: String,
+    },
+    FacetRemoved {
+        target: String,
+    },
+    ClaimAdded {
+        target: String,
+        claim: String,
+    },
+    ClaimRemoved {
+        target: String,
+        claim: String,
+    },
+    ClaimChanged {
+        target: String,
+        from: String,
+        to: String,
+        /// Whether the new claim has an evolution marker for the old text
+        prior_recorded: bool,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::ConceptAdded { concept } => write!(f, "added concept {}", concept),
+            Change::ConceptRemoved { concept } => write!(f, "removed concept {}", concept),
+            Change::FacetAdded { target } => write!(f, "added facet {}", target),
+            Change::FacetRemoved { target } => write!(f, "removed facet {}", target),
+            Change::ClaimAdded { target, .. } => write!(f, "added {} claim", target),
+            Change::ClaimRemoved { target, .. } => write!(f, "removed {} claim", target),
+            Change::ClaimChanged {
+                target,
+                prior_recorded,
+                ..
+            } => {
+                write!(f, "changed {} claim", target)?;
+                if *prior_recorded {
+                    write!(f, ", prior belief recorded")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Document {
+    /// Changes turning `self` into `new`, in the new document's order
+    ///
+    /// An added concept or facet is reported once, without its claims.
+    pub fn diff(&self, new: &Document) -> Vec<Change> {
+        let mut changes = Vec::new();
+        for concept in &self.concepts {
+            if new.concept(&concept.name).is_none() {
+                changes.push(Change::ConceptRemoved {
+                    concept: concept.name.clone(),
+                });
+            }
+        }
+        for concept in &new.concepts {
+            match self.concept(&concept.name) {
+                Some(old) => diff_facets(&old.facets, &concept.facets, &concept.name, &mut changes),
+                None => changes.push(Change::ConceptAdded {
+                    concept: concept.name.clone(),
+                }),
+            }
+        }
+        changes
+    }
+}
+
+fn diff_facets(old: &[Facet], new: &[Facet], parent: &str, changes: &mut Vec<Change>) {
+    for facet in old {
+        if !new.iter().any(|f| f.name == facet.name) {
+            changes.push(Change::FacetRemoved {
+                target: format!("{}.{}", parent, facet.name),
+            });
+        }
+    }
+    for facet in new {
+        let target = format!("{}.{}", parent, facet.name);
+        match old.iter().find(|f| f.name == facet.name) {
+            Some(previous) => {
+                diff_claims(&previous.claims, &facet.claims, &target, changes);
+                diff_facets(&previous.sub_facets, &facet.sub_facets, &target, changes);
+            }
+            None => changes.push(Change::FacetAdded { target }),
+        }
+    }
+}
+
+fn diff_claims(old: &[Claim], new: &[Claim], target: &str, changes: &mut Vec<Change>) {
+    // Claims present in both versions cancel out, duplicates one for one
+    let mut removed: Vec<&Claim> = old.iter().collect();
+    let mut added = Vec::new();
+    for claim in new {
+        let text = claim.data.to_wvf_string();
+        match removed.iter().position(|c| c.data.to_wvf_string() == text) {
+            Some(i) => {
+                removed.remove(i);
+            }
+            None => added.push(claim),
+        }
+    }
+
+    for claim in added {
+        let data = &claim.data;
+        let records = |old: &Claim| {
+            data.evolution
+                .iter()
+                .any(|m| m.prior_belief == old.data.text)
+        };
+        let paired = removed.iter().position(|old| {
+            (data.id.is_some() && data.id == old.data.id)
+                || data.text == old.data.text
+                || records(old)
+        });
+        changes.push(match paired {
+            Some(i) => {
+                let old = removed.remove(i);
+                Change::ClaimChanged {
+                    target: target.to_string(),
+                    from: old.data.to_wvf_string(),
+                    to: data.to_wvf_string(),
+                    prior_recorded: records(old),
+                }
+            }
+            None => Change::ClaimAdded {
+                target: target.to_string(),
+                claim: data.to_wvf_string(),
+            },
+        });
+    }
+    changes.extend(removed.into_iter().map(|claim| Change::ClaimRemoved {
+        target: target.to_string(),
+        claim: claim.data.to_wvf_string(),
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old = Document::parse(
+            "Power\n  .nature\n    - corrupts\n    - concentrates\n  .limits\n    - checked\n\nTrust\n  .basis\n    - earned\n",
+        );
+        let new = Document::parse(
+            "Power\n  .nature\n    - reveals [<= corrupts]\n    - concentrates | unchecked\n    - spreads\n\nTrust\n  .basis\n    - earned\n  .erosion\n    - slow\n\nMarkets\n  .prices\n    - signal\n",
+        );
+        let changes: Vec<String> = old.diff(&new).iter().map(Change::to_string).collect();
+        assert_eq!(
+            changes,
+            [
+                "removed facet Power.limits",
+                "changed Power.nature claim, prior belief recorded",
+                "changed Power.nature claim",
+                "added Power.nature claim",
+                "added facet Trust.erosion",
+                "added concept Markets",
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+}
@@ -0,0 +1,1003 @@
+//! Document model, programmatic builder, and serialization
+//!
+//! `Document` is the hierarchical view of a worldview (concepts → facets →
+//! claims) built from parsed lines. `DocumentBuilder` produces documents in
+//! code, and `Document::to_wvf_string` writes canonical `.wvf` text.
+//!
+//! Parsed documents also remember their original source lines, so `Display`
+//! reproduces the input exactly (`Document::parse(s).to_string() == s`) while
+//! re-rendering only the nodes that were changed.
+
+use std::fmt;
+use thiserror::Error;
+
+use crate::{
+    ClaimData, ClaimExpr, LineType, Operand, ParsedLine, SpecVersion, VERSION_DIRECTIVE,
+    ValidationError, ValidationOptions, ValidationResult, parse_claim, validate_with_options,
+};
+use crate::sources::SourceDefinition;
+
+/// A complete Worldview document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    /// The `wvf X.Y` declaration, written first
+    pub version: Option<VersionDeclaration>,
+    pub includes: Vec<Include>,
+    pub concepts: Vec<Concept>,
+    /// The `sources:` appendix, written after the concepts
+    pub sources: Option<Sources>,
+    /// Blank lines after the last node (lossless output only)
+    pub trailing: Vec<String>,
+    /// Line terminator used when writing
+    pub line_ending: LineEnding,
+    /// Whether the text ends with a line terminator
+    pub final_newline: bool,
+    /// Whether the text starts with a UTF-8 byte order mark
+    pub byte_order_mark: bool,
+}
+
+/// Line terminator style
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Original text attached to a parsed node
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trivia {
+    /// Blank or unparseable lines immediately before the node
+    pub leading: Vec<String>,
+    /// The node's own line as written
+    pub raw: String,
+}
+
+/// A `wvf X.Y` spec version declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDeclaration {
+    /// The version as written, which may not be one this crate knows
+    pub version: String,
+    pub source: Option<Trivia>,
+}
+
+/// An `>include` directive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Include {
+    pub path: String,
+    pub source: Option<Trivia>,
+}
+
+/// The `sources:` section and its entries
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sources {
+    pub entries: Vec<SourceEntry>,
+    /// Source line number, or 0 for nodes created in code
+    pub line: usize,
+    pub source: Option<Trivia>,
+}
+
+/// A registered `@source` handle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceEntry {
+    pub handle: String,
+    pub description: String,
+    /// Source line number, or 0 for nodes created in code
+    pub line: usize,
+    pub source: Option<Trivia>,
+}
+
+/// A concept and its facets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Concept {
+    pub name: String,
+    /// Other names references may use for the concept (`Power (aka Authority)`)
+    pub aliases: Vec<String>,
+    pub facets: Vec<Facet>,
+    /// Source line number, or 0 for nodes created in code
+    pub line: usize,
+    pub source: Option<Trivia>,
+}
+
+/// A facet with its claims and (with nested facets) sub-facets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Facet {
+    pub name: String,
+    pub claims: Vec<Claim>,
+    pub sub_facets: Vec<Facet>,
+    /// Source line number, or 0 for nodes created in code
+    pub line: usize,
+    pub source: Option<Trivia>,
+}
+
+/// A claim within a facet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claim {
+    pub data: ClaimData<'static>,
+    /// Source line number, or 0 for nodes created in code
+    pub line: usize,
+    pub source: Option<Trivia>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Document {
+            version: None,
+            includes: Vec::new(),
+            concepts: Vec::new(),
+            sources: None,
+            trailing: Vec::new(),
+            line_ending: LineEnding::Lf,
+            final_newline: true,
+            byte_order_mark: false,
+        }
+    }
+}
+
+impl Document {
+    /// Parse a document, keeping whatever structure is recognizable
+    ///
+    /// Facets and claims missing their prefix are read by indentation (and written
+    /// back as-is unless changed); other invalid lines are kept only as trivia. Use
+    /// `validate` to find out what was wrong.
+    pub fn parse(input: &str) -> Document {
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        let mut doc = Document::from_lines(&result.lines);
+        if input.contains("\r\n") {
+            doc.line_ending = LineEnding::CrLf;
+        }
+        doc.final_newline = input.is_empty() || input.ends_with('\n');
+        doc.byte_order_mark = input.starts_with(crate::BYTE_ORDER_MARK);
+        doc
+    }
+
+    /// Build the hierarchy from already-tokenized lines
+    pub fn from_lines(lines: &[ParsedLine<'_>]) -> Document {
+        let mut doc = Document::default();
+        let mut pending: Vec<String> = Vec::new();
+
+        for line in lines {
+            match &line.line_type {
+                LineType::Blank => pending.push(line.raw.to_string()),
+                LineType::Version(version) => {
+                    doc.version = Some(VersionDeclaration {
+                        version: version.to_string(),
+                        source: Trivia::take(&mut pending, &line.raw),
+                    })
+                }
+                LineType::Include(path) => doc.includes.push(Include {
+                    path: path.to_string(),
+                    source: Trivia::take(&mut pending, &line.raw),
+                }),
+                LineType::Concept(name) => {
+                    let mut concept = Concept::at(name.as_ref(), line.line_number);
+                    concept.aliases = crate::alias::aliases(&line.raw)
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect();
+                    concept.source = Trivia::take(&mut pending, &line.raw);
+                    doc.concepts.push(concept);
+                }
+                LineType::Facet(name) => {
+                    let mut facet = Facet::at(name.as_ref(), line.line_number);
+                    facet.source = Trivia::take(&mut pending, &line.raw);
+                    if let Some(concept) = doc.concepts.last_mut() {
+                        concept.facets.push(facet);
+                    }
+                }
+                LineType::SubFacet(name) => {
+                    let mut sub = Facet::at(name.as_ref(), line.line_number);
+                    sub.source = Trivia::take(&mut pending, &line.raw);
+                    if let Some(facet) = doc.last_facet_mut() {
+                        facet.sub_facets.push(sub);
+                    }
+                }
+                LineType::SourcesSection => {
+                    let sources = doc.sources.get_or_insert_with(Sources::default);
+                    sources.line = line.line_number;
+                    sources.source = Trivia::take(&mut pending, &line.raw);
+                }
+                LineType::SourceDefinition(definition) => {
+                    let entry = SourceEntry {
+                        handle: definition.handle.to_string(),
+                        description: definition.description.to_string(),
+                        line: line.line_number,
+                        source: Trivia::take(&mut pending, &line.raw),
+                    };
+                    doc.sources.get_or_insert_with(Sources::default).entries.push(entry);
+                }
+                LineType::Claim(data) => {
+                    let claim = Claim {
+                        data: data.clone().into_owned(),
+                        line: line.line_number,
+                        source: Trivia::take(&mut pending, &line.raw),
+                    };
+                    let nested = crate::indent_width(&line.raw) > crate::CLAIM_INDENT;
+                    if let Some(facet) = doc.last_facet_mut() {
+                        match facet.sub_facets.last_mut() {
+                            Some(sub) if nested => sub.claims.push(claim),
+                            _ => facet.claims.push(claim),
+                        }
+                    }
+                }
+            }
+        }
+
+        doc.trailing = pending;
+        doc
+    }
+
+    fn last_facet_mut(&mut self) -> Option<&mut Facet> {
+        self.concepts.last_mut().and_then(|c| c.facets.last_mut())
+    }
+
+    /// The spec version the document declares, if it is a known one
+    pub fn spec_version(&self) -> Option<SpecVersion> {
+        self.version.as_ref().and_then(|v| v.version.parse().ok())
+    }
+
+    /// Look up a concept by name, or else by alias
+    pub fn concept(&self, name: &str) -> Option<&Concept> {
+        let index = self.concept_index(name)?;
+        Some(&self.concepts[index])
+    }
+
+    /// Look up a concept by name or alias, mutably
+    pub fn concept_mut(&mut self, name: &str) -> Option<&mut Concept> {
+        let index = self.concept_index(name)?;
+        Some(&mut self.concepts[index])
+    }
+
+    fn concept_index(&self, name: &str) -> Option<usize> {
+        self.concepts.iter().position(|c| c.name == name).or_else(|| {
+            self.concepts
+                .iter()
+                .position(|c| c.aliases.iter().any(|alias| alias == name))
+        })
+    }
+
+    /// Every `Concept.facet` (and `Concept.facet.sub`) target defined here, with its line
+    pub fn reference_targets(&self) -> Vec<(String, usize)> {
+        let mut targets = Vec::new();
+        for concept in &self.concepts {
+            for facet in &concept.facets {
+                let target = format!("{}.{}", concept.name, facet.name);
+                for sub in &facet.sub_facets {
+                    targets.push((format!("{}.{}", target, sub.name), sub.line));
+                }
+                targets.push((target, facet.line));
+            }
+        }
+        targets
+    }
+
+    /// Line defining a `Concept.facet` target, if it is in this document; the
+    /// concept may be named by an alias
+    pub fn definition_line(&self, target: &str) -> Option<usize> {
+        let target = self.resolve_target(target)?;
+        self.reference_targets()
+            .into_iter()
+            .find(|(t, _)| *t == target)
+            .map(|(_, line)| line)
+    }
+
+    /// Every claim, sub-facet claims included, in document order
+    pub fn claims(&self) -> impl Iterator<Item = &Claim> {
+        fn collect<'d>(facets: &'d [Facet], out: &mut Vec<&'d Claim>) {
+            for facet in facets {
+                out.extend(&facet.claims);
+                collect(&facet.sub_facets, out);
+            }
+        }
+        let mut claims = Vec::new();
+        for concept in &self.concepts {
+            collect(&concept.facets, &mut claims);
+        }
+        claims.into_iter()
+    }
+
+    /// Claims with a condition about `subject`, negated or not, in document order
+    pub fn claims_conditioned_on(&self, subject: &str) -> Vec<&Claim> {
+        self.claims()
+            .filter(|claim| claim.data.parsed_conditions().iter().any(|c| c.is_about(subject)))
+            .collect()
+    }
+
+    /// Claims carrying the `symbol` modifier (`?`, `!`, ...), in document order
+    pub fn claims_with_modifier(&self, symbol: char) -> impl Iterator<Item = &Claim> {
+        self.claims()
+            .filter(move |claim| claim.data.modifiers.iter().any(|m| m.symbol == symbol))
+    }
+
+    /// True if any facet in the document has sub-facets
+    pub fn has_nested_facets(&self) -> bool {
+        self.concepts
+            .iter()
+            .flat_map(|c| &c.facets)
+            .any(|f| !f.sub_facets.is_empty())
+    }
+
+    /// Validate the serialized form of this document
+    pub fn validate(&self) -> ValidationResult<'static> {
+        let options = ValidationOptions {
+            nested_facets: self.has_nested_facets(),
+            ..ValidationOptions::default()
+        };
+        validate_with_options(&self.to_string(), &options).into_owned()
+    }
+
+    /// Serialize to canonical `.wvf` text
+    ///
+    /// Concepts are separated by a blank line; claims are written in positional
+    /// order (text, conditions, sources, references, evolution marker). Source
+    /// formatting is discarded.
+    pub fn to_wvf_string(&self) -> String {
+        self.render(false)
+    }
+
+    fn render(&self, lossless: bool) -> String {
+        let mut lines: Vec<String> = Vec::new();
+
+        if let Some(ref declaration) = self.version {
+            let canonical = format!("{} {}", VERSION_DIRECTIVE, declaration.version);
+            let unchanged = |raw: &str| {
+                crate::version::declaration(raw.trim()) == Some(declaration.version.as_str())
+            };
+            push_node(
+                &mut lines,
+                declaration.source.as_ref(),
+                lossless,
+                false,
+                canonical,
+                unchanged,
+            );
+        }
+        for include in &self.includes {
+            let canonical = format!("{} {}", crate::INCLUDE_DIRECTIVE, include.path);
+            let unchanged = |raw: &str| {
+                crate::include::directive_path(raw.trim()).is_some_and(|p| p == include.path)
+            };
+            push_node(
+                &mut lines,
+                include.source.as_ref(),
+                lossless,
+                false,
+                canonical,
+                unchanged,
+            );
+        }
+        for (i, concept) in self.concepts.iter().enumerate() {
+            let separated = i > 0 || !lines.is_empty();
+            let unchanged = |raw: &str| {
+                crate::alias::split(raw.trim()).is_some_and(|(name, aliases)| {
+                    name == concept.name && aliases == concept.aliases
+                })
+            };
+            push_node(
+                &mut lines,
+                concept.source.as_ref(),
+                lossless,
+                separated,
+                concept.heading(),
+                unchanged,
+            );
+            for facet in &concept.facets {
+                render_facet(&mut lines, facet, crate::FACET_INDENT, lossless);
+            }
+        }
+        if let Some(ref sources) = self.sources {
+            let separated = !lines.is_empty();
+            push_node(
+                &mut lines,
+                sources.source.as_ref(),
+                lossless,
+                separated,
+                crate::SOURCES_SECTION.to_string(),
+                |raw| raw.trim() == crate::SOURCES_SECTION,
+            );
+            for entry in &sources.entries {
+                let unchanged = |raw: &str| {
+                    SourceDefinition::parse(raw.trim()).is_some_and(|d| {
+                        d.handle == entry.handle && d.description == entry.description
+                    })
+                };
+                let canonical = format!("{}{}", " ".repeat(crate::FACET_INDENT), entry);
+                push_node(
+                    &mut lines,
+                    entry.source.as_ref(),
+                    lossless,
+                    false,
+                    canonical,
+                    unchanged,
+                );
+            }
+        }
+        if lossless {
+            lines.extend(self.trailing.iter().cloned());
+        }
+
+        let ending = self.line_ending.as_str();
+        let mut out = String::new();
+        if self.byte_order_mark {
+            out.push(crate::BYTE_ORDER_MARK);
+        }
+        out.push_str(&lines.join(ending));
+        if self.final_newline && !lines.is_empty() {
+            out.push_str(ending);
+        }
+        out
+    }
+}
+
+/// Append a node's lines, preferring its original text when it still matches
+fn push_node(
+    lines: &mut Vec<String>,
+    source: Option<&Trivia>,
+    lossless: bool,
+    separated: bool,
+    canonical: String,
+    unchanged: impl Fn(&str) -> bool,
+) {
+    match source {
+        Some(trivia) if lossless => {
+            lines.extend(trivia.leading.iter().cloned());
+            if unchanged(&trivia.raw) {
+                lines.push(trivia.raw.clone());
+            } else {
+                lines.push(canonical);
+            }
+        }
+        _ => {
+            if separated {
+                lines.push(String::new());
+            }
+            lines.push(canonical);
+        }
+    }
+}
+
+fn render_facet(lines: &mut Vec<String>, facet: &Facet, indent: usize, lossless: bool) {
+    let pad = " ".repeat(indent);
+    let unchanged = |raw: &str| {
+        let raw = raw.trim();
+        raw.strip_prefix(crate::FACET_PREFIX).unwrap_or(raw).trim() == facet.name
+    };
+    let canonical = format!("{}{}{}", pad, crate::FACET_PREFIX, facet.name);
+    push_node(
+        lines,
+        facet.source.as_ref(),
+        lossless,
+        false,
+        canonical,
+        unchanged,
+    );
+
+    for claim in &facet.claims {
+        let unchanged = |raw: &str| {
+            let raw = raw.trim();
+            let text = raw.strip_prefix(crate::CLAIM_PREFIX).unwrap_or(raw);
+            parse_claim(text.trim()) == claim.data
+        };
+        let canonical = format!(
+            "{}  {} {}",
+            pad,
+            crate::CLAIM_PREFIX,
+            claim.data.to_wvf_string()
+        );
+        push_node(
+            lines,
+            claim.source.as_ref(),
+            lossless,
+            false,
+            canonical,
+            unchanged,
+        );
+    }
+    for sub in &facet.sub_facets {
+        render_facet(lines, sub, indent + 2, lossless);
+    }
+}
+
+/// Lossless output: original text for unchanged nodes, canonical text for the rest
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(true))
+    }
+}
+
+impl Trivia {
+    /// Attach the pending leading lines and `raw` to a node
+    fn take(pending: &mut Vec<String>, raw: &str) -> Option<Trivia> {
+        Some(Trivia {
+            leading: std::mem::take(pending),
+            raw: raw.to_string(),
+        })
+    }
+}
+
+impl VersionDeclaration {
+    pub fn new(version: SpecVersion) -> VersionDeclaration {
+        VersionDeclaration {
+            version: version.to_string(),
+            source: None,
+        }
+    }
+}
+
+impl Include {
+    pub fn new(path: impl Into<String>) -> Include {
+        Include {
+            path: path.into(),
+            source: None,
+        }
+    }
+}
+
+impl SourceEntry {
+    pub fn new(handle: impl Into<String>, description: impl Into<String>) -> SourceEntry {
+        SourceEntry {
+            handle: handle.into(),
+            description: description.into(),
+            line: 0,
+            source: None,
+        }
+    }
+}
+
+impl fmt::Display for SourceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.description.is_empty() {
+            write!(f, "{}:", self.handle)
+        } else {
+            write!(f, "{}: {}", self.handle, self.description)
+        }
+    }
+}
+
+impl Concept {
+    /// A new concept with no facets
+    pub fn new(name: impl Into<String>) -> Concept {
+        Concept::at(name, 0)
+    }
+
+    fn at(name: impl Into<String>, line: usize) -> Concept {
+        Concept {
+            name: name.into(),
+            aliases: Vec::new(),
+            facets: Vec::new(),
+            line,
+            source: None,
+        }
+    }
+
+    /// Look up a facet by name
+    pub fn facet(&self, name: &str) -> Option<&Facet> {
+        self.facets.iter().find(|f| f.name == name)
+    }
+
+    /// The concept line: the name, then any aliases (`Power (aka Authority)`)
+    pub fn heading(&self) -> String {
+        crate::alias::heading(&self.name, &self.aliases)
+    }
+
+    /// A new concept from a concept line, the inverse of `heading`
+    ///
+    /// A malformed alias list is kept in the name, for validation to report.
+    pub fn from_heading(heading: &str) -> Concept {
+        let heading = heading.trim();
+        match crate::alias::split(heading) {
+            Some((name, aliases)) => Concept {
+                aliases: aliases.into_iter().map(str::to_string).collect(),
+                ..Concept::new(name)
+            },
+            None => Concept::new(heading),
+        }
+    }
+
+    /// Look up a facet by name, mutably
+    pub fn facet_mut(&mut self, name: &str) -> Option<&mut Facet> {
+        self.facets.iter_mut().find(|f| f.name == name)
+    }
+}
+
+impl Facet {
+    /// A new facet with no claims
+    pub fn new(name: impl Into<String>) -> Facet {
+        Facet::at(name, 0)
+    }
+
+    fn at(name: impl Into<String>, line: usize) -> Facet {
+        Facet {
+            name: name.into(),
+            claims: Vec::new(),
+            sub_facets: Vec::new(),
+            line,
+            source: None,
+        }
+    }
+}
+
+impl Claim {
+    /// Parse claim text (without the `- ` prefix) into a claim
+    pub fn parse(text: &str) -> Claim {
+        Claim {
+            data: parse_claim(text.trim()).into_owned(),
+            line: 0,
+            source: None,
+        }
+    }
+
+    /// Relation paths for graph export: one entry per chain or single relation
+    pub fn paths(&self) -> Vec<(&'static str, Vec<&Operand<'static>>)> {
+        self.data
+            .expression
+            .as_ref()
+            .map(ClaimExpr::paths)
+            .unwrap_or_default()
+    }
+}
+
+impl ClaimData<'_> {
+    /// Serialize in positional order: text, conditions, sources, references, evolution, ID
+    pub fn to_wvf_string(&self) -> String {
+        let mut out = self.text.to_string();
+        for condition in &self.conditions {
+            out.push_str(" | ");
+            out.push_str(condition);
+        }
+        for source in &self.sources {
+            out.push_str(" @");
+            out.push_str(source);
+        }
+        for reference in &self.references {
+            out.push_str(" &");
+            out.push_str(reference);
+        }
+        for evo in &self.evolution {
+            out.push_str(" [<= ");
+            out.push_str(&evo.prior_belief);
+            if let Some(date) = evo.changed_at {
+                out.push_str(&format!(" {}{}", crate::DATE_PREFIX, date));
+            }
+            out.push(']');
+        }
+        if let Some(ref id) = self.id {
+            out.push(' ');
+            out.push_str(crate::CLAIM_ID_PREFIX);
+            out.push_str(id);
+        }
+        out
+    }
+}
+
+/// Errors from `DocumentBuilder::build`
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("facet '{0}' added before any concept")]
+    FacetWithoutConcept(String),
+
+    #[error("alias '{0}' added before any concept")]
+    AliasWithoutConcept(String),
+
+    #[error("sub-facet '{0}' added before any facet")]
+    SubFacetWithoutFacet(String),
+
+    #[error("claim '{0}' added before any facet")]
+    ClaimWithoutFacet(String),
+
+    #[error("built document is invalid: {}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; "))]
+    Invalid(Vec<ValidationError>),
+}
+
+/// Fluent builder for documents
+///
+/// Each call appends under the most recent concept/facet:
+///
+/// ```
+/// use worldview_validator::DocumentBuilder;
+///
+/// let doc = DocumentBuilder::new()
+///     .concept("Trust")
+///     .facet("formation")
+///     .claim("slow")
+///     .claim("requires consistency | over time")
+///     .build()
+///     .unwrap();
+/// assert!(doc.to_wvf_string().starts_with("Trust\n  .formation\n    - slow\n"));
+/// ```
+#[derive(Debug, Default)]
+pub struct DocumentBuilder {
+    doc: Document,
+    in_sub_facet: bool,
+    error: Option<BuildError>,
+}
+
+impl DocumentBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare the spec version the document targets
+    pub fn spec_version(mut self, version: SpecVersion) -> Self {
+        self.doc.version = Some(VersionDeclaration::new(version));
+        self
+    }
+
+    /// Add an `>include` directive
+    pub fn include(mut self, path: impl Into<String>) -> Self {
+        self.doc.includes.push(Include::new(path));
+        self
+    }
+
+    /// Register a source handle in the `sources:` section
+    pub fn source(mut self, handle: impl Into<String>, description: impl Into<String>) -> Self {
+        self.doc
+            .sources
+            .get_or_insert_with(Sources::default)
+            .entries
+            .push(SourceEntry::new(handle, description));
+        self
+    }
+
+    /// Start a new concept
+    pub fn concept(mut self, name: impl Into<String>) -> Self {
+        self.doc.concepts.push(Concept::new(name));
+        self.in_sub_facet = false;
+        self
+    }
+
+    /// Give the current concept another name
+    pub fn alias(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        match self.doc.concepts.last_mut() {
+            Some(concept) => concept.aliases.push(name),
+            None => self.fail(BuildError::AliasWithoutConcept(name)),
+        }
+        self
+    }
+
+    /// Start a new facet under the current concept
+    pub fn facet(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        match self.doc.concepts.last_mut() {
+            Some(concept) => concept.facets.push(Facet::new(name)),
+            None => self.fail(BuildError::FacetWithoutConcept(name)),
+        }
+        self.in_sub_facet = false;
+        self
+    }
+
+    /// Start a new sub-facet under the current facet
+    pub fn sub_facet(mut self, name: impl Into<String>) -> Self {
+        let name = name.into();
+        match self.doc.last_facet_mut() {
+            Some(facet) => {
+                facet.sub_facets.push(Facet::new(name));
+                self.in_sub_facet = true;
+            }
+            None => self.fail(BuildError::SubFacetWithoutFacet(name)),
+        }
+        self
+    }
+
+    /// Add a claim (positional syntax, without the `- ` prefix) to the current facet
+    pub fn claim(mut self, text: &str) -> Self {
+        let claim = Claim::parse(text);
+        let in_sub_facet = self.in_sub_facet;
+        match self.doc.last_facet_mut() {
+            Some(facet) => match facet.sub_facets.last_mut() {
+                Some(sub) if in_sub_facet => sub.claims.push(claim),
+                _ => facet.claims.push(claim),
+            },
+            None => self.fail(BuildError::ClaimWithoutFacet(text.to_string())),
+        }
+        self
+    }
+
+    fn fail(&mut self, error: BuildError) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Finish building, validating the result
+    pub fn build(self) -> Result<Document, BuildError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let result = self.doc.validate();
+        if result.is_valid() {
+            Ok(self.doc)
+        } else {
+            Err(BuildError::Invalid(result.errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_serializes_canonical_text() {
+        let doc = DocumentBuilder::new()
+            .concept("Trust")
+            .facet("formation")
+            .claim("slow")
+            .claim("requires consistency | over time @experience")
+            .facet("erosion")
+            .claim("fast !")
+            .claim("asymmetric vs formation &Trust.formation")
+            .concept("Power")
+            .facet("core")
+            .claim("adaptive [<= inherently good]")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            doc.to_wvf_string(),
+            "Trust\n  .formation\n    - slow\n    - requires consistency | over time @experience\n  .erosion\n    - fast !\n    - asymmetric vs formation &Trust.formation\n\nPower\n  .core\n    - adaptive [<= inherently good]\n"
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_claim_without_facet() {
+        let err = DocumentBuilder::new()
+            .concept("Trust")
+            .claim("slow")
+            .build();
+        assert_eq!(err, Err(BuildError::ClaimWithoutFacet("slow".to_string())));
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_document() {
+        let err = DocumentBuilder::new()
+            .concept("Trust")
+            .facet("formation")
+            .build();
+        assert!(matches!(err, Err(BuildError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_builder_sub_facets() {
+        let doc = DocumentBuilder::new()
+            .concept("Economics")
+            .facet("markets")
+            .sub_facet("labor")
+            .claim("wages sticky")
+            .build()
+            .unwrap();
+        assert_eq!(
+            doc.to_wvf_string(),
+            "Economics\n  .markets\n    .labor\n      - wages sticky\n"
+        );
+    }
+
+    #[test]
+    fn test_lossless_round_trip() {
+        let inputs = [
+            include_str!("../../example.wvf"),
+            "Power\n  .core\n    -   corrupts   | unchecked\n\n\n\nTrust\n  .x\n    - y",
+            "Power  \r\n  .core\r\n    - corrupts\r\n\r\n",
+            ">include other.wvf\n\nPower\n  .core\n    - corrupts &other:Trust.x\n",
+            "Economics\n  .markets\n    .labor\n      - wages sticky\n",
+        ];
+        for input in inputs {
+            assert_eq!(Document::parse(input).to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_misprefixed_lines_round_trip() {
+        let input = "Power\n  core\n    corrupts\n";
+        let doc = Document::parse(input);
+        assert_eq!(doc.concepts[0].facets[0].claims[0].data.text, "corrupts");
+        assert_eq!(doc.to_string(), input);
+        assert_eq!(doc.to_wvf_string(), "Power\n  .core\n    - corrupts\n");
+    }
+
+    #[test]
+    fn test_lossless_keeps_byte_order_mark() {
+        let input = "\u{FEFF}Power\r\n  .core\r\n    - corrupts\r\n";
+        let doc = Document::parse(input);
+        assert!(doc.byte_order_mark);
+        assert_eq!(doc.concepts[0].name, "Power");
+        assert_eq!(doc.to_string(), input);
+    }
+
+    #[test]
+    fn test_version_declaration_round_trips() {
+        let input = "wvf  1.0\n\nPower\n  .core\n    - corrupts\n";
+        let doc = Document::parse(input);
+        assert_eq!(doc.spec_version(), Some(SpecVersion::V1_0));
+        assert_eq!(doc.to_string(), input);
+        assert!(doc.to_wvf_string().starts_with("wvf 1.0\n\nPower\n"));
+
+        let built = DocumentBuilder::new()
+            .spec_version(SpecVersion::V1_0)
+            .concept("Power")
+            .facet("core")
+            .claim("corrupts ^id:power-corrupts")
+            .build();
+        assert!(matches!(built, Err(BuildError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_lossless_rerenders_only_changed_nodes() {
+        let input = "Power\n  .core\n    -   corrupts   | unchecked\n    -  reveals character\n";
+        let mut doc = Document::parse(input);
+        doc.concepts[0].facets[0].claims[1]
+            .data
+            .sources
+            .push("history".into());
+        assert_eq!(
+            doc.to_string(),
+            "Power\n  .core\n    -   corrupts   | unchecked\n    - reveals character @history\n"
+        );
+    }
+
+    #[test]
+    fn test_claim_paths() {
+        let claim = Claim::parse("power => corruption => distrust vs accountability");
+        let paths: Vec<(&str, Vec<&str>)> = claim
+            .paths()
+            .into_iter()
+            .map(|(op, operands)| (op, operands.iter().map(|o| o.text.as_ref()).collect()))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("=>", vec!["power", "corruption", "distrust"]),
+                ("vs", vec!["distrust", "accountability"]),
+            ]
+        );
+        assert!(Claim::parse("slow").paths().is_empty());
+    }
+
+    #[test]
+    fn test_claims_conditioned_on() {
+        let doc = Document::parse(
+            "Trust\n  .erosion\n    - fast | when low transparency\n    - slow | over time\n  .repair\n    - possible | not low transparency\n",
+        );
+        let lines: Vec<usize> = doc
+            .claims_conditioned_on("Low transparency")
+            .iter()
+            .map(|c| c.line)
+            .collect();
+        assert_eq!(lines, [3, 6]);
+    }
+
+    #[test]
+    fn test_claims_with_modifier() {
+        let doc = Document::parse(
+            "Power\n  .nature\n    - corrupts ?\n    - concentrates !\n    .limits\n      - checks ?\n",
+        );
+        assert_eq!(doc.claims().count(), 3);
+        let lines: Vec<usize> = doc.claims_with_modifier('?').map(|c| c.line).collect();
+        assert_eq!(lines, [3, 6]);
+    }
+
+    #[test]
+    fn test_definition_lines() {
+        let doc = Document::parse("Economics\n  .markets\n    .labor\n      - sticky\n");
+        assert_eq!(doc.definition_line("Economics.markets"), Some(2));
+        assert_eq!(doc.definition_line("Economics.markets.labor"), Some(3));
+        assert_eq!(doc.definition_line("Economics.labor"), None);
+    }
+
+    #[test]
+    fn test_parse_then_serialize() {
+        let input = "Power\n  .nature\n    - corrupts | unchecked !\n    - concentration^ => abuse^ @historical-pattern\n\nTrust\n  .erosion\n    - asymmetric vs formation &Power.nature\n";
+        let doc = Document::parse(input);
+        assert_eq!(doc.concepts.len(), 2);
+        assert_eq!(doc.concepts[0].facets[0].claims[1].line, 4);
+        assert_eq!(doc.to_wvf_string(), input);
+    }
+}
@@ -0,0 +1,479 @@
+//! Expression trees for claims built from brief forms
+//!
+//! Claim text is split into words and operators, then parsed left to right:
+//! `a => b vs c` is `(a => b) vs c`, while repeating an operator extends a
+//! chain: `power => corruption => distrust` is one `=>` path through three
+//! operands. An operand is the run of words between two operators, so `single violation => collapse?` relates the phrase `single
+//! violation` to `collapse` (modifier `?`). Symbolic operators may be written
+//! without spaces (`power=>corruption`); `vs` only counts as a whole word.
+
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::{BriefFormUsage, ClaimData, SpecConfig, is_grapheme_boundary, owned};
+
+/// A claim's relational structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClaimExpr<'a> {
+    Operand(Operand<'a>),
+    /// `left operator right`
+    Relation {
+        operator: &'static str,
+        left: Box<ClaimExpr<'a>>,
+        right: Box<ClaimExpr<'a>>,
+    },
+    /// Three or more operands joined by one operator (`a => b => c`)
+    Chain {
+        operator: &'static str,
+        operands: Vec<Operand<'a>>,
+    },
+}
+
+/// The words on one side of an operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operand<'a> {
+    /// Text without trailing modifiers; empty if the operator has nothing on this side
+    pub text: Cow<'a, str>,
+    /// Trailing modifiers, attached (`abuse^`) or standalone (`fast !`), in order
+    pub modifiers: Vec<char>,
+}
+
+impl<'a> ClaimExpr<'a> {
+    /// Operands from left to right
+    pub fn operands(&self) -> Vec<&Operand<'a>> {
+        let mut out = Vec::new();
+        self.collect_operands(&mut out);
+        out
+    }
+
+    fn collect_operands<'e>(&'e self, out: &mut Vec<&'e Operand<'a>>) {
+        match self {
+            ClaimExpr::Operand(operand) => out.push(operand),
+            ClaimExpr::Relation { left, right, .. } => {
+                left.collect_operands(out);
+                right.collect_operands(out);
+            }
+            ClaimExpr::Chain { operands, .. } => out.extend(operands),
+        }
+    }
+
+    /// Every relation as a path of operands joined by one operator
+    ///
+    /// A chain is a single multi-edge path; a relation is a two-operand path
+    /// between the operands directly beside its operator.
+    pub fn paths(&self) -> Vec<(&'static str, Vec<&Operand<'a>>)> {
+        let mut out = Vec::new();
+        self.collect_paths(&mut out);
+        out
+    }
+
+    fn collect_paths<'e>(&'e self, out: &mut Vec<(&'static str, Vec<&'e Operand<'a>>)>) {
+        match self {
+            ClaimExpr::Operand(_) => {}
+            ClaimExpr::Relation {
+                operator,
+                left,
+                right,
+            } => {
+                left.collect_paths(out);
+                out.push((operator, vec![left.last_operand(), right.first_operand()]));
+                right.collect_paths(out);
+            }
+            ClaimExpr::Chain { operator, operands } => {
+                out.push((operator, operands.iter().collect()));
+            }
+        }
+    }
+
+    /// Each operator with the operands directly beside it, from left to right
+    pub fn brief_forms(&self) -> Vec<BriefFormUsage<'a>> {
+        let mut out = Vec::new();
+        self.collect_brief_forms(&mut out);
+        out
+    }
+
+    fn collect_brief_forms(&self, out: &mut Vec<BriefFormUsage<'a>>) {
+        for (operator, operands) in self.paths() {
+            for pair in operands.windows(2) {
+                out.push(BriefFormUsage {
+                    operator,
+                    left_operand: pair[0].text.clone(),
+                    right_operand: pair[1].text.clone(),
+                });
+            }
+        }
+    }
+
+    pub(crate) fn first_operand(&self) -> &Operand<'a> {
+        match self {
+            ClaimExpr::Operand(operand) => operand,
+            ClaimExpr::Relation { left, .. } => left.first_operand(),
+            ClaimExpr::Chain { operands, .. } => &operands[0],
+        }
+    }
+
+    pub(crate) fn last_operand(&self) -> &Operand<'a> {
+        match self {
+            ClaimExpr::Operand(operand) => operand,
+            ClaimExpr::Relation { right, .. } => right.last_operand(),
+            ClaimExpr::Chain { operands, .. } => &operands[operands.len() - 1],
+        }
+    }
+
+    pub fn into_owned(self) -> ClaimExpr<'static> {
+        match self {
+            ClaimExpr::Operand(operand) => ClaimExpr::Operand(operand.into_owned()),
+            ClaimExpr::Relation {
+                operator,
+                left,
+                right,
+            } => ClaimExpr::Relation {
+                operator,
+                left: Box::new(left.into_owned()),
+                right: Box::new(right.into_owned()),
+            },
+            ClaimExpr::Chain { operator, operands } => ClaimExpr::Chain {
+                operator,
+                operands: operands.into_iter().map(Operand::into_owned).collect(),
+            },
+        }
+    }
+}
+
+impl Operand<'_> {
+    pub fn into_owned(self) -> Operand<'static> {
+        Operand {
+            text: owned(self.text),
+            modifiers: self.modifiers,
+        }
+    }
+
+    fn describe(&self, spec: &SpecConfig) -> String {
+        if self.modifiers.is_empty() {
+            return self.text.to_string();
+        }
+        let meanings: Vec<&str> = self
+            .modifiers
+            .iter()
+            .map(|&symbol| spec.modifier_meaning(symbol).unwrap_or(""))
+            .collect();
+        format!("{} ({})", self.text, meanings.join(", "))
+    }
+}
+
+/// What an operator means in prose (`causes, leads to` is `causes`)
+pub fn operator_meaning(operator: &str) -> Option<&'static str> {
+    SpecConfig::builtin().operator_meaning(operator)
+}
+
+/// What a modifier means in prose
+pub fn modifier_meaning(symbol: char) -> Option<&'static str> {
+    SpecConfig::builtin().modifier_meaning(symbol)
+}
+
+impl ClaimExpr<'_> {
+    fn describe(&self, nested: bool, spec: &SpecConfig) -> String {
+        let joined = match self {
+            ClaimExpr::Operand(operand) => return operand.describe(spec),
+            ClaimExpr::Relation {
+                operator,
+                left,
+                right,
+            } => format!(
+                "{} {} {}",
+                left.describe(true, spec),
+                spec.operator_meaning(operator).unwrap_or(operator),
+                right.describe(true, spec)
+            ),
+            ClaimExpr::Chain { operator, operands } => {
+                let meaning = spec.operator_meaning(operator).unwrap_or(operator);
+                let described: Vec<String> = operands.iter().map(|o| o.describe(spec)).collect();
+                described.join(&format!(" {} ", meaning))
+            }
+        };
+        if nested {
+            format!("({})", joined)
+        } else {
+            joined
+        }
+    }
+}
+
+impl ClaimData<'_> {
+    /// Claim text with brief forms and modifiers spelled out
+    ///
+    /// `concentration^ => abuse` reads `concentration (increasing) causes abuse`.
+    pub fn describe(&self) -> String {
+        self.describe_with(SpecConfig::builtin())
+    }
+
+    /// Like `describe`, with the meanings of `spec`
+    pub fn describe_with(&self, spec: &SpecConfig) -> String {
+        match &self.expression {
+            Some(expr) => expr.describe(false, spec),
+            None => {
+                let words: Vec<Range<usize>> = lex(&self.text, spec)
+                    .into_iter()
+                    .filter_map(|piece| match piece {
+                        Piece::Word(range) => Some(range),
+                        _ => None,
+                    })
+                    .collect();
+                operand(&self.text, &words, spec).describe(spec)
+            }
+        }
+    }
+}
+
+/// Parse claim text; `None` if it contains no brief form
+pub(crate) fn parse_expression<'a>(text: &'a str, spec: &SpecConfig) -> Option<ClaimExpr<'a>> {
+    let pieces = lex(text, spec);
+    if !pieces
+        .iter()
+        .any(|piece| matches!(piece, Piece::Operator(_)))
+    {
+        return None;
+    }
+
+    let mut expr: Option<ClaimExpr> = None;
+    let mut pending_operator = None;
+    let mut words: Vec<Range<usize>> = Vec::new();
+
+    for piece in pieces.into_iter().chain(std::iter::once(Piece::End)) {
+        let operator = match piece {
+            Piece::Word(range) => {
+                words.push(range);
+                continue;
+            }
+            Piece::Operator(op) => Some(op),
+            Piece::End => None,
+        };
+        let operand = operand(text, &words, spec);
+        words.clear();
+        expr = Some(match (expr, pending_operator) {
+            (Some(left), Some(operator)) => extend(left, operator, operand),
+            _ => ClaimExpr::Operand(operand),
+        });
+        pending_operator = operator;
+    }
+    expr
+}
+
+/// Append `operator operand`, extending a chain when the operator repeats
+fn extend<'a>(left: ClaimExpr<'a>, operator: &'static str, operand: Operand<'a>) -> ClaimExpr<'a> {
+    match left {
+        ClaimExpr::Chain {
+            operator: op,
+            mut operands,
+        } if op == operator => {
+            operands.push(operand);
+            ClaimExpr::Chain { operator, operands }
+        }
+        ClaimExpr::Relation {
+            operator: op,
+            left: first,
+            right: second,
+        } if op == operator => match (*first, *second) {
+            (ClaimExpr::Operand(first), ClaimExpr::Operand(second)) => ClaimExpr::Chain {
+                operator,
+                operands: vec![first, second, operand],
+            },
+            (first, second) => ClaimExpr::Relation {
+                operator,
+                left: Box::new(ClaimExpr::Relation {
+                    operator: op,
+                    left: Box::new(first),
+                    right: Box::new(second),
+                }),
+                right: Box::new(ClaimExpr::Operand(operand)),
+            },
+        },
+        left => ClaimExpr::Relation {
+            operator,
+            left: Box::new(left),
+            right: Box::new(ClaimExpr::Operand(operand)),
+        },
+    }
+}
+
+enum Piece {
+    Word(Range<usize>),
+    Operator(&'static str),
+    End,
+}
+
+/// Split claim text into words and operators
+fn lex(text: &str, spec: &SpecConfig) -> Vec<Piece> {
+    let mut pieces = Vec::new();
+    for word in text.split_whitespace() {
+        let start = word.as_ptr() as usize - text.as_ptr() as usize;
+        if let Some(op) = spec.operator(word) {
+            pieces.push(Piece::Operator(op));
+            continue;
+        }
+
+        // Symbolic operators inside a word, when the whole symbol run is the operator
+        let mut fragment = start;
+        let mut chars = word.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if !spec.is_operator_symbol(c) {
+                continue;
+            }
+            let mut end = i + c.len_utf8();
+            while let Some(&(j, next)) = chars.peek() {
+                if !spec.is_operator_symbol(next) {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+            let run = &word[i..end];
+            let operator = spec
+                .operator(run)
+                .filter(|_| is_grapheme_boundary(word, end));
+            if let Some(op) = operator {
+                if fragment < start + i {
+                    pieces.push(Piece::Word(fragment..start + i));
+                }
+                pieces.push(Piece::Operator(op));
+                fragment = start + end;
+            }
+        }
+        if fragment < start + word.len() {
+            pieces.push(Piece::Word(fragment..start + word.len()));
+        }
+    }
+    pieces
+}
+
+/// An operand from consecutive words, splitting off trailing modifiers
+fn operand<'a>(text: &'a str, words: &[Range<usize>], spec: &SpecConfig) -> Operand<'a> {
+    let mut words = words;
+    let mut modifiers = Vec::new();
+
+    // Standalone modifiers (`fast !`, `trust v`) after at least one word
+    while let [rest @ .., last] = words
+        && !rest.is_empty()
+        && let Some(symbol) = standalone_modifier(&text[last.clone()], spec)
+    {
+        modifiers.push(symbol);
+        words = rest;
+    }
+    modifiers.reverse();
+
+    let (Some(first), Some(last)) = (words.first(), words.last()) else {
+        return Operand {
+            text: Cow::Borrowed(""),
+            modifiers,
+        };
+    };
+    let phrase = &text[first.start..last.end];
+    let stem = phrase.trim_end_matches(spec.attached_modifiers().as_slice());
+    let stem = if stem.is_empty() { phrase } else { stem };
+    let mut attached: Vec<char> = phrase[stem.len()..].chars().collect();
+    attached.append(&mut modifiers);
+
+    Operand {
+        text: Cow::Borrowed(stem),
+        modifiers: attached,
+    }
+}
+
+fn standalone_modifier(word: &str, spec: &SpecConfig) -> Option<char> {
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if spec.is_modifier(c) => Some(c),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_expression(text: &str) -> Option<ClaimExpr<'_>> {
+        super::parse_expression(text, SpecConfig::builtin())
+    }
+
+    fn operand(text: &str) -> ClaimExpr<'_> {
+        ClaimExpr::Operand(Operand {
+            text: Cow::Borrowed(text),
+            modifiers: Vec::new(),
+        })
+    }
+
+    fn relation<'a>(
+        operator: &'static str,
+        left: ClaimExpr<'a>,
+        right: ClaimExpr<'a>,
+    ) -> ClaimExpr<'a> {
+        ClaimExpr::Relation {
+            operator,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    #[test]
+    fn test_plain_text_has_no_expression() {
+        assert_eq!(parse_expression("reveals character"), None);
+        assert_eq!(parse_expression("canvas vs. paint-by-numbers"), None);
+    }
+
+    #[test]
+    fn test_left_associative_relations() {
+        assert_eq!(
+            parse_expression("power => corruption vs accountability"),
+            Some(relation(
+                "vs",
+                relation("=>", operand("power"), operand("corruption")),
+                operand("accountability"),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_operand_phrases_and_modifiers() {
+        let expr = parse_expression("single violation => collapse? !").unwrap();
+        let operands = expr.operands();
+        assert_eq!(operands[0].text, "single violation");
+        assert_eq!(operands[1].text, "collapse");
+        assert_eq!(operands[1].modifiers, ['?', '!']);
+    }
+
+    #[test]
+    fn test_operators_inside_words() {
+        let expr = parse_expression("concentration^=>abuse^").unwrap();
+        let operands = expr.operands();
+        assert_eq!(operands[0].text, "concentration");
+        assert_eq!(operands[1].text, "abuse");
+        assert_eq!(operands[1].modifiers, ['^']);
+
+        // Only a symbol run that is exactly a brief form counts
+        assert_eq!(parse_expression("a!=b x==y self-aware"), None);
+    }
+
+    #[test]
+    fn test_missing_operands_are_empty() {
+        let forms = parse_expression("=> corruption =").unwrap().brief_forms();
+        assert_eq!(forms[0].left_operand, "");
+        assert_eq!(forms[1].left_operand, "corruption");
+        assert_eq!(forms[1].right_operand, "");
+    }
+
+    #[test]
+    fn test_describe() {
+        let describe = |text| crate::parse_claim(text).describe();
+        assert_eq!(
+            describe("concentration^ => abuse? !"),
+            "concentration (increasing) causes abuse (uncertain, strong)"
+        );
+        assert_eq!(
+            describe("power => corruption => distrust"),
+            "power causes corruption causes distrust"
+        );
+        assert_eq!(describe("a => b vs c"), "(a causes b) contrasts with c");
+        assert_eq!(describe("corrupts?"), "corrupts (uncertain)");
+        assert_eq!(describe("reveals character"), "reveals character");
+    }
+}
@@ -0,0 +1,170 @@
+//! C API for embedding validation in other languages
+//!
+//! `wvf_validate` returns an opaque result owned by the caller until passed to
+//! `wvf_free`. Message strings belong to the result and stay valid until then.
+//! The declarations are in `include/worldview.h`.
+
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+
+use crate::ValidationError;
+
+/// A validation result with messages prepared as C strings
+pub struct WvfResult {
+    errors: Vec<Diagnostic>,
+    warnings: Vec<Diagnostic>,
+}
+
+struct Diagnostic {
+    line: usize,
+    message: CString,
+}
+
+impl Diagnostic {
+    fn new(error: &ValidationError) -> Self {
+        Diagnostic {
+            line: error.line(),
+            // Messages are built from the input, which came in as a C string
+            message: CString::new(error.to_string()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Validate a NUL-terminated document, returning NULL if `input` is NULL
+///
+/// Invalid UTF-8 is replaced with U+FFFD before validating.
+///
+/// # Safety
+///
+/// `input` must be NULL or point to a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_validate(input: *const c_char) -> *mut WvfResult {
+    if input.is_null() {
+        return ptr::null_mut();
+    }
+    let input = unsafe { CStr::from_ptr(input) }.to_string_lossy();
+    let result = crate::validate(&input);
+    Box::into_raw(Box::new(WvfResult {
+        errors: result.errors.iter().map(Diagnostic::new).collect(),
+        warnings: result.warnings.iter().map(Diagnostic::new).collect(),
+    }))
+}
+
+/// Release a result from `wvf_validate`; NULL is ignored
+///
+/// # Safety
+///
+/// `result` must be NULL or a pointer from `wvf_validate` not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_free(result: *mut WvfResult) {
+    if !result.is_null() {
+        drop(unsafe { Box::from_raw(result) });
+    }
+}
+
+/// Whether the document has no errors (warnings allowed)
+///
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_is_valid(result: *const WvfResult) -> bool {
+    unsafe { result.as_ref() }.is_some_and(|r| r.errors.is_empty())
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_error_count(result: *const WvfResult) -> usize {
+    unsafe { result.as_ref() }.map_or(0, |r| r.errors.len())
+}
+
+/// The message of error `index` (`line N: ...`), or NULL if out of range
+///
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_error_message(
+    result: *const WvfResult,
+    index: usize,
+) -> *const c_char {
+    message(unsafe { result.as_ref() }.and_then(|r| r.errors.get(index)))
+}
+
+/// The 1-based line of error `index`, or 0 if out of range
+///
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_error_line(result: *const WvfResult, index: usize) -> usize {
+    unsafe { result.as_ref() }
+        .and_then(|r| r.errors.get(index))
+        .map_or(0, |d| d.line)
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_warning_count(result: *const WvfResult) -> usize {
+    unsafe { result.as_ref() }.map_or(0, |r| r.warnings.len())
+}
+
+/// The message of warning `index`, or NULL if out of range
+///
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_warning_message(
+    result: *const WvfResult,
+    index: usize,
+) -> *const c_char {
+    message(unsafe { result.as_ref() }.and_then(|r| r.warnings.get(index)))
+}
+
+/// The 1-based line of warning `index`, or 0 if out of range
+///
+/// # Safety
+///
+/// `result` must be NULL or a live pointer from `wvf_validate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wvf_warning_line(result: *const WvfResult, index: usize) -> usize {
+    unsafe { result.as_ref() }
+        .and_then(|r| r.warnings.get(index))
+        .map_or(0, |d| d.line)
+}
+
+fn message(diagnostic: Option<&Diagnostic>) -> *const c_char {
+    diagnostic.map_or(ptr::null(), |d| d.message.as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_round_trip() {
+        let input = CString::new("Power\n  nature\n    - corrupts\n").unwrap();
+        unsafe {
+            let result = wvf_validate(input.as_ptr());
+            assert!(!wvf_is_valid(result));
+            assert_eq!(wvf_error_count(result), 1);
+            assert_eq!(wvf_error_line(result, 0), 2);
+            let message = CStr::from_ptr(wvf_error_message(result, 0));
+            assert_eq!(
+                message.to_str().unwrap(),
+                "line 2: facet must have '.' prefix"
+            );
+            assert!(wvf_error_message(result, 1).is_null());
+            wvf_free(result);
+
+            assert!(wvf_validate(ptr::null()).is_null());
+            assert_eq!(wvf_error_count(ptr::null()), 0);
+            wvf_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Belief history from evolution markers
+//!
+//! A claim's markers list the beliefs it replaced, nearest first, so
+//! `adaptive [<= neutral] [<= inherently good]` is the history
+//! `inherently good → neutral → adaptive`. Dated markers
+//! (`[<= neutral :2021]`) place changes on a timeline across claims.
+
+use crate::Date;
+use crate::document::{Document, Facet};
+
+/// One belief replacing another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeliefChange {
+    /// `Concept.facet` (or `Concept.facet.sub`) holding the claim
+    pub target: String,
+    /// Line of the claim recording the change, or 0 for claims created in code
+    pub line: usize,
+    pub from: String,
+    pub to: String,
+    /// When `from` was given up, if the marker is dated
+    pub changed_at: Option<Date>,
+}
+
+impl Document {
+    /// Every belief change, in chronological order
+    ///
+    /// Dated changes come first, oldest first; undated changes follow in
+    /// document order, oldest first within a claim.
+    pub fn evolution_timeline(&self) -> Vec<BeliefChange> {
+        let mut changes = Vec::new();
+        for concept in &self.concepts {
+            collect(&concept.facets, &concept.name, &mut changes);
+        }
+        changes.sort_by_key(|change| (change.changed_at.is_none(), change.changed_at));
+        changes
+    }
+}
+
+fn collect(facets: &[Facet], parent: &str, changes: &mut Vec<BeliefChange>) {
+    for facet in facets {
+        let target = format!("{}.{}", parent, facet.name);
+        for claim in &facet.claims {
+            // Markers are nearest first: each prior belief gave way to the one before it
+            let evolution = &claim.data.evolution;
+            for (i, evo) in evolution.iter().enumerate().rev() {
+                let to = match i {
+                    0 => &claim.data.text,
+                    _ => &evolution[i - 1].prior_belief,
+                };
+                changes.push(BeliefChange {
+                    target: target.clone(),
+                    line: claim.line,
+                    from: evo.prior_belief.to_string(),
+                    to: to.to_string(),
+                    changed_at: evo.changed_at,
+                });
+            }
+        }
+        collect(&facet.sub_facets, &target, changes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate;
+
+    #[test]
+    fn test_multiple_and_nested_markers() {
+        let input = "Human-nature\n  .cognition\n    - adaptive [<= neutral] [<= inherently good]\n    - bounded [<= rational [<= perfectly rational]]\n";
+        assert!(validate(input).is_valid());
+
+        let doc = Document::parse(input);
+        let timeline = doc.evolution_timeline();
+        let changes: Vec<(usize, &str, &str)> = timeline
+            .iter()
+            .map(|c| (c.line, c.from.as_str(), c.to.as_str()))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (3, "inherently good", "neutral"),
+                (3, "neutral", "adaptive"),
+                (4, "perfectly rational", "rational"),
+                (4, "rational", "bounded"),
+            ]
+        );
+        assert_eq!(timeline[0].target, "Human-nature.cognition");
+    }
+
+    #[test]
+    fn test_dated_changes_are_chronological() {
+        let doc = Document::parse(
+            "Power\n  .nature\n    - corrupts [<= neutral :2023] [<= benign :2019]\n    - reveals [<= conceals]\n  .limits\n    - checked [<= unchecked :2021-06]\n",
+        );
+        let timeline = doc.evolution_timeline();
+        let order: Vec<(&str, Option<String>)> = timeline
+            .iter()
+            .map(|c| (c.from.as_str(), c.changed_at.map(|d| d.to_string())))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                ("benign", Some("2019".into())),
+                ("unchecked", Some("2021-06".into())),
+                ("neutral", Some("2023".into())),
+                ("conceals", None),
+            ]
+        );
+        assert!(doc.validate().is_valid());
+    }
+
+    #[test]
+    fn test_unclosed_nested_marker() {
+        let result = validate("Human-nature\n  .cognition\n    - adaptive [<= neutral [<= good]\n");
+        assert!(
+            result
+                .errors
+                .iter()
+                .any(|e| matches!(e, crate::ValidationError::UnclosedEvolutionMarker { .. }))
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Stable claim identifiers
+//!
+//! A claim can carry an explicit ID (`- corrupts ^id:power-corrupts`) that
+//! survives rewording. Claims without one are addressed by a hash of their
+//! canonical text, which survives reformatting but not edits.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::document::{Claim, Document, Facet};
+use crate::{CLAIM_ID_PREFIX, ClaimData, LineType, ParsedLine, ValidationError};
+
+/// Byte range of the first `^id:` word in claim text
+pub(crate) fn find_claim_id(text: &str) -> Option<Range<usize>> {
+    text.split_whitespace()
+        .find(|word| word.starts_with(CLAIM_ID_PREFIX))
+        .map(|word| {
+            let start = word.as_ptr() as usize - text.as_ptr() as usize;
+            start..start + word.len()
+        })
+}
+
+/// Remove the `^id:` word from claim text, returning the text and the ID
+pub(crate) fn extract_claim_id(text: &str) -> (Cow<'_, str>, Option<Cow<'_, str>>) {
+    let Some(range) = find_claim_id(text) else {
+        return (Cow::Borrowed(text), None);
+    };
+    let id = Cow::Borrowed(&text[range.start + CLAIM_ID_PREFIX.len()..range.end]);
+    let before = text[..range.start].trim_end();
+    let after = text[range.end..].trim_start();
+    let cleaned = match (before.is_empty(), after.is_empty()) {
+        (_, true) => Cow::Borrowed(before),
+        (true, false) => Cow::Borrowed(after),
+        (false, false) => Cow::Owned(format!("{} {}", before, after)),
+    };
+    (cleaned, Some(id))
+}
+
+/// IDs are ASCII letters, digits, `-`, and `_`
+pub(crate) fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Report explicit IDs used by more than one claim
+pub(crate) fn duplicate_ids(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    let mut first_use: HashMap<&str, usize> = HashMap::new();
+    let mut errors = Vec::new();
+    for line in lines {
+        let LineType::Claim(claim) = &line.line_type else {
+            continue;
+        };
+        let Some(id) = claim.id.as_deref().filter(|id| is_valid_id(id)) else {
+            continue;
+        };
+        match first_use.get(id) {
+            Some(&first_line) => errors.push(ValidationError::DuplicateClaimId {
+                line: line.line_number,
+                id: id.to_string(),
+                first_line,
+            }),
+            None => {
+                first_use.insert(id, line.line_number);
+            }
+        }
+    }
+    errors
+}
+
+impl ClaimData<'_> {
+    /// The explicit ID, or a hash of the claim's canonical text with whitespace collapsed
+    pub fn stable_id(&self) -> String {
+        match self.id {
+            Some(ref id) => id.to_string(),
+            None => {
+                let canonical = self.to_wvf_string();
+                content_hash(&canonical.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+        }
+    }
+}
+
+/// 48-bit FNV-1a hash as 12 hex digits; fixed so IDs are the same on every platform and release
+fn content_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:012x}", hash >> 16)
+}
+
+impl Document {
+    /// Find a claim by its stable ID (explicit or derived)
+    pub fn claim_by_id(&self, id: &str) -> Option<&Claim> {
+        self.concepts
+            .iter()
+            .find_map(|concept| find_in(&concept.facets, id))
+    }
+}
+
+fn find_in<'d>(facets: &'d [Facet], id: &str) -> Option<&'d Claim> {
+    facets.iter().find_map(|facet| {
+        facet
+            .claims
+            .iter()
+            .find(|claim| claim.data.stable_id() == id)
+            .or_else(|| find_in(&facet.sub_facets, id))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate;
+
+    #[test]
+    fn test_explicit_claim_id() {
+        let claim = Claim::parse("corrupts @history ^id:power-corrupts &Trust.formation");
+        assert_eq!(claim.data.id.as_deref(), Some("power-corrupts"));
+        assert_eq!(claim.data.text, "corrupts");
+        assert_eq!(claim.data.references, ["Trust.formation"]);
+        assert_eq!(claim.data.stable_id(), "power-corrupts");
+        assert_eq!(
+            claim.data.to_wvf_string(),
+            "corrupts @history &Trust.formation ^id:power-corrupts"
+        );
+    }
+
+    #[test]
+    fn test_derived_claim_id_ignores_formatting() {
+        let a = Claim::parse("power  =>  corruption |unchecked");
+        let b = Claim::parse("power => corruption | unchecked");
+        assert_eq!(a.data.stable_id(), b.data.stable_id());
+        assert_eq!(a.data.stable_id().len(), 12);
+        assert_ne!(
+            a.data.stable_id(),
+            Claim::parse("power => virtue").data.stable_id()
+        );
+
+        let doc = Document::parse("Power\n  .core\n    - corrupts\n    - reveals ^id:r1\n");
+        let id = doc.concepts[0].facets[0].claims[0].data.stable_id();
+        assert_eq!(doc.claim_by_id(&id).unwrap().line, 3);
+        assert_eq!(doc.claim_by_id("r1").unwrap().line, 4);
+    }
+
+    #[test]
+    fn test_invalid_and_duplicate_ids() {
+        let result = validate(
+            "Power\n  .core\n    - corrupts ^id:a\n    - reveals ^id:a\n    - tests ^id:\n",
+        );
+        assert_eq!(
+            result.errors,
+            [
+                ValidationError::InvalidClaimId {
+                    line: 5,
+                    id: String::new()
+                },
+                ValidationError::DuplicateClaimId {
+                    line: 4,
+                    id: "a".into(),
+                    first_line: 3
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Resolution of `>include` directives across files
+//!
+//! Included files contribute their Concept.facet definitions as reference targets
+//! for the including document. Includes are followed transitively, relative to
+//! the file containing the directive; cycles and unreadable files are reported
+//! against the directive line in the root document.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::reference::{namespace_for, qualified};
+use crate::{
+    INCLUDE_DIRECTIVE, ValidationError, ValidationOptions, collect_valid_references, trace,
+    validate_with_options,
+};
+
+/// Reference targets and errors gathered while following includes
+#[derive(Debug, Default)]
+pub(crate) struct IncludeResolution {
+    pub refs: HashSet<String>,
+    pub errors: Vec<ValidationError>,
+}
+
+/// Follow every include reachable from `input`, which is the contents of `path`
+pub(crate) fn resolve(input: &str, path: &Path, options: &ValidationOptions) -> IncludeResolution {
+    let _span = trace::span!("includes");
+    let mut resolution = IncludeResolution::default();
+    let mut stack = vec![canonical(path)];
+    let mut visited = HashSet::new();
+    let base = path.parent().unwrap_or(Path::new(""));
+
+    for (line, target) in include_directives(input) {
+        follow(
+            &base.join(target),
+            line,
+            options,
+            &mut stack,
+            &mut visited,
+            &mut resolution,
+        );
+    }
+
+    resolution
+}
+
+/// The path of an `>include` directive line, empty if it names none
+///
+/// The directive must be followed by whitespace or the end of the line, so
+/// `>includefoo bar.wvf` is not an include.
+pub(crate) fn directive_path(content: &str) -> Option<&str> {
+    let rest = content.strip_prefix(INCLUDE_DIRECTIVE)?;
+    (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+}
+
+/// Unindented `>include` directives in a document, with their line numbers
+pub(crate) fn include_directives(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input.lines().enumerate().filter_map(|(idx, line)| {
+        let target = directive_path(line.trim_end())?;
+        (!target.is_empty()).then_some((idx + 1, target))
+    })
+}
+
+fn follow(
+    path: &Path,
+    root_line: usize,
+    options: &ValidationOptions,
+    stack: &mut Vec<PathBuf>,
+    visited: &mut HashSet<PathBuf>,
+    resolution: &mut IncludeResolution,
+) {
+    let key = canonical(path);
+    if stack.contains(&key) {
+        resolution.errors.push(ValidationError::IncludeCycle {
+            line: root_line,
+            path: path.display().to_string(),
+        });
+        return;
+    }
+    if !visited.insert(key.clone()) {
+        return;
+    }
+
+    let content = match read(path) {
+        Some(content) => content,
+        None => {
+            resolution.errors.push(ValidationError::IncludeNotFound {
+                line: root_line,
+                path: path.display().to_string(),
+            });
+            return;
+        }
+    };
+
+    let included = validate_with_options(&content, options);
+    let targets = collect_valid_references(&included.lines);
+    if let Some(namespace) = namespace_for(path) {
+        resolution.refs.extend(targets.iter().map(|target| qualified(&namespace, target)));
+    }
+    resolution.refs.extend(targets);
+
+    let base = path.parent().unwrap_or(Path::new(""));
+    stack.push(key);
+    for (_, target) in include_directives(&content) {
+        follow(&base.join(target), root_line, options, stack, visited, resolution);
+    }
+    stack.pop();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+/// There is no filesystem in the browser, so every include is reported as not found
+#[cfg(target_arch = "wasm32")]
+fn read(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Canonical form of a path for cycle detection, falling back to the path as given
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{directive_path, include_directives};
+    use crate::{LineType, ValidationError, validate, validate_file};
+    use std::fs;
+
+    #[test]
+    fn test_include_resolves_cross_file_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("trust.wvf"), "Trust\n  .formation\n    - slow\n").unwrap();
+        let main = dir.path().join("main.wvf");
+        fs::write(
+            &main,
+            ">include trust.wvf\n\nPower\n  .core\n    - corrupts &Trust.formation\n",
+        )
+        .unwrap();
+
+        let result = validate_file(&main).unwrap();
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+    }
+
+    #[test]
+    fn test_include_without_file_context_is_unresolved() {
+        let input = ">include trust.wvf\n\nPower\n  .core\n    - corrupts &Trust.formation";
+        let result = validate(input);
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::UndefinedReference { .. })));
+    }
+
+    #[test]
+    fn test_include_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.wvf");
+        fs::write(&main, ">include nowhere.wvf\nPower\n  .core\n    - corrupts\n").unwrap();
+
+        let result = validate_file(&main).unwrap();
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::IncludeNotFound { line: 1, .. })));
+    }
+
+    #[test]
+    fn test_include_cycle_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.wvf"), ">include b.wvf\nA\n  .x\n    - one\n").unwrap();
+        fs::write(dir.path().join("b.wvf"), ">include a.wvf\nB\n  .y\n    - two\n").unwrap();
+
+        let result = validate_file(&dir.path().join("a.wvf")).unwrap();
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::IncludeCycle { .. })));
+    }
+
+    #[test]
+    fn test_include_namespaced_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("trust.wvf"), "Trust\n  .formation\n    - slow\n").unwrap();
+        let main = dir.path().join("main.wvf");
+        fs::write(
+            &main,
+            ">include trust.wvf\nPower\n  .core\n    - corrupts &trust:Trust.formation\n",
+        )
+        .unwrap();
+
+        let result = validate_file(&main).unwrap();
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+    }
+
+    #[test]
+    fn test_directive_needs_whitespace() {
+        assert_eq!(directive_path(">include trust.wvf"), Some("trust.wvf"));
+        assert_eq!(directive_path(">include"), Some(""));
+        assert_eq!(directive_path(">includefoo bar.wvf"), None);
+
+        let result = validate(">includefoo bar.wvf\n  .core\n    - corrupts\n");
+        assert!(!matches!(result.lines[0].line_type, LineType::Include(_)));
+        assert_eq!(include_directives(">includefoo bar.wvf\n").count(), 0);
+    }
+
+    #[test]
+    fn test_empty_include() {
+        let result = validate(">include\nPower\n  .core\n    - corrupts");
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::EmptyInclude { line: 1 })));
+    }
+}
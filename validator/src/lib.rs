@@ -4,9 +4,82 @@
 //! It checks structural correctness (hierarchy, indentation), claim syntax, brief forms,
 //! modifiers, and evolution markers.
 
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::fmt;
+use std::ops::{Range, RangeBounds, RangeInclusive};
+use std::path::Path;
 use thiserror::Error;
+use unicode_segmentation::GraphemeCursor;
+
+mod alias;
+mod cluster;
+mod compress;
+mod condition;
+mod date;
+mod diff;
+mod document;
+mod expr;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod history;
+mod id;
+mod include;
+mod lint;
+mod profile;
+mod redact;
+mod refactor;
+mod reference;
+mod schema;
+mod search;
+mod sort;
+mod sources;
+mod spec;
+#[cfg(feature = "sqlite")]
+mod store;
+mod suggest;
+mod token;
+mod token_estimate;
+mod trace;
+mod validator;
+mod version;
+mod visit;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(not(target_arch = "wasm32"))]
+mod workspace;
+
+pub use alias::{ALIAS_CLOSE, ALIAS_OPEN};
+pub use cluster::{Cluster, ClusterMember};
+pub use compress::PriorityPolicy;
+pub use condition::{Condition, ConditionKind};
+pub use date::{DATE_PREFIX, Date};
+pub use diff::Change;
+pub use document::{
+    BuildError, Claim, Concept, Document, DocumentBuilder, Facet, Include, LineEnding, SourceEntry,
+    Sources, Trivia, VersionDeclaration,
+};
+pub use expr::{ClaimExpr, Operand, modifier_meaning, operator_meaning};
+pub use history::BeliefChange;
+pub use lint::{ClaimLimits, normalize_claim};
+pub use profile::{Profile, validate_with_profile};
+pub use redact::{SENSITIVITY_LEVELS, sensitivity_level};
+pub use refactor::{RefactorError, Rename};
+pub use reference::{NAMESPACE_SEPARATOR, Reference, ReferenceCount, namespace_for};
+pub use schema::{NamingConvention, SCHEMA_FILE, Schema, SchemaError, validate_against_schema};
+pub use search::SearchHit;
+pub use sort::{ClaimOrder, SortKey, SortOrder};
+pub use sources::{Citation, SourceDefinition};
+pub use spec::{SpecConfig, SpecError};
+#[cfg(feature = "sqlite")]
+pub use store::{Store, StoreError, SyncStats};
+pub use token::{SpannedToken, TokenKind, tokenize, tokenize_with};
+pub use token_estimate::{TokenEstimate, estimate_tokens};
+pub use validator::{Validator, ValidatorBuilder};
+pub use version::{SpecVersion, VERSION_DIRECTIVE};
+pub use visit::Visitor;
+#[cfg(not(target_arch = "wasm32"))]
+pub use workspace::{WorkspaceFile, WorkspaceResult, validate_workspace, validate_workspace_with_options};
 
 // Token definitions generated at compile time from spec/tokens.yaml
 include!(concat!(env!("OUT_DIR"), "/tokens.rs"));
@@ -40,24 +113,131 @@ pub enum ValidationError {
     #[error("line {line}: orphan claim (no preceding facet)")]
     OrphanClaim { line: usize },
 
+    #[error("line {line}: orphan sub-facet (no preceding facet)")]
+    OrphanSubFacet { line: usize },
+
     #[error("line {line}: empty claim text")]
     EmptyClaimText { line: usize },
 
     #[error("line {line}: unexpected indentation level ({found} spaces)")]
     UnexpectedIndentation { line: usize, found: usize },
 
+    #[error("line {line}: indentation uses tabs (indent with spaces, 2 per level)")]
+    TabIndentation { line: usize },
+
     #[error("line {line}: concept name cannot be empty")]
     EmptyConceptName { line: usize },
 
+    #[error("line {line}: malformed alias list (expected 'Concept (aka Alias, Other)')")]
+    MalformedAliases { line: usize },
+
     #[error("line {line}: facet name cannot be empty")]
     EmptyFacetName { line: usize },
 
+    #[error("line {line}: unexpected control character {character:?}")]
+    UnexpectedControlCharacter { line: usize, character: char },
+
+    // Spec version errors
+    #[error("line {line}: unknown spec version '{version}' (expected {})", version::known())]
+    UnknownSpecVersion { line: usize, version: String },
+
+    #[error("line {line}: {construct} require wvf {required} (this file targets wvf {declared})")]
+    NewerSpecConstruct {
+        line: usize,
+        construct: &'static str,
+        required: SpecVersion,
+        declared: SpecVersion,
+    },
+
+    // Whitespace errors (only with `strict_whitespace`)
+    #[error("line {line}: file starts with a UTF-8 byte order mark")]
+    ByteOrderMark { line: usize },
+
+    #[error("line {line}: trailing whitespace")]
+    TrailingWhitespace { line: usize },
+
+    // Include errors
+    #[error("line {line}: include directive has no path")]
+    EmptyInclude { line: usize },
+
+    #[error("line {line}: included file '{path}' could not be read")]
+    IncludeNotFound { line: usize, path: String },
+
+    #[error("line {line}: include cycle through '{path}'")]
+    IncludeCycle { line: usize, path: String },
+
+    // Source registry errors
+    #[error("line {line}: invalid source definition (expected 'handle: description')")]
+    InvalidSourceDefinition { line: usize },
+
+    #[error("line {line}: source '@{handle}' is not in the sources registry")]
+    UndefinedSource { line: usize, handle: String },
+
+    #[error("line {line}: claim under '{scope}' must cite a source")]
+    MissingSource { line: usize, scope: String },
+
+    // Schema errors (only with a `Schema`)
+    #[error("line {line}: concept '{concept}' is not in the schema")]
+    ConceptNotInSchema { line: usize, concept: String },
+
+    #[error("line {line}: concept '{concept}' is missing required facet '.{facet}'")]
+    MissingRequiredFacet { line: usize, concept: String, facet: String },
+
+    #[error("line {line}: {kind} '{name}' is not {convention}")]
+    NamingViolation {
+        line: usize,
+        kind: &'static str,
+        name: String,
+        convention: NamingConvention,
+    },
+
+    #[error("line {line}: facet '{facet}' has {count} claims (at least {min} required)")]
+    TooFewClaims { line: usize, facet: String, count: usize, min: usize },
+
+    #[error("line {line}: facet '{facet}' has {count} claims (at most {max} allowed)")]
+    TooManyClaims { line: usize, facet: String, count: usize, max: usize },
+
     // Inline element errors
     #[error("line {line}: invalid reference format '{reference}' (expected &Concept.facet)")]
     InvalidReferenceFormat { line: usize, reference: String },
 
-    #[error("line {line}: undefined reference '{reference}' (no such concept.facet in document)")]
-    UndefinedReference { line: usize, reference: String },
+    #[error(
+        "line {line}: undefined reference '{reference}' (no such concept.facet in document){}",
+        did_you_mean(.suggestion)
+    )]
+    UndefinedReference {
+        line: usize,
+        reference: String,
+        /// The closest defined target, when the reference looks like a typo of it
+        suggestion: Option<String>,
+    },
+
+    #[error("line {line}: unknown namespace '{namespace}' in reference")]
+    UnknownNamespace { line: usize, namespace: String },
+
+    #[error("line {line}: {kind} '{name}' cannot be referenced ({})", reference::break_reason(*.character))]
+    UnreferencableName {
+        line: usize,
+        kind: &'static str,
+        name: String,
+        character: char,
+    },
+
+    #[error("line {line}: {kind} '{name}' contains '.', so references to it are ambiguous")]
+    AmbiguousReferenceTarget { line: usize, kind: &'static str, name: String },
+
+    #[error("line {line}: {kind} '{name}' is never referenced")]
+    UnreferencedDefinition { line: usize, kind: &'static str, name: String },
+
+    #[error("line {line}: alias '{alias}' is already a name of concept '{other}' (line {other_line})")]
+    AliasCollision { line: usize, alias: String, other: String, other_line: usize },
+
+    // Sensitivity marker warnings
+    #[error("line {line}: sensitivity marker '.{name}' has no effect: {reason}")]
+    MalformedSensitivityMarker { line: usize, name: String, reason: String },
+
+    #[error("line {line}: reference '&{reference}' points into '.{level}' claims, which redaction removes")]
+    SensitiveReference { line: usize, reference: String, level: &'static str },
 
     #[error("line {line}: empty condition (standalone '|')")]
     EmptyCondition { line: usize },
@@ -75,6 +255,15 @@ pub enum ValidationError {
     #[error("line {line}: brief form '{operator}' missing right operand")]
     BriefFormMissingRightOperand { line: usize, operator: String },
 
+    #[error("line {line}: chain of '{operator}' missing operand {position}")]
+    ChainMissingOperand { line: usize, operator: String, position: usize },
+
+    #[error("line {line}: invalid claim ID '{id}' (use letters, digits, '-', and '_')")]
+    InvalidClaimId { line: usize, id: String },
+
+    #[error("line {line}: claim ID '{id}' is already used on line {first_line}")]
+    DuplicateClaimId { line: usize, id: String, first_line: usize },
+
     // Evolution marker errors
     #[error("line {line}: unclosed evolution marker '[<=' (missing ']')")]
     UnclosedEvolutionMarker { line: usize },
@@ -85,81 +274,593 @@ pub enum ValidationError {
     #[error("line {line}: malformed evolution marker (expected '[<= prior belief]')")]
     MalformedEvolutionMarker { line: usize },
 
+    #[error("line {line}: {reason} in evolution marker")]
+    InvalidEvolutionDate { line: usize, reason: String },
+
+    #[error("line {line}: duplicate condition '| {condition}'")]
+    DuplicateCondition { line: usize, condition: String },
+
+    #[error("line {line}: claim repeats the claim on line {other_line}")]
+    DuplicateClaim { line: usize, other_line: usize },
+
+    #[error("line {line}: claim packs too much: {reason}; split it into several claims")]
+    ClaimTooComplex { line: usize, reason: String },
+
+    #[error("line {line}: facet '{facet}' has {count} claims (more than {max}); group them into sub-facets")]
+    FacetTooLarge { line: usize, facet: String, count: usize, max: usize },
+
     // Modifier warnings (these are softer - might be intentional)
     #[error("line {line}: standalone modifier '{modifier}' may be unintentional")]
     StandaloneModifier { line: usize, modifier: String },
+
+    #[error("line {line}: unknown operator '{operator}' (did you mean '{suggestion}'?)")]
+    UnknownOperator {
+        line: usize,
+        operator: String,
+        suggestion: &'static str,
+    },
+
+    #[error(
+        "line {line}: '{left} {operator} {right}' contradicts '{other_operator}' between the same operands on line {other_line}"
+    )]
+    ContradictoryRelation {
+        line: usize,
+        left: String,
+        right: String,
+        operator: &'static str,
+        other_operator: &'static str,
+        other_line: usize,
+    },
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(target) => format!(" - did you mean '&{}'?", target),
+        None => String::new(),
+    }
 }
 
 impl ValidationError {
     /// Returns true if this is a warning rather than a hard error
     pub fn is_warning(&self) -> bool {
-        matches!(self, ValidationError::StandaloneModifier { .. })
+        matches!(
+            self,
+            ValidationError::StandaloneModifier { .. }
+                | ValidationError::DuplicateCondition { .. }
+                | ValidationError::DuplicateClaim { .. }
+                | ValidationError::ClaimTooComplex { .. }
+                | ValidationError::FacetTooLarge { .. }
+                | ValidationError::UndefinedSource { .. }
+                | ValidationError::MissingSource { .. }
+                | ValidationError::UnknownOperator { .. }
+                | ValidationError::ContradictoryRelation { .. }
+                | ValidationError::UnreferencableName { .. }
+                | ValidationError::AmbiguousReferenceTarget { .. }
+                | ValidationError::UnreferencedDefinition { .. }
+                | ValidationError::AliasCollision { .. }
+                | ValidationError::MalformedSensitivityMarker { .. }
+                | ValidationError::SensitiveReference { .. }
+        )
+    }
+
+    /// The 1-based line the error was reported on
+    pub fn line(&self) -> usize {
+        match self {
+            ValidationError::InvalidIndentation { line, .. }
+            | ValidationError::MissingFacetPrefix { line, .. }
+            | ValidationError::MissingClaimPrefix { line, .. }
+            | ValidationError::ConceptWithoutFacets { line, .. }
+            | ValidationError::FacetWithoutClaims { line, .. }
+            | ValidationError::OrphanFacet { line, .. }
+            | ValidationError::OrphanClaim { line, .. }
+            | ValidationError::OrphanSubFacet { line, .. }
+            | ValidationError::EmptyClaimText { line, .. }
+            | ValidationError::UnexpectedIndentation { line, .. }
+            | ValidationError::TabIndentation { line, .. }
+            | ValidationError::EmptyConceptName { line, .. }
+            | ValidationError::MalformedAliases { line, .. }
+            | ValidationError::AliasCollision { line, .. }
+            | ValidationError::EmptyFacetName { line, .. }
+            | ValidationError::UnexpectedControlCharacter { line, .. }
+            | ValidationError::UnknownSpecVersion { line, .. }
+            | ValidationError::NewerSpecConstruct { line, .. }
+            | ValidationError::ByteOrderMark { line, .. }
+            | ValidationError::TrailingWhitespace { line, .. }
+            | ValidationError::EmptyInclude { line, .. }
+            | ValidationError::IncludeNotFound { line, .. }
+            | ValidationError::IncludeCycle { line, .. }
+            | ValidationError::InvalidSourceDefinition { line, .. }
+            | ValidationError::UndefinedSource { line, .. }
+            | ValidationError::MissingSource { line, .. }
+            | ValidationError::ConceptNotInSchema { line, .. }
+            | ValidationError::MissingRequiredFacet { line, .. }
+            | ValidationError::NamingViolation { line, .. }
+            | ValidationError::TooFewClaims { line, .. }
+            | ValidationError::TooManyClaims { line, .. }
+            | ValidationError::InvalidReferenceFormat { line, .. }
+            | ValidationError::UndefinedReference { line, .. }
+            | ValidationError::UnknownNamespace { line, .. }
+            | ValidationError::UnreferencableName { line, .. }
+            | ValidationError::AmbiguousReferenceTarget { line, .. }
+            | ValidationError::UnreferencedDefinition { line, .. }
+            | ValidationError::MalformedSensitivityMarker { line, .. }
+            | ValidationError::SensitiveReference { line, .. }
+            | ValidationError::EmptyCondition { line, .. }
+            | ValidationError::DuplicateCondition { line, .. }
+            | ValidationError::DuplicateClaim { line, .. }
+            | ValidationError::ClaimTooComplex { line, .. }
+            | ValidationError::FacetTooLarge { line, .. }
+            | ValidationError::EmptySource { line, .. }
+            | ValidationError::EmptyReference { line, .. }
+            | ValidationError::BriefFormMissingLeftOperand { line, .. }
+            | ValidationError::BriefFormMissingRightOperand { line, .. }
+            | ValidationError::ChainMissingOperand { line, .. }
+            | ValidationError::InvalidClaimId { line, .. }
+            | ValidationError::DuplicateClaimId { line, .. }
+            | ValidationError::ContradictoryRelation { line, .. }
+            | ValidationError::UnclosedEvolutionMarker { line, .. }
+            | ValidationError::EmptyEvolutionMarker { line, .. }
+            | ValidationError::MalformedEvolutionMarker { line, .. }
+            | ValidationError::InvalidEvolutionDate { line, .. }
+            | ValidationError::StandaloneModifier { line, .. }
+            | ValidationError::UnknownOperator { line, .. } => *line,
+        }
+    }
+
+    /// Stable kebab-case identifier for the kind of error (`undefined-reference`)
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidIndentation { .. } => "invalid-indentation",
+            ValidationError::MissingFacetPrefix { .. } => "missing-facet-prefix",
+            ValidationError::MissingClaimPrefix { .. } => "missing-claim-prefix",
+            ValidationError::ConceptWithoutFacets { .. } => "concept-without-facets",
+            ValidationError::FacetWithoutClaims { .. } => "facet-without-claims",
+            ValidationError::OrphanFacet { .. } => "orphan-facet",
+            ValidationError::OrphanClaim { .. } => "orphan-claim",
+            ValidationError::OrphanSubFacet { .. } => "orphan-sub-facet",
+            ValidationError::EmptyClaimText { .. } => "empty-claim-text",
+            ValidationError::UnexpectedIndentation { .. } => "unexpected-indentation",
+            ValidationError::TabIndentation { .. } => "tab-indentation",
+            ValidationError::EmptyConceptName { .. } => "empty-concept-name",
+            ValidationError::MalformedAliases { .. } => "malformed-aliases",
+            ValidationError::AliasCollision { .. } => "alias-collision",
+            ValidationError::EmptyFacetName { .. } => "empty-facet-name",
+            ValidationError::UnexpectedControlCharacter { .. } => "unexpected-control-character",
+            ValidationError::UnknownSpecVersion { .. } => "unknown-spec-version",
+            ValidationError::NewerSpecConstruct { .. } => "newer-spec-construct",
+            ValidationError::ByteOrderMark { .. } => "byte-order-mark",
+            ValidationError::TrailingWhitespace { .. } => "trailing-whitespace",
+            ValidationError::EmptyInclude { .. } => "empty-include",
+            ValidationError::IncludeNotFound { .. } => "include-not-found",
+            ValidationError::IncludeCycle { .. } => "include-cycle",
+            ValidationError::InvalidSourceDefinition { .. } => "invalid-source-definition",
+            ValidationError::UndefinedSource { .. } => "undefined-source",
+            ValidationError::MissingSource { .. } => "missing-source",
+            ValidationError::ConceptNotInSchema { .. } => "concept-not-in-schema",
+            ValidationError::MissingRequiredFacet { .. } => "missing-required-facet",
+            ValidationError::NamingViolation { .. } => "naming-violation",
+            ValidationError::TooFewClaims { .. } => "too-few-claims",
+            ValidationError::TooManyClaims { .. } => "too-many-claims",
+            ValidationError::InvalidReferenceFormat { .. } => "invalid-reference-format",
+            ValidationError::UndefinedReference { .. } => "undefined-reference",
+            ValidationError::UnknownNamespace { .. } => "unknown-namespace",
+            ValidationError::UnreferencableName { .. } => "unreferencable-name",
+            ValidationError::AmbiguousReferenceTarget { .. } => "ambiguous-reference-target",
+            ValidationError::UnreferencedDefinition { .. } => "unreferenced-definition",
+            ValidationError::MalformedSensitivityMarker { .. } => "malformed-sensitivity-marker",
+            ValidationError::SensitiveReference { .. } => "sensitive-reference",
+            ValidationError::EmptyCondition { .. } => "empty-condition",
+            ValidationError::DuplicateCondition { .. } => "duplicate-condition",
+            ValidationError::DuplicateClaim { .. } => "duplicate-claim",
+            ValidationError::ClaimTooComplex { .. } => "claim-too-complex",
+            ValidationError::FacetTooLarge { .. } => "facet-too-large",
+            ValidationError::EmptySource { .. } => "empty-source",
+            ValidationError::EmptyReference { .. } => "empty-reference",
+            ValidationError::BriefFormMissingLeftOperand { .. } => "brief-form-missing-left-operand",
+            ValidationError::BriefFormMissingRightOperand { .. } => "brief-form-missing-right-operand",
+            ValidationError::ChainMissingOperand { .. } => "chain-missing-operand",
+            ValidationError::InvalidClaimId { .. } => "invalid-claim-id",
+            ValidationError::DuplicateClaimId { .. } => "duplicate-claim-id",
+            ValidationError::ContradictoryRelation { .. } => "contradictory-relation",
+            ValidationError::UnclosedEvolutionMarker { .. } => "unclosed-evolution-marker",
+            ValidationError::EmptyEvolutionMarker { .. } => "empty-evolution-marker",
+            ValidationError::MalformedEvolutionMarker { .. } => "malformed-evolution-marker",
+            ValidationError::InvalidEvolutionDate { .. } => "invalid-evolution-date",
+            ValidationError::StandaloneModifier { .. } => "standalone-modifier",
+            ValidationError::UnknownOperator { .. } => "unknown-operator",
+        }
+    }
+
+    /// What the rule behind this kind of error requires, for rule listings
+    /// (`code()` names the rule)
+    pub fn description(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidIndentation { .. } => {
+                "Facets are indented 2 spaces and claims 4 (6 under sub-facets)."
+            }
+            ValidationError::MissingFacetPrefix { .. } => "A facet line starts with '.' after its indentation.",
+            ValidationError::MissingClaimPrefix { .. } => "A claim line starts with '- ' after its indentation.",
+            ValidationError::ConceptWithoutFacets { .. } => "Every concept has at least one facet.",
+            ValidationError::FacetWithoutClaims { .. } => "Every facet has at least one claim.",
+            ValidationError::OrphanFacet { .. } => "A facet follows the concept it belongs to.",
+            ValidationError::OrphanClaim { .. } => "A claim follows the facet it belongs to.",
+            ValidationError::OrphanSubFacet { .. } => "A sub-facet follows the facet it belongs to.",
+            ValidationError::EmptyClaimText { .. } => "A claim has text after its '- ' prefix.",
+            ValidationError::UnexpectedIndentation { .. } => {
+                "Indentation is 0, 2, or 4 spaces (6 with nested facets)."
+            }
+            ValidationError::TabIndentation { .. } => "Indentation uses spaces, 2 per level, not tabs.",
+            ValidationError::EmptyConceptName { .. } => "A concept line names the concept.",
+            ValidationError::MalformedAliases { .. } => {
+                "Aliases follow the concept name as '(aka Alias, Other)', ending the line."
+            }
+            ValidationError::AliasCollision { .. } => "An alias names only one concept.",
+            ValidationError::EmptyFacetName { .. } => "A facet line names the facet after its '.'.",
+            ValidationError::UnexpectedControlCharacter { .. } => {
+                "Lines contain no control characters other than tabs."
+            }
+            ValidationError::UnknownSpecVersion { .. } => "A 'wvf' declaration names a known spec version.",
+            ValidationError::NewerSpecConstruct { .. } => {
+                "Documents use only constructs of the spec version they target."
+            }
+            ValidationError::ByteOrderMark { .. } => "Files do not start with a UTF-8 byte order mark.",
+            ValidationError::TrailingWhitespace { .. } => "Lines do not end in whitespace.",
+            ValidationError::EmptyInclude { .. } => "An include directive gives the path to include.",
+            ValidationError::IncludeNotFound { .. } => "Included files exist and are readable.",
+            ValidationError::IncludeCycle { .. } => "Files do not include themselves, directly or indirectly.",
+            ValidationError::InvalidSourceDefinition { .. } => {
+                "Entries in the sources section have the form 'handle: description'."
+            }
+            ValidationError::UndefinedSource { .. } => {
+                "Cited @sources are defined in the sources section, when there is one."
+            }
+            ValidationError::MissingSource { .. } => "Claims in scopes that require sources cite one with @.",
+            ValidationError::ConceptNotInSchema { .. } => "Documents define only the concepts their schema allows.",
+            ValidationError::MissingRequiredFacet { .. } => "Concepts have every facet their schema requires.",
+            ValidationError::NamingViolation { .. } => {
+                "Concept and facet names follow the naming conventions of the schema."
+            }
+            ValidationError::TooFewClaims { .. } => "Facets hold at least the schema's minimum number of claims.",
+            ValidationError::TooManyClaims { .. } => "Facets hold at most the schema's maximum number of claims.",
+            ValidationError::InvalidReferenceFormat { .. } => {
+                "References have the form &Concept.facet, optionally with a namespace."
+            }
+            ValidationError::UndefinedReference { .. } => "References point at a concept.facet that is defined.",
+            ValidationError::UnknownNamespace { .. } => "Reference namespaces name an included file.",
+            ValidationError::UnreferencableName { .. } => {
+                "Concept and facet names contain no whitespace, '|', '@', '&', or ':', so they can be referenced."
+            }
+            ValidationError::AmbiguousReferenceTarget { .. } => {
+                "Concept and facet names contain no '.', so references to them are unambiguous."
+            }
+            ValidationError::UnreferencedDefinition { .. } => {
+                "Every concept and facet is the target of some reference (when enabled)."
+            }
+            ValidationError::MalformedSensitivityMarker { .. } => {
+                "Sensitivity markers are lowercase facets named after a level (.private, .confidential), not nested in the same level."
+            }
+            ValidationError::SensitiveReference { .. } => {
+                "Claims outside a sensitivity marker do not reference the claims inside it."
+            }
+            ValidationError::EmptyCondition { .. } => "A '|' is followed by a condition.",
+            ValidationError::DuplicateCondition { .. } => "A claim does not repeat a condition.",
+            ValidationError::DuplicateClaim { .. } => "A facet does not state the same claim twice.",
+            ValidationError::ClaimTooComplex { .. } => {
+                "A claim states one belief: few brief forms and conditions, and short text."
+            }
+            ValidationError::FacetTooLarge { .. } => {
+                "A facet holds few enough claims to scan; larger ones are grouped into sub-facets (when enabled)."
+            }
+            ValidationError::EmptySource { .. } => "An '@' is followed by a source.",
+            ValidationError::EmptyReference { .. } => "An '&' is followed by a reference.",
+            ValidationError::BriefFormMissingLeftOperand { .. } => {
+                "A brief-form operator has an operand on its left."
+            }
+            ValidationError::BriefFormMissingRightOperand { .. } => {
+                "A brief-form operator has an operand on its right."
+            }
+            ValidationError::ChainMissingOperand { .. } => "Every link in a chain of operators has an operand.",
+            ValidationError::InvalidClaimId { .. } => "Claim IDs use only letters, digits, '-', and '_'.",
+            ValidationError::DuplicateClaimId { .. } => "Claim IDs are unique within a document.",
+            ValidationError::ContradictoryRelation { .. } => {
+                "The same operands are not related by contradictory operators."
+            }
+            ValidationError::UnclosedEvolutionMarker { .. } => "An evolution marker '[<=' is closed with ']'.",
+            ValidationError::EmptyEvolutionMarker { .. } => "An evolution marker names the prior belief.",
+            ValidationError::MalformedEvolutionMarker { .. } => {
+                "Evolution markers have the form '[<= prior belief]'."
+            }
+            ValidationError::InvalidEvolutionDate { .. } => "Dates in evolution markers are valid YYYY-MM-DD dates.",
+            ValidationError::StandaloneModifier { .. } => {
+                "Modifiers (^, v, !, ?, *) attach to a term rather than standing alone."
+            }
+            ValidationError::UnknownOperator { .. } => "Brief forms use only the operators the spec defines.",
+        }
+    }
+
+    /// Byte range of the offending text in `text`, the line the error was
+    /// reported on; the line's content when the error has no narrower location
+    pub fn span(&self, text: &str) -> Range<usize> {
+        let end = text.trim_end().len();
+        let indent = end - text[..end].trim_start().len();
+        let find = |needle: &str| text.find(needle).map(|start| start..start + needle.len());
+        let span = match self {
+            ValidationError::InvalidIndentation { .. }
+            | ValidationError::UnexpectedIndentation { .. }
+            | ValidationError::TabIndentation { .. } => Some(0..indent),
+            ValidationError::TrailingWhitespace { .. } => Some(end..text.len()),
+            ValidationError::UnknownSpecVersion { version, .. } => text
+                .rfind(version.as_str())
+                .map(|start| start..start + version.len()),
+            ValidationError::ByteOrderMark { .. } => find("\u{feff}"),
+            ValidationError::UnexpectedControlCharacter { character, .. } => {
+                find(character.encode_utf8(&mut [0; 4]))
+            }
+            ValidationError::IncludeNotFound { path, .. } | ValidationError::IncludeCycle { path, .. } => {
+                find(path)
+            }
+            ValidationError::UndefinedSource { handle, .. } => find(&format!("@{}", handle)),
+            ValidationError::NamingViolation { name, .. }
+            | ValidationError::UnreferencableName { name, .. }
+            | ValidationError::AmbiguousReferenceTarget { name, .. }
+            | ValidationError::MalformedSensitivityMarker { name, .. } => find(name),
+            // Aliases follow the name, which may contain them
+            ValidationError::AliasCollision { alias, .. } => text
+                .rfind(alias.as_str())
+                .map(|start| start..start + alias.len()),
+            ValidationError::InvalidReferenceFormat { reference, .. }
+            | ValidationError::UndefinedReference { reference, .. }
+            | ValidationError::SensitiveReference { reference, .. } => find(&format!("&{}", reference)),
+            ValidationError::UnknownNamespace { namespace, .. } => find(&format!("&{}", namespace)),
+            // The repeat, not the first occurrence
+            ValidationError::DuplicateCondition { condition, .. } => text
+                .rfind(condition.as_str())
+                .map(|start| start..start + condition.len()),
+            ValidationError::InvalidClaimId { id, .. } | ValidationError::DuplicateClaimId { id, .. } => {
+                find(id)
+            }
+            ValidationError::BriefFormMissingLeftOperand { operator, .. }
+            | ValidationError::BriefFormMissingRightOperand { operator, .. }
+            | ValidationError::ChainMissingOperand { operator, .. }
+            | ValidationError::UnknownOperator { operator, .. } => find(operator),
+            ValidationError::ContradictoryRelation { operator, .. } => find(operator),
+            ValidationError::StandaloneModifier { modifier, .. } => find(modifier),
+            ValidationError::UnclosedEvolutionMarker { .. }
+            | ValidationError::EmptyEvolutionMarker { .. }
+            | ValidationError::MalformedEvolutionMarker { .. }
+            | ValidationError::InvalidEvolutionDate { .. } => {
+                find(EVOLUTION_OPEN).map(|span| span.start..end)
+            }
+            _ => None,
+        };
+        span.filter(|span| !span.is_empty()).unwrap_or(indent..end)
     }
 }
 
+// Parsed values borrow from the input where they can (`Cow::Borrowed`); each type
+// has an `into_owned` that detaches it from the input as the `'static` variant.
+
 /// The type of a parsed line
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum LineType {
+pub enum LineType<'a> {
     /// Empty line or whitespace only
     Blank,
+    /// An include directive (`>include path`, unindented)
+    Include(Cow<'a, str>),
     /// A concept (unindented text)
-    Concept(String),
+    Concept(Cow<'a, str>),
     /// A facet (2-space indent, '.' prefix)
-    Facet(String),
-    /// A claim (4-space indent, '-' prefix)
-    Claim(ClaimData),
+    Facet(Cow<'a, str>),
+    /// A sub-facet (4-space indent, '.' prefix); only produced with nested facets enabled
+    SubFacet(Cow<'a, str>),
+    /// A claim (4-space indent, '-' prefix; 6 spaces under a sub-facet)
+    Claim(ClaimData<'a>),
+    /// The `wvf X.Y` spec version declaration (first non-blank line, unindented)
+    Version(Cow<'a, str>),
+    /// The `sources:` section header (unindented)
+    SourcesSection,
+    /// A `handle: description` entry in the sources section (indented)
+    SourceDefinition(SourceDefinition<'a>),
+}
+
+impl LineType<'_> {
+    pub fn into_owned(self) -> LineType<'static> {
+        match self {
+            LineType::Blank => LineType::Blank,
+            LineType::Include(path) => LineType::Include(owned(path)),
+            LineType::Concept(name) => LineType::Concept(owned(name)),
+            LineType::Facet(name) => LineType::Facet(owned(name)),
+            LineType::SubFacet(name) => LineType::SubFacet(owned(name)),
+            LineType::Claim(claim) => LineType::Claim(claim.into_owned()),
+            LineType::Version(version) => LineType::Version(owned(version)),
+            LineType::SourcesSection => LineType::SourcesSection,
+            LineType::SourceDefinition(definition) => {
+                LineType::SourceDefinition(definition.into_owned())
+            }
+        }
+    }
 }
 
 /// Parsed claim data
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ClaimData {
-    pub text: String,
-    pub conditions: Vec<String>,
-    pub sources: Vec<String>,
-    pub references: Vec<String>,
-    pub brief_forms: Vec<BriefFormUsage>,
-    pub modifiers: Vec<ModifierUsage>,
-    pub evolution: Option<EvolutionMarker>,
+pub struct ClaimData<'a> {
+    pub text: Cow<'a, str>,
+    pub conditions: Vec<Cow<'a, str>>,
+    pub sources: Vec<Cow<'a, str>>,
+    pub references: Vec<Cow<'a, str>>,
+    /// Operators with their adjacent operands, from left to right
+    pub brief_forms: Vec<BriefFormUsage<'a>>,
+    pub modifiers: Vec<ModifierUsage<'a>>,
+    /// Prior beliefs this claim replaced, nearest first
+    pub evolution: Vec<EvolutionMarker<'a>>,
+    /// The claim text's relational structure; `None` if it has no brief form
+    pub expression: Option<ClaimExpr<'a>>,
+    /// Explicit `^id:` identifier; see `stable_id` for one every claim has
+    pub id: Option<Cow<'a, str>>,
+}
+
+impl ClaimData<'_> {
+    /// References parsed into structured form; malformed references are skipped
+    pub fn parsed_references(&self) -> Vec<Reference> {
+        self.references.iter().filter_map(|r| Reference::parse(r)).collect()
+    }
+
+    /// Conditions parsed into structured form
+    pub fn parsed_conditions(&self) -> Vec<Condition> {
+        self.conditions.iter().map(|c| Condition::parse(c)).collect()
+    }
+
+    pub fn into_owned(self) -> ClaimData<'static> {
+        ClaimData {
+            text: owned(self.text),
+            conditions: self.conditions.into_iter().map(owned).collect(),
+            sources: self.sources.into_iter().map(owned).collect(),
+            references: self.references.into_iter().map(owned).collect(),
+            brief_forms: self.brief_forms.into_iter().map(BriefFormUsage::into_owned).collect(),
+            modifiers: self.modifiers.into_iter().map(ModifierUsage::into_owned).collect(),
+            evolution: self.evolution.into_iter().map(EvolutionMarker::into_owned).collect(),
+            expression: self.expression.map(ClaimExpr::into_owned),
+            id: self.id.map(owned),
+        }
+    }
 }
 
 /// A brief form operator found in a claim
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct BriefFormUsage {
-    pub operator: String,
-    pub left_operand: String,
-    pub right_operand: String,
+pub struct BriefFormUsage<'a> {
+    pub operator: &'static str,
+    pub left_operand: Cow<'a, str>,
+    pub right_operand: Cow<'a, str>,
+}
+
+impl BriefFormUsage<'_> {
+    pub fn into_owned(self) -> BriefFormUsage<'static> {
+        BriefFormUsage {
+            operator: self.operator,
+            left_operand: owned(self.left_operand),
+            right_operand: owned(self.right_operand),
+        }
+    }
 }
 
 /// A modifier found in a claim
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ModifierUsage {
+pub struct ModifierUsage<'a> {
     pub symbol: char,
-    pub attached_to: String,
+    pub attached_to: Cow<'a, str>,
+}
+
+impl ModifierUsage<'_> {
+    pub fn into_owned(self) -> ModifierUsage<'static> {
+        ModifierUsage {
+            symbol: self.symbol,
+            attached_to: owned(self.attached_to),
+        }
+    }
 }
 
-/// An evolution marker [<= prior belief]
+/// An evolution marker [<= prior belief], optionally dated [<= prior belief :2022]
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct EvolutionMarker {
-    pub prior_belief: String,
+pub struct EvolutionMarker<'a> {
+    pub prior_belief: Cow<'a, str>,
+    /// When the prior belief was given up
+    pub changed_at: Option<Date>,
+}
+
+impl EvolutionMarker<'_> {
+    pub fn into_owned(self) -> EvolutionMarker<'static> {
+        EvolutionMarker {
+            prior_belief: owned(self.prior_belief),
+            changed_at: self.changed_at,
+        }
+    }
 }
 
 /// A parsed line with its metadata
 #[derive(Debug, Clone)]
-pub struct ParsedLine {
+pub struct ParsedLine<'a> {
     pub line_number: usize,
-    pub line_type: LineType,
-    pub raw: String,
+    pub line_type: LineType<'a>,
+    pub raw: Cow<'a, str>,
+}
+
+impl ParsedLine<'_> {
+    pub fn into_owned(self) -> ParsedLine<'static> {
+        ParsedLine {
+            line_number: self.line_number,
+            line_type: self.line_type.into_owned(),
+            raw: owned(self.raw),
+        }
+    }
+}
+
+fn owned(value: Cow<'_, str>) -> Cow<'static, str> {
+    Cow::Owned(value.into_owned())
+}
+
+/// Options controlling which extensions to the core grammar are accepted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// Allow sub-facets at 4-space indent with their claims at 6 spaces
+    pub nested_facets: bool,
+    /// Report a byte order mark and trailing whitespace instead of ignoring them
+    pub strict_whitespace: bool,
+    /// Accept facets missing `.` and claims missing `-` instead of reporting them
+    pub recover_prefixes: bool,
+    /// Report warnings as errors
+    pub deny_warnings: bool,
+    /// Scopes whose claims must cite an `@source`: a concept (`Science`), a facet
+    /// (`Science.methods`, including its sub-facets), or a facet in any concept
+    /// (`*.empirical`)
+    pub require_sources: Vec<String>,
+    /// Warn about concepts and facets that no `&reference` targets
+    pub report_unreferenced: bool,
+    /// Also flag claims that repeat another's words in a different order or
+    /// form (`consistency is required` after `requires consistency`)
+    pub fuzzy_duplicates: bool,
+    /// Brief forms, conditions, and length a claim may have before it is flagged
+    pub claim_limits: ClaimLimits,
+    /// Warn about facets and sub-facets with more claims of their own than this
+    pub max_facet_claims: Option<usize>,
+    /// Spec version for documents without a `wvf X.Y` declaration; the latest when unset
+    pub spec_version: Option<SpecVersion>,
+    /// Operators and modifiers claims are parsed with
+    pub spec: SpecConfig,
+    /// Structural constraints checked after the grammar
+    pub schema: Option<Schema>,
+    /// Report only diagnostics on these lines (1-based), such as an editor's
+    /// viewport; the whole document is still checked, so references resolve
+    pub lines: Option<RangeInclusive<usize>>,
 }
 
-/// Result of validation
+impl ValidationOptions {
+    /// Options with nested facets enabled
+    pub fn nested() -> Self {
+        ValidationOptions {
+            nested_facets: true,
+            ..ValidationOptions::default()
+        }
+    }
+}
+
+/// Result of validation; `lines` borrow from the validated input
 #[derive(Debug, Clone)]
-pub struct ValidationResult {
+pub struct ValidationResult<'a> {
     pub errors: Vec<ValidationError>,
     pub warnings: Vec<ValidationError>,
-    pub lines: Vec<ParsedLine>,
+    pub lines: Vec<ParsedLine<'a>>,
 }
 
-impl ValidationResult {
+impl<'a> ValidationResult<'a> {
+    /// Detach the parsed lines from the input
+    pub fn into_owned(self) -> ValidationResult<'static> {
+        ValidationResult {
+            errors: self.errors,
+            warnings: self.warnings,
+            lines: self.lines.into_iter().map(ParsedLine::into_owned).collect(),
+        }
+    }
+
     pub fn is_valid(&self) -> bool {
         self.errors.is_empty()
     }
@@ -167,9 +868,35 @@ impl ValidationResult {
     pub fn has_warnings(&self) -> bool {
         !self.warnings.is_empty()
     }
+
+    /// Errors and warnings on `lines` (1-based), in line order, errors first
+    /// on each line
+    pub fn diagnostics_in_range(&self, lines: impl RangeBounds<usize>) -> Vec<&ValidationError> {
+        let mut diagnostics: Vec<&ValidationError> = self
+            .errors
+            .iter()
+            .chain(&self.warnings)
+            .filter(|diagnostic| lines.contains(&diagnostic.line()))
+            .collect();
+        diagnostics.sort_by_key(|diagnostic| diagnostic.line());
+        diagnostics
+    }
+
+    /// Every parsed claim, in document order
+    pub fn claims(&self) -> impl Iterator<Item = &ClaimData<'a>> {
+        self.lines.iter().filter_map(|line| match &line.line_type {
+            LineType::Claim(claim) => Some(claim),
+            _ => None,
+        })
+    }
+
+    /// Errors on line `line` (1-based); warnings are in `warnings`
+    pub fn errors_for_line(&self, line: usize) -> impl Iterator<Item = &ValidationError> {
+        self.errors.iter().filter(move |error| error.line() == line)
+    }
 }
 
-impl fmt::Display for ValidationResult {
+impl fmt::Display for ValidationResult<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.is_valid() && !self.has_warnings() {
             write!(f, "Valid Worldview document")
@@ -196,90 +923,306 @@ impl fmt::Display for ValidationResult {
 }
 
 /// Validates a Worldview document
-pub fn validate(input: &str) -> ValidationResult {
+pub fn validate(input: &str) -> ValidationResult<'_> {
+    validate_with_options(input, &ValidationOptions::default())
+}
+
+/// Validates a Worldview document with the given options
+pub fn validate_with_options<'a>(input: &'a str, options: &ValidationOptions) -> ValidationResult<'a> {
+    validate_with_refs(input, options, &HashSet::new())
+}
+
+/// Validates a Worldview document, treating `external_refs` as additional reference targets
+fn validate_with_refs<'a>(
+    input: &'a str,
+    options: &ValidationOptions,
+    external_refs: &HashSet<String>,
+) -> ValidationResult<'a> {
+    let _span = trace::span!("validate", bytes = input.len());
     let mut errors = Vec::new();
     let mut warnings = Vec::new();
     let mut lines = Vec::new();
 
-    // First pass: tokenize lines
-    for (idx, raw_line) in input.lines().enumerate() {
-        let line_number = idx + 1;
-        let parsed = tokenize_line(raw_line, line_number, &mut errors);
-        lines.push(ParsedLine {
-            line_number,
-            line_type: parsed,
-            raw: raw_line.to_string(),
-        });
+    // A byte order mark is not part of the first line's content
+    let input = match input.strip_prefix(BYTE_ORDER_MARK) {
+        Some(rest) => {
+            if options.strict_whitespace {
+                errors.push(ValidationError::ByteOrderMark { line: 1 });
+            }
+            rest
+        }
+        None => input,
+    };
+
+    let mut in_sources = false;
+    let mut seen_content = false;
+
+    // First pass: tokenize lines (`lines()` also strips a `\r` before each `\n`)
+    {
+        let _span = trace::span!("tokenize");
+        for (idx, raw_line) in input.lines().enumerate() {
+            let line_number = idx + 1;
+            check_control_characters(raw_line, line_number, &mut errors);
+            let content = raw_line.trim_end_matches('\r');
+            if options.strict_whitespace && content.ends_with(char::is_whitespace) {
+                errors.push(ValidationError::TrailingWhitespace { line: line_number });
+            }
+            let parsed = if in_sources && indent_width(raw_line) > 0 && !content.trim().is_empty() {
+                sources::parse_line(content.trim(), line_number, &mut errors)
+            } else if let Some(declared) = version::declaration(content).filter(|_| !seen_content) {
+                if declared.parse::<SpecVersion>().is_err() {
+                    errors.push(ValidationError::UnknownSpecVersion {
+                        line: line_number,
+                        version: declared.to_string(),
+                    });
+                }
+                LineType::Version(Cow::Borrowed(declared))
+            } else {
+                tokenize_line(raw_line, line_number, options, &mut errors)
+            };
+            // The sources section runs until the next unindented line
+            in_sources = match parsed {
+                LineType::SourcesSection | LineType::SourceDefinition(_) => true,
+                LineType::Blank => in_sources,
+                _ => false,
+            };
+            seen_content |= !content.trim().is_empty();
+            lines.push(ParsedLine {
+                line_number,
+                line_type: parsed,
+                raw: Cow::Borrowed(raw_line),
+            });
+        }
     }
 
     // Collect valid Concept.facet pairs for reference validation
-    let valid_refs = collect_valid_references(&lines);
+    let valid_refs = {
+        let _span = trace::span!("references");
+        let mut valid_refs = collect_valid_references(&lines);
+        valid_refs.extend(external_refs.iter().cloned());
+        valid_refs
+    };
 
     // Second pass: validate structure
-    validate_structure(&lines, &mut errors);
+    {
+        let _span = trace::span!("structure");
+        validate_structure(&lines, &mut errors);
+    }
 
     // Third pass: validate claim syntax including brief forms, modifiers, evolution
-    for line in &lines {
-        if let LineType::Claim(claim) = &line.line_type {
-            validate_claim_syntax(line.line_number, claim, &valid_refs, &mut errors, &mut warnings);
+    {
+        let _span = trace::span!("claims");
+        for line in &lines {
+            if let LineType::Claim(claim) = &line.line_type {
+                validate_claim_syntax(
+                    line.line_number,
+                    claim,
+                    &valid_refs,
+                    &options.spec,
+                    &mut errors,
+                    &mut warnings,
+                );
+            }
         }
     }
 
+    // Fourth pass: document-wide checks and lints
+    {
+        let _span = trace::span!("lints");
+        errors.extend(id::duplicate_ids(&lines));
+        errors.extend(version::newer_constructs(&lines, version::target(&lines, options.spec_version)));
+        warnings.extend(lint::contradictory_relations(&lines));
+        warnings.extend(lint::duplicate_claims(&lines, options.fuzzy_duplicates));
+        warnings.extend(lint::complex_claims(&lines, &options.claim_limits));
+        warnings.extend(lint::large_facets(&lines, options.max_facet_claims));
+        warnings.extend(sources::undefined_sources(&lines));
+        warnings.extend(lint::missing_sources(&lines, &options.require_sources));
+        warnings.extend(reference::unreferencable_names(&lines));
+        warnings.extend(alias::alias_collisions(&lines));
+        warnings.extend(redact::sensitivity_markers(&lines));
+        if options.report_unreferenced {
+            warnings.extend(reference::unreferenced_definitions(&lines));
+        }
+        if let Some(schema) = &options.schema {
+            errors.extend(validate_against_schema(&lines, schema));
+        }
+    }
+
+    if let Some(range) = &options.lines {
+        errors.retain(|e| range.contains(&e.line()));
+        warnings.retain(|w| range.contains(&w.line()));
+    }
+    if options.deny_warnings {
+        errors.append(&mut warnings);
+    }
+
+    trace::event!(lines = lines.len(), errors = errors.len(), warnings = warnings.len(), "validated");
     ValidationResult { errors, warnings, lines }
 }
 
-/// Count leading spaces
-fn count_leading_spaces(line: &str) -> usize {
-    line.chars().take_while(|c| *c == ' ').count()
+/// Directive that pulls another file's definitions into scope
+pub const INCLUDE_DIRECTIVE: &str = ">include";
+
+/// Marks an explicit claim ID (`- corrupts ^id:power-corrupts`)
+pub const CLAIM_ID_PREFIX: &str = "^id:";
+
+/// Header of the optional section registering `@source` handles
+pub const SOURCES_SECTION: &str = "sources:";
+
+/// UTF-8 byte order mark, as some Windows editors write at the start of a file
+pub const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+/// Width of a line's indentation; a tab counts as one level so tab-indented files still parse
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .map_while(|c| match c {
+            ' ' => Some(1),
+            '\t' => Some(FACET_INDENT),
+            _ => None,
+        })
+        .sum()
+}
+
+/// Whether a line's indentation contains a tab
+fn has_tab_indent(line: &str) -> bool {
+    line.chars().take_while(|c| matches!(c, ' ' | '\t')).any(|c| c == '\t')
+}
+
+/// Replace each tab in leading indentation with `width` spaces
+pub fn expand_tabs(input: &str, width: usize) -> Cow<'_, str> {
+    if !input.lines().any(has_tab_indent) {
+        return Cow::Borrowed(input);
+    }
+    let mut out = String::with_capacity(input.len());
+    for line in input.split_inclusive('\n') {
+        let rest = line.trim_start_matches([' ', '\t']);
+        for c in line[..line.len() - rest.len()].chars() {
+            match c {
+                '\t' => out.extend(std::iter::repeat_n(' ', width)),
+                _ => out.push(c),
+            }
+        }
+        out.push_str(rest);
+    }
+    Cow::Owned(out)
+}
+
+/// Report the first control character on a line; tabs and a line-ending `\r` are left alone
+fn check_control_characters(line: &str, line_number: usize, errors: &mut Vec<ValidationError>) {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    if let Some(character) = line.chars().find(|c| c.is_control() && *c != '\t') {
+        errors.push(ValidationError::UnexpectedControlCharacter { line: line_number, character });
+    }
+}
+
+/// Whether byte offset `at` falls between grapheme clusters
+///
+/// An operator only counts if it ends on a boundary: `=` followed by a combining
+/// long solidus (U+0338) renders as `≠` and is not the `=` brief form.
+pub(crate) fn is_grapheme_boundary(text: &str, at: usize) -> bool {
+    GraphemeCursor::new(at, text.len(), true)
+        .is_boundary(text, 0)
+        .unwrap_or(false)
 }
 
 /// Tokenize a single line
-fn tokenize_line(line: &str, line_number: usize, errors: &mut Vec<ValidationError>) -> LineType {
+fn tokenize_line<'a>(
+    line: &'a str,
+    line_number: usize,
+    options: &ValidationOptions,
+    errors: &mut Vec<ValidationError>,
+) -> LineType<'a> {
     // Blank lines
     if line.trim().is_empty() {
         return LineType::Blank;
     }
 
-    let indent = count_leading_spaces(line);
+    let indent = indent_width(line);
     let content = line.trim();
+    if has_tab_indent(line) {
+        errors.push(ValidationError::TabIndentation { line: line_number });
+    }
 
     match indent {
+        0 if include::directive_path(content).is_some() => {
+            // Include: unindented '>include path'
+            let path = include::directive_path(content).unwrap_or_default();
+            if path.is_empty() {
+                errors.push(ValidationError::EmptyInclude { line: line_number });
+            }
+            LineType::Include(Cow::Borrowed(path))
+        }
+        0 if content == SOURCES_SECTION => LineType::SourcesSection,
         0 => {
             // Concept: no indent, bare text
             if content.is_empty() {
                 errors.push(ValidationError::EmptyConceptName { line: line_number });
                 LineType::Blank
             } else {
-                LineType::Concept(content.to_string())
+                if alias::split(content).is_none() {
+                    errors.push(ValidationError::MalformedAliases { line: line_number });
+                }
+                LineType::Concept(Cow::Borrowed(alias::name(content)))
             }
         }
         2 => {
             // Facet: 2-space indent, '.' prefix
             if !content.starts_with('.') {
-                errors.push(ValidationError::MissingFacetPrefix { line: line_number });
-                LineType::Blank
+                // Still read it as a facet, so its claims don't cascade into more errors
+                if !options.recover_prefixes {
+                    errors.push(ValidationError::MissingFacetPrefix { line: line_number });
+                }
+                LineType::Facet(Cow::Borrowed(content))
             } else {
                 let name = content[1..].trim();
                 if name.is_empty() {
                     errors.push(ValidationError::EmptyFacetName { line: line_number });
                 }
-                LineType::Facet(name.to_string())
+                LineType::Facet(Cow::Borrowed(name))
+            }
+        }
+        4 if options.nested_facets && content.starts_with('.') => {
+            // Sub-facet: 4-space indent, '.' prefix
+            let name = content[1..].trim();
+            if name.is_empty() {
+                errors.push(ValidationError::EmptyFacetName { line: line_number });
             }
+            LineType::SubFacet(Cow::Borrowed(name))
         }
         4 => {
             // Claim: 4-space indent, '-' prefix
             if !content.starts_with('-') {
-                errors.push(ValidationError::MissingClaimPrefix { line: line_number });
-                LineType::Blank
+                // Still read it as a claim, so its facet isn't reported as empty
+                if !options.recover_prefixes {
+                    errors.push(ValidationError::MissingClaimPrefix { line: line_number });
+                }
+                LineType::Claim(parse_claim_with(content, &options.spec))
             } else {
                 let claim_text = content[1..].trim();
-                let claim_data = parse_claim(claim_text);
+                let claim_data = parse_claim_with(claim_text, &options.spec);
                 LineType::Claim(claim_data)
             }
         }
+        6 if options.nested_facets => {
+            // Claim under a sub-facet: 6-space indent, '-' prefix
+            if !content.starts_with('-') {
+                if !options.recover_prefixes {
+                    errors.push(ValidationError::MissingClaimPrefix { line: line_number });
+                }
+                LineType::Claim(parse_claim_with(content, &options.spec))
+            } else {
+                LineType::Claim(parse_claim_with(content[1..].trim(), &options.spec))
+            }
+        }
         _ => {
             // Invalid indentation
-            if indent == 1 || indent == 3 {
+            if options.nested_facets && matches!(indent, 1 | 3 | 5) {
+                errors.push(ValidationError::InvalidIndentation {
+                    line: line_number,
+                    expected: "0, 2, 4, or 6",
+                    found: indent,
+                });
+            } else if indent == 1 || indent == 3 {
                 errors.push(ValidationError::InvalidIndentation {
                     line: line_number,
                     expected: "0, 2, or 4",
@@ -297,239 +1240,258 @@ fn tokenize_line(line: &str, line_number: usize, errors: &mut Vec<ValidationErro
 }
 
 /// Parse claim content into structured data
-fn parse_claim(text: &str) -> ClaimData {
-    let mut claim_text = String::new();
+fn parse_claim(text: &str) -> ClaimData<'_> {
+    parse_claim_with(text, SpecConfig::builtin())
+}
+
+/// Parse claim content with the operators and modifiers of `spec`
+pub(crate) fn parse_claim_with<'a>(text: &'a str, spec: &SpecConfig) -> ClaimData<'a> {
+    // The ID can sit anywhere, so remove it before positional parsing
+    let (text_without_id, id) = id::extract_claim_id(text);
+    let mut claim = match text_without_id {
+        Cow::Borrowed(body) => parse_claim_with_markers(body, spec),
+        Cow::Owned(body) => parse_claim_with_markers(&body, spec).into_owned(),
+    };
+    claim.id = id;
+    claim
+}
+
+fn parse_claim_with_markers<'a>(text: &'a str, spec: &SpecConfig) -> ClaimData<'a> {
+    // First, extract evolution markers if present
+    let (text_without_evolution, evolution) = extract_evolution_markers(text);
+    let mut claim = match text_without_evolution {
+        Cow::Borrowed(body) => parse_claim_body(body, spec),
+        Cow::Owned(body) => parse_claim_body(&body, spec).into_owned(),
+    };
+    claim.evolution = evolution;
+    claim
+}
+
+/// Parse inline elements (|, @, &) of a claim without its evolution marker
+fn parse_claim_body<'a>(text: &'a str, spec: &SpecConfig) -> ClaimData<'a> {
+    let mut claim_text = "";
     let mut conditions = Vec::new();
     let mut sources = Vec::new();
     let mut references = Vec::new();
 
-    // First, extract evolution marker if present
-    let (text_without_evolution, evolution) = extract_evolution_marker(text);
-    let text = text_without_evolution.as_str();
-
-    // Parse inline elements (|, @, &)
-    let mut current_segment = String::new();
+    let mut segment_start = 0;
     let mut in_claim = true;
-    let mut chars = text.chars().peekable();
+    // The open segment follows a `|`, so it is a condition even if empty
+    let mut after_bar = false;
+    let mut chars = text.char_indices().peekable();
 
-    while let Some(c) = chars.next() {
-        match c {
-            '|' => {
-                // Condition marker
-                if in_claim {
-                    claim_text = current_segment.trim().to_string();
-                    in_claim = false;
-                } else if !current_segment.trim().is_empty() {
-                    conditions.push(current_segment.trim().to_string());
-                }
-                current_segment = String::new();
-            }
-            '@' => {
-                // Source marker
-                if in_claim {
-                    claim_text = current_segment.trim().to_string();
-                    in_claim = false;
-                } else if !current_segment.trim().is_empty() {
-                    conditions.push(current_segment.trim().to_string());
-                }
-                current_segment = String::new();
-                // Collect source name (until space or another marker)
-                while let Some(&next) = chars.peek() {
-                    if next == ' ' || next == '|' || next == '@' || next == '&' {
-                        break;
-                    }
-                    current_segment.push(chars.next().unwrap());
-                }
-                if !current_segment.trim().is_empty() {
-                    sources.push(current_segment.trim().to_string());
-                }
-                current_segment = String::new();
-            }
-            '&' => {
-                // Reference marker
-                if in_claim {
-                    claim_text = current_segment.trim().to_string();
-                    in_claim = false;
-                } else if !current_segment.trim().is_empty() {
-                    conditions.push(current_segment.trim().to_string());
-                }
-                current_segment = String::new();
-                // Collect reference (until space or another marker)
-                while let Some(&next) = chars.peek() {
-                    if next == ' ' || next == '|' || next == '@' || next == '&' {
-                        break;
-                    }
-                    current_segment.push(chars.next().unwrap());
-                }
-                if !current_segment.trim().is_empty() {
-                    references.push(current_segment.trim().to_string());
-                }
-                current_segment = String::new();
-            }
-            _ => {
-                current_segment.push(c);
-            }
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '|' | '@' | '&') {
+            continue;
         }
-    }
 
-    // Handle remaining segment
-    if !current_segment.trim().is_empty() {
+        // Close the segment before the marker: claim text first, conditions after
+        let segment = text[segment_start..i].trim();
         if in_claim {
-            claim_text = current_segment.trim().to_string();
-        } else {
-            conditions.push(current_segment.trim().to_string());
+            claim_text = segment;
+            in_claim = false;
+        } else if after_bar || !segment.is_empty() {
+            conditions.push(Cow::Borrowed(segment));
         }
-    }
+        segment_start = i + c.len_utf8();
+        after_bar = c == '|';
 
-    // Extract brief forms from claim text
-    let brief_forms = extract_brief_forms(&claim_text);
+        if c == '|' {
+            // Condition marker: the condition runs to the next marker
+            continue;
+        }
+
+        // Source or reference marker: collect the name (until space or another marker)
+        let mut end = text.len();
+        while let Some(&(j, next)) = chars.peek() {
+            if next.is_whitespace() || matches!(next, '|' | '@' | '&') {
+                end = j;
+                break;
+            }
+            chars.next();
+        }
+        let name = text[segment_start..end].trim();
+        if !name.is_empty() {
+            let target = if c == '@' { &mut sources } else { &mut references };
+            target.push(Cow::Borrowed(name));
+        }
+        segment_start = end;
+    }
 
-    // Extract modifiers from claim text
-    let modifiers = extract_modifiers(&claim_text);
+    // Handle remaining segment
+    let segment = text[segment_start..].trim();
+    if in_claim {
+        claim_text = segment;
+    } else if after_bar || !segment.is_empty() {
+        conditions.push(Cow::Borrowed(segment));
+    }
 
+    let expression = expr::parse_expression(claim_text, spec);
     ClaimData {
-        text: claim_text,
+        text: Cow::Borrowed(claim_text),
         conditions,
         sources,
         references,
-        brief_forms,
-        modifiers,
-        evolution,
+        brief_forms: expression.as_ref().map(ClaimExpr::brief_forms).unwrap_or_default(),
+        modifiers: extract_modifiers(claim_text, spec),
+        evolution: Vec::new(),
+        expression,
+        id: None,
     }
 }
 
-/// Extract evolution marker [<= prior belief] from text
-fn extract_evolution_marker(text: &str) -> (String, Option<EvolutionMarker>) {
-    if let Some(start) = text.find("[<=") {
-        if let Some(end) = text[start..].find(']') {
-            let marker_content = &text[start + 3..start + end];
-            let prior_belief = marker_content.trim().to_string();
-            let text_before = &text[..start];
-            let text_after = &text[start + end + 1..];
-            let cleaned = format!("{}{}", text_before.trim(), text_after.trim());
-            return (
-                cleaned,
-                Some(EvolutionMarker { prior_belief }),
-            );
-        }
-        // Unclosed marker - return as-is, validation will catch it
-        return (text.to_string(), None);
-    }
-    (text.to_string(), None)
-}
+/// Opens an evolution marker `[<= prior belief]`
+const EVOLUTION_OPEN: &str = "[<=";
 
-/// Extract brief form usages from claim text
-fn extract_brief_forms(text: &str) -> Vec<BriefFormUsage> {
-    let mut usages = Vec::new();
-
-    // Check for each brief form operator
-    // Order matters: check longer operators first to avoid partial matches
-    // Minimal set: =>, vs, ~, =
-    let operators_by_length: &[&str] = &["=>", "vs", "~", "="];
-
-    let remaining = text.to_string();
-
-    for &op in operators_by_length {
-        // Special handling for = to avoid matching =>
-        if op == "=" {
-            // Look for standalone = not part of =>
-            let mut i = 0;
-            let chars: Vec<char> = remaining.chars().collect();
-            while i < chars.len() {
-                if chars[i] == '=' {
-                    let prev = if i > 0 { Some(chars[i - 1]) } else { None };
-                    let next = chars.get(i + 1);
-                    // Check it's not part of =>
-                    if prev != Some('<') && prev != Some('>') && next != Some(&'>') {
-                        // Found standalone =
-                        let before: String = chars[..i].iter().collect();
-                        let after: String = chars[i + 1..].iter().collect();
-                        let left = before.split_whitespace().last().unwrap_or("").to_string();
-                        let right = after.split_whitespace().next().unwrap_or("").to_string();
-                        if !left.is_empty() || !right.is_empty() {
-                            usages.push(BriefFormUsage {
-                                operator: op.to_string(),
-                                left_operand: left,
-                                right_operand: right,
-                            });
-                        }
-                    }
+/// Byte range of the first complete evolution marker, including any markers nested in it
+pub(crate) fn find_evolution_marker(text: &str) -> Option<std::ops::Range<usize>> {
+    let open = text.find(EVOLUTION_OPEN)?;
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open..open + i + 1);
                 }
-                i += 1;
             }
-            continue;
+            _ => {}
         }
+    }
+    None
+}
 
-        // For other operators
-        for (idx, _) in remaining.match_indices(op) {
-            let before = &remaining[..idx];
-            let after = &remaining[idx + op.len()..];
-
-            let left = before.split_whitespace().last().unwrap_or("").to_string();
-            let right = after.split_whitespace().next().unwrap_or("").to_string();
+/// Extract evolution markers from text, nearest prior belief first
+///
+/// `adaptive [<= neutral] [<= inherently good]` and the nested
+/// `adaptive [<= neutral [<= inherently good]]` both record that `adaptive`
+/// replaced `neutral`, which had replaced `inherently good`. An unclosed marker
+/// is left in the text for validation to report. The remaining text is borrowed
+/// unless markers sat between pieces of text.
+fn extract_evolution_markers(text: &str) -> (Cow<'_, str>, Vec<EvolutionMarker<'_>>) {
+    let mut markers = Vec::new();
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    while let Some(range) = find_evolution_marker(rest) {
+        pieces.push(rest[..range.start].trim());
+        let content = &rest[range.start + EVOLUTION_OPEN.len()..range.end - 1];
+        let (prior_belief, nested) = extract_evolution_markers(content.trim());
+        let (prior_belief, changed_at) = split_marker_date(prior_belief);
+        markers.push(EvolutionMarker {
+            prior_belief,
+            changed_at,
+        });
+        markers.extend(nested);
+        rest = &rest[range.end..];
+    }
+    pieces.push(rest.trim());
+    pieces.retain(|piece| !piece.is_empty());
+
+    let cleaned = match pieces.as_slice() {
+        [] => Cow::Borrowed(""),
+        [piece] => Cow::Borrowed(*piece),
+        _ => Cow::Owned(pieces.join(" ")),
+    };
+    (cleaned, markers)
+}
 
-            // Clean up modifiers from operands for matching
-            let left_clean = left.trim_end_matches(|c| "^v!?*".contains(c));
-            let right_clean = right.trim_end_matches(|c| "^v!?*".contains(c));
+/// Take a valid trailing `:date` off a prior belief; an invalid one is left for validation
+fn split_marker_date(content: Cow<'_, str>) -> (Cow<'_, str>, Option<Date>) {
+    let (belief, date) = date::split_date(&content);
+    let Some(date) = date.and_then(|d| d.parse().ok()) else {
+        return (content, None);
+    };
+    let len = belief.len();
+    let belief = match content {
+        Cow::Borrowed(c) => Cow::Borrowed(&c[..len]),
+        Cow::Owned(mut c) => {
+            c.truncate(len);
+            Cow::Owned(c)
+        }
+    };
+    (belief, Some(date))
+}
 
-            usages.push(BriefFormUsage {
-                operator: op.to_string(),
-                left_operand: left_clean.to_string(),
-                right_operand: right_clean.to_string(),
-            });
+/// Runs of operator-like symbols (`->`, `!=`, `→`) that are not brief forms
+///
+/// Runs of one repeated `-`, `!`, or `/` are punctuation (`--`, `!!`, `//`), not operators,
+/// and `[<=` belongs to an evolution marker.
+fn unknown_operators<'a>(text: &'a str, spec: &SpecConfig) -> Vec<&'a str> {
+    let is_symbol = |c: char| spec.is_operator_symbol(c) || suggest::is_arrow(c);
+    let mut found = Vec::new();
+    let mut run_start = None;
+
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (is_symbol(c), run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                let run = &text[start..i];
+                run_start = None;
+                let known = spec.operator(run).is_some();
+                let first = run.chars().next().unwrap_or(' ');
+                let punctuation = "-!/".contains(first) && run.chars().all(|c| c == first);
+                let marker = text[..start].ends_with('[');
+                let symbolic = run.chars().count() > 1 || run.chars().any(suggest::is_arrow);
+                if symbolic && !known && !punctuation && !marker {
+                    found.push(run);
+                }
+            }
+            _ => {}
         }
     }
-
-    usages
+    found
 }
 
 /// Extract modifier usages from claim text
-fn extract_modifiers(text: &str) -> Vec<ModifierUsage> {
+fn extract_modifiers<'a>(text: &'a str, spec: &SpecConfig) -> Vec<ModifierUsage<'a>> {
     let mut usages = Vec::new();
-    let modifier_chars = ['^', '!', '?', '*'];
+    // Symbols attach to a term; letters (like 'v') only count standing alone
+    let attached_chars = spec.attached_modifiers();
+    let is_after_operator = |prev: &str| spec.brief_forms().iter().any(|(op, _)| prev.ends_with(op));
 
     // Split into tokens
     let tokens: Vec<&str> = text.split_whitespace().collect();
 
     for (i, token) in tokens.iter().enumerate() {
         // Check for attached modifiers (e.g., "concentration^", "collapse?")
-        for &m in &modifier_chars {
-            if token.ends_with(m) && token.len() > 1 {
+        for &m in &attached_chars {
+            if token.ends_with(m) && token.len() > m.len_utf8() {
                 let attached = token.trim_end_matches(m);
                 usages.push(ModifierUsage {
                     symbol: m,
-                    attached_to: attached.to_string(),
+                    attached_to: Cow::Borrowed(attached),
                 });
             }
         }
 
         // Check for standalone modifiers (e.g., "fast !" where ! is separate token)
         // These modify the preceding term
-        for &m in &modifier_chars {
-            if token.len() == 1 && token.chars().next() == Some(m) && i > 0 {
+        for &m in &attached_chars {
+            if is_symbol(token, m) && i > 0 {
                 let prev = tokens[i - 1];
                 // Don't count if previous token is an operator
-                let is_after_operator = BRIEF_FORMS.iter().any(|(op, _)| prev.ends_with(op));
-                if !is_after_operator {
+                if !is_after_operator(prev) {
                     usages.push(ModifierUsage {
                         symbol: m,
-                        attached_to: prev.trim_end_matches(|c| "^!?*v".contains(c)).to_string(),
+                        attached_to: Cow::Borrowed(prev.trim_end_matches(|c| spec.is_modifier(c))),
                     });
                 }
             }
         }
 
-        // Check for 'v' modifier - it's special because it's also a letter
-        // It's a modifier when: standalone 'v' following a term
-        if *token == "v" && i > 0 {
-            let prev = tokens[i - 1];
-            // Don't treat 'v' as modifier if previous token is an operator
-            let is_after_operator = BRIEF_FORMS.iter().any(|(op, _)| prev.ends_with(op));
-            if !is_after_operator {
-                usages.push(ModifierUsage {
-                    symbol: 'v',
-                    attached_to: prev.trim_end_matches(|c| "^!?*".contains(c)).to_string(),
-                });
+        // Letter modifiers ('v') are special because they are also letters:
+        // they are modifiers only as a standalone word following a term
+        for &(m, _) in spec.modifiers().iter().filter(|(m, _)| m.is_alphanumeric()) {
+            if is_symbol(token, m) && i > 0 {
+                let prev = tokens[i - 1];
+                // Don't treat 'v' as modifier if previous token is an operator
+                if !is_after_operator(prev) {
+                    usages.push(ModifierUsage {
+                        symbol: m,
+                        attached_to: Cow::Borrowed(prev.trim_end_matches(attached_chars.as_slice())),
+                    });
+                }
             }
         }
     }
@@ -537,41 +1499,58 @@ fn extract_modifiers(text: &str) -> Vec<ModifierUsage> {
     usages
 }
 
+/// Whether `token` is the single character `symbol`
+fn is_symbol(token: &str, symbol: char) -> bool {
+    let mut chars = token.chars();
+    chars.next() == Some(symbol) && chars.next().is_none()
+}
+
 /// Validate document structure
-fn validate_structure(lines: &[ParsedLine], errors: &mut Vec<ValidationError>) {
-    let mut current_concept: Option<(usize, String)> = None;
-    let mut current_facet: Option<(usize, String)> = None;
+fn validate_structure(lines: &[ParsedLine<'_>], errors: &mut Vec<ValidationError>) {
+    let mut current_concept: Option<(usize, &str)> = None;
+    let mut current_facet: Option<(usize, &str)> = None;
+    let mut current_sub_facet: Option<(usize, &str)> = None;
     let mut concept_has_facet = false;
     let mut facet_has_claim = false;
+    let mut sub_facet_has_claim = false;
 
     for line in lines {
         match &line.line_type {
-            LineType::Blank => continue,
-            LineType::Concept(name) => {
+            LineType::Blank
+            | LineType::Include(_)
+            | LineType::Version(_)
+            | LineType::SourceDefinition(_) => continue,
+            LineType::Concept(_) | LineType::SourcesSection => {
+                check_sub_facet_closed(current_sub_facet.take(), sub_facet_has_claim, errors);
                 // Check previous concept had facets
-                if let Some((concept_line, concept_name)) = current_concept.take() {
-                    if !concept_has_facet {
-                        errors.push(ValidationError::ConceptWithoutFacets {
-                            line: concept_line,
-                            concept: concept_name,
-                        });
-                    }
+                if let Some((concept_line, concept_name)) = current_concept.take()
+                    && !concept_has_facet
+                {
+                    errors.push(ValidationError::ConceptWithoutFacets {
+                        line: concept_line,
+                        concept: concept_name.to_string(),
+                    });
                 }
                 // Check previous facet had claims
-                if let Some((facet_line, facet_name)) = current_facet.take() {
-                    if !facet_has_claim {
-                        errors.push(ValidationError::FacetWithoutClaims {
-                            line: facet_line,
-                            facet: facet_name,
-                        });
-                    }
+                if let Some((facet_line, facet_name)) = current_facet.take()
+                    && !facet_has_claim
+                {
+                    errors.push(ValidationError::FacetWithoutClaims {
+                        line: facet_line,
+                        facet: facet_name.to_string(),
+                    });
                 }
-                current_concept = Some((line.line_number, name.clone()));
+                // The sources section ends the last concept without starting another
+                current_concept = match &line.line_type {
+                    LineType::Concept(name) => Some((line.line_number, name.as_ref())),
+                    _ => None,
+                };
                 current_facet = None;
                 concept_has_facet = false;
                 facet_has_claim = false;
             }
             LineType::Facet(name) => {
+                check_sub_facet_closed(current_sub_facet.take(), sub_facet_has_claim, errors);
                 if current_concept.is_none() {
                     errors.push(ValidationError::OrphanFacet {
                         line: line.line_number,
@@ -580,18 +1559,42 @@ fn validate_structure(lines: &[ParsedLine], errors: &mut Vec<ValidationError>) {
                     concept_has_facet = true;
                 }
                 // Check previous facet had claims
-                if let Some((facet_line, facet_name)) = current_facet.take() {
-                    if !facet_has_claim {
-                        errors.push(ValidationError::FacetWithoutClaims {
-                            line: facet_line,
-                            facet: facet_name,
-                        });
-                    }
+                if let Some((facet_line, facet_name)) = current_facet.take()
+                    && !facet_has_claim
+                {
+                    errors.push(ValidationError::FacetWithoutClaims {
+                        line: facet_line,
+                        facet: facet_name.to_string(),
+                    });
                 }
-                current_facet = Some((line.line_number, name.clone()));
+                current_facet = Some((line.line_number, name.as_ref()));
                 facet_has_claim = false;
             }
+            LineType::SubFacet(name) => {
+                check_sub_facet_closed(current_sub_facet.take(), sub_facet_has_claim, errors);
+                if current_facet.is_none() {
+                    errors.push(ValidationError::OrphanSubFacet {
+                        line: line.line_number,
+                    });
+                } else {
+                    // A facet holding sub-facets needs no direct claims
+                    facet_has_claim = true;
+                }
+                current_sub_facet = Some((line.line_number, name.as_ref()));
+                sub_facet_has_claim = false;
+            }
+            LineType::Claim(_) if indent_width(&line.raw) == 6 => {
+                if current_sub_facet.is_none() {
+                    errors.push(ValidationError::OrphanClaim {
+                        line: line.line_number,
+                    });
+                } else {
+                    sub_facet_has_claim = true;
+                }
+            }
             LineType::Claim(_) => {
+                // A 4-space claim belongs to the facet and ends any open sub-facet
+                check_sub_facet_closed(current_sub_facet.take(), sub_facet_has_claim, errors);
                 if current_facet.is_none() {
                     errors.push(ValidationError::OrphanClaim {
                         line: line.line_number,
@@ -603,39 +1606,69 @@ fn validate_structure(lines: &[ParsedLine], errors: &mut Vec<ValidationError>) {
         }
     }
 
+    check_sub_facet_closed(current_sub_facet, sub_facet_has_claim, errors);
+
     // Check final concept and facet
-    if let Some((concept_line, concept_name)) = current_concept {
-        if !concept_has_facet {
-            errors.push(ValidationError::ConceptWithoutFacets {
-                line: concept_line,
-                concept: concept_name,
-            });
-        }
+    if let Some((concept_line, concept_name)) = current_concept
+        && !concept_has_facet
+    {
+        errors.push(ValidationError::ConceptWithoutFacets {
+            line: concept_line,
+            concept: concept_name.to_string(),
+        });
     }
-    if let Some((facet_line, facet_name)) = current_facet {
-        if !facet_has_claim {
-            errors.push(ValidationError::FacetWithoutClaims {
-                line: facet_line,
-                facet: facet_name,
-            });
-        }
+    if let Some((facet_line, facet_name)) = current_facet
+        && !facet_has_claim
+    {
+        errors.push(ValidationError::FacetWithoutClaims {
+            line: facet_line,
+            facet: facet_name.to_string(),
+        });
     }
 }
 
-/// Collect all valid Concept.facet reference targets from the document
-fn collect_valid_references(lines: &[ParsedLine]) -> HashSet<String> {
+/// Report a sub-facet that was closed without any claims
+fn check_sub_facet_closed(
+    sub_facet: Option<(usize, &str)>,
+    has_claim: bool,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some((line, facet)) = sub_facet
+        && !has_claim
+    {
+        errors.push(ValidationError::FacetWithoutClaims {
+            line,
+            facet: facet.to_string(),
+        });
+    }
+}
+
+/// Collect all valid Concept.facet (and Concept.facet.sub-facet) reference targets from the document
+fn collect_valid_references(lines: &[ParsedLine<'_>]) -> HashSet<String> {
     let mut valid_refs = HashSet::new();
-    let mut current_concept: Option<String> = None;
+    // The current concept's name, then its aliases
+    let mut current_names: Vec<&str> = Vec::new();
+    let mut current_facet: Option<&str> = None;
 
     for line in lines {
         match &line.line_type {
             LineType::Concept(name) => {
-                current_concept = Some(name.clone());
+                current_names = vec![name];
+                current_names.extend(alias::aliases(&line.raw));
+                current_facet = None;
             }
             LineType::Facet(name) => {
-                if let Some(ref concept) = current_concept {
+                for concept in &current_names {
                     valid_refs.insert(format!("{}.{}", concept, name));
                 }
+                current_facet = Some(name);
+            }
+            LineType::SubFacet(name) => {
+                if let Some(facet) = current_facet {
+                    for concept in &current_names {
+                        valid_refs.insert(format!("{}.{}.{}", concept, facet, name));
+                    }
+                }
             }
             _ => {}
         }
@@ -649,6 +1682,7 @@ fn validate_claim_syntax(
     line_number: usize,
     claim: &ClaimData,
     valid_refs: &HashSet<String>,
+    spec: &SpecConfig,
     errors: &mut Vec<ValidationError>,
     warnings: &mut Vec<ValidationError>,
 ) {
@@ -657,10 +1691,22 @@ fn validate_claim_syntax(
         errors.push(ValidationError::EmptyClaimText { line: line_number });
     }
 
-    // Check for empty conditions
+    // Check for empty and repeated conditions
+    let mut seen_conditions = Vec::new();
     for cond in &claim.conditions {
         if cond.is_empty() {
             errors.push(ValidationError::EmptyCondition { line: line_number });
+            continue;
+        }
+        let parsed = Condition::parse(cond);
+        let key = (parsed.negated, parsed.text.to_lowercase());
+        if seen_conditions.contains(&key) {
+            warnings.push(ValidationError::DuplicateCondition {
+                line: line_number,
+                condition: cond.to_string(),
+            });
+        } else {
+            seen_conditions.push(key);
         }
     }
 
@@ -678,59 +1724,76 @@ fn validate_claim_syntax(
         }
     }
 
-    // Validate reference format (should be Concept.facet)
-    for reference in &claim.references {
-        if !reference.is_empty() && !reference.contains('.') {
+    // Validate reference format ([namespace:]Concept.facet) and that targets exist
+    for reference in claim.references.iter().filter(|r| !r.is_empty()) {
+        let Some(parsed) = Reference::parse(reference) else {
             errors.push(ValidationError::InvalidReferenceFormat {
                 line: line_number,
-                reference: reference.clone(),
+                reference: reference.to_string(),
             });
+            continue;
+        };
+        if valid_refs.contains(reference.as_ref()) {
+            continue;
         }
-    }
-
-    // Validate references point to existing concept.facet pairs
-    for reference in &claim.references {
-        if !reference.is_empty() && reference.contains('.') && !valid_refs.contains(reference) {
-            errors.push(ValidationError::UndefinedReference {
-                line: line_number,
-                reference: reference.clone(),
-            });
+        if let Some(namespace) = parsed.namespace {
+            let prefix = format!("{}{}", namespace, NAMESPACE_SEPARATOR);
+            if !valid_refs.iter().any(|target| target.starts_with(&prefix)) {
+                errors.push(ValidationError::UnknownNamespace { line: line_number, namespace });
+                continue;
+            }
         }
+        errors.push(ValidationError::UndefinedReference {
+            line: line_number,
+            reference: reference.to_string(),
+            suggestion: suggest::closest(reference, valid_refs.iter().map(String::as_str))
+                .map(str::to_string),
+        });
     }
 
     // Validate brief forms have operands
-    for bf in &claim.brief_forms {
-        if bf.left_operand.is_empty() {
-            errors.push(ValidationError::BriefFormMissingLeftOperand {
-                line: line_number,
-                operator: bf.operator.clone(),
-            });
-        }
-        if bf.right_operand.is_empty() {
-            errors.push(ValidationError::BriefFormMissingRightOperand {
-                line: line_number,
-                operator: bf.operator.clone(),
-            });
-        }
+    if let Some(ref expression) = claim.expression {
+        validate_expression(expression, line_number, errors);
+    }
+
+    if let Some(ref id) = claim.id
+        && !id::is_valid_id(id)
+    {
+        errors.push(ValidationError::InvalidClaimId { line: line_number, id: id.to_string() });
     }
 
-    // Check for unclosed evolution markers in original text
-    if claim.text.contains("[<=") && !claim.text.contains(']') {
+    // Complete markers were extracted, so any left in the text are unclosed
+    if claim.text.contains(EVOLUTION_OPEN) {
         errors.push(ValidationError::UnclosedEvolutionMarker { line: line_number });
     }
 
     // Validate evolution marker content if present
-    if let Some(ref evo) = claim.evolution {
-        if evo.prior_belief.is_empty() {
-            errors.push(ValidationError::EmptyEvolutionMarker { line: line_number });
+    if claim.evolution.iter().any(|evo| evo.prior_belief.is_empty()) {
+        errors.push(ValidationError::EmptyEvolutionMarker { line: line_number });
+    }
+    for evo in &claim.evolution {
+        if let (_, Some(date)) = date::split_date(&evo.prior_belief)
+            && let Err(reason) = date.parse::<Date>()
+        {
+            errors.push(ValidationError::InvalidEvolutionDate { line: line_number, reason });
         }
     }
 
+    // Operator-like symbols that aren't brief forms lose their meaning (warning, not error)
+    for operator in unknown_operators(&claim.text, spec) {
+        warnings.push(ValidationError::UnknownOperator {
+            line: line_number,
+            operator: operator.to_string(),
+            suggestion: suggest::operator(operator),
+        });
+    }
+
     // Check for standalone modifiers that appear at the start (warning, not error)
     // Space-separated modifiers that follow a term are valid (e.g., "fast !")
+    let attached_chars = spec.attached_modifiers();
     let tokens: Vec<&str> = claim.text.split_whitespace().collect();
     for (i, token) in tokens.iter().enumerate() {
-        if *token == "^" || *token == "!" || *token == "?" || *token == "*" {
+        if attached_chars.iter().any(|&m| is_symbol(token, m)) {
             // Only warn if it's at the start (no preceding term) or follows an operator
             if i == 0 {
                 warnings.push(ValidationError::StandaloneModifier {
@@ -739,7 +1802,7 @@ fn validate_claim_syntax(
                 });
             } else {
                 let prev = tokens[i - 1];
-                let is_after_operator = BRIEF_FORMS.iter().any(|(op, _)| prev.ends_with(op));
+                let is_after_operator = spec.brief_forms().iter().any(|(op, _)| prev.ends_with(op));
                 if is_after_operator {
                     warnings.push(ValidationError::StandaloneModifier {
                         line: line_number,
@@ -751,10 +1814,72 @@ fn validate_claim_syntax(
     }
 }
 
+/// Check every relation and every step of a chain has operands
+fn validate_expression(expression: &ClaimExpr, line_number: usize, errors: &mut Vec<ValidationError>) {
+    match expression {
+        ClaimExpr::Operand(_) => {}
+        ClaimExpr::Relation { operator, left, right } => {
+            validate_expression(left, line_number, errors);
+            if left.last_operand().text.is_empty() {
+                errors.push(ValidationError::BriefFormMissingLeftOperand {
+                    line: line_number,
+                    operator: operator.to_string(),
+                });
+            }
+            if right.first_operand().text.is_empty() {
+                errors.push(ValidationError::BriefFormMissingRightOperand {
+                    line: line_number,
+                    operator: operator.to_string(),
+                });
+            }
+            validate_expression(right, line_number, errors);
+        }
+        ClaimExpr::Chain { operator, operands } => {
+            for (i, operand) in operands.iter().enumerate() {
+                if operand.text.is_empty() {
+                    errors.push(ValidationError::ChainMissingOperand {
+                        line: line_number,
+                        operator: operator.to_string(),
+                        position: i + 1,
+                    });
+                }
+            }
+        }
+    }
+}
+
 /// Validate a file by path
-pub fn validate_file(path: &std::path::Path) -> Result<ValidationResult, std::io::Error> {
+#[cfg(not(target_arch = "wasm32"))]
+pub fn validate_file(path: &Path) -> Result<ValidationResult<'static>, std::io::Error> {
+    validate_file_with_options(path, &ValidationOptions::default())
+}
+
+/// Validate a file by path with the given options
+///
+/// `>include` directives are resolved relative to the file, and references into
+/// included files are accepted.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn validate_file_with_options(
+    path: &Path,
+    options: &ValidationOptions,
+) -> Result<ValidationResult<'static>, std::io::Error> {
+    let _span = trace::span!("validate_file", path = %path.display());
     let content = std::fs::read_to_string(path)?;
-    Ok(validate(&content))
+    Ok(validate_in_context(&content, path, options).into_owned())
+}
+
+/// Validates `input` as the contents of `path`, resolving includes relative to it
+///
+/// Useful for checking an in-memory edit before it is written to disk.
+pub fn validate_in_context<'a>(
+    input: &'a str,
+    path: &Path,
+    options: &ValidationOptions,
+) -> ValidationResult<'a> {
+    let resolution = include::resolve(input, path, options);
+    let mut result = validate_with_refs(input, options, &resolution.refs);
+    result.errors.extend(resolution.errors);
+    result
 }
 
 #[cfg(test)]
@@ -861,15 +1986,31 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                assert_eq!(claim.text, "requires consistency");
-                assert!(claim.conditions.contains(&"over time".to_string()));
-                assert!(claim.sources.contains(&"personal-experience".to_string()));
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            assert_eq!(claim.text, "requires consistency");
+            assert!(claim.conditions.contains(&"over time".into()));
+            assert!(claim.sources.contains(&"personal-experience".into()));
         }
     }
 
+    #[test]
+    fn test_empty_and_duplicate_conditions() {
+        let result = validate("Trust\n  .formation\n    - slow | @personal-experience\n");
+        assert_eq!(result.errors, [ValidationError::EmptyCondition { line: 3 }]);
+
+        let result = validate("Trust\n  .formation\n    - slow | over time | Over time | when unchecked | unchecked\n");
+        assert!(result.is_valid());
+        assert_eq!(
+            result.warnings,
+            [
+                ValidationError::DuplicateCondition { line: 3, condition: "Over time".into() },
+                ValidationError::DuplicateCondition { line: 3, condition: "unchecked".into() },
+            ]
+        );
+    }
+
     #[test]
     fn test_claim_with_reference() {
         let input = r#"Trust
@@ -882,15 +2023,10 @@ Trust
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
         // Find the claim with the reference (second claim)
-        let claims: Vec<_> = result.lines.iter()
-            .filter_map(|l| match &l.line_type {
-                LineType::Claim(c) => Some(c),
-                _ => None,
-            })
-            .collect();
-        
+        let claims: Vec<_> = result.claims().collect();
+
         assert!(claims.len() >= 2);
-        assert!(claims[1].references.contains(&"Trust.formation".to_string()));
+        assert!(claims[1].references.contains(&"Trust.formation".into()));
     }
 
     #[test]
@@ -913,6 +2049,35 @@ Trust
         assert!(result.errors.iter().any(|e| matches!(e, ValidationError::UndefinedReference { .. })));
     }
 
+    #[test]
+    fn test_undefined_reference_suggestion() {
+        let input = "Trust\n  .formation\n    - slow\n  .erosion\n    - fast &Trust.formaton\n";
+        let result = validate(input);
+        let error = &result.errors[0];
+        assert_eq!(
+            *error,
+            ValidationError::UndefinedReference {
+                line: 5,
+                reference: "Trust.formaton".to_string(),
+                suggestion: Some("Trust.formation".to_string()),
+            }
+        );
+        assert!(error.to_string().ends_with("did you mean '&Trust.formation'?"));
+        assert_eq!(error.code(), "undefined-reference");
+        assert!(error.description().starts_with("References point at"));
+        let line = "    - fast &Trust.formaton";
+        assert_eq!(&line[error.span(line)], "&Trust.formaton");
+    }
+
+    #[test]
+    fn test_error_span_falls_back_to_line_content() {
+        let line = "  formation  ";
+        let error = ValidationError::MissingFacetPrefix { line: 2 };
+        assert_eq!(&line[error.span(line)], "formation");
+        let error = ValidationError::TrailingWhitespace { line: 2 };
+        assert_eq!(error.span(line), 11..13);
+    }
+
     #[test]
     fn test_valid_cross_reference() {
         // References between different concepts should work
@@ -938,14 +2103,14 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                assert!(!claim.brief_forms.is_empty());
-                let bf = &claim.brief_forms[0];
-                assert_eq!(bf.operator, "=>");
-                assert_eq!(bf.left_operand, "power");
-                assert_eq!(bf.right_operand, "corruption");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            assert!(!claim.brief_forms.is_empty());
+            let bf = &claim.brief_forms[0];
+            assert_eq!(bf.operator, "=>");
+            assert_eq!(bf.left_operand, "power");
+            assert_eq!(bf.right_operand, "corruption");
         }
     }
 
@@ -958,11 +2123,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let bf = claim.brief_forms.iter().find(|b| b.operator == "~");
-                assert!(bf.is_some(), "Expected ~ operator");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let bf = claim.brief_forms.iter().find(|b| b.operator == "~");
+            assert!(bf.is_some(), "Expected ~ operator");
         }
     }
 
@@ -975,11 +2140,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let bf = claim.brief_forms.iter().find(|b| b.operator == "vs");
-                assert!(bf.is_some(), "Expected vs operator");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let bf = claim.brief_forms.iter().find(|b| b.operator == "vs");
+            assert!(bf.is_some(), "Expected vs operator");
         }
     }
 
@@ -1005,6 +2170,31 @@ Trust
         assert!(result.errors.iter().any(|e| matches!(e, ValidationError::BriefFormMissingRightOperand { .. })));
     }
 
+    #[test]
+    fn test_chained_relation() {
+        let result = validate("Power\n  .core\n    - power => corruption => distrust\n");
+        assert!(result.is_valid());
+
+        let claim = parse_claim_body("power => corruption => distrust", SpecConfig::builtin());
+        let Some(ClaimExpr::Chain { operator, operands }) = &claim.expression else {
+            panic!("expected a chain, got {:?}", claim.expression);
+        };
+        assert_eq!(*operator, "=>");
+        assert_eq!(operands.len(), 3);
+        assert_eq!(claim.brief_forms.len(), 2);
+        assert_eq!(claim.brief_forms[1].left_operand, "corruption");
+    }
+
+    #[test]
+    fn test_chain_missing_operand() {
+        let result = validate("Power\n  .core\n    - power => => distrust\n");
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::ChainMissingOperand { position: 2, .. }
+        )));
+        assert!(!result.errors.iter().any(|e| matches!(e, ValidationError::BriefFormMissingLeftOperand { .. })));
+    }
+
     // ==================== Modifier tests ====================
 
     #[test]
@@ -1016,12 +2206,12 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let m = claim.modifiers.iter().find(|m| m.symbol == '^');
-                assert!(m.is_some(), "Expected ^ modifier");
-                assert_eq!(m.unwrap().attached_to, "concentration");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let m = claim.modifiers.iter().find(|m| m.symbol == '^');
+            assert!(m.is_some(), "Expected ^ modifier");
+            assert_eq!(m.unwrap().attached_to, "concentration");
         }
     }
 
@@ -1034,11 +2224,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let m = claim.modifiers.iter().find(|m| m.symbol == 'v');
-                assert!(m.is_some(), "Expected v modifier");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let m = claim.modifiers.iter().find(|m| m.symbol == 'v');
+            assert!(m.is_some(), "Expected v modifier");
         }
     }
 
@@ -1051,11 +2241,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let m = claim.modifiers.iter().find(|m| m.symbol == '!');
-                assert!(m.is_some(), "Expected ! modifier");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let m = claim.modifiers.iter().find(|m| m.symbol == '!');
+            assert!(m.is_some(), "Expected ! modifier");
         }
     }
 
@@ -1068,12 +2258,12 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let m = claim.modifiers.iter().find(|m| m.symbol == '?');
-                assert!(m.is_some(), "Expected ? modifier");
-                assert_eq!(m.unwrap().attached_to, "free-will");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let m = claim.modifiers.iter().find(|m| m.symbol == '?');
+            assert!(m.is_some(), "Expected ? modifier");
+            assert_eq!(m.unwrap().attached_to, "free-will");
         }
     }
 
@@ -1086,11 +2276,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                let m = claim.modifiers.iter().find(|m| m.symbol == '*');
-                assert!(m.is_some(), "Expected * modifier");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            let m = claim.modifiers.iter().find(|m| m.symbol == '*');
+            assert!(m.is_some(), "Expected * modifier");
         }
     }
 
@@ -1121,6 +2311,21 @@ Trust
         assert!(!result.has_warnings(), "Expected no warnings: {:?}", result.warnings);
     }
 
+    #[test]
+    fn test_unknown_operator_warnings() {
+        let input = "Power\n  .core\n    - power -> corruption\n    - authority != influence\n    - trust→collapse\n    - self-preserving -- mostly !!\n    - a => b ~ c\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+        assert_eq!(
+            result.warnings,
+            [
+                ValidationError::UnknownOperator { line: 3, operator: "->".into(), suggestion: "=>" },
+                ValidationError::UnknownOperator { line: 4, operator: "!=".into(), suggestion: "vs" },
+                ValidationError::UnknownOperator { line: 5, operator: "→".into(), suggestion: "=>" },
+            ]
+        );
+    }
+
     // ==================== Evolution marker tests ====================
 
     #[test]
@@ -1132,11 +2337,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                assert!(claim.evolution.is_some(), "Expected evolution marker");
-                assert_eq!(claim.evolution.as_ref().unwrap().prior_belief, "inherently good");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            assert!(!claim.evolution.is_empty(), "Expected evolution marker");
+            assert_eq!(claim.evolution[0].prior_belief, "inherently good");
         }
     }
 
@@ -1149,11 +2354,11 @@ Trust
         let result = validate(input);
         assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
 
-        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_))) {
-            if let LineType::Claim(claim) = &line.line_type {
-                assert!(claim.evolution.is_some());
-                assert_eq!(claim.evolution.as_ref().unwrap().prior_belief, "rational actor");
-            }
+        if let Some(line) = result.lines.iter().find(|l| matches!(l.line_type, LineType::Claim(_)))
+            && let LineType::Claim(claim) = &line.line_type
+        {
+            assert!(!claim.evolution.is_empty());
+            assert_eq!(claim.evolution[0].prior_belief, "rational actor");
         }
     }
 
@@ -1179,6 +2384,258 @@ Trust
         assert!(result.errors.iter().any(|e| matches!(e, ValidationError::EmptyEvolutionMarker { .. })));
     }
 
+    #[test]
+    fn test_dated_evolution_marker() {
+        let claim = parse_claim("bounded [<= rational actor :2022-03] [<= perfectly rational]");
+        assert_eq!(claim.evolution[0].prior_belief, "rational actor");
+        assert_eq!(claim.evolution[0].changed_at.unwrap().to_string(), "2022-03");
+        assert_eq!(claim.evolution[1].changed_at, None);
+
+        let result = validate("Human-nature\n  .cognition\n    - bounded [<= rational actor :2022-13]\n");
+        assert!(matches!(
+            result.errors.as_slice(),
+            [ValidationError::InvalidEvolutionDate { line: 3, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_evolution_marker_mid_text_keeps_words_apart() {
+        let (text, markers) = extract_evolution_markers("adaptive [<= inherently good] mostly");
+        assert_eq!(text, "adaptive mostly");
+        assert_eq!(markers[0].prior_belief, "inherently good");
+    }
+
+    // ==================== Tab indentation tests ====================
+
+    #[test]
+    fn test_tab_indentation() {
+        let input = "Power\n\t.core\n\t\t- corrupts\n  \t- mixed\n";
+        let result = validate(input);
+        assert_eq!(
+            result.errors,
+            [2, 3, 4].map(|line| ValidationError::TabIndentation { line })
+        );
+        // Lines are still parsed at one level per tab
+        assert!(matches!(result.lines[2].line_type, LineType::Claim(_)));
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("Power\n\t.core\n\t\t- a\tb\n", 2), "Power\n  .core\n    - a\tb\n");
+        assert_eq!(expand_tabs("Power\n\t\t.core\n", 1), "Power\n  .core\n");
+        assert!(matches!(expand_tabs("Power\n  .core\n", 2), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_missing_prefixes_report_only_root_cause() {
+        let input = "Power\n  core\n    corrupts\n    - reveals character\n";
+        let result = validate(input);
+        assert_eq!(
+            result.errors,
+            [
+                ValidationError::MissingFacetPrefix { line: 2 },
+                ValidationError::MissingClaimPrefix { line: 3 },
+            ]
+        );
+    }
+
+    // ==================== Whitespace tests ====================
+
+    #[test]
+    fn test_byte_order_mark_and_crlf_ignored_by_default() {
+        let input = "\u{FEFF}Power\r\n  .core  \r\n    - corrupts &Power.core\r\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+        assert_eq!(result.lines[0].line_type, LineType::Concept("Power".into()));
+    }
+
+    #[test]
+    fn test_strict_whitespace() {
+        let input = "\u{FEFF}Power\r\n  .core  \r\n    - corrupts\t\n\n";
+        let options = ValidationOptions {
+            strict_whitespace: true,
+            ..ValidationOptions::default()
+        };
+        let result = validate_with_options(input, &options);
+        assert_eq!(
+            result.errors,
+            [
+                ValidationError::ByteOrderMark { line: 1 },
+                ValidationError::TrailingWhitespace { line: 2 },
+                ValidationError::TrailingWhitespace { line: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_result_iterators() {
+        let input = "Power\n  .nature\n    - corrupts\n    - corrupts\n    - checks &Trust.limits\n";
+        let result = validate(input);
+        let claims: Vec<&str> = result.claims().map(|c| c.text.as_ref()).collect();
+        assert_eq!(claims, ["corrupts", "corrupts", "checks"]);
+        let codes: Vec<&str> = result.errors_for_line(5).map(|e| e.code()).collect();
+        assert_eq!(codes, ["undefined-reference"]);
+        assert_eq!(result.errors_for_line(3).count(), 0);
+    }
+
+    #[test]
+    fn test_diagnostics_in_range() {
+        let input = "Power\n  .core\n    - corrupts &Trust.limits\n    - corrupts\n\nTrust\n  .core\n  .erosion\n    - fast\n";
+        let result = validate(input);
+        fn found(result: &ValidationResult<'_>, lines: impl RangeBounds<usize>) -> Vec<(usize, &'static str)> {
+            result.diagnostics_in_range(lines).iter().map(|d| (d.line(), d.code())).collect()
+        }
+        assert_eq!(found(&result, 1..=4), [(3, "undefined-reference"), (4, "duplicate-claim")]);
+        assert_eq!(found(&result, 7..), [(7, "facet-without-claims")]);
+        assert_eq!(found(&result, 5..7), []);
+
+        // Limited to a viewport, the rest of the document still resolves references
+        let options = ValidationOptions {
+            lines: Some(4..=9),
+            ..ValidationOptions::default()
+        };
+        let result = validate_with_options(input, &options);
+        let codes: Vec<&str> = result.errors.iter().chain(&result.warnings).map(|d| d.code()).collect();
+        assert_eq!(codes, ["facet-without-claims", "duplicate-claim"]);
+    }
+
+    // ==================== Unicode tests ====================
+
+    #[test]
+    fn test_unicode_brief_form_operands() {
+        let input = "Experience\n  .growth\n    - naïveté => regret?\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+
+        let claim = parse_claim("naïveté => regret?");
+        assert_eq!(claim.brief_forms.len(), 1);
+        assert_eq!(claim.brief_forms[0].left_operand, "naïveté");
+        assert_eq!(claim.brief_forms[0].right_operand, "regret");
+        assert_eq!(claim.modifiers[0].attached_to, "regret");
+    }
+
+    #[test]
+    fn test_cjk_concepts_and_references() {
+        let input = "信頼\n  .形成\n    - 遅い [<= 速い]\n  .崩壊\n    - 速い ! &信頼.形成\u{3000}一度\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+
+        let claim = parse_claim("速い ! &信頼.形成\u{3000}一度");
+        assert_eq!(claim.text, "速い !");
+        assert_eq!(claim.references, ["信頼.形成"]);
+        assert_eq!(parse_claim("遅い [<= 速い]").evolution[0].prior_belief, "速い");
+    }
+
+    #[test]
+    fn test_combining_mark_is_not_an_operator() {
+        // `=` + U+0338 renders as `≠`
+        let claim = parse_claim("a =\u{338} b vs c");
+        assert_eq!(claim.brief_forms.len(), 1);
+        assert_eq!(claim.brief_forms[0].operator, "vs");
+    }
+
+    #[test]
+    fn test_unexpected_control_character() {
+        let input = "Power\n  .core\n    - rings a bell\u{7}\n";
+        let result = validate(input);
+        assert_eq!(
+            result.errors,
+            [ValidationError::UnexpectedControlCharacter { line: 3, character: '\u{7}' }]
+        );
+
+        assert!(validate("Power\r\n  .core\r\n    - corrupts\r\n").is_valid());
+    }
+
+    // ==================== Nested facet tests ====================
+
+    #[test]
+    fn test_nested_facets_valid() {
+        let input = r#"Economics
+  .markets
+    .labor
+      - wages sticky v | recession
+    .capital
+      - mobile ! &Economics.markets.labor
+  .policy
+    - tradeoffs everywhere"#;
+
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+        assert!(result.lines.iter().any(|l| l.line_type == LineType::SubFacet("labor".into())));
+    }
+
+    #[test]
+    fn test_nested_facets_disabled_by_default() {
+        let input = r#"Economics
+  .markets
+    .labor
+      - wages sticky"#;
+
+        let result = validate(input);
+        assert!(!result.is_valid());
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::MissingClaimPrefix { .. })));
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::UnexpectedIndentation { found: 6, .. })));
+    }
+
+    #[test]
+    fn test_sub_facet_without_claims() {
+        let input = r#"Economics
+  .markets
+    .labor
+    .capital
+      - mobile"#;
+
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::FacetWithoutClaims { facet, .. } if facet == "labor"
+        )));
+    }
+
+    #[test]
+    fn test_deep_claim_without_sub_facet() {
+        let input = r#"Economics
+  .markets
+      - wages sticky"#;
+
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert!(result.errors.iter().any(|e| matches!(e, ValidationError::OrphanClaim { .. })));
+    }
+
+    // ==================== Borrowing tests ====================
+
+    #[test]
+    fn test_parsed_lines_borrow_from_input() {
+        let input = "Power\n  .core\n    - power => corruption | unchecked @history &Power.core [<= benign]";
+        let result = validate(input);
+        assert!(result.is_valid());
+        assert!(matches!(result.lines[0].raw, Cow::Borrowed(_)));
+        let LineType::Claim(claim) = &result.lines[2].line_type else {
+            panic!("expected claim");
+        };
+        assert!(matches!(claim.text, Cow::Borrowed("power => corruption")));
+        assert!(matches!(claim.conditions[0], Cow::Borrowed("unchecked")));
+        assert!(matches!(claim.brief_forms[0].left_operand, Cow::Borrowed("power")));
+        assert!(matches!(
+            claim.evolution[0].prior_belief,
+            Cow::Borrowed("benign")
+        ));
+    }
+
+    #[test]
+    fn test_into_owned_outlives_input() {
+        let owned = {
+            let input = String::from("Power\n  .core\n    - corrupts ! | unchecked");
+            validate(&input).into_owned()
+        };
+        assert!(owned.is_valid());
+        let LineType::Claim(claim) = &owned.lines[2].line_type else {
+            panic!("expected claim");
+        };
+        assert_eq!(claim.text, "corrupts !");
+        assert_eq!(claim.modifiers[0].attached_to, "corrupts");
+    }
+
     // ==================== Full document tests ====================
 
     #[test]
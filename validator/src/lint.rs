@@ -0,0 +1,590 @@
+//! Document-wide semantic lints
+//!
+//! These look across claims rather than at one line, and report warnings: a
+//! worldview may hold tensions deliberately, but they should be visible.
+
+use std::collections::HashMap;
+
+use crate::{CLAIM_INDENT, Condition, LineType, ParsedLine, SpecConfig, ValidationError, indent_width};
+
+/// Operator pairs that contradict each other for the same operands
+const CONTRADICTIONS: &[(&str, &str)] = &[("vs", "="), ("vs", "~"), ("vs", "=>")];
+
+fn contradicts(a: &str, b: &str) -> bool {
+    CONTRADICTIONS
+        .iter()
+        .any(|&(x, y)| (x == a && y == b) || (x == b && y == a))
+}
+
+/// Flag operand pairs related by contradictory operators in different claims
+///
+/// Operands match case-insensitively and in either order, so `trust => power`
+/// conflicts with `Power vs trust`. Each later claim is reported once, against
+/// the first claim it contradicts.
+pub(crate) fn contradictory_relations(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    let mut seen: HashMap<(String, String), Vec<(&'static str, usize)>> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for line in lines {
+        let LineType::Claim(claim) = &line.line_type else {
+            continue;
+        };
+        for bf in &claim.brief_forms {
+            if bf.left_operand.is_empty() || bf.right_operand.is_empty() {
+                continue;
+            }
+            let left = bf.left_operand.to_lowercase();
+            let right = bf.right_operand.to_lowercase();
+            let key = if left <= right {
+                (left, right)
+            } else {
+                (right, left)
+            };
+            let earlier = seen.entry(key).or_default();
+            if let Some(&(operator, other_line)) = earlier
+                .iter()
+                .find(|(op, other)| *other != line.line_number && contradicts(op, bf.operator))
+            {
+                warnings.push(ValidationError::ContradictoryRelation {
+                    line: line.line_number,
+                    left: bf.left_operand.to_string(),
+                    right: bf.right_operand.to_string(),
+                    operator: bf.operator,
+                    other_operator: operator,
+                    other_line,
+                });
+            }
+            earlier.push((bf.operator, line.line_number));
+        }
+    }
+    warnings
+}
+
+/// Claim text reduced to what it asserts, to recognize one claim written two ways
+///
+/// Lowercases, collapses whitespace, and strips modifiers, attached
+/// (`slowly!`) or standing alone (`slowly !`), so `Trust forms slowly` and
+/// `trust forms slowly !` normalize alike. Operators and word order are kept.
+pub fn normalize_claim(text: &str) -> String {
+    let spec = SpecConfig::builtin();
+    let attached = spec.attached_modifiers();
+    let mut words: Vec<String> = Vec::new();
+    for token in text.split_whitespace() {
+        let word = token.trim_end_matches(attached.as_slice());
+        // A letter modifier (`v`) only counts standing alone after a term
+        let letter_modifier = word.chars().count() == 1
+            && word.chars().all(|c| c.is_alphanumeric() && spec.is_modifier(c))
+            && words.last().is_some_and(|prev| spec.operator(prev).is_none());
+        if !word.is_empty() && !letter_modifier {
+            words.push(word.to_lowercase());
+        }
+    }
+    words.join(" ")
+}
+
+/// Words too common to tell two phrasings of a claim apart
+const FILLER_WORDS: &[&str] = &["a", "an", "the", "is", "are", "be", "to", "of"];
+
+/// Stemmed words of normalized claim text, without filler words, in order
+pub(crate) fn content_words(normalized: &str) -> Vec<String> {
+    normalized
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty() && !FILLER_WORDS.contains(word))
+        .map(|word| stem(word).to_string())
+        .collect()
+}
+
+/// Flag claims that repeat an earlier claim in the same facet
+///
+/// Claims are compared by `normalize_claim`, which ignores modifiers, case,
+/// and spacing but keeps word order and operators. With `fuzzy`, plain claims
+/// (words only, no operators) also match when they have the same words in any
+/// order, ignoring filler words and plural or tense endings, so `consistency
+/// is required` repeats `requires consistency`, and so does `consistency
+/// requires`. Conditions and sources are not compared.
+pub(crate) fn duplicate_claims(lines: &[ParsedLine<'_>], fuzzy: bool) -> Vec<ValidationError> {
+    let mut seen: HashMap<Vec<String>, usize> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    for line in lines {
+        match &line.line_type {
+            LineType::Concept(_) | LineType::Facet(_) | LineType::SubFacet(_) => seen.clear(),
+            LineType::Claim(claim) => {
+                let normalized = normalize_claim(&claim.text);
+                let plain = normalized
+                    .split_whitespace()
+                    .all(|word| word.chars().any(char::is_alphanumeric));
+                let key = if fuzzy && plain && claim.brief_forms.is_empty() {
+                    let mut words = content_words(&normalized);
+                    words.sort();
+                    words.dedup();
+                    words
+                } else if normalized.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![normalized]
+                };
+                if key.is_empty() {
+                    continue;
+                }
+                match seen.get(&key) {
+                    Some(&other_line) => warnings.push(ValidationError::DuplicateClaim {
+                        line: line.line_number,
+                        other_line,
+                    }),
+                    None => {
+                        seen.insert(key, line.line_number);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+/// How much a single claim may hold before it should be split
+///
+/// `None` turns a limit off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimLimits {
+    pub max_brief_forms: Option<usize>,
+    pub max_conditions: Option<usize>,
+    /// Characters of claim text, not counting conditions, sources, or references
+    pub max_length: Option<usize>,
+}
+
+impl Default for ClaimLimits {
+    fn default() -> Self {
+        ClaimLimits {
+            max_brief_forms: Some(2),
+            max_conditions: Some(3),
+            max_length: Some(120),
+        }
+    }
+}
+
+impl ClaimLimits {
+    /// No limits at all
+    pub fn none() -> Self {
+        ClaimLimits {
+            max_brief_forms: None,
+            max_conditions: None,
+            max_length: None,
+        }
+    }
+}
+
+/// Flag claims that pack in more than `limits` allow
+///
+/// Conditions are counted once however often they repeat, since repeats are
+/// reported on their own.
+pub(crate) fn complex_claims(lines: &[ParsedLine<'_>], limits: &ClaimLimits) -> Vec<ValidationError> {
+    let mut warnings = Vec::new();
+    for line in lines {
+        let LineType::Claim(claim) = &line.line_type else {
+            continue;
+        };
+        let mut conditions: Vec<(bool, String)> = claim
+            .conditions
+            .iter()
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                let parsed = Condition::parse(c);
+                (parsed.negated, parsed.text.to_lowercase())
+            })
+            .collect();
+        conditions.sort();
+        conditions.dedup();
+        let measures = [
+            (claim.brief_forms.len(), limits.max_brief_forms, "brief forms"),
+            (conditions.len(), limits.max_conditions, "conditions"),
+            (claim.text.chars().count(), limits.max_length, "characters"),
+        ];
+        let over: Vec<String> = measures
+            .into_iter()
+            .filter_map(|(count, max, what)| {
+                max.filter(|&max| count > max)
+                    .map(|max| format!("{} {} (at most {})", count, what, max))
+            })
+            .collect();
+        if !over.is_empty() {
+            warnings.push(ValidationError::ClaimTooComplex {
+                line: line.line_number,
+                reason: over.join(", "),
+            });
+        }
+    }
+    warnings
+}
+
+/// A facet or sub-facet being counted: its line, `Concept.facet` path, and claims so far
+type Group = (usize, String, usize);
+
+/// Flag facets and sub-facets holding more than `max` claims
+///
+/// Only a group's own claims count, so grouping them into sub-facets clears
+/// the warning.
+pub(crate) fn large_facets(lines: &[ParsedLine<'_>], max: Option<usize>) -> Vec<ValidationError> {
+    let mut warnings = Vec::new();
+    let Some(max) = max else {
+        return warnings;
+    };
+    let mut report = |group: Option<Group>| {
+        if let Some((line, facet, count)) = group
+            && count > max
+        {
+            warnings.push(ValidationError::FacetTooLarge { line, facet, count, max });
+        }
+    };
+
+    let mut concept = "";
+    let mut facet: Option<Group> = None;
+    let mut sub_facet: Option<Group> = None;
+    for line in lines {
+        match &line.line_type {
+            LineType::Concept(name) => {
+                report(sub_facet.take());
+                report(facet.take());
+                concept = name;
+            }
+            LineType::Facet(name) => {
+                report(sub_facet.take());
+                report(facet.take());
+                facet = Some((line.line_number, format!("{}.{}", concept, name), 0));
+            }
+            LineType::SubFacet(name) => {
+                report(sub_facet.take());
+                if let Some((_, path, _)) = &facet {
+                    sub_facet = Some((line.line_number, format!("{}.{}", path, name), 0));
+                }
+            }
+            LineType::Claim(_) => {
+                let group = match sub_facet {
+                    Some(ref mut group) if indent_width(&line.raw) > CLAIM_INDENT => Some(group),
+                    _ => facet.as_mut(),
+                };
+                if let Some((_, _, count)) = group {
+                    *count += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    report(sub_facet);
+    report(facet);
+    warnings.sort_by_key(ValidationError::line);
+    warnings
+}
+
+/// `requires`, `required`, `requiring`, `require` → `requir`
+fn stem(word: &str) -> &str {
+    let stripped = ["ing", "ed", "es", "s"]
+        .iter()
+        .find_map(|suffix| word.strip_suffix(suffix))
+        .filter(|rest| rest.len() >= 3 && !word.ends_with("ss"))
+        .unwrap_or(word);
+    match stripped.strip_suffix('e') {
+        Some(rest) if rest.len() >= 3 => rest,
+        _ => stripped,
+    }
+}
+
+/// Flag unsourced claims in scopes that require sources
+///
+/// A scope is `Concept`, `Concept.facet` (covering its sub-facets), or
+/// `*.facet` for that facet in any concept.
+pub(crate) fn missing_sources(lines: &[ParsedLine<'_>], scopes: &[String]) -> Vec<ValidationError> {
+    let mut warnings = Vec::new();
+    if scopes.is_empty() {
+        return warnings;
+    }
+
+    let mut concept = "";
+    let mut facet = String::new();
+    let mut sub_facet: Option<String> = None;
+    for line in lines {
+        match &line.line_type {
+            LineType::Concept(name) => {
+                concept = name;
+                facet.clear();
+                sub_facet = None;
+            }
+            LineType::Facet(name) => {
+                facet = name.to_string();
+                sub_facet = None;
+            }
+            LineType::SubFacet(name) => sub_facet = Some(format!("{}.{}", facet, name)),
+            LineType::Claim(claim) if claim.sources.is_empty() => {
+                let path = match sub_facet {
+                    Some(ref path) if indent_width(&line.raw) > CLAIM_INDENT => path,
+                    _ => &facet,
+                };
+                if let Some(scope) = scopes.iter().find(|scope| in_scope(scope, concept, path)) {
+                    warnings.push(ValidationError::MissingSource {
+                        line: line.line_number,
+                        scope: scope.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+fn in_scope(scope: &str, concept: &str, facet: &str) -> bool {
+    let Some((scope_concept, scope_facet)) = scope.split_once('.') else {
+        return scope == concept;
+    };
+    (scope_concept == "*" || scope_concept == concept)
+        && facet
+            .strip_prefix(scope_facet)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ValidationError, ValidationOptions, normalize_claim, validate, validate_with_options};
+
+    #[test]
+    fn test_claim_too_complex() {
+        let input = "\
+Power
+  .nature
+    - trust => power ~ control vs fear
+    - concentrates | unchecked | scarce | urgent | opaque | unchecked
+    - corrupts
+";
+        let result = validate(input);
+        assert!(result.is_valid());
+        assert_eq!(
+            result.warnings,
+            [
+                // The repeat is reported, and counted once
+                ValidationError::DuplicateCondition {
+                    line: 4,
+                    condition: "unchecked".to_string(),
+                },
+                ValidationError::ClaimTooComplex {
+                    line: 3,
+                    reason: "3 brief forms (at most 2)".to_string(),
+                },
+                ValidationError::ClaimTooComplex {
+                    line: 4,
+                    reason: "4 conditions (at most 3)".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            result.warnings[1].to_string(),
+            "line 3: claim packs too much: 3 brief forms (at most 2); split it into several claims"
+        );
+
+        let long = format!("Power\n  .nature\n    - {}\n", "corrupts ".repeat(20));
+        let options = ValidationOptions {
+            claim_limits: crate::ClaimLimits {
+                max_length: Some(200),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(validate(&long).has_warnings());
+        assert!(!validate_with_options(&long, &options).has_warnings());
+        let off = ValidationOptions {
+            claim_limits: crate::ClaimLimits::none(),
+            ..Default::default()
+        };
+        let result = validate_with_options(input, &off);
+        assert!(result.warnings.iter().all(|w| w.code() != "claim-too-complex"));
+    }
+
+    #[test]
+    fn test_large_facets() {
+        let claims = |n: usize, indent: &str| -> String {
+            (0..n).map(|i| format!("{}- belief {}\n", indent, i)).collect()
+        };
+        let input = format!(
+            "Power\n  .core\n{}  .limits\n{}    .checks\n{}  .scope\n{}",
+            claims(4, "    "),
+            claims(2, "    "),
+            claims(4, "      "),
+            claims(3, "    "),
+        );
+        assert!(!validate_with_options(&input, &ValidationOptions::nested()).has_warnings());
+
+        let options = ValidationOptions {
+            nested_facets: true,
+            max_facet_claims: Some(3),
+            ..Default::default()
+        };
+        let result = validate_with_options(&input, &options);
+        assert_eq!(
+            result.warnings,
+            [
+                ValidationError::FacetTooLarge {
+                    line: 2,
+                    facet: "Power.core".to_string(),
+                    count: 4,
+                    max: 3,
+                },
+                ValidationError::FacetTooLarge {
+                    line: 10,
+                    facet: "Power.limits.checks".to_string(),
+                    count: 4,
+                    max: 3,
+                },
+            ]
+        );
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "line 2: facet 'Power.core' has 4 claims (more than 3); group them into sub-facets"
+        );
+    }
+
+    #[test]
+    fn test_contradictory_relation() {
+        let input = "Power\n  .nature\n    - trust => power\n  .limits\n    - Power vs trust\n";
+        let result = validate(input);
+        assert!(result.is_valid());
+        assert_eq!(
+            result.warnings,
+            [ValidationError::ContradictoryRelation {
+                line: 5,
+                left: "Power".to_string(),
+                right: "trust".to_string(),
+                operator: "vs",
+                other_operator: "=>",
+                other_line: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_consistent_relations_are_not_flagged() {
+        let input =
+            "Power\n  .nature\n    - trust => power\n    - power => trust\n    - trust ~ power\n";
+        assert!(!validate(input).has_warnings());
+    }
+
+    #[test]
+    fn test_duplicate_claim() {
+        let input = "Trust\n  .formation\n    - requires consistency\n    - Requires  consistency | over time\n    - consistency is required\n    - trust => power\n    - power => trust\n  .erosion\n    - requires consistency\n";
+        let result = validate(input);
+        assert!(result.is_valid());
+        assert_eq!(
+            result.warnings,
+            [ValidationError::DuplicateClaim {
+                line: 4,
+                other_line: 3
+            }]
+        );
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "line 4: claim repeats the claim on line 3"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_claim_keeps_order_and_operators() {
+        let input = "Trust\n  .formation\n    - power precedes trust\n    - trust precedes power\n    - slowed\n    - slow\n    - a != b\n    - a -> b\n";
+        let result = validate(input);
+        assert!(
+            !result.warnings.iter().any(|w| w.code() == "duplicate-claim"),
+            "{:?}",
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_duplicate_claim() {
+        let input = "Trust\n  .formation\n    - requires consistency\n    - consistency is required | over time\n    - trust => power\n    - power => trust\n    - a != b\n    - a -> b\n";
+        let options = ValidationOptions {
+            fuzzy_duplicates: true,
+            ..ValidationOptions::default()
+        };
+        let duplicates: Vec<ValidationError> = validate_with_options(input, &options)
+            .warnings
+            .into_iter()
+            .filter(|w| w.code() == "duplicate-claim")
+            .collect();
+        assert_eq!(
+            duplicates,
+            [ValidationError::DuplicateClaim {
+                line: 4,
+                other_line: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn test_normalize_claim() {
+        assert_eq!(normalize_claim("Trust  forms slowly !"), "trust forms slowly");
+        assert_eq!(normalize_claim("trust forms slowly"), "trust forms slowly");
+        assert_eq!(normalize_claim("Power^ => Corruption?"), "power => corruption");
+        assert_eq!(normalize_claim("influence v"), "influence");
+        // `v` is a word, not a modifier, after an operator
+        assert_eq!(normalize_claim("a => v"), "a => v");
+    }
+
+    #[test]
+    fn test_duplicate_claim_ignores_modifiers() {
+        let input = "Trust\n  .formation\n    - Trust forms slowly\n    - trust forms slowly !\n    - power^ => corruption\n    - Power => corruption*\n";
+        let result = validate(input);
+        assert_eq!(
+            result.warnings,
+            [
+                ValidationError::DuplicateClaim {
+                    line: 4,
+                    other_line: 3
+                },
+                ValidationError::DuplicateClaim {
+                    line: 6,
+                    other_line: 5
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_source() {
+        let input = "Science\n  .method\n    - falsifiable\n    - replicable @popper\nPower\n  .empirical\n    - corrupts\n  .nature\n    - reveals character\n";
+        let options = ValidationOptions {
+            require_sources: vec!["Science".into(), "*.empirical".into()],
+            ..ValidationOptions::nested()
+        };
+        let result = validate_with_options(input, &options);
+        assert_eq!(
+            result.warnings,
+            [
+                ValidationError::MissingSource {
+                    line: 3,
+                    scope: "Science".into()
+                },
+                ValidationError::MissingSource {
+                    line: 7,
+                    scope: "*.empirical".into()
+                },
+            ]
+        );
+        assert!(!validate(input).has_warnings());
+    }
+
+    #[test]
+    fn test_missing_source_in_sub_facet() {
+        let input =
+            "Power\n  .nature\n    .observed\n      - corrupts\n  .naturalism\n    - innate\n";
+        let options = ValidationOptions {
+            require_sources: vec!["Power.nature".into()],
+            ..ValidationOptions::nested()
+        };
+        let result = validate_with_options(input, &options);
+        assert_eq!(
+            result.warnings,
+            [ValidationError::MissingSource {
+                line: 4,
+                scope: "Power.nature".into()
+            }]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! Validation profiles: named sets of `ValidationOptions` for common workflows
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{ValidationOptions, ValidationResult, validate_with_options};
+
+/// Which checks run and how strictly their findings are treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// Errors fail, warnings are reported; whitespace is tolerated
+    #[default]
+    Standard,
+    /// Warnings fail too, and a byte order mark or trailing whitespace is an error
+    Strict,
+    /// Facets missing `.` and claims missing `-` are accepted
+    Lenient,
+    /// For machine-written edits: warnings fail, whitespace is tolerated
+    Agent,
+}
+
+impl Profile {
+    /// The options this profile validates with
+    pub fn options(self) -> ValidationOptions {
+        let mut options = ValidationOptions::default();
+        match self {
+            Profile::Standard => {}
+            Profile::Strict => {
+                options.deny_warnings = true;
+                options.strict_whitespace = true;
+            }
+            Profile::Lenient => options.recover_prefixes = true,
+            Profile::Agent => options.deny_warnings = true,
+        }
+        options
+    }
+}
+
+/// Validates a Worldview document under a profile
+pub fn validate_with_profile(input: &str, profile: Profile) -> ValidationResult<'_> {
+    validate_with_options(input, &profile.options())
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Profile::Standard),
+            "strict" => Ok(Profile::Strict),
+            "lenient" => Ok(Profile::Lenient),
+            "agent" => Ok(Profile::Agent),
+            _ => Err(format!(
+                "unknown profile '{}' (expected standard, strict, lenient, or agent)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Profile::Standard => "standard",
+            Profile::Strict => "strict",
+            Profile::Lenient => "lenient",
+            Profile::Agent => "agent",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationError;
+
+    #[test]
+    fn test_strict_denies_warnings() {
+        let input = "Power\n  .core\n    - power -> corruption \n";
+        assert!(validate_with_profile(input, Profile::Standard).is_valid());
+
+        let strict = validate_with_profile(input, Profile::Strict);
+        assert!(strict.warnings.is_empty());
+        assert!(matches!(
+            strict.errors[..],
+            [
+                ValidationError::TrailingWhitespace { line: 3 },
+                ValidationError::UnknownOperator { line: 3, .. },
+            ]
+        ));
+
+        let agent = validate_with_profile(input, Profile::Agent);
+        assert!(matches!(
+            agent.errors[..],
+            [ValidationError::UnknownOperator { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_lenient_recovers_prefixes() {
+        let input = "Power\n  core\n    corrupts\n";
+        assert!(!validate_with_profile(input, Profile::Standard).is_valid());
+
+        let result = validate_with_profile(input, Profile::Lenient);
+        assert!(result.is_valid(), "Expected valid: {:?}", result.errors);
+        let doc = crate::Document::from_lines(&result.lines);
+        assert_eq!(doc.concepts[0].facets[0].name, "core");
+        assert_eq!(doc.concepts[0].facets[0].claims[0].data.text, "corrupts");
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        assert_eq!("agent".parse(), Ok(Profile::Agent));
+        assert!("loose".parse::<Profile>().is_err());
+    }
+}
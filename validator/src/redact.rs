@@ -0,0 +1,179 @@
+//! Sensitivity markers, and stripping the claims they flag before sharing
+//!
+//! A facet or sub-facet named after a sensitivity level (`.private`,
+//! `.confidential`) marks every claim under it. `Document::redacted` removes
+//! the marked claims for the levels asked for, so exports and prompts can leave
+//! them out, and prunes what was left empty the way `Document::compress` does.
+//!
+//! ```text
+//! Health
+//!   .habits
+//!     - sleep before midnight
+//!     .private
+//!       - insomnia since 2019
+//! ```
+
+use crate::compress::{claims, retain};
+use crate::document::{Document, Facet};
+use crate::reference::Reference;
+use crate::{ParsedLine, ValidationError};
+
+/// Facet names that mark the claims under them as sensitive
+pub const SENSITIVITY_LEVELS: [&str; 2] = ["private", "confidential"];
+
+/// The sensitivity level a facet named `name` marks, if it is a marker
+pub fn sensitivity_level(name: &str) -> Option<&'static str> {
+    SENSITIVITY_LEVELS.into_iter().find(|level| *level == name)
+}
+
+impl Document {
+    /// A copy without the claims marked with any of `levels`
+    ///
+    /// Facets and concepts left without claims are pruned, references to
+    /// removed facets are dropped, and the source registry keeps only the
+    /// sources still cited. Unknown levels mark nothing.
+    pub fn redacted(&self, levels: &[&str]) -> Document {
+        let keep: Vec<bool> = claims(self)
+            .iter()
+            .map(|claim| !claim.facets.iter().any(|facet| levels.contains(facet)))
+            .collect();
+        retain(self, &keep)
+    }
+}
+
+/// Flag sensitivity markers that redaction would not act on as intended
+///
+/// A facet spelled like a level in another case (`.Private`) marks nothing,
+/// and a marker inside one of the same level marks nothing its parent does not
+/// already. A claim outside a marker that references into it keeps the
+/// reference in view while redaction drops it, so the reference is reported
+/// too.
+pub(crate) fn sensitivity_markers(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    let doc = Document::from_lines(lines);
+    let mut warnings = Vec::new();
+    for concept in &doc.concepts {
+        check_facets(&concept.facets, None, &mut warnings);
+    }
+
+    for claim in claims(&doc) {
+        let own: Vec<&str> = claim
+            .facets
+            .iter()
+            .filter_map(|facet| sensitivity_level(facet))
+            .collect();
+        for reference in &claim.claim.data.references {
+            let Some(parsed) = Reference::parse(reference) else {
+                continue;
+            };
+            if parsed.namespace.is_some() {
+                continue;
+            }
+            if let Some(level) = parsed
+                .facet
+                .split('.')
+                .find_map(sensitivity_level)
+                .filter(|level| !own.contains(level))
+            {
+                warnings.push(ValidationError::SensitiveReference {
+                    line: claim.claim.line,
+                    reference: reference.to_string(),
+                    level,
+                });
+            }
+        }
+    }
+    warnings.sort_by_key(ValidationError::line);
+    warnings
+}
+
+fn check_facets(facets: &[Facet], marker: Option<&str>, warnings: &mut Vec<ValidationError>) {
+    for facet in facets {
+        let level = sensitivity_level(&facet.name);
+        let reason = if level.is_some() && level == marker {
+            Some(format!("it is already inside '.{}'", facet.name))
+        } else if level.is_none() {
+            SENSITIVITY_LEVELS
+                .into_iter()
+                .find(|level| facet.name.eq_ignore_ascii_case(level))
+                .map(|level| format!("levels are lowercase; write '.{}'", level))
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            warnings.push(ValidationError::MalformedSensitivityMarker {
+                line: facet.line,
+                name: facet.name.clone(),
+                reason,
+            });
+        }
+        check_facets(&facet.sub_facets, marker.or(level), warnings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationOptions, validate_with_options};
+
+    const DOC: &str = "Health\n  .habits\n    - sleep before midnight @journal\n    .private\n      - insomnia since 2019 @doctor\n  .private\n    - family history &Health.habits.private\n\nWork\n  .confidential\n    - salary\n\nsources:\n  journal: my journal\n  doctor: a visit to the doctor\n";
+
+    fn warnings(input: &str) -> Vec<(usize, &'static str)> {
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert!(result.is_valid(), "{:?}", result.errors);
+        result
+            .warnings
+            .iter()
+            .map(|w| (w.line(), w.code()))
+            .collect()
+    }
+
+    #[test]
+    fn test_redacted_strips_marked_claims() {
+        let doc = Document::parse(DOC);
+        let redacted = doc.redacted(&["private"]);
+        assert_eq!(
+            redacted.to_wvf_string(),
+            "Health\n  .habits\n    - sleep before midnight @journal\n\nWork\n  .confidential\n    - salary\n\nsources:\n  journal: my journal\n"
+        );
+
+        let redacted = doc.redacted(&SENSITIVITY_LEVELS);
+        assert_eq!(redacted.concepts.len(), 1);
+        assert_eq!(doc.redacted(&[]), doc);
+    }
+
+    #[test]
+    fn test_redacted_drops_references_into_marked_facets() {
+        let doc = Document::parse(
+            "Health\n  .private\n    - insomnia\n  .habits\n    - sleep early &Health.private\n",
+        );
+        assert_eq!(
+            doc.redacted(&["private"]).to_wvf_string(),
+            "Health\n  .habits\n    - sleep early\n"
+        );
+    }
+
+    #[test]
+    fn test_sensitivity_markers() {
+        // A private claim may reference another private facet
+        assert_eq!(warnings(DOC), []);
+
+        let input = "Health\n  .Private\n    - insomnia\n  .private\n    - stress\n    .private\n      - diagnosis\n  .habits\n    - sleep early &Health.private\n";
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert_eq!(
+            warnings(input),
+            [
+                (2, "malformed-sensitivity-marker"),
+                (6, "malformed-sensitivity-marker"),
+                (9, "sensitive-reference"),
+            ]
+        );
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "line 2: sensitivity marker '.Private' has no effect: levels are lowercase; write '.private'"
+        );
+        assert_eq!(
+            result.warnings[2].to_string(),
+            "line 9: reference '&Health.private' points into '.private' claims, which redaction removes"
+        );
+    }
+}
@@ -0,0 +1,448 @@
+//! Structural refactorings on a `Document`
+//!
+//! Renames update the definition and every `&Concept.facet` reference that
+//! points at it, so a refactored document keeps validating. References in other
+//! files are updated with `Document::rewrite_references`. Claims can be moved
+//! between facets with `Document::move_claim`, or added directly with
+//! `Document::add_claim`.
+
+use thiserror::Error;
+
+use crate::document::{Claim, Concept, Document, Facet};
+use crate::reference::Reference;
+
+/// Errors from refactoring operations
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RefactorError {
+    #[error("concept '{0}' not found")]
+    ConceptNotFound(String),
+
+    #[error("facet '{concept}.{facet}' not found")]
+    FacetNotFound { concept: String, facet: String },
+
+    #[error("'{0}' already exists")]
+    AlreadyExists(String),
+
+    #[error("'{0}' is not a valid name")]
+    InvalidName(String),
+
+    #[error("'{0}' is not a Concept.facet path")]
+    InvalidPath(String),
+
+    #[error("no claim '{claim}' in {facet}")]
+    ClaimNotFound { facet: String, claim: String },
+}
+
+/// A rename, as applied to references
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rename {
+    Concept {
+        from: String,
+        to: String,
+    },
+    Facet {
+        concept: String,
+        from: String,
+        to: String,
+    },
+}
+
+impl Rename {
+    /// Apply to one reference, returning true if it changed
+    fn apply(&self, reference: &mut Reference) -> bool {
+        match self {
+            Rename::Concept { from, to } if reference.concept == *from => {
+                reference.concept = to.clone();
+                true
+            }
+            Rename::Facet { concept, from, to } if reference.concept == *concept => {
+                // Sub-facet references (`facet.sub`) follow their parent
+                let rest = match reference.facet.strip_prefix(from.as_str()) {
+                    Some(rest) if rest.is_empty() || rest.starts_with('.') => rest.to_string(),
+                    _ => return false,
+                };
+                reference.facet = format!("{}{}", to, rest);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Document {
+    /// Rename a concept, rewriting references to it
+    ///
+    /// Returns the number of references rewritten. Namespaced references point
+    /// into other files and are left alone.
+    pub fn rename_concept(&mut self, from: &str, to: &str) -> Result<usize, RefactorError> {
+        check_name(to)?;
+        if self.concept(from).is_none() {
+            return Err(RefactorError::ConceptNotFound(from.to_string()));
+        }
+        if from != to && self.concept(to).is_some() {
+            return Err(RefactorError::AlreadyExists(to.to_string()));
+        }
+
+        for concept in self.concepts.iter_mut().filter(|c| c.name == from) {
+            concept.name = to.to_string();
+        }
+        let rename = Rename::Concept {
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        Ok(self.rewrite_references(&rename, None))
+    }
+
+    /// Rename a facet of `concept`, rewriting references to it and its sub-facets
+    pub fn rename_facet(
+        &mut self,
+        concept: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<usize, RefactorError> {
+        check_name(to)?;
+        let owner = self
+            .concept_mut(concept)
+            .ok_or_else(|| RefactorError::ConceptNotFound(concept.to_string()))?;
+        if owner.facet(from).is_none() {
+            return Err(RefactorError::FacetNotFound {
+                concept: concept.to_string(),
+                facet: from.to_string(),
+            });
+        }
+        if from != to && owner.facet(to).is_some() {
+            return Err(RefactorError::AlreadyExists(format!("{}.{}", concept, to)));
+        }
+
+        for facet in owner.facets.iter_mut().filter(|f| f.name == from) {
+            facet.name = to.to_string();
+        }
+        let rename = Rename::Facet {
+            concept: concept.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        };
+        Ok(self.rewrite_references(&rename, None))
+    }
+
+    /// Rewrite references affected by a rename made elsewhere
+    ///
+    /// Unqualified references are always rewritten; namespaced ones only when
+    /// their namespace matches `namespace` (the defining file's namespace).
+    /// Returns the number of references rewritten.
+    pub fn rewrite_references(&mut self, rename: &Rename, namespace: Option<&str>) -> usize {
+        let mut rewritten = 0;
+        for claim in self.claims_mut() {
+            for raw in claim.data.references.iter_mut() {
+                let Some(mut reference) = Reference::parse(raw) else {
+                    continue;
+                };
+                if reference.namespace.is_some() && reference.namespace.as_deref() != namespace {
+                    continue;
+                }
+                if rename.apply(&mut reference) {
+                    *raw = reference.to_string().into();
+                    rewritten += 1;
+                }
+            }
+        }
+        rewritten
+    }
+
+    /// Move a claim between facets
+    ///
+    /// `from` and `to` are `Concept.facet` (or `Concept.facet.sub`) paths and
+    /// `claim` is the claim text without its inline elements. A missing target
+    /// facet is created under its existing concept or facet, so an overgrown
+    /// facet can be split. The moved claim is re-rendered at its new
+    /// indentation. A source facet left empty is kept; validating the result
+    /// will report it. A failed move leaves the document unchanged.
+    pub fn move_claim(&mut self, from: &str, claim: &str, to: &str) -> Result<(), RefactorError> {
+        let index = self
+            .facet_at_mut(from)?
+            .claims
+            .iter()
+            .position(|c| c.data.text == claim)
+            .ok_or_else(|| RefactorError::ClaimNotFound {
+                facet: from.to_string(),
+                claim: claim.to_string(),
+            })?;
+        match self.facet_at_mut(to) {
+            Ok(_) => {}
+            Err(RefactorError::FacetNotFound { .. }) => self.create_facet(to)?,
+            Err(e) => return Err(e),
+        }
+
+        let mut moved = self.facet_at_mut(from)?.claims.remove(index);
+        moved.source = None;
+        self.facet_at_mut(to)?.claims.push(moved);
+        Ok(())
+    }
+
+    /// Add an empty facet or sub-facet at `path` under its existing parent
+    fn create_facet(&mut self, path: &str) -> Result<(), RefactorError> {
+        let (parent, name) = path
+            .rsplit_once('.')
+            .ok_or_else(|| RefactorError::InvalidPath(path.to_string()))?;
+        check_name(name)?;
+        let siblings = match parent.split_once('.') {
+            None => {
+                &mut self
+                    .concept_mut(parent)
+                    .ok_or_else(|| RefactorError::ConceptNotFound(parent.to_string()))?
+                    .facets
+            }
+            Some((_, facets)) => {
+                let facet = self.facet_at_mut(parent)?;
+                // Sub-facets nest only one level deep
+                if facets.contains('.') {
+                    return Err(RefactorError::InvalidPath(path.to_string()));
+                }
+                &mut facet.sub_facets
+            }
+        };
+        siblings.push(Facet::new(name));
+        Ok(())
+    }
+
+    /// Append a claim to `concept.facet`, creating the concept and facet if needed
+    pub fn add_claim(
+        &mut self,
+        concept: &str,
+        facet: &str,
+        claim: Claim,
+    ) -> Result<(), RefactorError> {
+        check_name(concept)?;
+        check_name(facet)?;
+        let concept = match self.concepts.iter().position(|c| c.name == concept) {
+            Some(i) => &mut self.concepts[i],
+            None => {
+                self.concepts.push(Concept::new(concept));
+                self.concepts.last_mut().unwrap()
+            }
+        };
+        let target = match concept.facets.iter().position(|f| f.name == facet) {
+            Some(i) => &mut concept.facets[i],
+            None => {
+                concept.facets.push(Facet::new(facet));
+                concept.facets.last_mut().unwrap()
+            }
+        };
+        let text = claim.data.to_wvf_string();
+        if target.claims.iter().any(|c| c.data.to_wvf_string() == text) {
+            return Err(RefactorError::AlreadyExists(format!(
+                "{}.{}: {}",
+                concept.name, facet, text
+            )));
+        }
+        target.claims.push(claim);
+        Ok(())
+    }
+
+    /// Resolve a `Concept.facet[.sub]` path
+    fn facet_at_mut(&mut self, path: &str) -> Result<&mut Facet, RefactorError> {
+        let (concept_name, facets) = path
+            .split_once('.')
+            .ok_or_else(|| RefactorError::InvalidPath(path.to_string()))?;
+        let not_found = || RefactorError::FacetNotFound {
+            concept: concept_name.to_string(),
+            facet: facets.to_string(),
+        };
+        let concept = self
+            .concept_mut(concept_name)
+            .ok_or_else(|| RefactorError::ConceptNotFound(concept_name.to_string()))?;
+        let (facet_name, sub_name) = match facets.split_once('.') {
+            Some((facet, sub)) => (facet, Some(sub)),
+            None => (facets, None),
+        };
+        let facet = concept.facet_mut(facet_name).ok_or_else(not_found)?;
+        match sub_name {
+            None => Ok(facet),
+            Some(sub) => facet
+                .sub_facets
+                .iter_mut()
+                .find(|f| f.name == sub)
+                .ok_or_else(not_found),
+        }
+    }
+
+    /// Every claim in the document, including those under sub-facets
+    pub(crate) fn claims_mut(&mut self) -> impl Iterator<Item = &mut Claim> {
+        self.concepts
+            .iter_mut()
+            .flat_map(|c| c.facets.iter_mut())
+            .flat_map(facet_claims_mut)
+    }
+}
+
+fn facet_claims_mut(facet: &mut Facet) -> Box<dyn Iterator<Item = &mut Claim> + '_> {
+    Box::new(
+        facet
+            .claims
+            .iter_mut()
+            .chain(facet.sub_facets.iter_mut().flat_map(facet_claims_mut)),
+    )
+}
+
+/// Names must survive a round trip through `&Concept.facet`
+fn check_name(name: &str) -> Result<(), RefactorError> {
+    let invalid = name.trim().is_empty()
+        || name != name.trim()
+        || name.contains(['.', '&', '|', '@', crate::NAMESPACE_SEPARATOR])
+        || name.starts_with(crate::CLAIM_PREFIX);
+    if invalid {
+        return Err(RefactorError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Power\n  .core\n    - corrupts &Trust.formation\n\nTrust\n  .formation\n    - slow &Power.core\n    - fragile &Power.core | always\n";
+
+    #[test]
+    fn test_rename_concept_rewrites_references() {
+        let mut doc = Document::parse(INPUT);
+        assert_eq!(doc.rename_concept("Power", "Authority"), Ok(2));
+        let text = doc.to_string();
+        assert!(text.starts_with("Authority\n"));
+        assert!(text.contains("- slow &Authority.core\n"));
+        assert!(text.contains("- fragile | always &Authority.core\n"));
+        assert!(doc.validate().is_valid());
+    }
+
+    #[test]
+    fn test_rename_facet_rewrites_references() {
+        let mut doc = Document::parse(INPUT);
+        assert_eq!(doc.rename_facet("Trust", "formation", "origin"), Ok(1));
+        let text = doc.to_string();
+        assert!(text.contains("  .origin\n"));
+        assert!(text.contains("- corrupts &Trust.origin\n"));
+        assert!(doc.validate().is_valid());
+    }
+
+    #[test]
+    fn test_rename_errors() {
+        let mut doc = Document::parse(INPUT);
+        assert_eq!(
+            doc.rename_concept("Missing", "X"),
+            Err(RefactorError::ConceptNotFound("Missing".to_string()))
+        );
+        assert_eq!(
+            doc.rename_concept("Power", "Trust"),
+            Err(RefactorError::AlreadyExists("Trust".to_string()))
+        );
+        assert!(matches!(
+            doc.rename_facet("Power", "nope", "x"),
+            Err(RefactorError::FacetNotFound { .. })
+        ));
+        assert!(matches!(
+            doc.rename_facet("Power", "core", "a.b"),
+            Err(RefactorError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn test_move_claim_to_new_facet() {
+        let mut doc = Document::parse(
+            "Power\n  .core\n    - corrupts | unchecked\n    -  reveals character\n",
+        );
+        doc.move_claim("Power.core", "reveals character", "Power.revelation")
+            .unwrap();
+        assert_eq!(
+            doc.to_string(),
+            "Power\n  .core\n    - corrupts | unchecked\n  .revelation\n    - reveals character\n"
+        );
+        assert!(doc.validate().is_valid());
+    }
+
+    #[test]
+    fn test_move_claim_into_sub_facet() {
+        let mut doc = Document::parse(
+            "Economics\n  .markets\n    - efficient?\n    .labor\n      - wages sticky\n",
+        );
+        doc.move_claim("Economics.markets", "efficient?", "Economics.markets.labor")
+            .unwrap();
+        assert_eq!(
+            doc.to_string(),
+            "Economics\n  .markets\n    .labor\n      - wages sticky\n      - efficient?\n"
+        );
+    }
+
+    #[test]
+    fn test_add_claim() {
+        let mut doc = Document::parse(INPUT);
+        let claim = || Claim::parse("requires consistency | over time @experience");
+        doc.add_claim("Trust", "formation", claim()).unwrap();
+        doc.add_claim("Markets", "prices", Claim::parse("signal"))
+            .unwrap();
+        let text = doc.to_string();
+        assert!(text.contains(
+            "    - fragile &Power.core | always\n    - requires consistency | over time @experience\n"
+        ));
+        assert!(text.ends_with("Markets\n  .prices\n    - signal\n"));
+        assert!(matches!(
+            doc.add_claim("Trust", "formation", claim()),
+            Err(RefactorError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            doc.add_claim("Trust", "a.b", claim()),
+            Err(RefactorError::InvalidName(_))
+        ));
+    }
+
+    #[test]
+    fn test_move_claim_errors() {
+        let mut doc = Document::parse(INPUT);
+        assert!(matches!(
+            doc.move_claim("Power.core", "missing", "Trust.formation"),
+            Err(RefactorError::ClaimNotFound { .. })
+        ));
+        assert!(matches!(
+            doc.move_claim("Power.core", "corrupts", "Nowhere.x"),
+            Err(RefactorError::ConceptNotFound(_))
+        ));
+        assert_eq!(Document::parse(INPUT).to_string(), doc.to_string());
+    }
+
+    #[test]
+    fn test_failed_move_keeps_claim() {
+        let input = "Power\n  .core\n    - corrupts\n    - stays\n    .limits\n      - checks\n";
+        let mut doc = Document::parse(input);
+        assert!(matches!(
+            doc.move_claim("Power.core", "corrupts", "Power.missing.sub"),
+            Err(RefactorError::FacetNotFound { .. })
+        ));
+        assert!(matches!(
+            doc.move_claim("Power.core", "corrupts", "Power.core.a.b"),
+            Err(RefactorError::FacetNotFound { .. })
+        ));
+        assert!(matches!(
+            doc.move_claim("Power.core", "corrupts", "Power.core.limits.deeper"),
+            Err(RefactorError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            doc.move_claim("Power.core", "corrupts", "Power.bad|name"),
+            Err(RefactorError::InvalidName(_))
+        ));
+        assert_eq!(doc.to_string(), input);
+    }
+
+    #[test]
+    fn test_rewrite_namespaced_references() {
+        let mut doc = Document::parse(
+            "Teams\n  .trust\n    - earned &politics:Trust.formation &other:Trust.formation\n",
+        );
+        let rename = Rename::Concept {
+            from: "Trust".to_string(),
+            to: "Faith".to_string(),
+        };
+        assert_eq!(doc.rewrite_references(&rename, Some("politics")), 1);
+        assert!(
+            doc.to_string()
+                .contains("&politics:Faith.formation &other:Trust.formation")
+        );
+    }
+}
@@ -0,0 +1,448 @@
+//! Structured `&reference` targets, including namespaced cross-file references
+//!
+//! A reference is `&Concept.facet`, optionally qualified with the namespace of
+//! the file that defines it: `&politics:Trust.institutional`. A file's namespace
+//! is its file stem (`politics.wvf` → `politics`).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use crate::document::{Claim, Document, Facet};
+use crate::{LineType, ParsedLine, ValidationError};
+
+/// Separates a namespace from the Concept.facet target
+pub const NAMESPACE_SEPARATOR: char = ':';
+
+/// A parsed `&[namespace:]Concept.facet` reference
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+    pub namespace: Option<String>,
+    pub concept: String,
+    /// Facet name; dotted for sub-facets (`markets.labor`)
+    pub facet: String,
+}
+
+impl Reference {
+    /// Parse a reference body (without the leading `&`)
+    pub fn parse(reference: &str) -> Option<Reference> {
+        let (namespace, target) = match reference.split_once(NAMESPACE_SEPARATOR) {
+            Some((ns, target)) if !ns.is_empty() => (Some(ns.to_string()), target),
+            Some(_) => return None,
+            None => (None, reference),
+        };
+        let (concept, facet) = target.split_once('.')?;
+        if concept.is_empty() || facet.is_empty() {
+            return None;
+        }
+        Some(Reference {
+            namespace,
+            concept: concept.to_string(),
+            facet: facet.to_string(),
+        })
+    }
+
+    /// The unqualified `Concept.facet` target
+    pub fn target(&self) -> String {
+        format!("{}.{}", self.concept, self.facet)
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref ns) = self.namespace {
+            write!(f, "{}{}", ns, NAMESPACE_SEPARATOR)?;
+        }
+        write!(f, "{}.{}", self.concept, self.facet)
+    }
+}
+
+/// How often claims reference a facet or sub-facet defined in a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceCount {
+    /// `Concept.facet`, or `Concept.facet.sub` for a sub-facet
+    pub target: String,
+    /// Line defining the target
+    pub line: usize,
+    pub count: usize,
+}
+
+impl Document {
+    /// Every facet and sub-facet defined here with how often a claim
+    /// references it, most referenced first (then in document order)
+    ///
+    /// Namespaced references point into other files and are not counted.
+    pub fn reference_counts(&self) -> Vec<ReferenceCount> {
+        let mut counts: Vec<ReferenceCount> = Vec::new();
+        for (target, line) in self.reference_targets() {
+            if !counts.iter().any(|c| c.target == target) {
+                counts.push(ReferenceCount {
+                    target,
+                    line,
+                    count: 0,
+                });
+            }
+        }
+        for concept in &self.concepts {
+            count_facets(self, &concept.facets, &mut counts);
+        }
+        counts.sort_by_key(|c| std::cmp::Reverse(c.count));
+        counts
+    }
+
+    /// Claims with a reference to the `Concept.facet` `target`, in document
+    /// order; either side may name the concept by an alias
+    ///
+    /// Namespaced references point into other files and never match.
+    pub fn claims_referencing<'d>(&'d self, target: &str) -> impl Iterator<Item = &'d Claim> {
+        let target = self.resolve_target(target).unwrap_or_else(|| target.to_string());
+        self.claims().filter(move |claim| {
+            claim.data.parsed_references().iter().any(|reference| {
+                reference.namespace.is_none()
+                    && self
+                        .resolve_target(&reference.target())
+                        .unwrap_or_else(|| reference.target())
+                        == target
+            })
+        })
+    }
+}
+
+fn count_facets(doc: &Document, facets: &[Facet], counts: &mut [ReferenceCount]) {
+    for facet in facets {
+        for claim in &facet.claims {
+            let references = claim.data.references.iter();
+            let local = references
+                .filter_map(|r| Reference::parse(r))
+                .filter(|r| r.namespace.is_none());
+            for reference in local {
+                let Some(target) = doc.resolve_target(&reference.target()) else {
+                    continue;
+                };
+                if let Some(count) = counts.iter_mut().find(|c| c.target == target) {
+                    count.count += 1;
+                }
+            }
+        }
+        count_facets(doc, &facet.sub_facets, counts);
+    }
+}
+
+/// Flag concepts and facets that no `&reference` targets
+///
+/// A reference to a sub-facet counts for its facet and concept. A concept that
+/// is never referenced is reported once, rather than once per facet.
+pub(crate) fn unreferenced_definitions(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    let doc = Document::from_lines(lines);
+    let referenced: Vec<String> = doc
+        .reference_counts()
+        .into_iter()
+        .filter(|c| c.count > 0)
+        .map(|c| c.target)
+        .collect();
+    // A target is `path` itself or lies under it
+    let is_referenced = |path: &str| {
+        referenced.iter().any(|target| {
+            target
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        })
+    };
+
+    let mut warnings = Vec::new();
+    let mut seen = HashSet::new();
+    for concept in &doc.concepts {
+        if !is_referenced(&concept.name) {
+            if seen.insert(concept.name.clone()) {
+                warnings.push(ValidationError::UnreferencedDefinition {
+                    line: concept.line,
+                    kind: "concept",
+                    name: concept.name.clone(),
+                });
+            }
+            continue;
+        }
+        for facet in &concept.facets {
+            let target = format!("{}.{}", concept.name, facet.name);
+            if !is_referenced(&target) && seen.insert(target.clone()) {
+                warnings.push(ValidationError::UnreferencedDefinition {
+                    line: facet.line,
+                    kind: "facet",
+                    name: target,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// The namespace a file's definitions are addressable under
+pub fn namespace_for(path: &Path) -> Option<String> {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// Qualify a `Concept.facet` target with a namespace
+pub(crate) fn qualified(namespace: &str, target: &str) -> String {
+    format!("{}{}{}", namespace, NAMESPACE_SEPARATOR, target)
+}
+
+/// The first character of a name that no `&reference` to it can contain: a
+/// reference ends at whitespace or another marker, and `:` starts its target
+pub(crate) fn reference_break(name: &str) -> Option<char> {
+    name.chars()
+        .find(|&c| c.is_whitespace() || matches!(c, '|' | '@' | '&' | NAMESPACE_SEPARATOR))
+}
+
+/// Why a reference cannot contain `character`
+pub(crate) fn break_reason(character: char) -> String {
+    match character {
+        NAMESPACE_SEPARATOR => format!("'{}' separates a namespace", character),
+        c if c.is_whitespace() => "references end at whitespace".to_string(),
+        c => format!("references end at '{}'", c),
+    }
+}
+
+/// Flag concepts, facets, and sub-facets whose names `&Concept.facet` cannot
+/// name unambiguously
+///
+/// A name with whitespace, a marker, or `:` cannot be referenced at all. A name
+/// with `.` can, but the reference reads the same as one to a different split
+/// (`&Economy.labor.wages` names facet `labor.wages` or sub-facet `wages`).
+pub(crate) fn unreferencable_names(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    let mut warnings = Vec::new();
+    for line in lines {
+        let (kind, name) = match &line.line_type {
+            LineType::Concept(name) => ("concept", name),
+            LineType::Facet(name) => ("facet", name),
+            LineType::SubFacet(name) => ("sub-facet", name),
+            _ => continue,
+        };
+        if let Some(character) = reference_break(name) {
+            warnings.push(ValidationError::UnreferencableName {
+                line: line.line_number,
+                kind,
+                name: name.to_string(),
+                character,
+            });
+        } else if name.contains('.') {
+            warnings.push(ValidationError::AmbiguousReferenceTarget {
+                line: line.line_number,
+                kind,
+                name: name.to_string(),
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationError, validate, validate_workspace};
+    use std::fs;
+
+    #[test]
+    fn test_parse_plain_reference() {
+        let r = Reference::parse("Trust.formation").unwrap();
+        assert_eq!(r.namespace, None);
+        assert_eq!(r.concept, "Trust");
+        assert_eq!(r.facet, "formation");
+        assert_eq!(r.to_string(), "Trust.formation");
+    }
+
+    #[test]
+    fn test_parse_namespaced_reference() {
+        let r = Reference::parse("politics:Trust.institutional").unwrap();
+        assert_eq!(r.namespace.as_deref(), Some("politics"));
+        assert_eq!(r.target(), "Trust.institutional");
+        assert_eq!(r.to_string(), "politics:Trust.institutional");
+    }
+
+    #[test]
+    fn test_parse_malformed_references() {
+        assert!(Reference::parse("Trust").is_none());
+        assert!(Reference::parse(":Trust.formation").is_none());
+        assert!(Reference::parse("Trust.").is_none());
+        assert!(Reference::parse(".formation").is_none());
+    }
+
+    #[test]
+    fn test_reference_counts() {
+        let input = "\
+Trust
+  .formation
+    - slow &Power.core
+  .erosion
+    - fast &Trust.formation &Power.core &politics:Power.core
+Power
+  .core
+    - corrupts
+  .limits
+    - checks &Power.core
+";
+        let doc = Document::parse(input);
+        let counts: Vec<(String, usize)> = doc
+            .reference_counts()
+            .into_iter()
+            .map(|c| (c.target, c.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Power.core".to_string(), 3),
+                ("Trust.formation".to_string(), 1),
+                ("Trust.erosion".to_string(), 0),
+                ("Power.limits".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_claims_referencing() {
+        let input = "\
+Trust
+  .formation
+    - slow &Authority.core
+  .erosion
+    - fast &Trust.formation &politics:Power.core
+Power (aka Authority)
+  .core
+    - corrupts
+  .limits
+    - checks &Power.core
+";
+        let doc = Document::parse(input);
+        let lines = |target| -> Vec<usize> {
+            doc.claims_referencing(target).map(|c| c.line).collect()
+        };
+        assert_eq!(lines("Power.core"), [3, 10]);
+        assert_eq!(lines("Authority.core"), [3, 10]);
+        assert_eq!(lines("Trust.formation"), [5]);
+        assert_eq!(lines("Trust.erosion"), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_unreferenced_definitions_are_opt_in() {
+        let input = "\
+Trust
+  .formation
+    - slow &Power.core
+  .erosion
+    - fast
+Power
+  .core
+    - corrupts
+Markets
+  .labor
+    .wages
+      - sticky &Trust.formation
+";
+        assert!(!validate(input).has_warnings());
+
+        let options = crate::ValidationOptions {
+            nested_facets: true,
+            report_unreferenced: true,
+            ..Default::default()
+        };
+        let result = crate::validate_with_options(input, &options);
+        assert_eq!(
+            result.warnings,
+            vec![
+                ValidationError::UnreferencedDefinition {
+                    line: 4,
+                    kind: "facet",
+                    name: "Trust.erosion".to_string(),
+                },
+                ValidationError::UnreferencedDefinition {
+                    line: 9,
+                    kind: "concept",
+                    name: "Markets".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "line 4: facet 'Trust.erosion' is never referenced"
+        );
+    }
+
+    #[test]
+    fn test_names_references_cannot_reach() {
+        let input = "\
+Market Forces
+  .supply
+    - elastic
+Economy
+  .labor.wages
+    - sticky
+  .time:horizon
+    - long
+";
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        assert_eq!(
+            result.warnings,
+            vec![
+                ValidationError::UnreferencableName {
+                    line: 1,
+                    kind: "concept",
+                    name: "Market Forces".to_string(),
+                    character: ' ',
+                },
+                ValidationError::AmbiguousReferenceTarget {
+                    line: 5,
+                    kind: "facet",
+                    name: "labor.wages".to_string(),
+                },
+                ValidationError::UnreferencableName {
+                    line: 7,
+                    kind: "facet",
+                    name: "time:horizon".to_string(),
+                    character: ':',
+                },
+            ]
+        );
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "line 1: concept 'Market Forces' cannot be referenced (references end at whitespace)"
+        );
+        assert_eq!(
+            result.warnings[2].to_string(),
+            "line 7: facet 'time:horizon' cannot be referenced (':' separates a namespace)"
+        );
+    }
+
+    #[test]
+    fn test_unknown_namespace_in_single_file() {
+        let result = validate("Power\n  .core\n    - corrupts &politics:Trust.formation");
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::UnknownNamespace { namespace, .. } if namespace == "politics"
+        )));
+    }
+
+    #[test]
+    fn test_namespaced_reference_resolves_in_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("politics.wvf"),
+            "Trust\n  .institutional\n    - conditional\n",
+        )
+        .unwrap();
+        let work = dir.path().join("work.wvf");
+        fs::write(
+            &work,
+            "Teams\n  .trust\n    - earned &politics:Trust.institutional\n    - shaky &politics:Trust.formation\n",
+        )
+        .unwrap();
+
+        let result = validate_workspace(&[dir.path().to_path_buf()]);
+        let errors = &result.file(&work).unwrap().result.errors;
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(matches!(
+            &errors[0],
+            ValidationError::UndefinedReference { reference, .. } if reference == "politics:Trust.formation"
+        ));
+    }
+}
@@ -0,0 +1,482 @@
+//! Structural constraints shared by the documents of a team or template
+//!
+//! A schema (conventionally `worldview.schema.yaml`) goes beyond syntax: which
+//! concepts a document may define, which facets each must have, how concepts
+//! and facets are named, and how many claims a facet may hold.
+//!
+//! ```yaml
+//! concepts: [Trust, Power]
+//! required_facets:
+//!   "*": [nature]
+//!   Trust: [formation]
+//! naming:
+//!   concepts: PascalCase
+//!   facets: kebab-case
+//! claims:
+//!   min: 1
+//!   max: 7
+//! ```
+//!
+//! Every key is optional. Violations are errors, reported by
+//! `validate_against_schema` and, with `ValidationOptions::schema`, by
+//! `validate_with_options`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::{Document, Facet, ParsedLine, ValidationError};
+
+/// File name `worldview validate` looks for when no schema is given
+pub const SCHEMA_FILE: &str = "worldview.schema.yaml";
+
+/// Errors loading a schema
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    #[error("could not read {path}: {message}")]
+    Read { path: String, message: String },
+
+    #[error("invalid schema: {0}")]
+    Parse(String),
+}
+
+/// How concept or facet names are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamingConvention {
+    /// `market-forces`
+    KebabCase,
+    /// `market_forces`
+    SnakeCase,
+    /// `marketForces`
+    CamelCase,
+    /// `MarketForces`
+    PascalCase,
+    /// `Market Forces`
+    TitleCase,
+}
+
+impl NamingConvention {
+    pub const ALL: [NamingConvention; 5] = [
+        NamingConvention::KebabCase,
+        NamingConvention::SnakeCase,
+        NamingConvention::CamelCase,
+        NamingConvention::PascalCase,
+        NamingConvention::TitleCase,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NamingConvention::KebabCase => "kebab-case",
+            NamingConvention::SnakeCase => "snake_case",
+            NamingConvention::CamelCase => "camelCase",
+            NamingConvention::PascalCase => "PascalCase",
+            NamingConvention::TitleCase => "Title Case",
+        }
+    }
+
+    /// Whether `name` is written in this convention
+    pub fn matches(self, name: &str) -> bool {
+        let lower_word = |word: &str| {
+            !word.is_empty() && word.chars().all(|c| c.is_lowercase() || c.is_numeric())
+        };
+        let capitalized = |word: &str| {
+            let mut chars = word.chars();
+            chars.next().is_some_and(char::is_uppercase) && chars.all(char::is_alphanumeric)
+        };
+        match self {
+            NamingConvention::KebabCase => name.split('-').all(lower_word),
+            NamingConvention::SnakeCase => name.split('_').all(lower_word),
+            NamingConvention::CamelCase => {
+                name.starts_with(char::is_lowercase) && name.chars().all(char::is_alphanumeric)
+            }
+            NamingConvention::PascalCase => capitalized(name),
+            NamingConvention::TitleCase => name.split(' ').all(capitalized),
+        }
+    }
+}
+
+impl fmt::Display for NamingConvention {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NamingConvention {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NamingConvention::ALL
+            .into_iter()
+            .find(|convention| convention.as_str() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = NamingConvention::ALL.iter().map(|c| c.as_str()).collect();
+                format!(
+                    "unknown naming convention '{}' (expected one of: {})",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Constraints a document must meet beyond the grammar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    /// Concepts a document may define; any concept when empty
+    pub concepts: Vec<String>,
+    /// Facets a concept must have, by concept name, or `*` for every concept
+    pub required_facets: BTreeMap<String, Vec<String>>,
+    pub concept_naming: Option<NamingConvention>,
+    /// Applies to sub-facets too
+    pub facet_naming: Option<NamingConvention>,
+    /// Fewest claims a facet may hold, counting its sub-facets' claims
+    pub min_claims: Option<usize>,
+    /// Most claims a facet may hold, counting its sub-facets' claims
+    pub max_claims: Option<usize>,
+}
+
+impl Schema {
+    /// Load a schema from a YAML file
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(path: impl AsRef<std::path::Path>) -> Result<Schema, SchemaError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| SchemaError::Read {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Schema::from_yaml_str(&text)
+    }
+
+    /// Like `from_yaml`, from YAML text
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(text: &str) -> Result<Schema, SchemaError> {
+        #[derive(serde::Deserialize, Default)]
+        #[serde(default, deny_unknown_fields)]
+        struct File {
+            concepts: Vec<String>,
+            required_facets: BTreeMap<String, Vec<String>>,
+            naming: Naming,
+            claims: Claims,
+        }
+
+        #[derive(serde::Deserialize, Default)]
+        #[serde(default, deny_unknown_fields)]
+        struct Naming {
+            concepts: Option<String>,
+            facets: Option<String>,
+        }
+
+        #[derive(serde::Deserialize, Default)]
+        #[serde(default, deny_unknown_fields)]
+        struct Claims {
+            min: Option<usize>,
+            max: Option<usize>,
+        }
+
+        // An empty file is an empty schema
+        let file: Option<File> =
+            serde_yaml::from_str(text).map_err(|e| SchemaError::Parse(e.to_string()))?;
+        let file = file.unwrap_or_default();
+        let convention = |name: Option<String>| {
+            name.map(|n| n.parse::<NamingConvention>())
+                .transpose()
+                .map_err(SchemaError::Parse)
+        };
+        let schema = Schema {
+            concepts: file.concepts,
+            required_facets: file.required_facets,
+            concept_naming: convention(file.naming.concepts)?,
+            facet_naming: convention(file.naming.facets)?,
+            min_claims: file.claims.min,
+            max_claims: file.claims.max,
+        };
+        if let (Some(min), Some(max)) = (schema.min_claims, schema.max_claims)
+            && min > max
+        {
+            return Err(SchemaError::Parse(format!(
+                "claims.min ({}) is greater than claims.max ({})",
+                min, max
+            )));
+        }
+        Ok(schema)
+    }
+
+    /// Facets `concept` must have, those required of every concept first
+    fn required_for<'s>(&'s self, concept: &str) -> impl Iterator<Item = &'s String> {
+        let any = self.required_facets.get("*").into_iter().flatten();
+        let own = self
+            .required_facets
+            .get(concept)
+            .filter(|_| concept != "*")
+            .into_iter()
+            .flatten();
+        any.chain(own)
+    }
+}
+
+/// Check a parsed document against a schema
+///
+/// A concept defined in several places must have each required facet in one
+/// of them; missing facets are reported on its first definition.
+pub fn validate_against_schema(lines: &[ParsedLine<'_>], schema: &Schema) -> Vec<ValidationError> {
+    let doc = Document::from_lines(lines);
+    let mut errors = Vec::new();
+    let mut checked: Vec<&str> = Vec::new();
+
+    for concept in &doc.concepts {
+        if !schema.concepts.is_empty() && !schema.concepts.contains(&concept.name) {
+            errors.push(ValidationError::ConceptNotInSchema {
+                line: concept.line,
+                concept: concept.name.clone(),
+            });
+        }
+        check_name(
+            "concept",
+            &concept.name,
+            concept.line,
+            schema.concept_naming,
+            &mut errors,
+        );
+
+        if !checked.contains(&concept.name.as_str()) {
+            checked.push(&concept.name);
+            let defined: Vec<&str> = doc
+                .concepts
+                .iter()
+                .filter(|other| other.name == concept.name)
+                .flat_map(|other| other.facets.iter().map(|f| f.name.as_str()))
+                .collect();
+            for facet in schema.required_for(&concept.name) {
+                if !defined.contains(&facet.as_str()) {
+                    errors.push(ValidationError::MissingRequiredFacet {
+                        line: concept.line,
+                        concept: concept.name.clone(),
+                        facet: facet.clone(),
+                    });
+                }
+            }
+        }
+
+        for facet in &concept.facets {
+            check_facet(facet, schema, &mut errors);
+        }
+    }
+    errors
+}
+
+fn check_facet(facet: &Facet, schema: &Schema, errors: &mut Vec<ValidationError>) {
+    check_name(
+        "facet",
+        &facet.name,
+        facet.line,
+        schema.facet_naming,
+        errors,
+    );
+
+    let nested: usize = facet.sub_facets.iter().map(|s| s.claims.len()).sum();
+    let count = facet.claims.len() + nested;
+    // A facet with no claims at all is already reported as one
+    if let Some(min) = schema.min_claims
+        && count > 0
+        && count < min
+    {
+        errors.push(ValidationError::TooFewClaims {
+            line: facet.line,
+            facet: facet.name.clone(),
+            count,
+            min,
+        });
+    }
+    if let Some(max) = schema.max_claims
+        && count > max
+    {
+        errors.push(ValidationError::TooManyClaims {
+            line: facet.line,
+            facet: facet.name.clone(),
+            count,
+            max,
+        });
+    }
+
+    for sub_facet in &facet.sub_facets {
+        check_name(
+            "sub-facet",
+            &sub_facet.name,
+            sub_facet.line,
+            schema.facet_naming,
+            errors,
+        );
+    }
+}
+
+fn check_name(
+    kind: &'static str,
+    name: &str,
+    line: usize,
+    convention: Option<NamingConvention>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(convention) = convention
+        && !name.is_empty()
+        && !convention.matches(name)
+    {
+        errors.push(ValidationError::NamingViolation {
+            line,
+            kind,
+            name: name.to_string(),
+            convention,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationOptions, validate, validate_with_options};
+
+    fn check(input: &str, schema: &Schema) -> Vec<ValidationError> {
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        validate_against_schema(&result.lines, schema)
+    }
+
+    #[test]
+    fn test_naming_conventions() {
+        use NamingConvention::*;
+        assert!(KebabCase.matches("market-forces2"));
+        assert!(!KebabCase.matches("market--forces"));
+        assert!(!KebabCase.matches("Market-forces"));
+        assert!(SnakeCase.matches("market_forces"));
+        assert!(CamelCase.matches("marketForces"));
+        assert!(!CamelCase.matches("MarketForces"));
+        assert!(PascalCase.matches("MarketForces"));
+        assert!(!PascalCase.matches("Market Forces"));
+        assert!(TitleCase.matches("Market Forces"));
+        assert!(!TitleCase.matches("Market forces"));
+        for convention in NamingConvention::ALL {
+            assert_eq!(convention.as_str().parse(), Ok(convention));
+        }
+        assert!("SCREAMING".parse::<NamingConvention>().is_err());
+    }
+
+    #[test]
+    fn test_allowed_concepts_and_required_facets() {
+        let schema = Schema {
+            concepts: vec!["Trust".into(), "Power".into()],
+            required_facets: BTreeMap::from([
+                ("*".to_string(), vec!["nature".to_string()]),
+                ("Trust".to_string(), vec!["formation".to_string()]),
+            ]),
+            ..Schema::default()
+        };
+        let input = "\
+Trust
+  .formation
+    - slow
+Wealth
+  .nature
+    - compounding
+Trust
+  .nature
+    - fragile
+Power
+  .limits
+    - checks
+";
+        assert_eq!(
+            check(input, &schema),
+            vec![
+                ValidationError::ConceptNotInSchema {
+                    line: 4,
+                    concept: "Wealth".to_string()
+                },
+                ValidationError::MissingRequiredFacet {
+                    line: 10,
+                    concept: "Power".to_string(),
+                    facet: "nature".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_naming_and_claim_limits() {
+        let schema = Schema {
+            concept_naming: Some(NamingConvention::PascalCase),
+            facet_naming: Some(NamingConvention::KebabCase),
+            min_claims: Some(2),
+            max_claims: Some(3),
+            ..Schema::default()
+        };
+        let input = "\
+market forces
+  .price_signals
+    - sticky
+    - local
+  .supply-chains
+    - fragile
+  .labor
+    .wages
+      - sticky
+      - slow
+    .Hours
+      - long
+      - fixed
+";
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert!(result.is_valid(), "{:?}", result.errors);
+        let errors = validate_against_schema(&result.lines, &schema);
+        let found: Vec<(usize, &str)> = errors.iter().map(|e| (e.line(), e.code())).collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, "naming-violation"),
+                (2, "naming-violation"),
+                (5, "too-few-claims"),
+                (7, "too-many-claims"),
+                (11, "naming-violation"),
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "line 2: facet 'price_signals' is not kebab-case"
+        );
+        assert_eq!(
+            errors[3].to_string(),
+            "line 7: facet 'labor' has 4 claims (at most 3 allowed)"
+        );
+    }
+
+    #[test]
+    fn test_options_run_the_schema_pass() {
+        let options = ValidationOptions {
+            schema: Some(Schema {
+                concepts: vec!["Trust".into()],
+                ..Schema::default()
+            }),
+            ..ValidationOptions::default()
+        };
+        let result = validate_with_options("Power\n  .core\n    - corrupts\n", &options);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), "concept-not-in-schema");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml_str() {
+        let schema = Schema::from_yaml_str(
+            "concepts: [Trust]\nrequired_facets:\n  Trust: [formation]\nnaming:\n  facets: kebab-case\nclaims:\n  max: 7\n",
+        )
+        .unwrap();
+        assert_eq!(schema.concepts, vec!["Trust"]);
+        assert_eq!(schema.required_facets["Trust"], vec!["formation"]);
+        assert_eq!(schema.facet_naming, Some(NamingConvention::KebabCase));
+        assert_eq!(schema.max_claims, Some(7));
+        assert_eq!(Schema::from_yaml_str(""), Ok(Schema::default()));
+
+        assert!(Schema::from_yaml_str("naming:\n  facets: shouty\n").is_err());
+        assert!(Schema::from_yaml_str("claims:\n  min: 3\n  max: 2\n").is_err());
+        assert!(Schema::from_yaml_str("concept: [Trust]\n").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Ranked full-text search over claims
+//!
+//! Each claim is indexed with its text, conditions, and sources, and scored
+//! against the query with BM25 so rare terms and short claims rank higher.
+
+use std::collections::HashMap;
+
+use crate::document::{Document, Facet};
+
+/// BM25 term-frequency saturation
+const K1: f64 = 1.2;
+/// BM25 length normalization
+const B: f64 = 0.75;
+
+/// A claim matching a search, with where it sits in the hierarchy
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    /// `Concept.facet` (or `Concept.facet.sub`) holding the claim
+    pub target: String,
+    /// Line of the claim, or 0 for claims created in code
+    pub line: usize,
+    /// The claim in canonical form
+    pub claim: String,
+    pub score: f64,
+}
+
+/// Lowercased alphanumeric words
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+struct Entry {
+    target: String,
+    line: usize,
+    claim: String,
+    terms: Vec<String>,
+}
+
+impl Document {
+    /// Claims matching any query term, best match first (then in document order)
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let mut query: Vec<String> = terms(query).collect();
+        query.sort();
+        query.dedup();
+
+        let mut entries = Vec::new();
+        for concept in &self.concepts {
+            collect(&concept.facets, &concept.name, &mut entries);
+        }
+        if query.is_empty() || entries.is_empty() {
+            return Vec::new();
+        }
+
+        let total_len: usize = entries.iter().map(|e| e.terms.len()).sum();
+        let avg_len = total_len as f64 / entries.len() as f64;
+        let n = entries.len() as f64;
+        let idf: HashMap<&str, f64> = query
+            .iter()
+            .map(|term| {
+                let df = entries.iter().filter(|e| e.terms.contains(term)).count() as f64;
+                (term.as_str(), ((n - df + 0.5) / (df + 0.5) + 1.0).ln())
+            })
+            .collect();
+
+        let mut hits: Vec<SearchHit> = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let len = entry.terms.len() as f64;
+                let score: f64 = query
+                    .iter()
+                    .map(|term| {
+                        let tf = entry.terms.iter().filter(|t| *t == term).count() as f64;
+                        idf[term.as_str()] * tf * (K1 + 1.0)
+                            / (tf + K1 * (1.0 - B + B * len / avg_len))
+                    })
+                    .sum();
+                (score > 0.0).then_some(SearchHit {
+                    target: entry.target,
+                    line: entry.line,
+                    claim: entry.claim,
+                    score,
+                })
+            })
+            .collect();
+        // Stable sort keeps document order among equal scores
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        hits
+    }
+}
+
+fn collect(facets: &[Facet], parent: &str, entries: &mut Vec<Entry>) {
+    for facet in facets {
+        let target = format!("{}.{}", parent, facet.name);
+        for claim in &facet.claims {
+            let data = &claim.data;
+            let indexed = std::iter::once(&data.text)
+                .chain(&data.conditions)
+                .chain(&data.sources);
+            entries.push(Entry {
+                target: target.clone(),
+                line: claim.line,
+                claim: data.to_wvf_string(),
+                terms: indexed.flat_map(|text| terms(text)).collect(),
+            });
+        }
+        collect(&facet.sub_facets, &target, entries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranked_search() {
+        let doc = Document::parse(
+            "Government\n  .legitimacy\n    - requires transparency | democratic\n    - derives from consent\n  .power\n    - grows over time | without transparency | without oversight\n\nMarkets\n  .pricing\n    - prices reflect information @hayek\n",
+        );
+
+        let hits = doc.search("Transparency");
+        let found: Vec<(&str, usize)> = hits.iter().map(|h| (h.target.as_str(), h.line)).collect();
+        // The shorter claim ranks first
+        assert_eq!(
+            found,
+            [("Government.legitimacy", 3), ("Government.power", 6)]
+        );
+        assert_eq!(hits[0].claim, "requires transparency | democratic");
+        assert!(hits[0].score > hits[1].score);
+
+        // Sources are indexed; unmatched and empty queries find nothing
+        assert_eq!(doc.search("hayek")[0].line, 10);
+        assert!(doc.search("monarchy").is_empty());
+        assert!(doc.search("  ").is_empty());
+    }
+}
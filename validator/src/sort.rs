@@ -0,0 +1,270 @@
+//! Canonical ordering of concepts, facets, and claims
+//!
+//! Sorting moves nodes but keeps the document's layout: blank lines stay at the
+//! positions they occupied, so concepts remain separated after reordering.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::document::{Claim, Concept, Document, Facet, Trivia};
+
+/// How concepts or facets are ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    /// Keep the existing order
+    Preserve,
+    /// Case-insensitive by name
+    #[default]
+    Alphabetical,
+    /// Most claims first
+    Size,
+}
+
+/// How claims within a facet are ordered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClaimOrder {
+    /// Keep the existing order
+    #[default]
+    Preserve,
+    /// Case-insensitive by claim text
+    Alphabetical,
+    /// Emphatic (`!`) and notable (`*`) claims first, uncertain (`?`) last
+    Modifier,
+    /// Most recently added first, assuming claims are appended; claims created
+    /// in code count as newest
+    Recency,
+}
+
+/// Ordering applied by `Document::sort`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortOrder {
+    pub concepts: SortKey,
+    pub facets: SortKey,
+    pub claims: ClaimOrder,
+}
+
+impl Document {
+    /// Reorder concepts, facets (and sub-facets), and claims; the sort is stable
+    pub fn sort(&mut self, order: SortOrder) {
+        sort_keeping_layout(
+            &mut self.concepts,
+            |c| &mut c.source,
+            |a, b| {
+                compare(
+                    order.concepts,
+                    &a.name,
+                    &b.name,
+                    concept_size(a),
+                    concept_size(b),
+                )
+            },
+        );
+        for concept in &mut self.concepts {
+            sort_facets(&mut concept.facets, order);
+        }
+    }
+}
+
+fn sort_facets(facets: &mut [Facet], order: SortOrder) {
+    sort_keeping_layout(
+        facets,
+        |f| &mut f.source,
+        |a, b| compare(order.facets, &a.name, &b.name, facet_size(a), facet_size(b)),
+    );
+    for facet in facets {
+        sort_claims(&mut facet.claims, order.claims);
+        sort_facets(&mut facet.sub_facets, order);
+    }
+}
+
+fn sort_claims(claims: &mut [Claim], order: ClaimOrder) {
+    match order {
+        ClaimOrder::Preserve => {}
+        ClaimOrder::Alphabetical => sort_keeping_layout(
+            claims,
+            |c| &mut c.source,
+            |a, b| a.data.text.to_lowercase().cmp(&b.data.text.to_lowercase()),
+        ),
+        ClaimOrder::Modifier => sort_keeping_layout(
+            claims,
+            |c| &mut c.source,
+            |a, b| modifier_rank(a).cmp(&modifier_rank(b)),
+        ),
+        ClaimOrder::Recency => sort_keeping_layout(
+            claims,
+            |c| &mut c.source,
+            |a, b| recency(b).cmp(&recency(a)),
+        ),
+    }
+}
+
+fn compare(key: SortKey, a: &str, b: &str, size_a: usize, size_b: usize) -> Ordering {
+    match key {
+        SortKey::Preserve => Ordering::Equal,
+        SortKey::Alphabetical => a.to_lowercase().cmp(&b.to_lowercase()),
+        SortKey::Size => size_b.cmp(&size_a),
+    }
+}
+
+fn concept_size(concept: &Concept) -> usize {
+    concept.facets.iter().map(facet_size).sum()
+}
+
+fn facet_size(facet: &Facet) -> usize {
+    facet.claims.len() + facet.sub_facets.iter().map(facet_size).sum::<usize>()
+}
+
+fn modifier_rank(claim: &Claim) -> u8 {
+    let has = |symbol| claim.data.modifiers.iter().any(|m| m.symbol == symbol);
+    if has('!') {
+        0
+    } else if has('*') {
+        1
+    } else if has('?') {
+        3
+    } else {
+        2
+    }
+}
+
+fn recency(claim: &Claim) -> usize {
+    if claim.line == 0 {
+        usize::MAX
+    } else {
+        claim.line
+    }
+}
+
+/// Stable sort that leaves each position's leading blank lines in place
+fn sort_keeping_layout<T>(
+    items: &mut [T],
+    source: impl Fn(&mut T) -> &mut Option<Trivia>,
+    compare: impl Fn(&T, &T) -> Ordering,
+) {
+    let leading: Vec<Option<Vec<String>>> = items
+        .iter_mut()
+        .map(|item| {
+            source(item)
+                .as_mut()
+                .map(|t| std::mem::take(&mut t.leading))
+        })
+        .collect();
+    items.sort_by(compare);
+    for (item, lines) in items.iter_mut().zip(leading) {
+        if let Some(trivia) = source(item).as_mut() {
+            trivia.leading = lines.unwrap_or_default();
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(SortKey::Preserve),
+            "alpha" | "alphabetical" => Ok(SortKey::Alphabetical),
+            "size" => Ok(SortKey::Size),
+            _ => Err(format!(
+                "unknown sort key '{}' (expected preserve, alpha, or size)",
+                s
+            )),
+        }
+    }
+}
+
+impl FromStr for ClaimOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(ClaimOrder::Preserve),
+            "alpha" | "alphabetical" => Ok(ClaimOrder::Alphabetical),
+            "modifier" => Ok(ClaimOrder::Modifier),
+            "recency" => Ok(ClaimOrder::Recency),
+            _ => Err(format!(
+                "unknown claim order '{}' (expected preserve, alpha, modifier, or recency)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SortKey::Preserve => "preserve",
+            SortKey::Alphabetical => "alpha",
+            SortKey::Size => "size",
+        })
+    }
+}
+
+impl fmt::Display for ClaimOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ClaimOrder::Preserve => "preserve",
+            ClaimOrder::Alphabetical => "alpha",
+            ClaimOrder::Modifier => "modifier",
+            ClaimOrder::Recency => "recency",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Trust\n  .formation\n    - slow\n\nPower\n  .nature\n    - corrupts?\n    - reveals character !\n  .institutional\n    - self-preserving\n";
+
+    #[test]
+    fn test_sort_default_keeps_separators() {
+        let mut doc = Document::parse(INPUT);
+        doc.sort(SortOrder::default());
+        assert_eq!(
+            doc.to_string(),
+            "Power\n  .institutional\n    - self-preserving\n  .nature\n    - corrupts?\n    - reveals character !\n\nTrust\n  .formation\n    - slow\n"
+        );
+        assert!(doc.validate().is_valid());
+    }
+
+    #[test]
+    fn test_sort_by_size_and_modifier() {
+        let mut doc = Document::parse(INPUT);
+        doc.sort(SortOrder {
+            concepts: SortKey::Size,
+            facets: SortKey::Preserve,
+            claims: ClaimOrder::Modifier,
+        });
+        let power = &doc.concepts[0];
+        assert_eq!(power.name, "Power");
+        assert_eq!(power.facets[0].name, "nature");
+        assert_eq!(power.facets[0].claims[0].data.text, "reveals character !");
+    }
+
+    #[test]
+    fn test_sort_claims_by_recency() {
+        let mut doc = Document::parse("Power\n  .core\n    - old\n    - new\n");
+        doc.concepts[0].facets[0]
+            .claims
+            .push(Claim::parse("newest"));
+        doc.sort(SortOrder {
+            claims: ClaimOrder::Recency,
+            ..SortOrder::default()
+        });
+        let texts: Vec<_> = doc.concepts[0].facets[0]
+            .claims
+            .iter()
+            .map(|c| c.data.text.as_ref())
+            .collect();
+        assert_eq!(texts, ["newest", "new", "old"]);
+    }
+
+    #[test]
+    fn test_parse_sort_keys() {
+        assert_eq!("size".parse(), Ok(SortKey::Size));
+        assert_eq!("modifier".parse(), Ok(ClaimOrder::Modifier));
+        assert!("sideways".parse::<SortKey>().is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Source registry: an optional `sources:` appendix describing `@source` handles
+//!
+//! ```text
+//! sources:
+//!   personal-experience: lived observation
+//!   acton: Lord Acton, letter to Bishop Creighton (1887)
+//! ```
+//!
+//! Once a document has a registry, every cited handle is expected to be in it.
+//! Documents without one cite sources freely.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::document::{Document, Facet, SourceEntry};
+use crate::{LineType, ParsedLine, ValidationError, owned};
+
+/// A `handle: description` entry in the `sources:` section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceDefinition<'a> {
+    pub handle: Cow<'a, str>,
+    /// Free text, typically a citation or URL; may be empty
+    pub description: Cow<'a, str>,
+}
+
+impl SourceDefinition<'_> {
+    /// Parse an entry line's content (without indentation)
+    pub fn parse(content: &str) -> Option<SourceDefinition<'_>> {
+        let (handle, description) = content.split_once(':')?;
+        let handle = handle.trim();
+        let handle = handle.strip_prefix('@').unwrap_or(handle);
+        if handle.is_empty() || handle.contains(char::is_whitespace) {
+            return None;
+        }
+        Some(SourceDefinition {
+            handle: Cow::Borrowed(handle),
+            description: Cow::Borrowed(description.trim()),
+        })
+    }
+
+    pub fn into_owned(self) -> SourceDefinition<'static> {
+        SourceDefinition {
+            handle: owned(self.handle),
+            description: owned(self.description),
+        }
+    }
+}
+
+/// Tokenize an indented line inside the `sources:` section
+pub(crate) fn parse_line<'a>(
+    content: &'a str,
+    line_number: usize,
+    errors: &mut Vec<ValidationError>,
+) -> LineType<'a> {
+    match SourceDefinition::parse(content) {
+        Some(definition) => LineType::SourceDefinition(definition),
+        None => {
+            errors.push(ValidationError::InvalidSourceDefinition { line: line_number });
+            LineType::Blank
+        }
+    }
+}
+
+/// Warn about cited sources missing from the document's registry, if it has one
+pub(crate) fn undefined_sources(lines: &[ParsedLine<'_>]) -> Vec<ValidationError> {
+    if !lines
+        .iter()
+        .any(|line| matches!(line.line_type, LineType::SourcesSection))
+    {
+        return Vec::new();
+    }
+    let registry: HashSet<&str> = lines
+        .iter()
+        .filter_map(|line| match &line.line_type {
+            LineType::SourceDefinition(definition) => Some(definition.handle.as_ref()),
+            _ => None,
+        })
+        .collect();
+
+    let mut warnings = Vec::new();
+    for line in lines {
+        let LineType::Claim(claim) = &line.line_type else {
+            continue;
+        };
+        for source in &claim.sources {
+            if !registry.contains(source.as_ref()) {
+                warnings.push(ValidationError::UndefinedSource {
+                    line: line.line_number,
+                    handle: source.to_string(),
+                });
+            }
+        }
+    }
+    warnings
+}
+
+/// How often a source is cited, with its registry description if it has one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+    pub handle: String,
+    pub count: usize,
+    pub description: Option<String>,
+}
+
+impl Document {
+    /// Every cited or registered source, most cited first (then by handle)
+    ///
+    /// Registered sources that are never cited are included with a count of 0.
+    pub fn citations(&self) -> Vec<Citation> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for source in self.sources.iter().flat_map(|s| &s.entries) {
+            counts.entry(&source.handle).or_default();
+        }
+        for concept in &self.concepts {
+            count_facets(&concept.facets, &mut counts);
+        }
+
+        let mut citations: Vec<Citation> = counts
+            .into_iter()
+            .map(|(handle, count)| Citation {
+                handle: handle.to_string(),
+                count,
+                description: self.source(handle).map(|s| s.description.clone()),
+            })
+            .collect();
+        citations.sort_by_key(|c| std::cmp::Reverse(c.count));
+        citations
+    }
+
+    /// Look up a registered source by handle
+    pub fn source(&self, handle: &str) -> Option<&SourceEntry> {
+        self.sources
+            .iter()
+            .flat_map(|s| &s.entries)
+            .find(|entry| entry.handle == handle)
+    }
+}
+
+fn count_facets<'d>(facets: &'d [Facet], counts: &mut BTreeMap<&'d str, usize>) {
+    for facet in facets {
+        for claim in &facet.claims {
+            for source in &claim.data.sources {
+                *counts.entry(source.as_ref()).or_default() += 1;
+            }
+        }
+        count_facets(&facet.sub_facets, counts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate;
+
+    const INPUT: &str = "Power\n  .nature\n    - corrupts @acton\n    - reveals character @acton @caro\n\nsources:\n  acton: Lord Acton (1887)\n  machiavelli: The Prince\n";
+
+    #[test]
+    fn test_undefined_source() {
+        let result = validate(INPUT);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        assert_eq!(
+            result.warnings,
+            [ValidationError::UndefinedSource {
+                line: 4,
+                handle: "caro".into()
+            }]
+        );
+
+        // Without a registry, sources are not checked
+        assert!(!validate("Power\n  .nature\n    - corrupts @acton\n").has_warnings());
+    }
+
+    #[test]
+    fn test_invalid_source_definition() {
+        let result =
+            validate("Power\n  .nature\n    - corrupts\n\nsources:\n  no description here\n");
+        assert_eq!(
+            result.errors,
+            [ValidationError::InvalidSourceDefinition { line: 6 }]
+        );
+    }
+
+    #[test]
+    fn test_citations() {
+        let doc = Document::parse(INPUT);
+        let citations = doc.citations();
+        let counts: Vec<(&str, usize)> = citations
+            .iter()
+            .map(|c| (c.handle.as_str(), c.count))
+            .collect();
+        assert_eq!(counts, [("acton", 2), ("caro", 1), ("machiavelli", 0)]);
+        assert_eq!(
+            citations[0].description.as_deref(),
+            Some("Lord Acton (1887)")
+        );
+        assert_eq!(doc.to_string(), INPUT);
+    }
+
+    #[test]
+    fn test_build_with_sources() {
+        let doc = crate::DocumentBuilder::new()
+            .concept("Power")
+            .facet("nature")
+            .claim("corrupts @acton")
+            .source("acton", "Lord Acton")
+            .build()
+            .unwrap();
+        assert_eq!(
+            doc.to_wvf_string(),
+            "Power\n  .nature\n    - corrupts @acton\n\nsources:\n  acton: Lord Acton\n"
+        );
+    }
+}
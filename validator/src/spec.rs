@@ -0,0 +1,349 @@
+//! The brief form and modifier vocabulary, extensible at runtime
+//!
+//! The built-in vocabulary is generated from `spec/tokens.yaml` at compile
+//! time. A `SpecConfig` starts from it and can add operators and modifiers
+//! (or give existing ones new meanings), so an organization can validate
+//! documents against an extended spec without recompiling:
+//!
+//! ```yaml
+//! brief_forms:
+//!   - symbol: "-->"
+//!     meaning: "eventually leads to"
+//! modifiers:
+//!   - symbol: "%"
+//!     meaning: "probabilistic"
+//! ```
+//!
+//! A copy of `spec/tokens.yaml` is also a valid extension file; sections other
+//! than `brief_forms` and `modifiers` are ignored.
+
+use std::sync::{LazyLock, Mutex};
+use thiserror::Error;
+
+use crate::{BRIEF_FORMS, MODIFIERS};
+
+/// Symbols that make up operators, known or not (`=>`, `->`, `!=`)
+const OPERATOR_SYMBOLS: &str = "-=<>~!/";
+
+/// Characters with a fixed meaning in claims, which no operator or modifier may use
+const RESERVED: &[char] = &['|', '@', '&', '[', ']'];
+
+static BUILTIN: LazyLock<SpecConfig> = LazyLock::new(|| SpecConfig {
+    brief_forms: BRIEF_FORMS.to_vec(),
+    modifiers: MODIFIERS
+        .iter()
+        .filter_map(|(symbol, meaning)| Some((single_char(symbol)?, *meaning)))
+        .collect(),
+});
+
+/// Errors loading or extending a vocabulary
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    #[error("could not read {path}: {message}")]
+    Read { path: String, message: String },
+
+    #[error("invalid spec file: {0}")]
+    Parse(String),
+
+    #[error(
+        "invalid brief form '{0}' (use a word or a run of symbols, without whitespace or |@&[])"
+    )]
+    InvalidBriefForm(String),
+
+    #[error("invalid modifier '{0}' (use a single character other than whitespace or |@&[])")]
+    InvalidModifier(String),
+}
+
+/// The operators and modifiers claims are parsed with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecConfig {
+    /// Symbols with their meanings, in the order they are documented
+    brief_forms: Vec<(&'static str, &'static str)>,
+    modifiers: Vec<(char, &'static str)>,
+}
+
+impl Default for SpecConfig {
+    fn default() -> Self {
+        SpecConfig::builtin().clone()
+    }
+}
+
+impl SpecConfig {
+    /// The vocabulary from `spec/tokens.yaml`
+    pub fn builtin() -> &'static SpecConfig {
+        &BUILTIN
+    }
+
+    /// The built-in vocabulary extended with the `brief_forms` and `modifiers`
+    /// of a YAML file
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(path: impl AsRef<std::path::Path>) -> Result<SpecConfig, SpecError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| SpecError::Read {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        SpecConfig::from_yaml_str(&text)
+    }
+
+    /// Like `from_yaml`, from YAML text
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(text: &str) -> Result<SpecConfig, SpecError> {
+        #[derive(serde::Deserialize)]
+        struct Extension {
+            #[serde(default)]
+            brief_forms: Vec<Entry>,
+            #[serde(default)]
+            modifiers: Vec<Entry>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Entry {
+            symbol: String,
+            meaning: String,
+        }
+
+        let extension: Extension =
+            serde_yaml::from_str(text).map_err(|e| SpecError::Parse(e.to_string()))?;
+        let mut spec = SpecConfig::default();
+        for entry in extension.brief_forms {
+            spec.add_brief_form(&entry.symbol, &entry.meaning)?;
+        }
+        for entry in extension.modifiers {
+            let symbol =
+                single_char(&entry.symbol).ok_or(SpecError::InvalidModifier(entry.symbol))?;
+            spec.add_modifier(symbol, &entry.meaning)?;
+        }
+        Ok(spec)
+    }
+
+    /// Add an operator, or replace the meaning of an existing one
+    pub fn add_brief_form(&mut self, symbol: &str, meaning: &str) -> Result<(), SpecError> {
+        let word = symbol.chars().all(char::is_alphanumeric);
+        let symbolic = symbol.chars().all(|c| !c.is_alphanumeric());
+        let valid = !symbol.is_empty()
+            && (word || symbolic)
+            && !symbol.contains(|c: char| c.is_whitespace() || RESERVED.contains(&c));
+        if !valid {
+            return Err(SpecError::InvalidBriefForm(symbol.to_string()));
+        }
+        let meaning = intern(meaning);
+        match self.brief_forms.iter_mut().find(|(op, _)| *op == symbol) {
+            Some(existing) => existing.1 = meaning,
+            None => self.brief_forms.push((intern(symbol), meaning)),
+        }
+        Ok(())
+    }
+
+    /// Add a modifier, or replace the meaning of an existing one
+    ///
+    /// Symbols attach to the end of a term (`abuse^`); a letter (like the
+    /// built-in `v`) only counts as a modifier standing on its own.
+    pub fn add_modifier(&mut self, symbol: char, meaning: &str) -> Result<(), SpecError> {
+        if symbol.is_whitespace() || symbol.is_control() || RESERVED.contains(&symbol) {
+            return Err(SpecError::InvalidModifier(symbol.to_string()));
+        }
+        let meaning = intern(meaning);
+        match self.modifiers.iter_mut().find(|(s, _)| *s == symbol) {
+            Some(existing) => existing.1 = meaning,
+            None => self.modifiers.push((symbol, meaning)),
+        }
+        Ok(())
+    }
+
+    /// Operators with their meanings
+    pub fn brief_forms(&self) -> &[(&'static str, &'static str)] {
+        &self.brief_forms
+    }
+
+    /// Modifiers with their meanings
+    pub fn modifiers(&self) -> &[(char, &'static str)] {
+        &self.modifiers
+    }
+
+    /// The operator `word` is, if it is one
+    pub fn operator(&self, word: &str) -> Option<&'static str> {
+        self.brief_forms
+            .iter()
+            .find(|(op, _)| *op == word)
+            .map(|(op, _)| *op)
+    }
+
+    /// What an operator means in prose (`causes, leads to` gives `causes`)
+    pub fn operator_meaning(&self, operator: &str) -> Option<&'static str> {
+        self.brief_forms
+            .iter()
+            .find(|(op, _)| *op == operator)
+            .map(|(_, meaning)| first_meaning(meaning))
+    }
+
+    /// What a modifier means in prose
+    pub fn modifier_meaning(&self, symbol: char) -> Option<&'static str> {
+        self.modifiers
+            .iter()
+            .find(|(s, _)| *s == symbol)
+            .map(|(_, meaning)| first_meaning(meaning))
+    }
+
+    pub fn is_modifier(&self, symbol: char) -> bool {
+        self.modifiers.iter().any(|(s, _)| *s == symbol)
+    }
+
+    /// Modifiers that attach to the end of a term; letters only stand alone
+    pub(crate) fn attached_modifiers(&self) -> Vec<char> {
+        self.modifiers
+            .iter()
+            .map(|(symbol, _)| *symbol)
+            .filter(|symbol| !symbol.is_alphanumeric())
+            .collect()
+    }
+
+    /// Whether `c` can be part of a symbolic operator, known or not
+    pub(crate) fn is_operator_symbol(&self, c: char) -> bool {
+        OPERATOR_SYMBOLS.contains(c)
+            || self
+                .brief_forms
+                .iter()
+                .any(|(op, _)| !c.is_alphanumeric() && op.contains(c))
+    }
+}
+
+/// The leading sense of a spec meaning
+fn first_meaning(meaning: &'static str) -> &'static str {
+    meaning.split(',').next().unwrap_or(meaning).trim()
+}
+
+fn single_char(symbol: &str) -> Option<char> {
+    let mut chars = symbol.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// A `'static` copy of `text`, shared between every vocabulary that uses it
+///
+/// Parsed claims refer to operators as `&'static str`, so runtime additions
+/// live for the rest of the process; interning keeps reloading a spec from
+/// growing memory.
+fn intern(text: &str) -> &'static str {
+    static INTERNED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+    let mut interned = INTERNED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(existing) = interned.iter().find(|s| **s == text) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(text.to_string().into_boxed_str());
+    interned.push(leaked);
+    leaked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_matches_tokens_yaml() {
+        let spec = SpecConfig::builtin();
+        assert_eq!(spec.operator("=>"), Some("=>"));
+        assert_eq!(spec.operator("-->"), None);
+        assert!(spec.is_modifier('v'));
+        assert_eq!(spec.attached_modifiers(), vec!['^', '!', '?', '*']);
+    }
+
+    #[test]
+    fn test_add_and_redefine() {
+        let mut spec = SpecConfig::default();
+        spec.add_brief_form("-->", "eventually leads to").unwrap();
+        spec.add_brief_form("~", "resembles").unwrap();
+        spec.add_modifier('%', "probabilistic").unwrap();
+        assert_eq!(spec.operator("-->"), Some("-->"));
+        assert_eq!(spec.brief_forms().len(), BRIEF_FORMS.len() + 1);
+        assert!(spec.brief_forms().contains(&("~", "resembles")));
+        assert!(spec.is_modifier('%'));
+        assert!(spec.is_operator_symbol('>'));
+    }
+
+    #[test]
+    fn test_rejects_unusable_symbols() {
+        let mut spec = SpecConfig::default();
+        for symbol in ["", "a b", "|>", "x>", "&&"] {
+            assert_eq!(
+                spec.add_brief_form(symbol, "?"),
+                Err(SpecError::InvalidBriefForm(symbol.to_string()))
+            );
+        }
+        assert!(spec.add_modifier('@', "?").is_err());
+        assert!(spec.add_modifier(' ', "?").is_err());
+        assert_eq!(spec, SpecConfig::default());
+    }
+
+    #[test]
+    fn test_validation_uses_extended_vocabulary() {
+        let input = "Risk\n  .drift\n    - neglect-->failure %\n    - exposure %\n";
+        let result = crate::validate(input);
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.code() == "unknown-operator")
+        );
+
+        let mut options = crate::ValidationOptions::default();
+        options
+            .spec
+            .add_brief_form("-->", "eventually leads to")
+            .unwrap();
+        options.spec.add_modifier('%', "probabilistic").unwrap();
+        let result = crate::validate_with_options(input, &options);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+        let crate::LineType::Claim(claim) = &result.lines[2].line_type else {
+            panic!("expected a claim");
+        };
+        assert_eq!(claim.brief_forms[0].operator, "-->");
+        assert_eq!(claim.brief_forms[0].left_operand, "neglect");
+        let right = claim.expression.as_ref().unwrap().last_operand();
+        assert_eq!(
+            (right.text.as_ref(), right.modifiers.as_slice()),
+            ("failure", &['%'][..])
+        );
+        let crate::LineType::Claim(claim) = &result.lines[3].line_type else {
+            panic!("expected a claim");
+        };
+        assert_eq!(claim.modifiers[0].symbol, '%');
+        assert_eq!(claim.modifiers[0].attached_to, "exposure");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml_extends_builtin() {
+        let yaml = "\
+brief_forms:
+  - symbol: \"-->\"
+    meaning: \"eventually leads to\"
+modifiers:
+  - symbol: \"%\"
+    meaning: \"probabilistic\"
+";
+        let spec = SpecConfig::from_yaml_str(yaml).unwrap();
+        assert_eq!(spec.operator("-->"), Some("-->"));
+        assert_eq!(spec.operator("vs"), Some("vs"));
+        assert!(spec.is_modifier('%'));
+
+        let bad = "modifiers:\n  - symbol: \"%%\"\n    meaning: \"twice\"\n";
+        assert_eq!(
+            SpecConfig::from_yaml_str(bad),
+            Err(SpecError::InvalidModifier("%%".to_string()))
+        );
+        assert!(matches!(
+            SpecConfig::from_yaml_str("brief_forms: 3"),
+            Err(SpecError::Parse(_))
+        ));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_tokens_yaml_is_a_valid_extension() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../spec/tokens.yaml");
+        assert_eq!(SpecConfig::from_yaml(path).unwrap(), SpecConfig::default());
+    }
+}
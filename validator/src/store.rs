@@ -0,0 +1,450 @@
+//! SQLite storage for documents (`sqlite` feature)
+//!
+//! A [`Store`] mirrors a document into relational tables so large worldviews
+//! can be queried with SQL and served to other programs:
+//!
+//! | Table | Rows |
+//! |-------|------|
+//! | `concepts` | `id`, `name`, `position` |
+//! | `facets` | `id`, `concept_id`, `parent_id` (sub-facets), `name`, `path`, `position` |
+//! | `claims` | `id`, `facet_id`, `stable_id`, `occurrence`, `position`, `text`, `wvf` |
+//! | `conditions` | `claim_id`, `position`, `text` |
+//! | `claim_sources` | `claim_id`, `position`, `handle` |
+//! | `claim_references` | `claim_id`, `position`, `target` |
+//! | `sources` | `handle`, `description`, `position` |
+//! | `includes` | `path`, `position` |
+//!
+//! Syncing is incremental: claims are matched by facet and stable ID, so only
+//! new or edited claims are written and row IDs of untouched claims survive.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use rusqlite::{Connection, params};
+use thiserror::Error;
+
+use crate::document::{Claim, Concept, Document, Facet, Include, SourceEntry, Sources};
+
+const SCHEMA: &str = "
+PRAGMA foreign_keys = ON;
+CREATE TABLE IF NOT EXISTS concepts (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    position INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS facets (
+    id INTEGER PRIMARY KEY,
+    concept_id INTEGER NOT NULL REFERENCES concepts(id) ON DELETE CASCADE,
+    parent_id INTEGER REFERENCES facets(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL UNIQUE,
+    position INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS claims (
+    id INTEGER PRIMARY KEY,
+    facet_id INTEGER NOT NULL REFERENCES facets(id) ON DELETE CASCADE,
+    stable_id TEXT NOT NULL,
+    occurrence INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    text TEXT NOT NULL,
+    wvf TEXT NOT NULL,
+    UNIQUE (facet_id, stable_id, occurrence)
+);
+CREATE TABLE IF NOT EXISTS conditions (
+    claim_id INTEGER NOT NULL REFERENCES claims(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    text TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS claim_sources (
+    claim_id INTEGER NOT NULL REFERENCES claims(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    handle TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS claim_references (
+    claim_id INTEGER NOT NULL REFERENCES claims(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    target TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sources (
+    handle TEXT PRIMARY KEY,
+    description TEXT NOT NULL,
+    position INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS includes (
+    path TEXT NOT NULL,
+    position INTEGER NOT NULL
+);
+";
+
+/// Error reading or writing a store
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+/// What a sync changed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    /// Claims written because they are new or were edited
+    pub inserted: usize,
+    /// Claims removed because they are gone or were edited
+    pub deleted: usize,
+    /// Claims already stored (possibly at a new position)
+    pub unchanged: usize,
+}
+
+/// A document stored in a SQLite database
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Open (or create) a database file
+    pub fn open(path: impl AsRef<Path>) -> Result<Store, StoreError> {
+        Store::init(Connection::open(path)?)
+    }
+
+    /// Open a private in-memory database
+    pub fn open_in_memory() -> Result<Store, StoreError> {
+        Store::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Store, StoreError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Store { connection })
+    }
+
+    /// The underlying connection, for running SQL queries
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Make the store match `doc`, writing only what changed
+    pub fn sync(&mut self, doc: &Document) -> Result<SyncStats, StoreError> {
+        let tx = self.connection.transaction()?;
+        let mut stats = SyncStats::default();
+
+        let mut concepts = HashSet::new();
+        let mut facets = HashSet::new();
+        for (position, concept) in (0i64..).zip(&doc.concepts) {
+            let concept_id: i64 = tx.query_row(
+                "INSERT INTO concepts (name, position) VALUES (?1, ?2)
+                 ON CONFLICT (name) DO UPDATE SET position = excluded.position
+                 RETURNING id",
+                params![concept.name, position],
+                |row| row.get(0),
+            )?;
+            concepts.insert(concept_id);
+            let parent = FacetParent {
+                concept_id,
+                facet_id: None,
+                path: &concept.name,
+            };
+            sync_facets(&tx, &concept.facets, &parent, &mut facets, &mut stats)?;
+        }
+        // Claims of removed facets are deleted with them by the schema's cascade
+        let mut stmt = tx.prepare("SELECT facet_id FROM claims")?;
+        for facet_id in stmt.query_map([], |row| row.get::<_, i64>(0))? {
+            if !facets.contains(&facet_id?) {
+                stats.deleted += 1;
+            }
+        }
+        drop(stmt);
+        delete_missing(&tx, "concepts", &concepts)?;
+        delete_missing(&tx, "facets", &facets)?;
+
+        tx.execute("DELETE FROM sources", [])?;
+        for (position, entry) in (0i64..).zip(doc.sources.iter().flat_map(|s| &s.entries)) {
+            tx.execute(
+                "INSERT OR REPLACE INTO sources (handle, description, position) VALUES (?1, ?2, ?3)",
+                params![entry.handle, entry.description, position],
+            )?;
+        }
+        tx.execute("DELETE FROM includes", [])?;
+        for (position, include) in (0i64..).zip(&doc.includes) {
+            tx.execute(
+                "INSERT INTO includes (path, position) VALUES (?1, ?2)",
+                params![include.path, position],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(stats)
+    }
+
+    /// Read the stored document back
+    ///
+    /// Line numbers and original formatting are not stored, so the result
+    /// renders in canonical form.
+    pub fn load(&self) -> Result<Document, StoreError> {
+        let mut doc = Document::default();
+
+        let mut stmt = self
+            .connection
+            .prepare("SELECT path FROM includes ORDER BY position")?;
+        for path in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            doc.includes.push(Include::new(path?));
+        }
+
+        let mut stmt = self
+            .connection
+            .prepare("SELECT id, name FROM concepts ORDER BY position")?;
+        let concepts: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        for (concept_id, name) in concepts {
+            let mut concept = Concept::new(name);
+            concept.facets = self.load_facets(concept_id, None)?;
+            doc.concepts.push(concept);
+        }
+
+        let mut stmt = self
+            .connection
+            .prepare("SELECT handle, description FROM sources ORDER BY position")?;
+        let entries: Vec<SourceEntry> = stmt
+            .query_map([], |row| {
+                Ok(SourceEntry::new(
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                ))
+            })?
+            .collect::<Result<_, _>>()?;
+        if !entries.is_empty() {
+            doc.sources = Some(Sources {
+                entries,
+                ..Sources::default()
+            });
+        }
+        Ok(doc)
+    }
+
+    fn load_facets(
+        &self,
+        concept_id: i64,
+        parent_id: Option<i64>,
+    ) -> Result<Vec<Facet>, StoreError> {
+        let mut stmt = self.connection.prepare(
+            "SELECT id, name FROM facets WHERE concept_id = ?1 AND parent_id IS ?2 ORDER BY position",
+        )?;
+        let rows: Vec<(i64, String)> = stmt
+            .query_map(params![concept_id, parent_id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut claims = self
+            .connection
+            .prepare("SELECT wvf FROM claims WHERE facet_id = ?1 ORDER BY position")?;
+        let mut facets = Vec::new();
+        for (facet_id, name) in rows {
+            let mut facet = Facet::new(name);
+            for wvf in claims.query_map([facet_id], |row| row.get::<_, String>(0))? {
+                facet.claims.push(Claim::parse(&wvf?));
+            }
+            facet.sub_facets = self.load_facets(concept_id, Some(facet_id))?;
+            facets.push(facet);
+        }
+        Ok(facets)
+    }
+}
+
+/// Where a list of facets sits
+struct FacetParent<'p> {
+    concept_id: i64,
+    facet_id: Option<i64>,
+    path: &'p str,
+}
+
+fn sync_facets(
+    tx: &Connection,
+    facets: &[Facet],
+    parent: &FacetParent<'_>,
+    seen: &mut HashSet<i64>,
+    stats: &mut SyncStats,
+) -> Result<(), StoreError> {
+    for (position, facet) in (0i64..).zip(facets) {
+        let path = format!("{}.{}", parent.path, facet.name);
+        let facet_id: i64 = tx.query_row(
+            "INSERT INTO facets (concept_id, parent_id, name, path, position) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (path) DO UPDATE SET
+                 concept_id = excluded.concept_id, parent_id = excluded.parent_id, position = excluded.position
+             RETURNING id",
+            params![parent.concept_id, parent.facet_id, facet.name, path, position],
+            |row| row.get(0),
+        )?;
+        seen.insert(facet_id);
+        sync_claims(tx, facet_id, &facet.claims, stats)?;
+
+        let nested = FacetParent {
+            concept_id: parent.concept_id,
+            facet_id: Some(facet_id),
+            path: &path,
+        };
+        sync_facets(tx, &facet.sub_facets, &nested, seen, stats)?;
+    }
+    Ok(())
+}
+
+fn sync_claims(
+    tx: &Connection,
+    facet_id: i64,
+    claims: &[Claim],
+    stats: &mut SyncStats,
+) -> Result<(), StoreError> {
+    let mut stmt =
+        tx.prepare("SELECT id, stable_id, occurrence FROM claims WHERE facet_id = ?1")?;
+    let mut stored: HashMap<(String, i64), i64> = stmt
+        .query_map([facet_id], |row| {
+            Ok(((row.get(1)?, row.get(2)?), row.get(0)?))
+        })?
+        .collect::<Result<_, _>>()?;
+
+    // Identical claims in one facet are told apart by how many came before
+    let mut occurrences: HashMap<String, i64> = HashMap::new();
+    for (position, claim) in (0i64..).zip(claims) {
+        let stable_id = claim.data.stable_id();
+        let occurrence = occurrences.entry(stable_id.clone()).or_default();
+        let key = (stable_id, *occurrence);
+        *occurrence += 1;
+
+        if let Some(claim_id) = stored.remove(&key) {
+            tx.execute(
+                "UPDATE claims SET position = ?1 WHERE id = ?2",
+                params![position, claim_id],
+            )?;
+            stats.unchanged += 1;
+            continue;
+        }
+        let data = &claim.data;
+        let claim_id: i64 = tx.query_row(
+            "INSERT INTO claims (facet_id, stable_id, occurrence, position, text, wvf)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6) RETURNING id",
+            params![
+                facet_id,
+                key.0,
+                key.1,
+                position,
+                data.text,
+                data.to_wvf_string()
+            ],
+            |row| row.get(0),
+        )?;
+        for (table, column, values) in [
+            ("conditions", "text", &data.conditions),
+            ("claim_sources", "handle", &data.sources),
+            ("claim_references", "target", &data.references),
+        ] {
+            for (position, value) in (0i64..).zip(values) {
+                tx.execute(
+                    &format!(
+                        "INSERT INTO {table} (claim_id, position, {column}) VALUES (?1, ?2, ?3)"
+                    ),
+                    params![claim_id, position, value],
+                )?;
+            }
+        }
+        stats.inserted += 1;
+    }
+
+    for claim_id in stored.into_values() {
+        tx.execute("DELETE FROM claims WHERE id = ?1", [claim_id])?;
+        stats.deleted += 1;
+    }
+    Ok(())
+}
+
+/// Delete rows of `table` whose IDs were not seen during a sync
+fn delete_missing(tx: &Connection, table: &str, seen: &HashSet<i64>) -> Result<(), StoreError> {
+    let mut stmt = tx.prepare(&format!("SELECT id FROM {table}"))?;
+    let ids: Vec<i64> = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    for id in ids.into_iter().filter(|id| !seen.contains(id)) {
+        tx.execute(&format!("DELETE FROM {table} WHERE id = ?1"), [id])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "Power\n  .nature\n    - corrupts | absolute @acton &Trust.formation\n    - reveals character\n\nTrust\n  .formation\n    - slow\n\nsources:\n  acton: Lord Acton (1887)\n";
+
+    #[test]
+    fn test_round_trip_and_query() {
+        let mut store = Store::open_in_memory().unwrap();
+        let doc = Document::parse(INPUT);
+        let stats = store.sync(&doc).unwrap();
+        assert_eq!(
+            stats,
+            SyncStats {
+                inserted: 3,
+                deleted: 0,
+                unchanged: 0
+            }
+        );
+        assert_eq!(store.load().unwrap().to_wvf_string(), INPUT);
+
+        let cited: Vec<String> = store
+            .connection()
+            .prepare(
+                "SELECT f.path || ': ' || c.text FROM claims c
+                 JOIN facets f ON f.id = c.facet_id
+                 JOIN claim_sources s ON s.claim_id = c.id WHERE s.handle = 'acton'",
+            )
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(cited, ["Power.nature: corrupts"]);
+    }
+
+    #[test]
+    fn test_incremental_sync() {
+        let mut store = Store::open_in_memory().unwrap();
+        store.sync(&Document::parse(INPUT)).unwrap();
+        let id_of = |store: &Store, text: &str| -> i64 {
+            store
+                .connection()
+                .query_row("SELECT id FROM claims WHERE text = ?1", [text], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        };
+        let slow = id_of(&store, "slow");
+
+        // Edit one claim and drop a concept: untouched claims keep their rows
+        let edited = "Power\n  .nature\n    - reveals character\n    - corrupts | absolute @acton\n\nTrust\n  .formation\n    - slow\n";
+        let stats = store.sync(&Document::parse(edited)).unwrap();
+        assert_eq!(
+            stats,
+            SyncStats {
+                inserted: 1,
+                deleted: 1,
+                unchanged: 2
+            }
+        );
+        assert_eq!(id_of(&store, "slow"), slow);
+        assert_eq!(store.load().unwrap().to_wvf_string(), edited);
+
+        let stats = store
+            .sync(&Document::parse("Trust\n  .formation\n    - slow\n"))
+            .unwrap();
+        assert_eq!(
+            stats,
+            SyncStats {
+                inserted: 0,
+                deleted: 2,
+                unchanged: 1
+            }
+        );
+        let remaining: i64 = store
+            .connection()
+            .query_row("SELECT count(*) FROM conditions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
+}
@@ -0,0 +1,87 @@
+//! "Did you mean" suggestions for misspelled names
+
+/// Levenshtein distance, counted in chars
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// The candidate closest to `word`, if it is near enough to be a likely typo
+///
+/// Allows one edit per four characters (at least one); ties go to the
+/// alphabetically first candidate so suggestions are stable.
+pub(crate) fn closest<'a>(
+    word: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let limit = (word.chars().count() / 4).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+/// Unicode arrows and relation symbols people type in place of brief forms
+pub(crate) fn is_arrow(c: char) -> bool {
+    matches!(c, '→' | '⇒' | '⟶' | '←' | '⇐' | '↔' | '⇔' | '≈' | '≅' | '∼' | '≡' | '≠')
+}
+
+/// The brief form an unknown operator most likely meant
+pub(crate) fn operator(unknown: &str) -> &'static str {
+    let has = |set: &str| unknown.chars().any(|c| set.contains(c));
+    if has("!/≠↔⇔") || (has("<←⇐") && has(">→⇒⟶")) {
+        "vs"
+    } else if has("<>→⇒⟶←⇐") {
+        "=>"
+    } else if has("~≈≅∼") {
+        "~"
+    } else {
+        "="
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("formation", "formation"), 0);
+        assert_eq!(edit_distance("formaton", "formation"), 1);
+        assert_eq!(edit_distance("Trsut", "Trust"), 2);
+        assert_eq!(edit_distance("naïve", "naive"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_operator_suggestions() {
+        assert_eq!(operator("->"), "=>");
+        assert_eq!(operator("=<"), "=>");
+        assert_eq!(operator("→"), "=>");
+        assert_eq!(operator("!="), "vs");
+        assert_eq!(operator("<->"), "vs");
+        assert_eq!(operator("≈"), "~");
+        assert_eq!(operator("=="), "=");
+    }
+
+    #[test]
+    fn test_closest() {
+        let targets = ["Trust.formation", "Trust.erosion", "Power.core"];
+        assert_eq!(closest("Trust.formaton", targets), Some("Trust.formation"));
+        assert_eq!(closest("Power.cor", targets), Some("Power.core"));
+        assert_eq!(closest("Economics.markets", targets), None);
+    }
+}
@@ -0,0 +1,417 @@
+//! Token stream with byte spans, for syntax highlighting
+//!
+//! `tokenize` classifies every meaningful piece of a document without
+//! validating it, so editors can highlight partially written files. Tokens
+//! never overlap, never span lines, and are returned in source order.
+
+use std::ops::Range;
+
+use crate::id::find_claim_id;
+use crate::include::directive_path;
+use crate::version::declaration;
+use crate::{
+    BYTE_ORDER_MARK, CLAIM_PREFIX, FACET_PREFIX, SOURCES_SECTION, SpecConfig, find_evolution_marker,
+    is_grapheme_boundary,
+};
+
+/// What a token is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// The `wvf X.Y` spec version declaration
+    Version,
+    /// The `>include` directive and its path
+    Include,
+    ConceptName,
+    FacetName,
+    ClaimText,
+    /// A brief form (`=>`, `~`, `=`, `vs`)
+    Operator,
+    Modifier,
+    /// A `| condition`, including the `|`
+    Condition,
+    /// An `@source`, including the `@`
+    Source,
+    /// An `&Concept.facet` reference, including the `&`
+    Reference,
+    /// A whole `[<= prior belief]` marker
+    EvolutionMarker,
+    /// The `sources:` section header
+    SourcesSection,
+    /// A handle registered in the sources section
+    SourceDefinition,
+    /// An explicit `^id:` claim identifier
+    ClaimId,
+}
+
+/// A token and where it sits in the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpannedToken {
+    pub kind: TokenKind,
+    /// Byte range into the input
+    pub span: Range<usize>,
+    /// 1-based line number
+    pub line: usize,
+}
+
+impl SpannedToken {
+    /// The token's text within the input it came from
+    pub fn text<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.span.clone()]
+    }
+}
+
+/// Split a document into highlighted tokens
+pub fn tokenize(input: &str) -> Vec<SpannedToken> {
+    tokenize_with(input, SpecConfig::builtin())
+}
+
+/// Like `tokenize`, recognizing the operators and modifiers of `spec`
+pub fn tokenize_with(input: &str, spec: &SpecConfig) -> Vec<SpannedToken> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    if input.starts_with(BYTE_ORDER_MARK) {
+        offset = BYTE_ORDER_MARK.len_utf8();
+    }
+
+    let mut in_sources = false;
+    let mut seen_content = false;
+    for (idx, raw) in input[offset..].split_inclusive('\n').enumerate() {
+        let line = raw.trim_end_matches(['\n', '\r']);
+        // The sources section runs until the next unindented line
+        if !line.trim().is_empty() && !line.starts_with(char::is_whitespace) {
+            in_sources = line.trim() == SOURCES_SECTION;
+        }
+        let mut lexer = LineLexer {
+            line,
+            base: offset,
+            number: idx + 1,
+            in_sources,
+            first: !seen_content,
+            spec,
+            tokens: &mut tokens,
+        };
+        lexer.lex();
+        seen_content |= !line.trim().is_empty();
+        offset += raw.len();
+    }
+
+    tokens
+}
+
+struct LineLexer<'a, 't> {
+    line: &'a str,
+    base: usize,
+    number: usize,
+    in_sources: bool,
+    /// No content precedes this line, so it may be a version declaration
+    first: bool,
+    spec: &'t SpecConfig,
+    tokens: &'t mut Vec<SpannedToken>,
+}
+
+impl LineLexer<'_, '_> {
+    fn lex(&mut self) {
+        let content = self.line.trim();
+        if content.is_empty() {
+            return;
+        }
+        let start = self.line.len() - self.line.trim_start().len();
+        let end = start + content.len();
+        let indented = start > 0;
+
+        if self.in_sources {
+            match content.find(':') {
+                _ if !indented => self.push(TokenKind::SourcesSection, start, end),
+                Some(colon) => self.push_trimmed(TokenKind::SourceDefinition, start, start + colon),
+                None => {}
+            }
+        } else if self.first && !indented && declaration(content).is_some() {
+            self.push(TokenKind::Version, start, end);
+        } else if !indented && directive_path(content).is_some() {
+            self.push(TokenKind::Include, start, end);
+        } else if let Some(name) = content.strip_prefix(FACET_PREFIX) {
+            self.push_trimmed(TokenKind::FacetName, end - name.len(), end);
+        } else if let Some(body) = content.strip_prefix(CLAIM_PREFIX) {
+            self.claim(end - body.len(), end);
+        } else if !indented {
+            self.push(TokenKind::ConceptName, start, end);
+        }
+    }
+
+    /// Claim body: text, then `|` conditions, `@` sources, `&` references, and evolution markers
+    fn claim(&mut self, start: usize, end: usize) {
+        if let Some(id) = find_claim_id(&self.line[start..end]) {
+            self.claim(start, start + id.start);
+            self.push(TokenKind::ClaimId, start + id.start, start + id.end);
+            self.claim_elements(start + id.end, end, false);
+            return;
+        }
+        let mut segment = start;
+        let mut in_claim = true;
+        while let Some(marker) = find_evolution_marker(&self.line[segment..end]) {
+            self.claim_elements(segment, segment + marker.start, in_claim);
+            self.push(
+                TokenKind::EvolutionMarker,
+                segment + marker.start,
+                segment + marker.end,
+            );
+            segment += marker.end;
+            in_claim = false;
+        }
+        self.claim_elements(segment, end, in_claim);
+    }
+
+    /// Mirrors `parse_claim`: text runs until the first marker, later runs are conditions
+    fn claim_elements(&mut self, start: usize, end: usize, mut in_claim: bool) {
+        let mut segment = start;
+        let mut chars = self.line[start..end].char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            let at = start + i;
+            match c {
+                '|' | '@' | '&' => {
+                    self.segment(segment, at, in_claim);
+                    in_claim = false;
+                    if c == '|' {
+                        segment = at;
+                        continue;
+                    }
+                    let mut stop = end;
+                    while let Some(&(j, next)) = chars.peek() {
+                        if next.is_whitespace() || matches!(next, '|' | '@' | '&') {
+                            stop = start + j;
+                            break;
+                        }
+                        chars.next();
+                    }
+                    let kind = if c == '@' {
+                        TokenKind::Source
+                    } else {
+                        TokenKind::Reference
+                    };
+                    self.push(kind, at, stop);
+                    segment = stop;
+                }
+                _ => {}
+            }
+        }
+        self.segment(segment, end, in_claim);
+    }
+
+    fn segment(&mut self, start: usize, end: usize, in_claim: bool) {
+        if in_claim {
+            self.claim_text(start, end);
+        } else {
+            self.push_trimmed(TokenKind::Condition, start, end);
+        }
+    }
+
+    /// Claim text with operators and modifiers split out
+    fn claim_text(&mut self, start: usize, end: usize) {
+        let text = &self.line[start..end];
+        let mut marks: Vec<(TokenKind, usize, usize)> = Vec::new();
+        let mut previous_was_operator = true;
+
+        for (offset, word) in words(text) {
+            let at = start + offset;
+            if self.spec.operator(word).is_some() {
+                marks.push((TokenKind::Operator, at, at + word.len()));
+                previous_was_operator = true;
+                continue;
+            }
+            if is_modifier(word, self.spec) && !previous_was_operator {
+                marks.push((TokenKind::Modifier, at, at + word.len()));
+                continue;
+            }
+            previous_was_operator = false;
+
+            // Operators inside a word (`power=>corruption`), then a trailing modifier (`collapse?`)
+            let stem = word.trim_end_matches(self.spec.attached_modifiers().as_slice());
+            let mut search = 0;
+            while let Some((pos, op)) = find_operator(&stem[search..], self.spec) {
+                let op_start = at + search + pos;
+                marks.push((TokenKind::Operator, op_start, op_start + op.len()));
+                search += pos + op.len();
+            }
+            if stem.len() < word.len() && !stem.is_empty() {
+                marks.push((TokenKind::Modifier, at + stem.len(), at + word.len()));
+            }
+        }
+
+        let mut cursor = start;
+        for (kind, mark_start, mark_end) in marks {
+            self.push_trimmed(TokenKind::ClaimText, cursor, mark_start);
+            self.push(kind, mark_start, mark_end);
+            cursor = mark_end;
+        }
+        self.push_trimmed(TokenKind::ClaimText, cursor, end);
+    }
+
+    fn push_trimmed(&mut self, kind: TokenKind, start: usize, end: usize) {
+        let text = &self.line[start..end];
+        let trimmed = text.trim_start();
+        let start = start + (text.len() - trimmed.len());
+        self.push(kind, start, start + trimmed.trim_end().len());
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, end: usize) {
+        if start < end {
+            self.tokens.push(SpannedToken {
+                kind,
+                span: self.base + start..self.base + end,
+                line: self.number,
+            });
+        }
+    }
+}
+
+/// Whitespace-separated words with their byte offsets
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace()
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+fn is_modifier(word: &str, spec: &SpecConfig) -> bool {
+    let mut chars = word.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if spec.is_modifier(c))
+}
+
+/// First symbolic brief form inside a word; `vs` only counts as a whole word
+///
+/// Mirrors the claim expression lexer: a run of operator symbols counts only if
+/// the whole run is a brief form (`=>` in `power=>x`, nothing in `a!=b`), and not
+/// if a combining mark extends it (`=` + U+0338).
+fn find_operator(word: &str, spec: &SpecConfig) -> Option<(usize, &'static str)> {
+    let is_operator_symbol = |c| spec.is_operator_symbol(c);
+    let mut chars = word.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !is_operator_symbol(c) {
+            continue;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if !is_operator_symbol(next) {
+                break;
+            }
+            end = j + next.len_utf8();
+            chars.next();
+        }
+        let run = &word[start..end];
+        if let Some(op) = spec.operator(run)
+            && is_grapheme_boundary(word, end)
+        {
+            return Some((start, op));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(input)
+            .iter()
+            .map(|t| (t.kind, t.text(input)))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_structure() {
+        let input = "wvf 1.1\n>include other.wvf\nPower\n  .core\n    - corrupts\n";
+        assert_eq!(
+            kinds(input),
+            [
+                (TokenKind::Version, "wvf 1.1"),
+                (TokenKind::Include, ">include other.wvf"),
+                (TokenKind::ConceptName, "Power"),
+                (TokenKind::FacetName, "core"),
+                (TokenKind::ClaimText, "corrupts"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_multiple_evolution_markers() {
+        let input = "    - adaptive [<= neutral [<= good]] [<= bad] @history";
+        assert_eq!(
+            kinds(input),
+            [
+                (TokenKind::ClaimText, "adaptive"),
+                (TokenKind::EvolutionMarker, "[<= neutral [<= good]]"),
+                (TokenKind::EvolutionMarker, "[<= bad]"),
+                (TokenKind::Source, "@history"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_sources_section() {
+        let input = "Power\n  .core\n    - corrupts @acton\nsources:\n  acton: Lord Acton\n";
+        assert_eq!(
+            &kinds(input)[4..],
+            [
+                (TokenKind::SourcesSection, "sources:"),
+                (TokenKind::SourceDefinition, "acton"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_claim_elements() {
+        let input = "    - power => corruption ! | unchecked @history &Trust.formation [<= benign]";
+        assert_eq!(
+            kinds(input),
+            [
+                (TokenKind::ClaimText, "power"),
+                (TokenKind::Operator, "=>"),
+                (TokenKind::ClaimText, "corruption"),
+                (TokenKind::Modifier, "!"),
+                (TokenKind::Condition, "| unchecked"),
+                (TokenKind::Source, "@history"),
+                (TokenKind::Reference, "&Trust.formation"),
+                (TokenKind::EvolutionMarker, "[<= benign]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_attached_modifiers_and_operators() {
+        let input = "    - concentration^=>abuse^ vs collapse?";
+        assert_eq!(
+            kinds(input),
+            [
+                (TokenKind::ClaimText, "concentration^"),
+                (TokenKind::Operator, "=>"),
+                (TokenKind::ClaimText, "abuse"),
+                (TokenKind::Modifier, "^"),
+                (TokenKind::Operator, "vs"),
+                (TokenKind::ClaimText, "collapse"),
+                (TokenKind::Modifier, "?"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unicode() {
+        let input = "    - naïveté=>regret a=\u{338}b";
+        assert_eq!(
+            kinds(input),
+            [
+                (TokenKind::ClaimText, "naïveté"),
+                (TokenKind::Operator, "=>"),
+                (TokenKind::ClaimText, "regret a=\u{338}b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_spans_and_lines() {
+        let input = "Power\r\n  .core\r\n    - corrupts\r\n";
+        let tokens = tokenize(input);
+        assert_eq!(tokens[2].line, 3);
+        assert_eq!(tokens[2].text(input), "corrupts");
+        assert!(tokens.windows(2).all(|w| w[0].span.end <= w[1].span.start));
+    }
+}
@@ -0,0 +1,127 @@
+//! Token counts, for sizing a document against a model's context window
+//!
+//! With the `tiktoken` feature, OpenAI models are counted with their own
+//! encoding and other models (Claude, Gemini, local models), whose tokenizers
+//! are not public, are approximated with `cl100k_base`. Without it, tokens are
+//! estimated at four characters each.
+
+use crate::document::Document;
+
+/// Token counts from `Document::token_estimate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenEstimate {
+    /// Encoding counted with (`o200k_base`, `cl100k_base`, ...), or `chars/4`
+    /// for the character estimate
+    pub tokenizer: &'static str,
+    /// Whether `tokenizer` is the model's own rather than an approximation
+    pub exact: bool,
+    /// Tokens in the document's canonical text
+    pub total: usize,
+    /// Tokens in each concept's canonical text, in document order
+    pub concepts: Vec<(String, usize)>,
+}
+
+/// Rough token count of `text`, at about four characters per token
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+impl Document {
+    /// Tokens `model` would read for the canonical text, in total and per concept
+    ///
+    /// Concepts are counted on their own, so their counts add up to a little
+    /// less than the total, which also covers the version line, includes,
+    /// sources, and blank lines between concepts.
+    pub fn token_estimate(&self, model: &str) -> TokenEstimate {
+        let (tokenizer, exact, count) = counter(model);
+        let concepts = self
+            .concepts
+            .iter()
+            .map(|concept| {
+                let alone = Document {
+                    concepts: vec![concept.clone()],
+                    ..Document::default()
+                };
+                (concept.name.clone(), count(&alone.to_wvf_string()))
+            })
+            .collect();
+        TokenEstimate {
+            tokenizer,
+            exact,
+            total: count(&self.to_wvf_string()),
+            concepts,
+        }
+    }
+}
+
+#[cfg(feature = "tiktoken")]
+fn counter(model: &str) -> (&'static str, bool, impl Fn(&str) -> usize) {
+    use tiktoken_rs::tokenizer::{Tokenizer, get_tokenizer};
+
+    let (tokenizer, exact) = match get_tokenizer(model) {
+        Some(tokenizer) => (tokenizer, true),
+        None => (Tokenizer::Cl100kBase, false),
+    };
+    let (name, bpe) = match tokenizer {
+        Tokenizer::O200kBase => ("o200k_base", tiktoken_rs::o200k_base_singleton()),
+        Tokenizer::Cl100kBase => ("cl100k_base", tiktoken_rs::cl100k_base_singleton()),
+        Tokenizer::P50kBase => ("p50k_base", tiktoken_rs::p50k_base_singleton()),
+        Tokenizer::P50kEdit => ("p50k_edit", tiktoken_rs::p50k_edit_singleton()),
+        Tokenizer::R50kBase | Tokenizer::Gpt2 => ("r50k_base", tiktoken_rs::r50k_base_singleton()),
+    };
+    (name, exact, move |text: &str| {
+        bpe.encode_ordinary(text).len()
+    })
+}
+
+#[cfg(not(feature = "tiktoken"))]
+fn counter(_model: &str) -> (&'static str, bool, impl Fn(&str) -> usize) {
+    ("chars/4", false, estimate_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "Trust\n  .formation\n    - slow\n    - requires consistency | over time\n\nPower\n  .nature\n    - corrupts | unchecked\n";
+
+    #[test]
+    fn test_token_estimate_per_concept() {
+        let doc = Document::parse(DOC);
+        let estimate = doc.token_estimate("claude-sonnet-4-20250514");
+        let names: Vec<&str> = estimate.concepts.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["Trust", "Power"]);
+        assert!(!estimate.exact);
+
+        let sum: usize = estimate.concepts.iter().map(|(_, count)| count).sum();
+        assert!(
+            sum <= estimate.total && estimate.total <= sum + 2,
+            "{:?}",
+            estimate
+        );
+        assert_eq!(doc.token_estimate("gpt-4o").concepts.len(), 2);
+    }
+
+    #[cfg(not(feature = "tiktoken"))]
+    #[test]
+    fn test_token_estimate_without_tokenizer() {
+        let doc = Document::parse(DOC);
+        let estimate = doc.token_estimate("gpt-4o");
+        assert_eq!(estimate.tokenizer, "chars/4");
+        assert_eq!(estimate.total, estimate_tokens(DOC));
+    }
+
+    #[cfg(feature = "tiktoken")]
+    #[test]
+    fn test_token_estimate_with_tiktoken() {
+        let doc = Document::parse(DOC);
+        let estimate = doc.token_estimate("gpt-4o");
+        assert_eq!(estimate.tokenizer, "o200k_base");
+        assert!(estimate.exact);
+        assert!(estimate.total > 0 && estimate.total < estimate_tokens(DOC) * 2);
+
+        let estimate = doc.token_estimate("claude-sonnet-4-20250514");
+        assert_eq!(estimate.tokenizer, "cl100k_base");
+        assert!(!estimate.exact);
+    }
+}
@@ -0,0 +1,37 @@
+//! Spans around validation passes, for `tracing` subscribers
+//!
+//! With the `tracing` feature, `span!` enters a debug-level span that lasts to
+//! the end of the enclosing scope and `event!` emits a debug-level event.
+//! Without it both expand to nothing and their fields are not evaluated.
+
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($name:literal $(, $($fields:tt)+)?) => {
+        tracing::debug_span!($name $(, $($fields)+)?).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($name:literal $(, $($fields:tt)+)?) => {
+        $crate::trace::Disabled
+    };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($($args:tt)+) => {
+        tracing::debug!($($args)+)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    ($($args:tt)+) => {};
+}
+
+/// Stand-in for an entered span when tracing is compiled out
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Disabled;
+
+pub(crate) use {event, span};
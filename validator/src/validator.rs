@@ -0,0 +1,185 @@
+//! A reusable validator with a registered vocabulary
+//!
+//! Embedders that extend the claim grammar register their operators and
+//! modifiers once and validate many documents with them:
+//!
+//! ```
+//! use worldview_validator::Validator;
+//!
+//! let validator = Validator::builder()
+//!     .with_brief_form("-->", "eventually leads to")
+//!     .with_modifier('%', "probabilistic")
+//!     .build()
+//!     .unwrap();
+//! let result = validator.validate("Risk\n  .drift\n    - neglect --> failure %\n");
+//! assert!(result.is_valid() && !result.has_warnings());
+//! ```
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use crate::{
+    ClaimData, SpannedToken, SpecConfig, SpecError, ValidationOptions, ValidationResult,
+    parse_claim_with, tokenize_with, validate_with_options,
+};
+
+/// Validates documents with fixed options and vocabulary
+#[derive(Debug, Clone, Default)]
+pub struct Validator {
+    options: ValidationOptions,
+}
+
+impl Validator {
+    pub fn builder() -> ValidatorBuilder {
+        ValidatorBuilder::default()
+    }
+
+    pub fn options(&self) -> &ValidationOptions {
+        &self.options
+    }
+
+    /// The operators and modifiers claims are parsed with
+    pub fn spec(&self) -> &SpecConfig {
+        &self.options.spec
+    }
+
+    pub fn validate<'a>(&self, input: &'a str) -> ValidationResult<'a> {
+        validate_with_options(input, &self.options)
+    }
+
+    /// Validate a file by path, resolving its `>include` directives
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn validate_file(&self, path: &Path) -> Result<ValidationResult<'static>, std::io::Error> {
+        crate::validate_file_with_options(path, &self.options)
+    }
+
+    /// Parse a claim (without its `- ` prefix)
+    pub fn parse_claim<'a>(&self, text: &'a str) -> ClaimData<'a> {
+        parse_claim_with(text, &self.options.spec)
+    }
+
+    /// Highlighting tokens, with registered operators and modifiers recognized
+    pub fn tokenize(&self, input: &str) -> Vec<SpannedToken> {
+        tokenize_with(input, &self.options.spec)
+    }
+}
+
+/// Builds a `Validator`
+///
+/// Registration errors are kept until `build`, so calls can be chained.
+#[derive(Debug, Default)]
+pub struct ValidatorBuilder {
+    options: ValidationOptions,
+    error: Option<SpecError>,
+}
+
+impl ValidatorBuilder {
+    /// Validate with these options, keeping the vocabulary registered so far
+    pub fn with_options(mut self, options: ValidationOptions) -> Self {
+        let spec = std::mem::take(&mut self.options.spec);
+        self.options = ValidationOptions { spec, ..options };
+        self
+    }
+
+    /// Start from this vocabulary, such as one from `SpecConfig::from_yaml`
+    pub fn with_spec(mut self, spec: SpecConfig) -> Self {
+        self.options.spec = spec;
+        self
+    }
+
+    /// Register a modifier (`'%'` for "probabilistic"), or redefine one
+    pub fn with_modifier(mut self, symbol: char, meaning: &str) -> Self {
+        if let Err(e) = self.options.spec.add_modifier(symbol, meaning) {
+            self.error.get_or_insert(e);
+        }
+        self
+    }
+
+    /// Register an operator (`"-->"` for "eventually leads to"), or redefine one
+    pub fn with_brief_form(mut self, symbol: &str, meaning: &str) -> Self {
+        if let Err(e) = self.options.spec.add_brief_form(symbol, meaning) {
+            self.error.get_or_insert(e);
+        }
+        self
+    }
+
+    /// Finish building; fails with the first symbol that could not be registered
+    pub fn build(self) -> Result<Validator, SpecError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(Validator {
+                options: self.options,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LineType, TokenKind, ValidationError};
+
+    #[test]
+    fn test_registered_vocabulary_is_used_throughout() {
+        let validator = Validator::builder()
+            .with_brief_form("-->", "eventually leads to")
+            .with_modifier('%', "probabilistic")
+            .with_options(ValidationOptions::nested())
+            .build()
+            .unwrap();
+        assert!(validator.options().nested_facets);
+
+        let claim = validator.parse_claim("neglect-->failure% | unchecked");
+        assert_eq!(claim.brief_forms[0].operator, "-->");
+        assert_eq!(claim.modifiers[0].symbol, '%');
+        assert_eq!(
+            claim.describe_with(validator.spec()),
+            "neglect eventually leads to failure (probabilistic)"
+        );
+
+        let input = "Risk\n  .drift\n    - neglect --> failure %\n";
+        let kinds: Vec<TokenKind> = validator.tokenize(input).iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::ConceptName,
+                TokenKind::FacetName,
+                TokenKind::ClaimText,
+                TokenKind::Operator,
+                TokenKind::ClaimText,
+                TokenKind::Modifier,
+            ]
+        );
+        let result = validator.validate(input);
+        assert!(result.is_valid() && !result.has_warnings());
+        assert!(matches!(result.lines[2].line_type, LineType::Claim(_)));
+    }
+
+    #[test]
+    fn test_standalone_registered_modifier_is_checked() {
+        let validator = Validator::builder()
+            .with_modifier('%', "probabilistic")
+            .build()
+            .unwrap();
+        let result = validator.validate("Risk\n  .drift\n    - % failure\n");
+        assert_eq!(
+            result.warnings,
+            vec![ValidationError::StandaloneModifier {
+                line: 3,
+                modifier: "%".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_first_registration_error_is_reported() {
+        let built = Validator::builder()
+            .with_brief_form("a b", "spaced")
+            .with_modifier('|', "bar")
+            .build();
+        assert_eq!(
+            built.unwrap_err(),
+            SpecError::InvalidBriefForm("a b".to_string())
+        );
+    }
+}
@@ -0,0 +1,284 @@
+//! Spec versions and the `wvf 1.1` declaration
+//!
+//! A document may declare the version of the spec it targets on its first
+//! non-blank line. Constructs introduced in a later version are errors in that
+//! document, so a file written against 1.0 keeps its meaning as the format
+//! grows. Files without a declaration are checked against
+//! `ValidationOptions::spec_version`, or the latest version when that is unset.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{LineType, ParsedLine, Reference, ValidationError};
+
+/// Keyword of the version declaration (`wvf 1.1`)
+pub const VERSION_DIRECTIVE: &str = "wvf";
+
+/// A version of the Worldview spec
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpecVersion {
+    /// Concepts, facets, and claims with conditions, sources, references,
+    /// brief forms, modifiers, and evolution markers
+    V1_0,
+    /// Adds includes, namespaced references, sub-facets, claim IDs, the
+    /// sources section, and dated evolution markers
+    V1_1,
+}
+
+impl SpecVersion {
+    pub const LATEST: SpecVersion = SpecVersion::V1_1;
+
+    /// Every version, oldest first
+    pub const ALL: [SpecVersion; 2] = [SpecVersion::V1_0, SpecVersion::V1_1];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SpecVersion::V1_0 => "1.0",
+            SpecVersion::V1_1 => "1.1",
+        }
+    }
+}
+
+impl fmt::Display for SpecVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SpecVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SpecVersion::ALL
+            .into_iter()
+            .find(|version| version.as_str() == s)
+            .ok_or_else(|| format!("unknown spec version '{}' (expected {})", s, known()))
+    }
+}
+
+/// `1.0 or 1.1`
+pub(crate) fn known() -> String {
+    let names: Vec<&str> = SpecVersion::ALL.iter().map(|v| v.as_str()).collect();
+    match names.split_last() {
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => String::new(),
+    }
+}
+
+/// The version in a `wvf X.Y` declaration; `content` is the trimmed line
+///
+/// Only dotted numbers count, so a concept that happens to start with `wvf`
+/// is still a concept.
+pub(crate) fn declaration(content: &str) -> Option<&str> {
+    let version = content.strip_prefix(VERSION_DIRECTIVE)?;
+    if !version.starts_with(' ') {
+        return None;
+    }
+    let version = version.trim();
+    let numeric = version
+        .split('.')
+        .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    numeric.then_some(version)
+}
+
+/// The version a document is checked against: its declaration, or `default`
+///
+/// An unknown declared version is reported by the caller; nothing newer than
+/// it can be detected, so the latest version is assumed.
+pub(crate) fn target(lines: &[ParsedLine<'_>], default: Option<SpecVersion>) -> SpecVersion {
+    let declared = lines.iter().find_map(|line| match &line.line_type {
+        LineType::Version(version) => Some(version.parse().unwrap_or(SpecVersion::LATEST)),
+        _ => None,
+    });
+    declared.or(default).unwrap_or(SpecVersion::LATEST)
+}
+
+/// Report constructs that need a newer version than `declared`
+pub(crate) fn newer_constructs(
+    lines: &[ParsedLine<'_>],
+    declared: SpecVersion,
+) -> Vec<ValidationError> {
+    if declared == SpecVersion::LATEST {
+        return Vec::new();
+    }
+    let mut errors = Vec::new();
+    for line in lines {
+        for (construct, required) in constructs(&line.line_type) {
+            if required > declared {
+                errors.push(ValidationError::NewerSpecConstruct {
+                    line: line.line_number,
+                    construct,
+                    required,
+                    declared,
+                });
+            }
+        }
+    }
+    errors
+}
+
+/// Constructs on a line that not every version has, with the version that introduced each
+fn constructs(line_type: &LineType<'_>) -> Vec<(&'static str, SpecVersion)> {
+    let mut found = Vec::new();
+    match line_type {
+        LineType::Include(_) => found.push(("include directives", SpecVersion::V1_1)),
+        LineType::SubFacet(_) => found.push(("sub-facets", SpecVersion::V1_1)),
+        LineType::SourcesSection => found.push(("the sources section", SpecVersion::V1_1)),
+        LineType::Claim(claim) => {
+            if claim.id.is_some() {
+                found.push(("claim IDs", SpecVersion::V1_1));
+            }
+            let namespaced = claim
+                .references
+                .iter()
+                .filter_map(|r| Reference::parse(r))
+                .any(|r| r.namespace.is_some());
+            if namespaced {
+                found.push(("namespaced references", SpecVersion::V1_1));
+            }
+            if claim.evolution.iter().any(|m| m.changed_at.is_some()) {
+                found.push(("dated evolution markers", SpecVersion::V1_1));
+            }
+        }
+        _ => {}
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationOptions, validate, validate_with_options};
+
+    #[test]
+    fn test_parse_and_display() {
+        for version in SpecVersion::ALL {
+            assert_eq!(version.to_string().parse::<SpecVersion>(), Ok(version));
+        }
+        assert!(SpecVersion::V1_0 < SpecVersion::LATEST);
+        let err = "2.0".parse::<SpecVersion>().unwrap_err();
+        assert!(err.contains("1.0 or 1.1"), "{}", err);
+    }
+
+    #[test]
+    fn test_declaration_requires_a_version_number() {
+        assert_eq!(declaration("wvf 1.0"), Some("1.0"));
+        assert_eq!(declaration("wvf  2"), Some("2"));
+        assert_eq!(declaration("wvf"), None);
+        assert_eq!(declaration("wvf notes"), None);
+        assert_eq!(declaration("wvf1.0"), None);
+        assert_eq!(declaration("wvf 1..0"), None);
+    }
+
+    #[test]
+    fn test_declaration_is_not_a_concept() {
+        let input = "wvf 1.1\n\nTrust\n  .formation\n    - slow\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        assert_eq!(result.lines[0].line_type, LineType::Version("1.1".into()));
+    }
+
+    #[test]
+    fn test_declaration_only_on_first_content_line() {
+        let input = "Trust\n  .formation\n    - slow\nwvf 1.0\n  .nature\n    - earned\n";
+        let result = validate(input);
+        assert!(result.is_valid(), "{:?}", result.errors);
+        assert_eq!(
+            result.lines[3].line_type,
+            LineType::Concept("wvf 1.0".into())
+        );
+    }
+
+    #[test]
+    fn test_newer_constructs_in_older_version() {
+        let input = "\
+wvf 1.0
+>include politics.wvf
+
+Trust
+  .formation
+    - slow ^id:trust-slow
+    - earned &politics:Power.nature
+    - fast [<= instant :2020]
+sources:
+  acton: Lord Acton
+";
+        let result = validate(input);
+        let constructs: Vec<(usize, &str)> = result
+            .errors
+            .iter()
+            .filter_map(|e| match e {
+                ValidationError::NewerSpecConstruct {
+                    line, construct, ..
+                } => Some((*line, *construct)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            constructs,
+            vec![
+                (2, "include directives"),
+                (6, "claim IDs"),
+                (7, "namespaced references"),
+                (8, "dated evolution markers"),
+                (9, "the sources section"),
+            ]
+        );
+        let include = ValidationError::NewerSpecConstruct {
+            line: 2,
+            construct: "include directives",
+            required: SpecVersion::V1_1,
+            declared: SpecVersion::V1_0,
+        };
+        assert!(result.errors.contains(&include));
+        assert_eq!(
+            include.to_string(),
+            "line 2: include directives require wvf 1.1 (this file targets wvf 1.0)"
+        );
+    }
+
+    #[test]
+    fn test_sub_facets_need_1_1() {
+        let input = "wvf 1.0\nEconomics\n  .markets\n    .labor\n      - sticky\n";
+        let result = validate_with_options(input, &ValidationOptions::nested());
+        assert!(result.errors.iter().any(|e| matches!(
+            e,
+            ValidationError::NewerSpecConstruct {
+                line: 4,
+                construct: "sub-facets",
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_default_version_applies_without_declaration() {
+        let input = "Trust\n  .formation\n    - slow ^id:trust-slow\n";
+        assert!(validate(input).is_valid());
+
+        let options = ValidationOptions {
+            spec_version: Some(SpecVersion::V1_0),
+            ..ValidationOptions::default()
+        };
+        let result = validate_with_options(input, &options);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].code(), "newer-spec-construct");
+
+        // A declaration overrides the default
+        let declared = format!("wvf 1.1\n{}", input);
+        assert!(validate_with_options(&declared, &options).is_valid());
+    }
+
+    #[test]
+    fn test_unknown_version() {
+        let result = validate("wvf 9.0\nTrust\n  .formation\n    - slow ^id:trust-slow\n");
+        assert_eq!(
+            result.errors,
+            vec![ValidationError::UnknownSpecVersion {
+                line: 1,
+                version: "9.0".to_string()
+            }]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Document traversal for custom analyses
+//!
+//! `Document::walk` visits every node in document order: each concept, then
+//! its facets, each facet's claims, and, for each claim, its references and
+//! brief forms, before moving on to the facet's sub-facets. A `Visitor`
+//! overrides only the methods for the nodes it cares about; the rest do
+//! nothing.
+
+use crate::BriefFormUsage;
+use crate::document::{Claim, Concept, Document, Facet};
+use crate::reference::Reference;
+
+/// Callbacks for `Document::walk`
+pub trait Visitor {
+    fn visit_concept(&mut self, _concept: &Concept) {}
+
+    /// A facet or sub-facet, with the concept it belongs to
+    fn visit_facet(&mut self, _concept: &Concept, _facet: &Facet) {}
+
+    /// A claim, with the facet or sub-facet holding it
+    fn visit_claim(&mut self, _facet: &Facet, _claim: &Claim) {}
+
+    /// A well-formed `&reference` in a claim; malformed ones are skipped
+    fn visit_reference(&mut self, _claim: &Claim, _reference: &Reference) {}
+
+    fn visit_brief_form(&mut self, _claim: &Claim, _brief_form: &BriefFormUsage<'_>) {}
+}
+
+impl Document {
+    /// Visit every concept, facet, claim, reference, and brief form in
+    /// document order
+    pub fn walk(&self, visitor: &mut (impl Visitor + ?Sized)) {
+        for concept in &self.concepts {
+            visitor.visit_concept(concept);
+            walk_facets(concept, &concept.facets, visitor);
+        }
+    }
+}
+
+fn walk_facets(concept: &Concept, facets: &[Facet], visitor: &mut (impl Visitor + ?Sized)) {
+    for facet in facets {
+        visitor.visit_facet(concept, facet);
+        for claim in &facet.claims {
+            visitor.visit_claim(facet, claim);
+            for reference in claim.data.parsed_references() {
+                visitor.visit_reference(claim, &reference);
+            }
+            for brief_form in &claim.data.brief_forms {
+                visitor.visit_brief_form(claim, brief_form);
+            }
+        }
+        walk_facets(concept, &facet.sub_facets, visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records each visit as `kind:name@line`
+    #[derive(Default)]
+    struct Trace(Vec<String>);
+
+    impl Visitor for Trace {
+        fn visit_concept(&mut self, concept: &Concept) {
+            self.0
+                .push(format!("concept:{}@{}", concept.name, concept.line));
+        }
+
+        fn visit_facet(&mut self, concept: &Concept, facet: &Facet) {
+            self.0.push(format!(
+                "facet:{}.{}@{}",
+                concept.name, facet.name, facet.line
+            ));
+        }
+
+        fn visit_claim(&mut self, facet: &Facet, claim: &Claim) {
+            self.0.push(format!(
+                "claim:{}/{}@{}",
+                facet.name, claim.data.text, claim.line
+            ));
+        }
+
+        fn visit_reference(&mut self, claim: &Claim, reference: &Reference) {
+            self.0
+                .push(format!("reference:{}@{}", reference, claim.line));
+        }
+
+        fn visit_brief_form(&mut self, claim: &Claim, brief_form: &BriefFormUsage<'_>) {
+            self.0
+                .push(format!("brief-form:{}@{}", brief_form.operator, claim.line));
+        }
+    }
+
+    #[test]
+    fn test_walk_visits_in_document_order() {
+        let input = "Power\n  .nature\n    - corrupts => isolation &Trust.erosion\n    .limits\n      - checks\n\nTrust\n  .erosion\n    - fast\n";
+        let mut trace = Trace::default();
+        Document::parse(input).walk(&mut trace);
+        assert_eq!(
+            trace.0,
+            [
+                "concept:Power@1",
+                "facet:Power.nature@2",
+                "claim:nature/corrupts => isolation@3",
+                "reference:Trust.erosion@3",
+                "brief-form:=>@3",
+                "facet:Power.limits@4",
+                "claim:limits/checks@5",
+                "concept:Trust@7",
+                "facet:Trust.erosion@8",
+                "claim:erosion/fast@9",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visitor_overrides_only_what_it_needs() {
+        struct Uncertain(usize);
+        impl Visitor for Uncertain {
+            fn visit_claim(&mut self, _facet: &Facet, claim: &Claim) {
+                if claim.data.modifiers.iter().any(|m| m.symbol == '?') {
+                    self.0 += 1;
+                }
+            }
+        }
+
+        let doc = Document::parse(
+            "Power\n  .nature\n    - corrupts ?\n    - concentrates\n    - spreads ?\n",
+        );
+        let mut uncertain = Uncertain(0);
+        doc.walk(&mut uncertain);
+        assert_eq!(uncertain.0, 2);
+    }
+}
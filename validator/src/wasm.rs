@@ -0,0 +1,50 @@
+//! JavaScript bindings for validating in the browser
+//!
+//! Build with `wasm-pack build validator --target web -- --features wasm`.
+//! `validate(input)` returns `{ valid, diagnostics }`, where each diagnostic is
+//! `{ line, severity, message }` with a 1-based line and `"error"` or `"warning"`.
+//! There is no filesystem in the browser, so `>include` directives are not followed.
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::ValidationError;
+
+/// Validate a document, returning its diagnostics as a plain JS object
+#[wasm_bindgen(js_name = validate)]
+pub fn validate_js(input: &str) -> JsValue {
+    let result = crate::validate(input);
+    let diagnostics: Array = result
+        .errors
+        .iter()
+        .chain(&result.warnings)
+        .map(diagnostic)
+        .collect();
+    let object = Object::new();
+    set(&object, "valid", result.is_valid().into());
+    set(&object, "diagnostics", diagnostics.into());
+    object.into()
+}
+
+fn diagnostic(error: &ValidationError) -> JsValue {
+    // The line is its own field, so drop the "line N: " prefix from the message
+    let message = error.to_string();
+    let message = message
+        .split_once(": ")
+        .map_or(message.as_str(), |(_, rest)| rest);
+    let object = Object::new();
+    set(&object, "line", (error.line() as u32).into());
+    let severity = if error.is_warning() {
+        "warning"
+    } else {
+        "error"
+    };
+    set(&object, "severity", severity.into());
+    set(&object, "message", message.into());
+    object.into()
+}
+
+fn set(object: &Object, key: &str, value: JsValue) {
+    // Setting a property on a fresh plain object cannot fail
+    let _ = Reflect::set(object, &key.into(), &value);
+}
@@ -0,0 +1,194 @@
+//! Workspace-level validation across multiple files
+//!
+//! Every file in a workspace shares one reference table, so `&Concept.facet` in
+//! one file resolves against definitions in its siblings. Directories passed in
+//! are expanded to the `.wvf` files they contain.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::reference::{namespace_for, qualified};
+use crate::{
+    ValidationOptions, ValidationResult, collect_valid_references, include, trace,
+    validate_with_options, validate_with_refs,
+};
+
+/// Validation outcome for one file in a workspace
+#[derive(Debug, Clone)]
+pub struct WorkspaceFile {
+    pub path: PathBuf,
+    pub result: ValidationResult<'static>,
+}
+
+/// Result of validating a set of files together
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceResult {
+    pub files: Vec<WorkspaceFile>,
+    /// Files that could not be read, with the I/O error message
+    pub unreadable: Vec<(PathBuf, String)>,
+    /// Every reference target in the workspace and the file(s) defining it
+    pub definitions: BTreeMap<String, Vec<PathBuf>>,
+}
+
+impl WorkspaceResult {
+    pub fn is_valid(&self) -> bool {
+        self.unreadable.is_empty() && self.files.iter().all(|f| f.result.is_valid())
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.files.iter().any(|f| f.result.has_warnings())
+    }
+
+    /// The file defining a `Concept.facet` target, if any
+    pub fn defined_in(&self, target: &str) -> Option<&Path> {
+        self.definitions
+            .get(target)
+            .and_then(|paths| paths.first())
+            .map(PathBuf::as_path)
+    }
+
+    /// Result for a specific file in the workspace
+    pub fn file(&self, path: &Path) -> Option<&WorkspaceFile> {
+        self.files.iter().find(|f| f.path == path)
+    }
+}
+
+impl fmt::Display for WorkspaceResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            writeln!(f, "{}:", file.path.display())?;
+            writeln!(f, "{}", file.result)?;
+        }
+        for (path, error) in &self.unreadable {
+            writeln!(f, "{}: could not be read ({})", path.display(), error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validates a set of files (or directories of `.wvf` files) as one workspace
+pub fn validate_workspace(paths: &[PathBuf]) -> WorkspaceResult {
+    validate_workspace_with_options(paths, &ValidationOptions::default())
+}
+
+/// Validates a workspace with the given options
+pub fn validate_workspace_with_options(
+    paths: &[PathBuf],
+    options: &ValidationOptions,
+) -> WorkspaceResult {
+    let _span = trace::span!("workspace", paths = paths.len());
+    let mut workspace = WorkspaceResult::default();
+    let mut sources = Vec::new();
+
+    for path in expand_paths(paths) {
+        match std::fs::read_to_string(&path) {
+            Ok(content) => sources.push((path, content)),
+            Err(e) => workspace.unreadable.push((path, e.to_string())),
+        }
+    }
+
+    // Build the combined reference table
+    for (path, content) in &sources {
+        let parsed = validate_with_options(content, options);
+        for target in collect_valid_references(&parsed.lines) {
+            workspace
+                .definitions
+                .entry(target)
+                .or_default()
+                .push(path.clone());
+        }
+    }
+    let mut all_refs: HashSet<String> = workspace.definitions.keys().cloned().collect();
+    for (target, paths) in &workspace.definitions {
+        for namespace in paths.iter().filter_map(|p| namespace_for(p)) {
+            all_refs.insert(qualified(&namespace, target));
+        }
+    }
+
+    for (path, content) in sources {
+        let _span = trace::span!("file", path = %path.display());
+        let resolution = include::resolve(&content, &path, options);
+        let mut refs = all_refs.clone();
+        refs.extend(resolution.refs);
+        let mut result = validate_with_refs(&content, options, &refs).into_owned();
+        result.errors.extend(resolution.errors);
+        workspace.files.push(WorkspaceFile { path, result });
+    }
+
+    workspace
+}
+
+/// Expand directories to the `.wvf` files directly inside them, keeping files as given
+fn expand_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let mut entries: Vec<PathBuf> = std::fs::read_dir(path)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "wvf"))
+                .collect();
+            entries.sort();
+            files.extend(entries);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationError;
+    use std::fs;
+
+    #[test]
+    fn test_workspace_resolves_sibling_references() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("trust.wvf"),
+            "Trust\n  .formation\n    - slow &Power.core\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("power.wvf"),
+            "Power\n  .core\n    - corrupts &Trust.formation\n",
+        )
+        .unwrap();
+
+        let result = validate_workspace(&[dir.path().to_path_buf()]);
+        assert_eq!(result.files.len(), 2);
+        assert!(result.is_valid(), "Expected valid: {}", result);
+        assert_eq!(
+            result.defined_in("Trust.formation"),
+            Some(dir.path().join("trust.wvf").as_path())
+        );
+    }
+
+    #[test]
+    fn test_workspace_reports_undefined_reference() {
+        let dir = tempfile::tempdir().unwrap();
+        let power = dir.path().join("power.wvf");
+        fs::write(&power, "Power\n  .core\n    - corrupts &Trust.erosion\n").unwrap();
+
+        let result = validate_workspace(std::slice::from_ref(&power));
+        let file = result.file(&power).unwrap();
+        assert!(
+            file.result
+                .errors
+                .iter()
+                .any(|e| matches!(e, ValidationError::UndefinedReference { .. }))
+        );
+    }
+
+    #[test]
+    fn test_workspace_unreadable_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = validate_workspace(&[dir.path().join("missing.wvf")]);
+        assert!(!result.is_valid());
+        assert_eq!(result.unreadable.len(), 1);
+    }
+}
@@ -0,0 +1,270 @@
+//! Property tests over randomly generated valid documents
+//!
+//! The generator builds documents through `DocumentBuilder` from a small
+//! vocabulary: concepts with facets and sub-facets, claims with brief forms,
+//! modifiers, conditions, sources, references to generated facets, evolution
+//! markers, and claim IDs, optionally with a sources registry and a version
+//! declaration. Each claim's first word carries its index, so no two claims
+//! repeat or contradict each other.
+
+use proptest::prelude::*;
+use proptest::sample::{Index, select, subsequence};
+use worldview_validator::{
+    Document, DocumentBuilder, Facet, SpecVersion, ValidationOptions, validate_with_options,
+};
+
+const CONCEPTS: &[&str] = &["Power", "Trust", "Markets", "Memory", "Science", "Work"];
+const FACETS: &[&str] = &["core", "formation", "erosion", "limits", "signals", "costs"];
+const SUB_FACETS: &[&str] = &["labor", "capital", "norms", "incentives"];
+const WORDS: &[&str] = &[
+    "trust", "power", "growth", "caution", "reform", "status", "habit", "risk", "evidence",
+    "consent",
+];
+const OPERATORS: &[&str] = &["=>", "~", "=", "vs"];
+const MODIFIERS: &[&str] = &["^", "!", "?", "*"];
+const CONDITIONS: &[&str] = &[
+    "unchecked",
+    "over time",
+    "when trust is low",
+    "without oversight",
+];
+const HANDLES: &[&str] = &["experience", "acton", "game-theory"];
+const DATES: &[&str] = &["2022", "2022-03", "2023-11-05"];
+
+#[derive(Debug, Clone)]
+struct ClaimSpec {
+    words: Vec<&'static str>,
+    relation: Option<(&'static str, &'static str)>,
+    modifier: Option<&'static str>,
+    conditions: Vec<&'static str>,
+    sources: Vec<&'static str>,
+    /// Which generated facet to reference
+    reference: Option<Index>,
+    prior: Option<(&'static str, Option<&'static str>)>,
+    with_id: bool,
+}
+
+#[derive(Debug, Clone)]
+struct FacetSpec {
+    name: &'static str,
+    claims: Vec<ClaimSpec>,
+    sub_facets: Vec<(&'static str, Vec<ClaimSpec>)>,
+}
+
+#[derive(Debug, Clone)]
+struct DocSpec {
+    concepts: Vec<(&'static str, Vec<FacetSpec>)>,
+    registry: bool,
+    declare_version: bool,
+}
+
+fn claim_spec() -> impl Strategy<Value = ClaimSpec> {
+    (
+        prop::collection::vec(select(WORDS), 1..=3),
+        prop::option::of((select(OPERATORS), select(WORDS))),
+        prop::option::of(select(MODIFIERS)),
+        subsequence(CONDITIONS, 0..=2),
+        subsequence(HANDLES, 0..=2),
+        prop::option::weighted(0.3, any::<Index>()),
+        prop::option::weighted(0.2, (select(WORDS), prop::option::of(select(DATES)))),
+        prop::bool::weighted(0.2),
+    )
+        .prop_map(
+            |(words, relation, modifier, conditions, sources, reference, prior, with_id)| {
+                ClaimSpec {
+                    words,
+                    relation,
+                    modifier,
+                    conditions,
+                    sources,
+                    reference,
+                    prior,
+                    with_id,
+                }
+            },
+        )
+}
+
+fn claims(min: usize) -> impl Strategy<Value = Vec<ClaimSpec>> {
+    prop::collection::vec(claim_spec(), min..=3)
+}
+
+fn facet_spec() -> impl Strategy<Value = (Vec<ClaimSpec>, Vec<(&'static str, Vec<ClaimSpec>)>)> {
+    prop_oneof![
+        3 => claims(1).prop_map(|claims| (claims, Vec::new())),
+        // A facet holding sub-facets needs no claims of its own
+        1 => (claims(0), subsequence(SUB_FACETS, 1..=2)).prop_flat_map(|(own, names)| {
+            let count = names.len();
+            prop::collection::vec(claims(1), count).prop_map(move |sub_claims| {
+                (own.clone(), names.iter().copied().zip(sub_claims).collect())
+            })
+        }),
+    ]
+}
+
+fn doc_spec() -> impl Strategy<Value = DocSpec> {
+    let concept = subsequence(FACETS, 1..=3).prop_flat_map(|names| {
+        let count = names.len();
+        prop::collection::vec(facet_spec(), count).prop_map(move |facets| {
+            names
+                .iter()
+                .zip(facets)
+                .map(|(&name, (claims, sub_facets))| FacetSpec {
+                    name,
+                    claims,
+                    sub_facets,
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+    (subsequence(CONCEPTS, 1..=3), any::<bool>(), any::<bool>()).prop_flat_map(
+        move |(names, registry, declare_version)| {
+            let count = names.len();
+            prop::collection::vec(concept.clone(), count).prop_map(move |facets| DocSpec {
+                concepts: names.iter().copied().zip(facets).collect(),
+                registry,
+                declare_version,
+            })
+        },
+    )
+}
+
+/// Claim text for `spec`, the `index`th claim of the document
+fn claim_text(spec: &ClaimSpec, index: usize, targets: &[String]) -> String {
+    let mut words: Vec<String> = spec.words.iter().map(|w| w.to_string()).collect();
+    words[0] = format!("{}-{}", words[0], index);
+    let mut text = words.join(" ");
+    if let Some(modifier) = spec.modifier {
+        text.push_str(modifier);
+    }
+    if let Some((operator, right)) = spec.relation {
+        text = format!("{} {} {}", text, operator, right);
+    }
+    for condition in &spec.conditions {
+        text = format!("{} | {}", text, condition);
+    }
+    for source in &spec.sources {
+        text = format!("{} @{}", text, source);
+    }
+    if let Some(reference) = &spec.reference {
+        text = format!("{} &{}", text, reference.get(targets));
+    }
+    if let Some((prior, date)) = spec.prior {
+        match date {
+            Some(date) => text = format!("{} [<= {} :{}]", text, prior, date),
+            None => text = format!("{} [<= {}]", text, prior),
+        }
+    }
+    if spec.with_id {
+        text = format!("{} ^id:claim-{}", text, index);
+    }
+    text
+}
+
+fn build(spec: &DocSpec) -> Document {
+    let mut targets = Vec::new();
+    for (concept, facets) in &spec.concepts {
+        for facet in facets {
+            targets.push(format!("{}.{}", concept, facet.name));
+            for (sub, _) in &facet.sub_facets {
+                targets.push(format!("{}.{}.{}", concept, facet.name, sub));
+            }
+        }
+    }
+
+    let mut builder = DocumentBuilder::new();
+    if spec.declare_version {
+        builder = builder.spec_version(SpecVersion::V1_1);
+    }
+    let mut index = 0;
+    let mut claim = |builder: DocumentBuilder, claim: &ClaimSpec| {
+        index += 1;
+        builder.claim(&claim_text(claim, index, &targets))
+    };
+    for (concept, facets) in &spec.concepts {
+        builder = builder.concept(*concept);
+        for facet in facets {
+            builder = builder.facet(facet.name);
+            for spec in &facet.claims {
+                builder = claim(builder, spec);
+            }
+            for (sub, claims) in &facet.sub_facets {
+                builder = builder.sub_facet(*sub);
+                for spec in claims {
+                    builder = claim(builder, spec);
+                }
+            }
+        }
+    }
+    if spec.registry {
+        for handle in HANDLES {
+            builder = builder.source(*handle, format!("Where {} comes from", handle));
+        }
+    }
+    builder.build().expect("generated document builds")
+}
+
+/// The document without line numbers and source text, which parsing adds
+fn structure(mut doc: Document) -> Document {
+    fn strip(facet: &mut Facet) {
+        facet.line = 0;
+        facet.source = None;
+        for claim in &mut facet.claims {
+            claim.line = 0;
+            claim.source = None;
+        }
+        facet.sub_facets.iter_mut().for_each(strip);
+    }
+    if let Some(version) = &mut doc.version {
+        version.source = None;
+    }
+    for concept in &mut doc.concepts {
+        concept.line = 0;
+        concept.source = None;
+        concept.facets.iter_mut().for_each(strip);
+    }
+    if let Some(sources) = &mut doc.sources {
+        sources.line = 0;
+        sources.source = None;
+        for entry in &mut sources.entries {
+            entry.line = 0;
+            entry.source = None;
+        }
+    }
+    doc
+}
+
+proptest! {
+    #[test]
+    fn generated_documents_validate_cleanly(spec in doc_spec()) {
+        let doc = build(&spec);
+        let text = doc.to_wvf_string();
+        let result = validate_with_options(&text, &ValidationOptions::nested());
+        prop_assert!(result.is_valid(), "{}\n{}", text, result);
+        prop_assert!(!result.has_warnings(), "{}\n{}", text, result);
+    }
+
+    #[test]
+    fn formatting_is_idempotent(spec in doc_spec()) {
+        let text = build(&spec).to_wvf_string();
+        let reformatted = Document::parse(&text).to_wvf_string();
+        prop_assert_eq!(&reformatted, &text);
+        // Lossless output of a canonical document is the document itself
+        prop_assert_eq!(Document::parse(&text).to_string(), text);
+    }
+
+    #[test]
+    fn parsing_serialized_document_round_trips(spec in doc_spec()) {
+        let doc = build(&spec);
+        let parsed = Document::parse(&doc.to_wvf_string());
+        prop_assert_eq!(structure(parsed), doc);
+    }
+
+    #[test]
+    fn diff_with_itself_is_empty(spec in doc_spec()) {
+        let doc = build(&spec);
+        prop_assert!(doc.diff(&doc).is_empty());
+        let parsed = Document::parse(&doc.to_wvf_string());
+        prop_assert_eq!(doc.diff(&parsed), vec![]);
+    }
+}